@@ -1,12 +1,30 @@
-use std::fs::OpenOptions;
-use std::path::PathBuf;
+use std::fs::{File, OpenOptions};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
 
 use structopt::StructOpt;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
 
+use secalc_core::config::Config;
+use secalc_core::data::blocks::{BlockKind, GridSize};
+use secalc_core::data::custom::CustomBlocks;
 use secalc_core::data::Data;
+use secalc_core::data::query::{BlockProperty, BlockSort};
+use secalc_core::grid::compare::fleet_metrics;
+use secalc_core::grid::export;
+use secalc_core::grid::gyroscope::{GridDimensions, strongest_gyroscope, gyroscopes_required, TurnTarget};
+use secalc_core::grid::{CustomConsumer, Direction, GridCalculator, PowerConsumer};
+use secalc_core::grid::planet::{DEFAULT_ENVIRONMENTS, PlanetPreset};
+use secalc_core::grid::trip::trip;
+use secalc_core::grid::recommendation;
+use secalc_core::grid::warning;
+use secalc_core::grid::watch::{DEFAULT_WATCH_INTERVAL, watch_grid};
+use secalc_core::precision::Quantity;
+use secalc_core::units::{LitresPerSecond, MegaWatts, Meters, Seconds};
 
 #[derive(Debug, StructOpt)]
-#[structopt(name = "SECalc", about = "Space Engineers Calculator")]
+#[structopt(name = "SECalc", about = "Space Engineers Calculator", after_help = "Set the SECALC_LOG environment variable to a filter such as `debug` or `secalc_core=trace` to print the duration of\ndata loading and calculations to stderr.")]
 struct Opt {
   #[structopt(subcommand)]
   command: Command
@@ -20,14 +38,172 @@ enum Command {
     #[structopt(long, short, parse(from_os_str))]
     /// Space Engineers directory to extract game data from. Automatically inferred if not set
     se_directory: Option<PathBuf>,
-    /// File to write extracted data to
+    /// File to write extracted data to, in binary format if it has the `bin` extension, or JSON otherwise
     #[structopt(parse(from_os_str))]
     output_file: PathBuf,
   },
+  #[structopt()]
+  /// Converts game data between JSON and the binary format, which is much faster to load, optionally merging in other
+  /// game data
+  ConvertData {
+    /// Game data file to convert, or a directory containing a data.json file or Space Engineers game files
+    #[structopt(parse(from_os_str))]
+    data_file: PathBuf,
+    #[structopt(long, number_of_values = 1, parse(from_os_str))]
+    /// Game data (e.g., of DLC or mods) to layer over the data in order, overriding blocks with the same identifier.
+    /// Can be given multiple times
+    layer: Vec<PathBuf>,
+    /// File to write data to, in binary format if it has the `bin` extension, or JSON otherwise
+    #[structopt(parse(from_os_str))]
+    output_file: PathBuf,
+  },
+  #[structopt()]
+  /// Checks game data for problems such as missing localizations and blocks without mass, printing each problem and
+  /// exiting with a failure code if there are any
+  ValidateData {
+    /// Game data file to check, or a directory containing a data.json file or Space Engineers game files
+    #[structopt(parse(from_os_str))]
+    data_file: PathBuf,
+  },
+  #[structopt()]
+  /// Calculates a grid and prints its results and the contribution of each block as Markdown
+  Calculate {
+    /// Game data file to calculate with, or a directory containing a data.json file or Space Engineers game files
+    #[structopt(parse(from_os_str))]
+    data_file: PathBuf,
+    /// Grid file to calculate
+    #[structopt(parse(from_os_str))]
+    grid_file: PathBuf,
+    #[structopt(long, parse(from_os_str))]
+    /// File with custom blocks to layer over the game data. Defaults to the custom blocks file of the configuration
+    /// shared with the GUIs
+    custom_blocks: Option<PathBuf>,
+    #[structopt(long, number_of_values = 1, parse(from_os_str))]
+    /// Grid file of a module to merge into the grid, summing block counts and additional mass. Can be given multiple
+    /// times
+    add: Vec<PathBuf>,
+    #[structopt(long)]
+    /// Multiply all block counts by this factor, after merging modules
+    scale: Option<f64>,
+    #[structopt(long, number_of_values = 1, parse(try_from_str = parse_kind_factor))]
+    /// Multiply counts of blocks of a kind by a factor, as kind=factor (e.g., container=2). Can be given multiple times
+    scale_kind: Vec<(BlockKind, f64)>,
+    #[structopt(long)]
+    /// Do not count hydrogen consumption of idle hydrogen thrusters, as with "thrust on demand" mods
+    no_idle_hydrogen_thrusters: bool,
+    #[structopt(long)]
+    /// Order in which power consumers are added to the cumulative power consumption, as a comma-separated list of
+    /// jump-drive, generator, up-down-thruster, front-back-thruster, left-right-thruster, ship-tool, and battery. Omitted
+    /// consumers are disabled
+    power_priority: Option<String>,
+    #[structopt(long, number_of_values = 1, parse(try_from_str = parse_custom_power_consumer))]
+    /// Add a named power consumer that is not in the game data, as name=MW (e.g., "Shield generator=12"), which counts
+    /// as misc consumption. Can be given multiple times
+    power_consumer: Vec<CustomConsumer<MegaWatts>>,
+    #[structopt(long, number_of_values = 1, parse(try_from_str = parse_custom_hydrogen_consumer))]
+    /// Add a named hydrogen consumer that is not in the game data, as name=L/s (e.g., "Modded engine=40"), which counts
+    /// as engine consumption. Can be given multiple times
+    hydrogen_consumer: Vec<CustomConsumer<LitresPerSecond>>,
+    #[structopt(long, number_of_values = 1, parse(try_from_str = parse_environment))]
+    /// Calculate in this environment (e.g., space, moon, earthlike), printing a result column per environment instead
+    /// of the breakdown. Can be given multiple times
+    environment: Vec<PlanetPreset>,
+    #[structopt(long)]
+    /// Calculate in space, on the Moon, and on Earthlike planets, like passing these with --environment
+    environments: bool,
+    #[structopt(long)]
+    /// Also print the time and thruster fuel for a trip of this many meters in space, which accelerates up to the max
+    /// speed, coasts, and brakes with the opposite thrusters
+    trip: Option<f64>,
+    #[structopt(long, default_value = "front")]
+    /// Direction of the trip: up, down, front, back, left, or right
+    trip_direction: Direction,
+    #[structopt(long, parse(try_from_str = parse_dimensions))]
+    /// Also print how many gyroscopes the grid needs to turn as fast as --turn-time or --angular-acceleration, given its
+    /// outer dimensions in meters as WxHxL (e.g., 20x10x40)
+    dimensions: Option<GridDimensions>,
+    #[structopt(long, default_value = "10")]
+    /// Time in seconds to turn 180° from standstill to standstill, that gyroscopes are required for with --dimensions
+    turn_time: f64,
+    #[structopt(long)]
+    /// Angular acceleration in rad/s^2 that gyroscopes are required for with --dimensions, instead of --turn-time
+    angular_acceleration: Option<f64>,
+    #[structopt(long)]
+    /// Print results as JSON in a stable layout for other tools, instead of Markdown
+    json: bool,
+    #[structopt(long)]
+    /// Print the components needed to build the grid as Component/<id>=<count> lines for projector and assembler
+    /// scripts, instead of results
+    components: bool,
+    #[structopt(long)]
+    /// Keep running, and print the results again whenever the grid file or a module file changes, such as when saved
+    /// from an external editor
+    watch: bool,
+  },
+  #[structopt()]
+  /// Compares key metrics (mass, thrust-to-weight ratios, endurance, and build cost) of multiple grids in a single
+  /// Markdown table
+  Fleet {
+    /// Game data file to calculate with, or a directory containing a data.json file or Space Engineers game files
+    #[structopt(parse(from_os_str))]
+    data_file: PathBuf,
+    /// Grid files to compare
+    #[structopt(parse(from_os_str), required = true)]
+    grid_files: Vec<PathBuf>,
+    #[structopt(long, parse(from_os_str))]
+    /// File with custom blocks to layer over the game data. Defaults to the custom blocks file of the configuration
+    /// shared with the GUIs
+    custom_blocks: Option<PathBuf>,
+  },
+  #[structopt()]
+  /// Lists blocks matching given filters as Markdown
+  Blocks {
+    /// Game data file to list blocks from, or a directory containing a data.json file or Space Engineers game files
+    #[structopt(parse(from_os_str))]
+    data_file: PathBuf,
+    #[structopt(long, parse(from_os_str))]
+    /// File with custom blocks to layer over the game data. Defaults to the custom blocks file of the configuration
+    /// shared with the GUIs
+    custom_blocks: Option<PathBuf>,
+    #[structopt(long)]
+    /// Only list blocks of this grid size: small or large
+    size: Option<GridSize>,
+    #[structopt(long, number_of_values = 1)]
+    /// Only list blocks of these kinds: battery, thruster, hydrogen-engine, reactor, renewable, generator,
+    /// hydrogen-tank, ship-tool, utility, accessory, automation, life-support, gyroscope, weapon, container, or cockpit
+    kind: Vec<BlockKind>,
+    #[structopt(long)]
+    /// Only list blocks whose name contains this text, ignoring case
+    name: Option<String>,
+    #[structopt(long, number_of_values = 1, parse(try_from_str = parse_threshold))]
+    /// Only list blocks whose property is greater than the value, e.g. `thrust=100000`
+    min: Vec<(BlockProperty, f64)>,
+    #[structopt(long, number_of_values = 1, parse(try_from_str = parse_threshold))]
+    /// Only list blocks whose property is less than the value, e.g. `mass=1000`
+    max: Vec<(BlockProperty, f64)>,
+    #[structopt(long, default_value = "index")]
+    /// Sort blocks by: index, name, mass, power, or thrust
+    sort: BlockSort,
+    #[structopt(long)]
+    /// Sort blocks in descending order
+    descending: bool,
+  },
+  #[structopt()]
+  /// Prints the location and contents of the configuration that is shared with the GUIs
+  Config,
 }
 
 fn main() {
   let opt: Opt = Opt::from_args();
+  tracing_subscriber::fmt()
+    .with_env_filter(EnvFilter::from_env("SECALC_LOG"))
+    .with_span_events(FmtSpan::CLOSE)
+    .with_writer(std::io::stderr)
+    .init();
+  let config = Config::load().unwrap_or_else(|e| {
+    eprintln!("Could not load configuration, using defaults: {}", e);
+    Config::default()
+  });
   match opt.command {
     Command::ExtractGameData {
       se_directory,
@@ -35,12 +211,273 @@ fn main() {
     } => {
       let se_directory = se_directory.or(get_se_path()).expect("Space Engineers directory was not set, and failed to automatically infer the directory");
       let data = Data::extract_from_se_dir(se_directory).expect("Failed to read Space Engineers data");
-      let writer = OpenOptions::new().write(true).create(true).open(output_file).expect("Failed to create a writer for writing game data to file");
-      data.to_json(writer).expect("Failed to write game data to file");
+      write_data(&data, output_file);
+    },
+    Command::ConvertData {
+      data_file,
+      layer,
+      output_file
+    } => {
+      let mut data = load_data(data_file, None);
+      for layer_file in layer {
+        let layer_data = load_data(&layer_file, None);
+        let report = data.merge(layer_data);
+        for id in &report.overridden_blocks {
+          eprintln!("{}: overrides block '{}'", layer_file.display(), id);
+        }
+        for id in &report.overridden_components {
+          eprintln!("{}: overrides component '{}'", layer_file.display(), id);
+        }
+        for id in &report.overridden_gas_properties {
+          eprintln!("{}: overrides gas property '{}'", layer_file.display(), id);
+        }
+      }
+      write_data(&data, output_file);
+    },
+    Command::ValidateData {
+      data_file
+    } => {
+      let data = Data::from_path(data_file).expect("Failed to read game data");
+      let diagnostics = data.validate();
+      for diagnostic in &diagnostics {
+        println!("{}", diagnostic);
+      }
+      if !diagnostics.is_empty() {
+        std::process::exit(1);
+      }
+    },
+    Command::Calculate {
+      data_file,
+      grid_file,
+      custom_blocks,
+      add,
+      scale,
+      scale_kind,
+      no_idle_hydrogen_thrusters,
+      power_priority,
+      power_consumer,
+      hydrogen_consumer,
+      mut environment,
+      environments,
+      trip: trip_distance,
+      trip_direction,
+      dimensions,
+      turn_time,
+      angular_acceleration,
+      json,
+      components,
+      watch,
+    } => {
+      let data = load_data(data_file, custom_blocks.or_else(|| config.custom_blocks_path.clone()));
+      if environments {
+        environment.extend_from_slice(&DEFAULT_ENVIRONMENTS);
+      }
+      // Returns an error message instead of panicking, so that watching can continue after an invalid change.
+      let calculate = |mut calculator: GridCalculator| -> Result<(), String> {
+        if let Some(factor) = scale {
+          calculator.scale(factor).map_err(|e| format!("Failed to scale grid: {}", e))?;
+        }
+        for (kind, factor) in &scale_kind {
+          calculator.scale_kinds(*factor, &[*kind], &data.blocks).map_err(|e| format!("Failed to scale grid: {}", e))?;
+        }
+        if no_idle_hydrogen_thrusters {
+          calculator.idle_hydrogen_thrusters = false;
+        }
+        if let Some(power_priority) = &power_priority {
+          calculator.power_priority = PowerConsumer::parse_priority(power_priority).map_err(|e| format!("Invalid power priority: {}", e))?;
+        }
+        calculator.custom_power_consumers.extend(power_consumer.iter().cloned());
+        calculator.custom_hydrogen_consumers.extend(hydrogen_consumer.iter().cloned());
+        calculator.validate().map_err(|e| format!("Invalid grid options: {}", e))?;
+        if components {
+          print!("{}", export::to_component_list(&calculator.component_totals(&data)));
+        } else if json || environment.is_empty() {
+          let calculated = calculator.calculate_with_breakdown(&data);
+          for warning in warning::check(&calculated, &calculator, &data) {
+            eprintln!("Warning: {}", warning);
+          }
+          for recommendation in recommendation::recommend(&calculated, &calculator, &data) {
+            eprintln!("Recommendation: {}", recommendation);
+          }
+          if json {
+            println!("{}", export::to_json(&calculated));
+          } else {
+            print!("{}", export::to_markdown(&calculated));
+          }
+        } else {
+          print!("{}", export::environments_to_markdown(&calculator.calculate_in_environments(&data, &environment)));
+        }
+        if !json && !components {
+          let weapons = calculator.weapon_groups(&data);
+          if !weapons.is_empty() {
+            print!("{}", export::weapons_to_markdown(&weapons));
+          }
+        }
+        if let (Some(distance), false) = (trip_distance, json || components) {
+          let calculated = calculator.calculate(&data);
+          let trip = |filled| trip(&calculated, calculator.max_speed, trip_direction, Meters(distance), filled);
+          print!("{}", export::trip_to_markdown(trip_direction, distance, trip(false), trip(true)));
+        }
+        if let (Some(dimensions), false) = (dimensions, json || components) {
+          let target = angular_acceleration.map_or(TurnTarget::HalfTurn(Seconds(turn_time)), TurnTarget::AngularAcceleration);
+          let (small, large) = calculator.block_count_by_size(&data);
+          let size = if small > large { GridSize::Small } else { GridSize::Large };
+          match strongest_gyroscope(&data, size) {
+            Some(gyroscope) => {
+              let mass = calculator.calculate(&data).volume_mass;
+              let required = |mass| gyroscopes_required(&gyroscope.details, mass, dimensions, target);
+              let to_add = |mass| calculator.gyroscopes_to_add(&data, &gyroscope.details, mass, dimensions, target);
+              print!("{}", export::gyroscopes_to_markdown(target, gyroscope.name(&data.localization),
+                [required(mass.total_mass_empty), required(mass.total_mass_filled)],
+                [to_add(mass.total_mass_empty), to_add(mass.total_mass_filled)]));
+            }
+            None => eprintln!("No {:?} gyroscopes in the game data", size),
+          }
+        }
+        Ok(())
+      };
+      if watch {
+        watch_grid(&grid_file, &add, DEFAULT_WATCH_INTERVAL, |calculator| {
+          match calculator {
+            Ok(calculator) => if let Err(e) = calculate(calculator) {
+              eprintln!("{}", e);
+            },
+            Err(e) => eprintln!("Failed to read grid: {}", e),
+          }
+          true
+        });
+      } else {
+        let mut calculator = read_grid(grid_file);
+        for module_file in add {
+          calculator.merge(&read_grid(module_file)).expect("Failed to merge grid");
+        }
+        if let Err(e) = calculate(calculator) {
+          eprintln!("{}", e);
+          std::process::exit(1);
+        }
+      }
+    },
+    Command::Fleet {
+      data_file,
+      grid_files,
+      custom_blocks,
+    } => {
+      let data = load_data(data_file, custom_blocks.or_else(|| config.custom_blocks_path.clone()));
+      let fleet: Vec<_> = grid_files.iter()
+        .map(|grid_file| {
+          let name = grid_file.file_name().map(|n| n.to_string_lossy().trim_end_matches(".grid.json").to_owned()).unwrap_or_default();
+          (name, fleet_metrics(&read_grid(grid_file), &data))
+        })
+        .collect();
+      print!("{}", export::fleet_to_markdown(&fleet));
+    },
+    Command::Blocks {
+      data_file,
+      custom_blocks,
+      size,
+      kind,
+      name,
+      min,
+      max,
+      sort,
+      descending,
+    } => {
+      let data = load_data(data_file, custom_blocks.or_else(|| config.custom_blocks_path.clone()));
+      let mut query = data.blocks.query(&data.localization, &data.components).sort_by(sort, descending);
+      if let Some(size) = size { query = query.size(size); }
+      for kind in kind { query = query.kind(kind); }
+      if let Some(name) = name { query = query.name_contains(&name); }
+      for (property, value) in min { query = query.greater_than(property, value); }
+      for (property, value) in max { query = query.less_than(property, value); }
+      println!("| Name | Size | Kind | Mass (kg) | Power output (MW) | Thrust (N) |");
+      println!("|---|---|---|--:|--:|--:|");
+      for block in query.run() {
+        println!("| {} | {} | {} | {} | {} | {} |", block.name, block.size, block.kind, Quantity::Mass.format(block.mass), Quantity::Power.format(block.power_output), Quantity::Force.format(block.thrust));
+      }
+    },
+    Command::Config => {
+      match Config::file_path() {
+        Some(file_path) => eprintln!("Configuration file: {}", file_path.display()),
+        None => eprintln!("No configuration directory was found"),
+      }
+      println!("{}", config.to_json_string().expect("Failed to write configuration"));
     },
   }
 }
 
+fn read_grid<P: AsRef<Path>>(grid_file: P) -> GridCalculator {
+  GridCalculator::from_json(File::open(grid_file).expect("Failed to open grid file")).expect("Failed to read grid")
+}
+
+/// Loads game data from `path` with custom blocks from `custom_blocks_file` layered over it, printing problems with the
+/// data in debug builds.
+fn load_data<P: AsRef<Path>>(path: P, custom_blocks_file: Option<PathBuf>) -> Data {
+  let mut data = Data::from_path(path).expect("Failed to read game data");
+  if let Some(custom_blocks_file) = custom_blocks_file {
+    let custom_blocks = CustomBlocks::from_json(File::open(custom_blocks_file).expect("Failed to open custom blocks file")).expect("Failed to read custom blocks");
+    data.add_custom_blocks(&custom_blocks);
+  }
+  if cfg!(debug_assertions) {
+    for diagnostic in data.validate() {
+      eprintln!("Game data problem: {}", diagnostic);
+    }
+  }
+  data
+}
+
+fn write_data(data: &Data, output_file: PathBuf) {
+  let binary = Data::is_binary_file(&output_file);
+  let writer = BufWriter::new(OpenOptions::new().write(true).create(true).truncate(true).open(output_file).expect("Failed to create a writer for writing game data to file"));
+  if binary {
+    data.to_binary(writer)
+  } else {
+    data.to_json(writer)
+  }.expect("Failed to write game data to file");
+}
+
+fn parse_threshold(s: &str) -> Result<(BlockProperty, f64), String> {
+  let mut split = s.splitn(2, '=');
+  let property = split.next().unwrap_or_default().parse().map_err(|e| format!("{}", e))?;
+  let value = split.next().ok_or_else(|| format!("Expected <property>=<value>, got '{}'", s))?.parse().map_err(|e| format!("{}", e))?;
+  Ok((property, value))
+}
+
+fn parse_kind_factor(s: &str) -> Result<(BlockKind, f64), String> {
+  let mut split = s.splitn(2, '=');
+  let kind = split.next().unwrap_or_default().parse().map_err(|e| format!("{}", e))?;
+  let factor = split.next().ok_or_else(|| format!("Expected <kind>=<factor>, got '{}'", s))?.parse().map_err(|e| format!("{}", e))?;
+  Ok((kind, factor))
+}
+
+fn parse_custom_consumer(s: &str, unit: &str) -> Result<(String, f64), String> {
+  let mut split = s.rsplitn(2, '=');
+  let consumption = split.next().unwrap_or_default().trim().parse().map_err(|e| format!("{}", e))?;
+  let name = split.next().ok_or_else(|| format!("Expected <name>=<{}>, got '{}'", unit, s))?.trim().to_owned();
+  Ok((name, consumption))
+}
+
+fn parse_custom_power_consumer(s: &str) -> Result<CustomConsumer<MegaWatts>, String> {
+  let (name, consumption) = parse_custom_consumer(s, "MW")?;
+  Ok(CustomConsumer { name, consumption: MegaWatts(consumption) })
+}
+
+fn parse_custom_hydrogen_consumer(s: &str) -> Result<CustomConsumer<LitresPerSecond>, String> {
+  let (name, consumption) = parse_custom_consumer(s, "L/s")?;
+  Ok(CustomConsumer { name, consumption: LitresPerSecond(consumption) })
+}
+
+fn parse_dimensions(s: &str) -> Result<GridDimensions, String> {
+  let dimensions = s.split('x').map(|d| d.trim().parse::<f64>().map_err(|e| format!("{}", e))).collect::<Result<Vec<_>, _>>()?;
+  match dimensions.as_slice() {
+    [width, height, length] => Ok(GridDimensions { width: Meters(*width), height: Meters(*height), length: Meters(*length) }),
+    _ => Err(format!("Expected <width>x<height>x<length>, got '{}'", s)),
+  }
+}
+
+fn parse_environment(s: &str) -> Result<PlanetPreset, String> {
+  PlanetPreset::find_by_name(s).copied().ok_or_else(|| format!("Unknown environment '{}'", s))
+}
+
 #[cfg(windows)]
 fn get_se_path() -> Option<PathBuf> {
   use winreg::enums::*;
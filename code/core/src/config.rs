@@ -0,0 +1,185 @@
+//! User configuration shared by all frontends, persisted in a single file so that settings behave consistently no
+//! matter which frontend changes them.
+
+use std::fs::{self, File};
+use std::io;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::format::UnitPreferences;
+use crate::grid::GridCalculator;
+use crate::grid::template::GridTemplates;
+
+/// Theme variant to use.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Theme {
+  /// Use the variant preferred by the system, or light if a frontend cannot detect that preference.
+  System,
+  Light,
+  Dark,
+}
+
+impl Default for Theme {
+  fn default() -> Self { Theme::System }
+}
+
+impl Theme {
+  pub fn iter() -> impl Iterator<Item=&'static Theme> {
+    use self::Theme::*;
+    static THEMES: [Theme; 3] = [System, Light, Dark];
+    THEMES.iter()
+  }
+
+  pub fn id(&self) -> &'static str {
+    match self {
+      Theme::System => "system",
+      Theme::Light => "light",
+      Theme::Dark => "dark",
+    }
+  }
+
+  pub fn from_id(id: &str) -> Option<Self> {
+    Self::iter().find(|t| t.id() == id).copied()
+  }
+
+  pub fn label(&self) -> &'static str {
+    match self {
+      Theme::System => "System default",
+      Theme::Light => "Light",
+      Theme::Dark => "Dark",
+    }
+  }
+}
+
+#[derive(Error, Debug)]
+pub enum LoadError {
+  #[error("Could not read configuration file")]
+  Read(#[from] io::Error),
+  #[error("Could not read configuration from JSON")]
+  FromJSON(#[from] serde_json::Error),
+}
+
+#[derive(Error, Debug)]
+pub enum SaveError {
+  #[error("Could not find a configuration directory to store the configuration in")]
+  NoConfigDir,
+  #[error("Could not write configuration file")]
+  Write(#[from] io::Error),
+  #[error("Could not write configuration to JSON")]
+  ToJSON(#[from] serde_json::Error),
+}
+
+/// User configuration shared by the GUIs and the CLI. Frontend-specific state, such as window geometry, is stored by
+/// the frontends themselves.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Config {
+  pub theme: Theme,
+  /// Units to show results in.
+  #[serde(alias = "unit_preferences")]
+  pub units: UnitPreferences,
+  /// Data file (e.g., extracted with mods), or a directory containing one or Space Engineers game files, to use instead
+  /// of the built-in data.
+  pub data_path: Option<PathBuf>,
+  /// File with custom blocks to layer over the data.
+  pub custom_blocks_path: Option<PathBuf>,
+  /// Translations file to translate the user interface with, or English if there is none.
+  pub translations_path: Option<PathBuf>,
+  /// Recently opened or saved grid files, most recent first.
+  pub recent_files: Vec<PathBuf>,
+  /// Default gravity multiplier of new calculations.
+  pub gravity_multiplier: f64,
+  /// Default container multiplier of new calculations.
+  pub container_multiplier: f64,
+  /// Default planetary influence of new calculations.
+  pub planetary_influence: f64,
+  /// Templates that can be applied onto calculators.
+  pub templates: GridTemplates,
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    let calculator = GridCalculator::default();
+    Self {
+      theme: Theme::default(),
+      units: UnitPreferences::default(),
+      data_path: None,
+      custom_blocks_path: None,
+      translations_path: None,
+      recent_files: Vec::new(),
+      gravity_multiplier: calculator.gravity_multiplier,
+      container_multiplier: calculator.container_multiplier,
+      planetary_influence: calculator.planetary_influence,
+      templates: GridTemplates::default(),
+    }
+  }
+}
+
+impl Config {
+  const FILE_NAME: &'static str = "config.json";
+  pub const MAX_RECENT_FILES: usize = 10;
+
+  /// Creates a calculator with the default options of this configuration.
+  pub fn default_calculator(&self) -> GridCalculator {
+    GridCalculator {
+      gravity_multiplier: self.gravity_multiplier,
+      container_multiplier: self.container_multiplier,
+      planetary_influence: self.planetary_influence,
+      ..GridCalculator::default()
+    }
+  }
+
+  /// Moves `file_path` to the front of the recent files, keeping at most 10 recent files.
+  pub fn push_recent_file(&mut self, file_path: PathBuf) {
+    self.recent_files.retain(|p| p != &file_path);
+    self.recent_files.insert(0, file_path);
+    self.recent_files.truncate(Self::MAX_RECENT_FILES);
+  }
+
+  /// Iterates over the recent files that still exist.
+  pub fn iter_recent_files(&self) -> impl Iterator<Item=&PathBuf> {
+    self.recent_files.iter().filter(|p| p.exists())
+  }
+
+  /// Path of the configuration file: `SECalc/config.json` in the configuration directory of the user, or `None` if
+  /// there is no such directory, such as on the web.
+  pub fn file_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("SECalc").join(Self::FILE_NAME))
+  }
+
+  /// Loads the configuration from `file_path`, or returns the default configuration if there is no configuration file.
+  pub fn load() -> Result<Self, LoadError> {
+    match Self::file_path() {
+      Some(file_path) if file_path.exists() => Self::from_json(File::open(file_path)?),
+      _ => Ok(Self::default()),
+    }
+  }
+
+  /// Saves the configuration to `file_path`, creating its directory if needed.
+  pub fn save(&self) -> Result<(), SaveError> {
+    let file_path = Self::file_path().ok_or(SaveError::NoConfigDir)?;
+    if let Some(dir_path) = file_path.parent() {
+      fs::create_dir_all(dir_path)?;
+    }
+    self.to_json(File::create(file_path)?)?;
+    Ok(())
+  }
+
+  pub fn from_json<R: io::Read>(reader: R) -> Result<Self, LoadError> {
+    Ok(serde_json::from_reader(reader)?)
+  }
+
+  pub fn from_json_string(string: &str) -> Result<Self, LoadError> {
+    Ok(serde_json::from_str(string)?)
+  }
+
+  pub fn to_json<W: io::Write>(&self, writer: W) -> Result<(), SaveError> {
+    Ok(serde_json::to_writer_pretty(writer, self)?)
+  }
+
+  pub fn to_json_string(&self) -> Result<String, SaveError> {
+    Ok(serde_json::to_string_pretty(self)?)
+  }
+}
@@ -1,5 +1,6 @@
 use std::backtrace::Backtrace;
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
@@ -10,6 +11,8 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use walkdir::WalkDir;
 
+use crate::precision::Quantity;
+
 use super::components::Components;
 use super::gas_properties::GasProperties;
 use super::localization::Localization;
@@ -20,6 +23,18 @@ pub const VOLUME_MULTIPLIER: f64 = 1000.0;
 
 /// Default FuelProductionToCapacityMultiplier in SE's code.
 pub const DEFAULT_FUEL_PRODUCTION_TO_CAPACITY_MULTIPLIER: f64 = 3600.0;
+/// Power consumption of operating ship tools (MW), which is hardcoded in SE's code instead of being defined per block.
+pub const SHIP_TOOL_POWER_CONSUMPTION: f64 = 0.002;
+/// Power consumption of utility blocks at maximum range (MW), for definitions that do not specify their power drain.
+pub const DEFAULT_UTILITY_POWER_CONSUMPTION: f64 = 0.002;
+/// Power consumption of lights and LCD panels (MW), for definitions that do not specify their required power input.
+pub const DEFAULT_ACCESSORY_POWER_CONSUMPTION: f64 = 0.0001;
+/// Power consumption of automation blocks (MW), for definitions that do not specify their required power input.
+pub const DEFAULT_AUTOMATION_POWER_CONSUMPTION: f64 = 0.0005;
+/// Power consumption of life support blocks (MW), for definitions that do not specify their power consumption.
+pub const DEFAULT_LIFE_SUPPORT_POWER_CONSUMPTION: f64 = 0.002;
+/// Power consumption of gyroscopes (MW), for definitions that do not specify their required power input.
+pub const DEFAULT_GYROSCOPE_POWER_CONSUMPTION: f64 = 0.00003;
 
 
 #[derive(Error, Debug)]
@@ -32,6 +47,10 @@ pub enum Error {
   ReadEntityComponentsFile { file: PathBuf, source: std::io::Error },
   #[error("Could not XML parse EntityComponents file '{file}'")]
   ParseEntityComponentsFile { file: PathBuf, source: roxmltree::Error },
+  #[error("Could not read weapons or ammo magazines file '{file}'")]
+  ReadWeaponsFile { file: PathBuf, source: std::io::Error },
+  #[error("Could not XML parse weapons or ammo magazines file '{file}'")]
+  ParseWeaponsFile { file: PathBuf, source: roxmltree::Error },
   #[error("Unexpected XML structure")]
   XmlStructure(Backtrace),
 }
@@ -63,6 +82,52 @@ pub trait FromDef: Clone + Debug {
 }
 
 
+/// Statistic of a block, for displaying to users.
+#[derive(Clone, Debug)]
+pub struct BlockStat {
+  pub label: &'static str,
+  pub value: f64,
+  pub unit: &'static str,
+}
+
+impl BlockStat {
+  pub fn new(label: &'static str, value: f64, unit: &'static str) -> Self {
+    Self { label, value, unit }
+  }
+
+  /// Gets the kind of quantity of this statistic from its unit, so that it is shown with the precision of results.
+  pub fn quantity(&self) -> Quantity {
+    match self.unit {
+      "kg" => Quantity::Mass,
+      "L" => Quantity::Volume,
+      "L/s" | "#/s" | "#/min" => Quantity::Flow,
+      "#" => Quantity::Items,
+      "MW" => Quantity::Power,
+      "MWh" => Quantity::Energy,
+      "N" | "N*m" => Quantity::Force,
+      "m" => Quantity::Distance,
+      _ => Quantity::Ratio,
+    }
+  }
+
+  /// Formats the value of this statistic with its unit.
+  pub fn format(&self) -> String {
+    format!("{} {}", self.quantity().format(self.value), self.unit)
+  }
+}
+
+/// Block details which can list their statistics.
+pub trait DetailStats {
+  fn stats(&self, gas_properties: &GasProperties) -> Vec<BlockStat>;
+
+  /// Gets the maximum power output (MW), or 0.0 if the block does not output power.
+  fn power_output(&self) -> f64 { 0.0 }
+
+  /// Gets the force (N), or 0.0 if the block does not produce thrust.
+  fn thrust(&self) -> f64 { 0.0 }
+}
+
+
 /// Alias for block identifiers.
 pub type BlockId = String;
 
@@ -95,6 +160,15 @@ impl<T> Block<T> {
   }
 }
 
+impl<T: DetailStats> Block<T> {
+  /// Gets the statistics of this block: its mass followed by the statistics of its details.
+  pub fn stats(&self, components: &Components, gas_properties: &GasProperties) -> Vec<BlockStat> {
+    let mut stats = vec![BlockStat::new("Mass", self.mass(components), "kg")];
+    stats.extend(self.details.stats(gas_properties));
+    stats
+  }
+}
+
 impl<T: FromDef> Block<T> {
   pub fn from_def(def: &Node, entity_components: &Node, index: u64) -> Self {
     let id_node = def.child_elem("Id").unwrap();
@@ -155,6 +229,18 @@ pub struct Battery {
   pub output: f64,
 }
 
+impl DetailStats for Battery {
+  fn stats(&self, _gas_properties: &GasProperties) -> Vec<BlockStat> {
+    vec![
+      BlockStat::new("Capacity", self.capacity, "MWh"),
+      BlockStat::new("Maximum input", self.input, "MW"),
+      BlockStat::new("Maximum output", self.output, "MW"),
+    ]
+  }
+
+  fn power_output(&self) -> f64 { self.output }
+}
+
 impl FromDef for Battery {
   fn from_def(def: &Node, _entity_components: &Node) -> Self {
     let capacity: f64 = def.parse_child_elem("MaxStoredPower").unwrap().unwrap();
@@ -182,6 +268,17 @@ impl ThrusterType {
       t => panic!("Unrecognized thruster type {}", t),
     }
   }
+
+  /// Gets the lower and upper bound of the effectiveness of thrusters of this type, or `None` if their effectiveness is
+  /// only determined by planetary influence. Ion thrusters never exceed their force in vacuum, and atmospheric
+  /// thrusters never exceed their force in full atmosphere.
+  pub fn effectiveness_bounds(self) -> Option<(f64, f64)> {
+    match self {
+      ThrusterType::Ion => Some((0.0, 1.0)),
+      ThrusterType::Atmospheric => Some((0.0, 1.0)),
+      ThrusterType::Hydrogen => None,
+    }
+  }
 }
 
 /// Thruster.
@@ -205,6 +302,37 @@ pub struct Thruster {
 }
 
 impl Thruster {
+  /// Gets the effectiveness of this thruster at `planetary_influence`. Thrusters that need an atmosphere produce no
+  /// force below their minimum planetary influence, and the effectiveness is clamped to the bounds of the thruster type.
+  /// Otherwise, the effectiveness is linearly interpolated between the effectiveness at the minimum and maximum
+  /// planetary influence.
+  pub fn effectiveness(&self, planetary_influence: f64) -> f64 {
+    if self.needs_atmosphere_for_influence && planetary_influence < self.min_planetary_influence {
+      return 0.0;
+    }
+    let effectiveness = self.interpolated_effectiveness(planetary_influence);
+    match self.ty.effectiveness_bounds() {
+      Some((min, max)) => effectiveness.clamp(min, max),
+      None => effectiveness,
+    }
+  }
+
+  fn interpolated_effectiveness(&self, planetary_influence: f64) -> f64 {
+    // Avoid dividing by zero when the influence range is empty.
+    if self.min_planetary_influence >= self.max_planetary_influence {
+      return if planetary_influence < self.max_planetary_influence { self.effectiveness_at_min_influence } else { self.effectiveness_at_max_influence };
+    }
+    // Clamp planetary influence value.
+    let planetary_influence = planetary_influence.clamp(self.min_planetary_influence, self.max_planetary_influence);
+    // Slope-intercept form equation: y = mx + b
+    // Calculate m: m = (y2 - y1) / (x2 - x1)
+    let m = (self.effectiveness_at_min_influence - self.effectiveness_at_max_influence) / (self.min_planetary_influence - self.max_planetary_influence);
+    // Calculate b: b = y + -mx (choose x,y on the line)
+    let b = self.effectiveness_at_max_influence + (-1.0 * m * self.max_planetary_influence);
+    // Calculate y: y = mx + b
+    m * planetary_influence + b
+  }
+
   pub fn actual_max_consumption(&self, gas_properties: &GasProperties) -> f64 {
     if let Some(id) = &self.fuel_gas_id {
       if let Some(gas_property) = gas_properties.get(id) {
@@ -230,6 +358,19 @@ impl Thruster {
   }
 }
 
+impl DetailStats for Thruster {
+  fn stats(&self, gas_properties: &GasProperties) -> Vec<BlockStat> {
+    let consumption_unit = if self.fuel_gas_id.is_some() { "L/s" } else { "MW" };
+    vec![
+      BlockStat::new("Force", self.force, "N"),
+      BlockStat::new("Maximum consumption", self.actual_max_consumption(gas_properties), consumption_unit),
+      BlockStat::new("Minimum consumption", self.actual_min_consumption(gas_properties), consumption_unit),
+    ]
+  }
+
+  fn thrust(&self) -> f64 { self.force }
+}
+
 impl FromDef for Thruster {
   fn from_def(def: &Node, _entity_components: &Node) -> Self {
     let ty = ThrusterType::from_def(def);
@@ -258,6 +399,18 @@ pub struct HydrogenEngine {
   pub max_fuel_consumption: f64,
 }
 
+impl DetailStats for HydrogenEngine {
+  fn stats(&self, _gas_properties: &GasProperties) -> Vec<BlockStat> {
+    vec![
+      BlockStat::new("Fuel capacity", self.fuel_capacity, "L"),
+      BlockStat::new("Maximum power generation", self.max_power_generation, "MW"),
+      BlockStat::new("Maximum fuel consumption", self.max_fuel_consumption, "L/s"),
+    ]
+  }
+
+  fn power_output(&self) -> f64 { self.max_power_generation }
+}
+
 impl FromDef for HydrogenEngine {
   fn from_def(def: &Node, _entity_components: &Node) -> Self {
     let fuel_capacity: f64 = def.parse_child_elem("FuelCapacity").unwrap().unwrap();
@@ -278,6 +431,17 @@ pub struct Reactor {
   pub max_fuel_consumption: f64,
 }
 
+impl DetailStats for Reactor {
+  fn stats(&self, _gas_properties: &GasProperties) -> Vec<BlockStat> {
+    vec![
+      BlockStat::new("Maximum power generation", self.max_power_generation, "MW"),
+      BlockStat::new("Maximum fuel consumption", self.max_fuel_consumption, "#/s"),
+    ]
+  }
+
+  fn power_output(&self) -> f64 { self.max_power_generation }
+}
+
 impl FromDef for Reactor {
   fn from_def(def: &Node, _entity_components: &Node) -> Self {
     let max_power_generation: f64 = def.parse_child_elem("MaxPowerOutput").unwrap().unwrap();
@@ -288,6 +452,253 @@ impl FromDef for Reactor {
 }
 
 
+/// Renewable power source: solar panel or wind turbine
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Renewable {
+  /// Maximum power generation (MW), in full sunlight or wind
+  pub max_power_generation: f64,
+}
+
+impl DetailStats for Renewable {
+  fn stats(&self, _gas_properties: &GasProperties) -> Vec<BlockStat> {
+    vec![
+      BlockStat::new("Maximum power generation", self.max_power_generation, "MW"),
+    ]
+  }
+
+  fn power_output(&self) -> f64 { self.max_power_generation }
+}
+
+impl FromDef for Renewable {
+  fn from_def(def: &Node, _entity_components: &Node) -> Self {
+    let max_power_generation: f64 = def.parse_child_elem("MaxPowerOutput").unwrap().unwrap();
+    Renewable { max_power_generation }
+  }
+}
+
+
+/// Ship tool: drill, welder, or grinder
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShipTool {
+  /// Operational power consumption (MW), while drilling, welding, or grinding
+  pub operational_power_consumption: f64,
+}
+
+impl DetailStats for ShipTool {
+  fn stats(&self, _gas_properties: &GasProperties) -> Vec<BlockStat> {
+    vec![
+      BlockStat::new("Operational power consumption", self.operational_power_consumption, "MW"),
+    ]
+  }
+}
+
+impl FromDef for ShipTool {
+  fn from_def(_def: &Node, _entity_components: &Node) -> Self {
+    ShipTool { operational_power_consumption: SHIP_TOOL_POWER_CONSUMPTION }
+  }
+}
+
+
+/// Utility block with a configurable range: antenna, beacon, or ore detector
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Utility {
+  /// Maximum broadcast or detection range (m)
+  pub max_range: f64,
+  /// Power consumption at maximum range (MW), which scales linearly with the configured range
+  pub max_power_consumption: f64,
+}
+
+impl DetailStats for Utility {
+  fn stats(&self, _gas_properties: &GasProperties) -> Vec<BlockStat> {
+    vec![
+      BlockStat::new("Maximum range", self.max_range, "m"),
+      BlockStat::new("Maximum power consumption", self.max_power_consumption, "MW"),
+    ]
+  }
+}
+
+impl FromDef for Utility {
+  fn from_def(def: &Node, _entity_components: &Node) -> Self {
+    let max_range = def.parse_child_elem("MaxBroadcastRadius").unwrap()
+      .or_else(|| def.parse_child_elem("MaximumRange").unwrap())
+      .unwrap_or(0.0);
+    let max_power_consumption = def.parse_child_elem::<f64>("MaxBroadcastPowerDrainkW").unwrap()
+      .map_or(DEFAULT_UTILITY_POWER_CONSUMPTION, |kw| kw / 1000.0);
+    Utility { max_range, max_power_consumption }
+  }
+}
+
+
+/// Accessory with a small constant power consumption: interior light, spotlight, or LCD panel
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Accessory {
+  /// Power consumption (MW)
+  pub power_consumption: f64,
+}
+
+impl DetailStats for Accessory {
+  fn stats(&self, _gas_properties: &GasProperties) -> Vec<BlockStat> {
+    vec![
+      BlockStat::new("Power consumption", self.power_consumption, "MW"),
+    ]
+  }
+}
+
+impl FromDef for Accessory {
+  fn from_def(def: &Node, _entity_components: &Node) -> Self {
+    let power_consumption = def.parse_child_elem("RequiredPowerInput").unwrap().unwrap_or(DEFAULT_ACCESSORY_POWER_CONSUMPTION);
+    Accessory { power_consumption }
+  }
+}
+
+
+/// Automation block with a small constant power consumption: programmable block, timer block, event controller, or
+/// AI block
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Automation {
+  /// Power consumption (MW)
+  pub power_consumption: f64,
+}
+
+impl DetailStats for Automation {
+  fn stats(&self, _gas_properties: &GasProperties) -> Vec<BlockStat> {
+    vec![
+      BlockStat::new("Power consumption", self.power_consumption, "MW"),
+    ]
+  }
+}
+
+impl FromDef for Automation {
+  fn from_def(def: &Node, _entity_components: &Node) -> Self {
+    let power_consumption = def.parse_child_elem("RequiredPowerInput").unwrap().unwrap_or(DEFAULT_AUTOMATION_POWER_CONSUMPTION);
+    Automation { power_consumption }
+  }
+}
+
+
+/// Life support block: medical room, survival kit, or cryo chamber
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LifeSupport {
+  /// Power consumption (MW)
+  pub power_consumption: f64,
+  // TODO: oxygen consumption
+}
+
+impl DetailStats for LifeSupport {
+  fn stats(&self, _gas_properties: &GasProperties) -> Vec<BlockStat> {
+    vec![
+      BlockStat::new("Power consumption", self.power_consumption, "MW"),
+    ]
+  }
+}
+
+impl FromDef for LifeSupport {
+  fn from_def(def: &Node, _entity_components: &Node) -> Self {
+    let power_consumption = def.parse_child_elem("RequiredPowerInput").unwrap()
+      .or_else(|| def.parse_child_elem("IdlePowerConsumption").unwrap())
+      .unwrap_or(DEFAULT_LIFE_SUPPORT_POWER_CONSUMPTION);
+    LifeSupport { power_consumption }
+  }
+}
+
+
+/// Gyroscope
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Gyroscope {
+  /// Maximum torque (N*m)
+  pub torque: f64,
+  /// Power consumption (MW)
+  pub power_consumption: f64,
+}
+
+impl DetailStats for Gyroscope {
+  fn stats(&self, _gas_properties: &GasProperties) -> Vec<BlockStat> {
+    vec![
+      BlockStat::new("Torque", self.torque, "N*m"),
+      BlockStat::new("Power consumption", self.power_consumption, "MW"),
+    ]
+  }
+}
+
+impl FromDef for Gyroscope {
+  fn from_def(def: &Node, _entity_components: &Node) -> Self {
+    let torque: f64 = def.parse_child_elem("ForceMagnitude").unwrap().unwrap();
+    let power_consumption = def.parse_child_elem("RequiredPowerInput").unwrap().unwrap_or(DEFAULT_GYROSCOPE_POWER_CONSUMPTION);
+    Gyroscope { torque, power_consumption }
+  }
+}
+
+
+/// Ammo magazine that a weapon is loaded with.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AmmoMagazine {
+  pub id: String,
+  /// Rounds per magazine
+  pub rounds: f64,
+  /// Mass (kg)
+  pub mass: f64,
+  /// Volume (L)
+  pub volume: f64,
+}
+
+/// Weapon (gun, launcher, or turret)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Weapon {
+  /// Identifier of the weapon definition, which defines the ammo and rate of fire.
+  pub weapon_id: String,
+  /// Inventory volume for ammo (L)
+  pub inventory_volume: f64,
+  /// Rate of fire (rounds/min)
+  pub rate_of_fire: f64,
+  /// Ammo magazine, or `None` if the weapon definition was not found.
+  pub magazine: Option<AmmoMagazine>,
+}
+
+impl Weapon {
+  /// Gets the number of whole magazines in `fill` percent of the inventory volume.
+  pub fn magazines(&self, fill: f64) -> f64 {
+    match &self.magazine {
+      Some(magazine) if magazine.volume > 0.0 => (self.inventory_volume * (fill / 100.0) / magazine.volume).floor(),
+      _ => 0.0,
+    }
+  }
+
+  /// Gets the mass of the magazines in `fill` percent of the inventory volume (kg).
+  pub fn ammo_mass(&self, fill: f64) -> f64 {
+    self.magazine.as_ref().map_or(0.0, |magazine| self.magazines(fill) * magazine.mass)
+  }
+
+  /// Gets the number of rounds in `fill` percent of the inventory volume.
+  pub fn rounds(&self, fill: f64) -> f64 {
+    self.magazine.as_ref().map_or(0.0, |magazine| self.magazines(fill) * magazine.rounds)
+  }
+}
+
+impl DetailStats for Weapon {
+  fn stats(&self, _gas_properties: &GasProperties) -> Vec<BlockStat> {
+    let mut stats = vec![
+      BlockStat::new("Inventory volume", self.inventory_volume, "L"),
+      BlockStat::new("Rate of fire", self.rate_of_fire, "#/min"),
+    ];
+    if let Some(magazine) = &self.magazine {
+      stats.push(BlockStat::new("Rounds per magazine", magazine.rounds, "#"));
+      stats.push(BlockStat::new("Magazine mass", magazine.mass, "kg"));
+      stats.push(BlockStat::new("Magazine volume", magazine.volume, "L"));
+    }
+    stats
+  }
+}
+
+impl FromDef for Weapon {
+  fn from_def(def: &Node, _entity_components: &Node) -> Self {
+    let weapon_id = def.child_elem("WeaponDefinitionId").and_then(|node| node.parse_attribute("Subtype").unwrap()).unwrap_or_default();
+    let inventory_volume = def.parse_child_elem::<f64>("InventoryMaxVolume").unwrap().unwrap_or(0.0) * VOLUME_MULTIPLIER;
+    // The rate of fire and magazine are set from the weapon definition by `Blocks::resolve_weapons`.
+    Weapon { weapon_id, inventory_volume, rate_of_fire: 0.0, magazine: None }
+  }
+}
+
+
 /// Generator (O2/H2)
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Generator {
@@ -305,6 +716,19 @@ pub struct Generator {
   pub hydrogen_generation: f64,
 }
 
+impl DetailStats for Generator {
+  fn stats(&self, _gas_properties: &GasProperties) -> Vec<BlockStat> {
+    vec![
+      BlockStat::new("Ice consumption", self.ice_consumption, "#/s"),
+      BlockStat::new("Inventory volume (ice only)", self.inventory_volume_ice, "L"),
+      BlockStat::new("Operational power consumption", self.operational_power_consumption, "MW"),
+      BlockStat::new("Idle power consumption", self.idle_power_consumption, "MW"),
+      BlockStat::new("Oxygen generation", self.oxygen_generation, "L/s"),
+      BlockStat::new("Hydrogen generation", self.hydrogen_generation, "L/s"),
+    ]
+  }
+}
+
 impl FromDef for Generator {
   fn from_def(def: &Node, _entity_components: &Node) -> Self {
     let ice_consumption: f64 = def.parse_child_elem("IceConsumptionPerSecond").unwrap().unwrap();
@@ -345,6 +769,16 @@ pub struct HydrogenTank {
   pub idle_power_consumption: f64,
 }
 
+impl DetailStats for HydrogenTank {
+  fn stats(&self, _gas_properties: &GasProperties) -> Vec<BlockStat> {
+    vec![
+      BlockStat::new("Capacity", self.capacity, "L"),
+      BlockStat::new("Operational power consumption", self.operational_power_consumption, "MW"),
+      BlockStat::new("Idle power consumption", self.idle_power_consumption, "MW"),
+    ]
+  }
+}
+
 impl FromDef for HydrogenTank {
   fn from_def(def: &Node, _entity_components: &Node) -> Self {
     let capacity: f64 = def.parse_child_elem("Capacity").unwrap().unwrap();
@@ -364,6 +798,12 @@ pub struct Container {
   pub store_any: bool,
 }
 
+impl DetailStats for Container {
+  fn stats(&self, _gas_properties: &GasProperties) -> Vec<BlockStat> {
+    vec![BlockStat::new("Inventory volume", self.capacity, "L")]
+  }
+}
+
 impl FromDef for Container {
   fn from_def(def: &Node, entity_components: &Node) -> Self {
     let subtype_id: String = def.child_elem("Id").unwrap().parse_child_elem("SubtypeId").unwrap().unwrap();
@@ -398,6 +838,12 @@ pub struct Cockpit {
   pub capacity: f64,
 }
 
+impl DetailStats for Cockpit {
+  fn stats(&self, _gas_properties: &GasProperties) -> Vec<BlockStat> {
+    vec![BlockStat::new("Inventory volume", self.capacity, "L")]
+  }
+}
+
 impl FromDef for Cockpit {
   fn from_def(def: &Node, _entity_components: &Node) -> Self {
     let has_inventory = def.parse_child_elem("HasInventory").unwrap().unwrap_or(true);
@@ -413,12 +859,148 @@ pub struct Blocks {
   pub thrusters: LinkedHashMap<BlockId, Block<Thruster>>,
   pub hydrogen_engines: LinkedHashMap<BlockId, Block<HydrogenEngine>>,
   pub reactors: LinkedHashMap<BlockId, Block<Reactor>>,
+  #[serde(default)]
+  pub renewables: LinkedHashMap<BlockId, Block<Renewable>>,
   pub generators: LinkedHashMap<BlockId, Block<Generator>>,
   pub hydrogen_tanks: LinkedHashMap<BlockId, Block<HydrogenTank>>,
+  #[serde(default)]
+  pub ship_tools: LinkedHashMap<BlockId, Block<ShipTool>>,
+  #[serde(default)]
+  pub utilities: LinkedHashMap<BlockId, Block<Utility>>,
+  #[serde(default)]
+  pub accessories: LinkedHashMap<BlockId, Block<Accessory>>,
+  #[serde(default)]
+  pub automations: LinkedHashMap<BlockId, Block<Automation>>,
+  #[serde(default)]
+  pub life_supports: LinkedHashMap<BlockId, Block<LifeSupport>>,
+  #[serde(default)]
+  pub gyroscopes: LinkedHashMap<BlockId, Block<Gyroscope>>,
+  #[serde(default)]
+  pub weapons: LinkedHashMap<BlockId, Block<Weapon>>,
   pub containers: LinkedHashMap<BlockId, Block<Container>>,
   pub cockpits: LinkedHashMap<BlockId, Block<Cockpit>>,
 }
 
+/// Kind of block, corresponding to the block maps of `Blocks`.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Hash, Debug)]
+pub enum BlockKind {
+  Battery,
+  Thruster,
+  HydrogenEngine,
+  Reactor,
+  Renewable,
+  Generator,
+  HydrogenTank,
+  ShipTool,
+  Utility,
+  Accessory,
+  Automation,
+  LifeSupport,
+  Gyroscope,
+  Weapon,
+  Container,
+  Cockpit,
+}
+
+impl BlockKind {
+  pub fn iter() -> impl Iterator<Item=&'static BlockKind> {
+    use self::BlockKind::*;
+    static KINDS: [BlockKind; 16] = [Battery, Thruster, HydrogenEngine, Reactor, Renewable, Generator, HydrogenTank, ShipTool, Utility, Accessory, Automation, LifeSupport, Gyroscope, Weapon, Container, Cockpit];
+    KINDS.iter()
+  }
+}
+
+/// Reference to a block of any kind.
+#[derive(Copy, Clone, Debug)]
+pub enum BlockRef<'a> {
+  Battery(&'a Block<Battery>),
+  Thruster(&'a Block<Thruster>),
+  HydrogenEngine(&'a Block<HydrogenEngine>),
+  Reactor(&'a Block<Reactor>),
+  Renewable(&'a Block<Renewable>),
+  Generator(&'a Block<Generator>),
+  HydrogenTank(&'a Block<HydrogenTank>),
+  ShipTool(&'a Block<ShipTool>),
+  Utility(&'a Block<Utility>),
+  Accessory(&'a Block<Accessory>),
+  Automation(&'a Block<Automation>),
+  LifeSupport(&'a Block<LifeSupport>),
+  Gyroscope(&'a Block<Gyroscope>),
+  Weapon(&'a Block<Weapon>),
+  Container(&'a Block<Container>),
+  Cockpit(&'a Block<Cockpit>),
+}
+
+/// Evaluates `$expr` with `$block` bound to the block of `$block_ref`, whatever its kind.
+macro_rules! with_block {
+  ($block_ref:expr, $block:ident => $expr:expr) => {
+    match $block_ref {
+      BlockRef::Battery($block) => $expr,
+      BlockRef::Thruster($block) => $expr,
+      BlockRef::HydrogenEngine($block) => $expr,
+      BlockRef::Reactor($block) => $expr,
+      BlockRef::Renewable($block) => $expr,
+      BlockRef::Generator($block) => $expr,
+      BlockRef::HydrogenTank($block) => $expr,
+      BlockRef::ShipTool($block) => $expr,
+      BlockRef::Utility($block) => $expr,
+      BlockRef::Accessory($block) => $expr,
+      BlockRef::Automation($block) => $expr,
+      BlockRef::LifeSupport($block) => $expr,
+      BlockRef::Gyroscope($block) => $expr,
+      BlockRef::Weapon($block) => $expr,
+      BlockRef::Container($block) => $expr,
+      BlockRef::Cockpit($block) => $expr,
+    }
+  }
+}
+
+impl<'a> BlockRef<'a> {
+  pub fn kind(self) -> BlockKind {
+    match self {
+      BlockRef::Battery(_) => BlockKind::Battery,
+      BlockRef::Thruster(_) => BlockKind::Thruster,
+      BlockRef::HydrogenEngine(_) => BlockKind::HydrogenEngine,
+      BlockRef::Reactor(_) => BlockKind::Reactor,
+      BlockRef::Renewable(_) => BlockKind::Renewable,
+      BlockRef::Generator(_) => BlockKind::Generator,
+      BlockRef::HydrogenTank(_) => BlockKind::HydrogenTank,
+      BlockRef::ShipTool(_) => BlockKind::ShipTool,
+      BlockRef::Utility(_) => BlockKind::Utility,
+      BlockRef::Accessory(_) => BlockKind::Accessory,
+      BlockRef::Automation(_) => BlockKind::Automation,
+      BlockRef::LifeSupport(_) => BlockKind::LifeSupport,
+      BlockRef::Gyroscope(_) => BlockKind::Gyroscope,
+      BlockRef::Weapon(_) => BlockKind::Weapon,
+      BlockRef::Container(_) => BlockKind::Container,
+      BlockRef::Cockpit(_) => BlockKind::Cockpit,
+    }
+  }
+
+  pub fn id(self) -> &'a BlockId { with_block!(self, b => &b.id) }
+
+  pub fn index(self) -> u64 { with_block!(self, b => b.index) }
+
+  pub fn size(self) -> GridSize { with_block!(self, b => b.size) }
+
+  pub fn name(self, localization: &'a Localization) -> &'a str { with_block!(self, b => b.name(localization)) }
+
+  /// Gets the name of this block before localization, which is the localization key.
+  pub fn unlocalized_name(self) -> &'a str { with_block!(self, b => &b.name) }
+
+  pub fn components(self) -> &'a LinkedHashMap<String, f64> { with_block!(self, b => &b.components) }
+
+  pub fn has_physics(self) -> bool { with_block!(self, b => b.has_physics) }
+
+  pub fn mass(self, components: &Components) -> f64 { with_block!(self, b => b.mass(components)) }
+
+  pub fn details(self) -> &'a dyn DetailStats { with_block!(self, b => &b.details) }
+
+  pub fn stats(self, components: &Components, gas_properties: &GasProperties) -> Vec<BlockStat> {
+    with_block!(self, b => b.stats(components, gas_properties))
+  }
+}
+
 impl Blocks {
   pub fn from_se_dir<P: AsRef<Path>>(se_dir_path: P) -> Result<Self> {
     Self::from_sbc_files(se_dir_path.as_ref().join("Content/Data/"), se_dir_path.as_ref().join("Content/Data/EntityComponents.sbc"))
@@ -437,7 +1019,7 @@ impl Blocks {
 
     let mut blocks = Blocks::default();
     let mut id = 0;
-    let cube_blocks_file_paths = WalkDir::new(cube_blocks_search_dir)
+    let cube_blocks_file_paths = WalkDir::new(cube_blocks_search_dir.as_ref())
       .into_iter()
       .filter_map(|de| {
         if let Ok(de) = de {
@@ -477,6 +1059,10 @@ impl Blocks {
               let block = Block::<Reactor>::from_def(&def, &entity_components_node, id);
               blocks.reactors.insert(block.id.clone(), block);
             }
+            "MyObjectBuilder_SolarPanelDefinition" | "MyObjectBuilder_WindTurbineDefinition" => {
+              let block = Block::<Renewable>::from_def(&def, &entity_components_node, id);
+              blocks.renewables.insert(block.id.clone(), block);
+            }
             "MyObjectBuilder_OxygenGeneratorDefinition" => {
               let block = Block::<Generator>::from_def(&def, &entity_components_node, id);
               blocks.generators.insert(block.id.clone(), block);
@@ -486,6 +1072,37 @@ impl Blocks {
               let block = Block::<HydrogenTank>::from_def(&def, &entity_components_node, id);
               blocks.hydrogen_tanks.insert(block.id.clone(), block);
             }
+            "MyObjectBuilder_ShipDrillDefinition" | "MyObjectBuilder_ShipWelderDefinition" | "MyObjectBuilder_ShipGrinderDefinition" => {
+              let block = Block::<ShipTool>::from_def(&def, &entity_components_node, id);
+              blocks.ship_tools.insert(block.id.clone(), block);
+            }
+            "MyObjectBuilder_RadioAntennaDefinition" | "MyObjectBuilder_BeaconDefinition" | "MyObjectBuilder_OreDetectorDefinition" => {
+              let block = Block::<Utility>::from_def(&def, &entity_components_node, id);
+              blocks.utilities.insert(block.id.clone(), block);
+            }
+            "MyObjectBuilder_LightingBlockDefinition" | "MyObjectBuilder_TextPanelDefinition" => {
+              let block = Block::<Accessory>::from_def(&def, &entity_components_node, id);
+              blocks.accessories.insert(block.id.clone(), block);
+            }
+            "MyObjectBuilder_ProgrammableBlockDefinition" | "MyObjectBuilder_TimerBlockDefinition"
+            | "MyObjectBuilder_EventControllerBlockDefinition" | "MyObjectBuilder_FlightMovementBlockDefinition"
+            | "MyObjectBuilder_OffensiveCombatBlockDefinition" | "MyObjectBuilder_DefensiveCombatBlockDefinition"
+            | "MyObjectBuilder_BasicMissionBlockDefinition" | "MyObjectBuilder_PathRecorderBlockDefinition" => {
+              let block = Block::<Automation>::from_def(&def, &entity_components_node, id);
+              blocks.automations.insert(block.id.clone(), block);
+            }
+            "MyObjectBuilder_MedicalRoomDefinition" | "MyObjectBuilder_SurvivalKitDefinition" | "MyObjectBuilder_CryoChamberDefinition" => {
+              let block = Block::<LifeSupport>::from_def(&def, &entity_components_node, id);
+              blocks.life_supports.insert(block.id.clone(), block);
+            }
+            "MyObjectBuilder_GyroDefinition" => {
+              let block = Block::<Gyroscope>::from_def(&def, &entity_components_node, id);
+              blocks.gyroscopes.insert(block.id.clone(), block);
+            }
+            "MyObjectBuilder_WeaponBlockDefinition" | "MyObjectBuilder_LargeTurretBaseDefinition" => {
+              let block = Block::<Weapon>::from_def(&def, &entity_components_node, id);
+              blocks.weapons.insert(block.id.clone(), block);
+            }
             "MyObjectBuilder_CargoContainerDefinition" => {
               let block = Block::<Container>::from_def(&def, &entity_components_node, id);
               blocks.containers.insert(block.id.clone(), block);
@@ -500,10 +1117,208 @@ impl Blocks {
         id += 1;
       }
     }
+    let data_dir = cube_blocks_search_dir.as_ref();
+    blocks.resolve_weapons(&data_dir.join("Weapons.sbc"), &data_dir.join("AmmoMagazines.sbc"))?;
 
     Ok(blocks)
   }
 
+  /// Sets the rate of fire and ammo magazine of weapons from the weapon definitions in `weapons_file_path` and the
+  /// ammo magazine definitions in `ammo_magazines_file_path`. Weapons are left without ammo if either file is missing.
+  fn resolve_weapons(&mut self, weapons_file_path: &Path, ammo_magazines_file_path: &Path) -> Result<()> {
+    if self.weapons.is_empty() || !weapons_file_path.exists() || !ammo_magazines_file_path.exists() { return Ok(()); }
+    let read = |file_path: &Path| read_string_from_file(file_path)
+      .map_err(|source| Error::ReadWeaponsFile { file: file_path.to_path_buf(), source });
+    fn parse<'input>(file_path: &Path, string: &'input str) -> Result<Document<'input>> {
+      Document::parse(string).map_err(|source| Error::ParseWeaponsFile { file: file_path.to_path_buf(), source })
+    }
+
+    let ammo_magazines_string = read(ammo_magazines_file_path)?;
+    let ammo_magazines_doc = parse(ammo_magazines_file_path, &ammo_magazines_string)?;
+    let ammo_magazines_root_node = ammo_magazines_doc.root().first_element_child()
+      .ok_or(Error::XmlStructure(Backtrace::capture()))?;
+    let ammo_magazines_node = ammo_magazines_root_node.child_elem("AmmoMagazines")
+      .ok_or(Error::XmlStructure(Backtrace::capture()))?;
+    let mut magazines = HashMap::new();
+    for def in ammo_magazines_node.children_elems("AmmoMagazine") {
+      let id: String = match def.child_elem("Id").and_then(|node| node.parse_child_elem("SubtypeId").unwrap()) {
+        Some(id) => id,
+        None => continue,
+      };
+      let rounds = def.parse_child_elem("Capacity").unwrap().unwrap_or(0.0);
+      let mass = def.parse_child_elem("Mass").unwrap().unwrap_or(0.0);
+      let volume = def.parse_child_elem("Volume").unwrap().unwrap_or(0.0);
+      magazines.insert(id.clone(), AmmoMagazine { id, rounds, mass, volume });
+    }
+
+    let weapons_string = read(weapons_file_path)?;
+    let weapons_doc = parse(weapons_file_path, &weapons_string)?;
+    let weapons_root_node = weapons_doc.root().first_element_child()
+      .ok_or(Error::XmlStructure(Backtrace::capture()))?;
+    let weapons_node = weapons_root_node.child_elem("Weapons")
+      .ok_or(Error::XmlStructure(Backtrace::capture()))?;
+    let mut weapons = HashMap::new();
+    for def in weapons_node.children_elems("Weapon") {
+      let id: String = match def.child_elem("Id").and_then(|node| node.parse_child_elem("SubtypeId").unwrap()) {
+        Some(id) => id,
+        None => continue,
+      };
+      // Projectile and missile ammo data both define the rate of fire.
+      let rate_of_fire = def.descendants().find_map(|node| node.parse_attribute::<f64, _>("RateOfFire").unwrap()).unwrap_or(0.0);
+      let magazine_id: Option<String> = def.child_elem("AmmoMagazines")
+        .and_then(|node| node.children_elems("AmmoMagazine").next().and_then(|node| node.parse_attribute("Subtype").unwrap()));
+      weapons.insert(id, (rate_of_fire, magazine_id));
+    }
+
+    for (_, block) in self.weapons.iter_mut() {
+      if let Some((rate_of_fire, magazine_id)) = weapons.get(&block.details.weapon_id) {
+        block.details.rate_of_fire = *rate_of_fire;
+        block.details.magazine = magazine_id.as_ref().and_then(|id| magazines.get(id)).cloned();
+      }
+    }
+    Ok(())
+  }
+
+  /// Iterates over all blocks, ordered by kind and then by definition order.
+  pub fn iter(&self) -> impl Iterator<Item=BlockRef> {
+    self.batteries.values().map(BlockRef::Battery)
+      .chain(self.thrusters.values().map(BlockRef::Thruster))
+      .chain(self.hydrogen_engines.values().map(BlockRef::HydrogenEngine))
+      .chain(self.reactors.values().map(BlockRef::Reactor))
+      .chain(self.renewables.values().map(BlockRef::Renewable))
+      .chain(self.generators.values().map(BlockRef::Generator))
+      .chain(self.hydrogen_tanks.values().map(BlockRef::HydrogenTank))
+      .chain(self.ship_tools.values().map(BlockRef::ShipTool))
+      .chain(self.utilities.values().map(BlockRef::Utility))
+      .chain(self.accessories.values().map(BlockRef::Accessory))
+      .chain(self.automations.values().map(BlockRef::Automation))
+      .chain(self.life_supports.values().map(BlockRef::LifeSupport))
+      .chain(self.gyroscopes.values().map(BlockRef::Gyroscope))
+      .chain(self.weapons.values().map(BlockRef::Weapon))
+      .chain(self.containers.values().map(BlockRef::Container))
+      .chain(self.cockpits.values().map(BlockRef::Cockpit))
+  }
+
+  /// Gets the block with `id`, or `None` if there is no such block.
+  pub fn get(&self, id: &BlockId) -> Option<BlockRef> {
+    self.batteries.get(id).map(BlockRef::Battery)
+      .or_else(|| self.thrusters.get(id).map(BlockRef::Thruster))
+      .or_else(|| self.hydrogen_engines.get(id).map(BlockRef::HydrogenEngine))
+      .or_else(|| self.reactors.get(id).map(BlockRef::Reactor))
+      .or_else(|| self.renewables.get(id).map(BlockRef::Renewable))
+      .or_else(|| self.generators.get(id).map(BlockRef::Generator))
+      .or_else(|| self.hydrogen_tanks.get(id).map(BlockRef::HydrogenTank))
+      .or_else(|| self.ship_tools.get(id).map(BlockRef::ShipTool))
+      .or_else(|| self.utilities.get(id).map(BlockRef::Utility))
+      .or_else(|| self.accessories.get(id).map(BlockRef::Accessory))
+      .or_else(|| self.automations.get(id).map(BlockRef::Automation))
+      .or_else(|| self.life_supports.get(id).map(BlockRef::LifeSupport))
+      .or_else(|| self.gyroscopes.get(id).map(BlockRef::Gyroscope))
+      .or_else(|| self.weapons.get(id).map(BlockRef::Weapon))
+      .or_else(|| self.containers.get(id).map(BlockRef::Container))
+      .or_else(|| self.cockpits.get(id).map(BlockRef::Cockpit))
+  }
+
+  /// Layers `other` over these blocks: a block of `other` overrides the block with the same identifier in place, and
+  /// other blocks are added after all blocks of their kind. Returns the identifiers of overridden blocks, in the order of
+  /// `other`.
+  pub fn merge(&mut self, other: Blocks) -> Vec<BlockId> {
+    fn merge_map<T>(map: &mut LinkedHashMap<BlockId, Block<T>>, other: LinkedHashMap<BlockId, Block<T>>, index_offset: u64, overridden: &mut Vec<BlockId>) {
+      for (id, mut block) in other {
+        match map.get_mut(&id) {
+          Some(existing) => {
+            block.index = existing.index;
+            *existing = block;
+            overridden.push(id);
+          }
+          None => {
+            block.index += index_offset;
+            map.insert(id, block);
+          }
+        }
+      }
+    }
+
+    let mut overridden = Vec::new();
+    // Blocks that changed kind cannot be overridden in place: remove them so that they are added as the new kind.
+    let changed_kind: Vec<_> = other.iter()
+      .filter_map(|block| self.get(block.id()).filter(|existing| existing.kind() != block.kind()).map(|existing| (block.id().clone(), existing.kind())))
+      .collect();
+    for (id, kind) in changed_kind {
+      self.remove(&id, kind);
+      overridden.push(id);
+    }
+    let index_offset = self.iter().map(|b| b.index() + 1).max().unwrap_or(0);
+    merge_map(&mut self.batteries, other.batteries, index_offset, &mut overridden);
+    merge_map(&mut self.thrusters, other.thrusters, index_offset, &mut overridden);
+    merge_map(&mut self.hydrogen_engines, other.hydrogen_engines, index_offset, &mut overridden);
+    merge_map(&mut self.reactors, other.reactors, index_offset, &mut overridden);
+    merge_map(&mut self.renewables, other.renewables, index_offset, &mut overridden);
+    merge_map(&mut self.generators, other.generators, index_offset, &mut overridden);
+    merge_map(&mut self.hydrogen_tanks, other.hydrogen_tanks, index_offset, &mut overridden);
+    merge_map(&mut self.ship_tools, other.ship_tools, index_offset, &mut overridden);
+    merge_map(&mut self.utilities, other.utilities, index_offset, &mut overridden);
+    merge_map(&mut self.accessories, other.accessories, index_offset, &mut overridden);
+    merge_map(&mut self.automations, other.automations, index_offset, &mut overridden);
+    merge_map(&mut self.life_supports, other.life_supports, index_offset, &mut overridden);
+    merge_map(&mut self.gyroscopes, other.gyroscopes, index_offset, &mut overridden);
+    merge_map(&mut self.weapons, other.weapons, index_offset, &mut overridden);
+    merge_map(&mut self.containers, other.containers, index_offset, &mut overridden);
+    merge_map(&mut self.cockpits, other.cockpits, index_offset, &mut overridden);
+    overridden
+  }
+
+  fn remove(&mut self, id: &BlockId, kind: BlockKind) {
+    match kind {
+      BlockKind::Battery => { self.batteries.remove(id); }
+      BlockKind::Thruster => { self.thrusters.remove(id); }
+      BlockKind::HydrogenEngine => { self.hydrogen_engines.remove(id); }
+      BlockKind::Reactor => { self.reactors.remove(id); }
+      BlockKind::Renewable => { self.renewables.remove(id); }
+      BlockKind::Generator => { self.generators.remove(id); }
+      BlockKind::HydrogenTank => { self.hydrogen_tanks.remove(id); }
+      BlockKind::ShipTool => { self.ship_tools.remove(id); }
+      BlockKind::Utility => { self.utilities.remove(id); }
+      BlockKind::Accessory => { self.accessories.remove(id); }
+      BlockKind::Automation => { self.automations.remove(id); }
+      BlockKind::LifeSupport => { self.life_supports.remove(id); }
+      BlockKind::Gyroscope => { self.gyroscopes.remove(id); }
+      BlockKind::Weapon => { self.weapons.remove(id); }
+      BlockKind::Container => { self.containers.remove(id); }
+      BlockKind::Cockpit => { self.cockpits.remove(id); }
+    }
+  }
+
+  /// Gets the localized name of the block with `id`, or `None` if there is no such block.
+  pub fn localized_name<'a>(&'a self, id: &BlockId, localization: &'a Localization) -> Option<&'a str> {
+    self.get(id).map(|b| b.name(localization))
+  }
+
+  /// Gets the statistics of the block with `id`, or `None` if there is no such block.
+  pub fn stats(&self, id: &BlockId, components: &Components, gas_properties: &GasProperties) -> Option<Vec<BlockStat>> {
+    self.get(id).map(|b| b.stats(components, gas_properties))
+  }
+
+  /// Iterates over the identifier, localized name, and grid size of all blocks.
+  pub fn iter_names<'a>(&'a self, localization: &'a Localization) -> impl Iterator<Item=(&'a BlockId, &'a str, GridSize)> + 'a {
+    self.iter().map(move |b| (b.id(), b.name(localization), b.size()))
+  }
+
+  /// Finds the block whose localized name or identifier best matches `query`, ignoring case and non-alphanumeric
+  /// characters, optionally restricted to blocks of grid `size`. Returns `None` if no block matches well enough.
+  pub fn find_by_name<'a>(&'a self, query: &str, size: Option<GridSize>, localization: &'a Localization) -> Option<&'a BlockId> {
+    /// Minimum similarity for a block to match.
+    const THRESHOLD: f64 = 0.5;
+    let query = normalize_name(query);
+    if query.is_empty() { return None; }
+    self.iter_names(localization)
+      .filter(|(_, _, s)| size.map_or(true, |size| size == *s))
+      .map(|(id, name, _)| (id, name_similarity(&query, &normalize_name(name)).max(name_similarity(&query, &normalize_name(id)))))
+      .filter(|(_, similarity)| *similarity >= THRESHOLD)
+      .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+      .map(|(id, _)| id)
+  }
+
   pub fn small_and_large_sorted<'a, T, I: Iterator<Item=&'a Block<T>>>(iter: I) -> (Vec<&'a Block<T>>, Vec<&'a Block<T>>) {
     let mut small_vec = Vec::new();
     let mut large_vec = Vec::new();
@@ -520,3 +1335,29 @@ impl Blocks {
 }
 
 
+/// Lowercases `name` and removes all non-alphanumeric characters.
+fn normalize_name(name: &str) -> String {
+  name.chars().filter(|c| c.is_alphanumeric()).flat_map(|c| c.to_lowercase()).collect()
+}
+
+/// Gets the similarity of two normalized names as the Dice coefficient of their character bigrams, which is 1.0 for
+/// equal names and 0.0 for names without common bigrams.
+fn name_similarity(a: &str, b: &str) -> f64 {
+  if a == b { return 1.0; }
+  fn bigrams(s: &str) -> Vec<(char, char)> {
+    let chars: Vec<char> = s.chars().collect();
+    chars.windows(2).map(|w| (w[0], w[1])).collect()
+  }
+  let a = bigrams(a);
+  let mut b = bigrams(b);
+  if a.is_empty() || b.is_empty() { return 0.0; }
+  let total = (a.len() + b.len()) as f64;
+  let mut common = 0;
+  for bigram in a {
+    if let Some(index) = b.iter().position(|other| *other == bigram) {
+      b.swap_remove(index);
+      common += 1;
+    }
+  }
+  2.0 * common as f64 / total
+}
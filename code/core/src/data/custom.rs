@@ -0,0 +1,254 @@
+use std::io;
+
+use linked_hash_map::LinkedHashMap;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::debug_span;
+
+use super::blocks::{Accessory, AmmoMagazine, Automation, Battery, Block, BlockId, Blocks, Container, GridSize, Gyroscope, HydrogenEngine, HydrogenTank, LifeSupport, Reactor, Renewable, ShipTool, Thruster, ThrusterType, Utility, Weapon};
+use super::components::{Component, Components};
+use super::gas_properties::GasProperties;
+use super::localization::Localization;
+use super::{Data, MergeReport};
+
+#[derive(Error, Debug)]
+pub enum ReadError {
+  #[error("Could not read custom blocks from JSON")]
+  FromJSON(#[from] serde_json::Error),
+}
+
+#[derive(Error, Debug)]
+pub enum WriteError {
+  #[error("Could not write custom blocks to JSON")]
+  ToJSON(#[from] serde_json::Error),
+}
+
+/// Block defined by the user, for example of a mod that was not extracted.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct CustomBlock {
+  pub id: BlockId,
+  pub name: String,
+  pub size: GridSize,
+  /// Mass (kg)
+  pub mass: f64,
+  pub details: CustomBlockDetails,
+}
+
+impl CustomBlock {
+  fn localization_key(&self) -> String { format!("DisplayName_Custom_{}", self.id) }
+
+  /// Gets the identifier of the single component of this block, which has the mass of this block, as the mass of blocks
+  /// is calculated from their components.
+  fn component_id(&self) -> String { format!("Custom.{}", self.id) }
+
+  fn to_block<T>(&self, index: u64, details: T) -> Block<T> {
+    let mut components = LinkedHashMap::new();
+    components.insert(self.component_id(), 1.0);
+    Block { id: self.id.clone(), index, name: self.localization_key(), size: self.size, components, has_physics: true, details }
+  }
+}
+
+/// Kind and statistics of a custom block.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub enum CustomBlockDetails {
+  Container {
+    /// Inventory volume (L)
+    volume: f64,
+  },
+  Thruster {
+    ty: ThrusterType,
+    /// Force (N)
+    force: f64,
+    /// Maximum consumption (MW for ion and atmospheric thrusters, L/s of hydrogen for hydrogen thrusters)
+    consumption: f64,
+  },
+  Battery {
+    /// Power capacity (MWh)
+    capacity: f64,
+    /// Maximum power input (MW)
+    input: f64,
+    /// Maximum power output (MW)
+    output: f64,
+  },
+  Reactor {
+    /// Maximum power generation (MW)
+    power: f64,
+  },
+  Renewable {
+    /// Maximum power generation (MW), in full sunlight or wind
+    power: f64,
+  },
+  HydrogenEngine {
+    /// Maximum power generation (MW)
+    power: f64,
+    /// Maximum hydrogen consumption (L/s)
+    fuel: f64,
+    /// Hydrogen capacity (L)
+    fuel_capacity: f64,
+  },
+  HydrogenTank {
+    /// Hydrogen capacity (L)
+    capacity: f64,
+    /// Operational power consumption (MW)
+    power: f64,
+  },
+  ShipTool {
+    /// Operational power consumption (MW)
+    power: f64,
+  },
+  Utility {
+    /// Maximum broadcast or detection range (m)
+    range: f64,
+    /// Power consumption at maximum range (MW)
+    power: f64,
+  },
+  Accessory {
+    /// Power consumption (MW)
+    power: f64,
+  },
+  Automation {
+    /// Power consumption (MW)
+    power: f64,
+  },
+  LifeSupport {
+    /// Power consumption (MW)
+    power: f64,
+  },
+  Gyroscope {
+    /// Maximum torque (N*m)
+    torque: f64,
+    /// Power consumption (MW)
+    power: f64,
+  },
+  Weapon {
+    /// Inventory volume for ammo (L)
+    volume: f64,
+    /// Rate of fire (rounds/min)
+    rate_of_fire: f64,
+    /// Rounds per magazine
+    magazine_rounds: f64,
+    /// Mass of a magazine (kg)
+    magazine_mass: f64,
+    /// Volume of a magazine (L)
+    magazine_volume: f64,
+  },
+}
+
+/// Custom blocks, stored in a JSON file that is layered over the game data.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct CustomBlocks {
+  pub blocks: Vec<CustomBlock>,
+}
+
+impl CustomBlocks {
+  pub fn from_json<R: io::Read>(reader: R) -> Result<Self, ReadError> {
+    let _span = debug_span!("deserialize_custom_blocks").entered();
+    let custom_blocks = serde_json::from_reader(reader)?;
+    Ok(custom_blocks)
+  }
+
+  pub fn to_json<W: io::Write>(&self, writer: W) -> Result<(), WriteError> {
+    serde_json::to_writer_pretty(writer, self)?;
+    Ok(())
+  }
+}
+
+impl Data {
+  /// Layers `custom_blocks` over this data with `merge`, overriding blocks with the same identifier.
+  pub fn add_custom_blocks(&mut self, custom_blocks: &CustomBlocks) -> MergeReport {
+    let _span = debug_span!("add_custom_blocks", count = custom_blocks.blocks.len()).entered();
+    let hydrogen_energy_density = self.gas_properties.get("Hydrogen").map(|g| g.energy_density).filter(|d| *d > 0.0);
+    let mut blocks = Blocks::default();
+    let mut components = LinkedHashMap::new();
+    let mut localization = LinkedHashMap::new();
+    for (index, custom) in custom_blocks.blocks.iter().enumerate() {
+      let index = index as u64;
+      components.insert(custom.component_id(), Component { name: custom.localization_key(), mass: custom.mass, volume: 0.0 });
+      localization.insert(custom.localization_key(), custom.name.clone());
+      let id = custom.id.clone();
+      match custom.details {
+        CustomBlockDetails::Container { volume } => {
+          blocks.containers.insert(id, custom.to_block(index, Container { capacity: volume, store_any: true }));
+        }
+        CustomBlockDetails::Thruster { ty, force, consumption } => {
+          let thruster = Thruster::custom(ty, force, consumption, hydrogen_energy_density);
+          blocks.thrusters.insert(id, custom.to_block(index, thruster));
+        }
+        CustomBlockDetails::Battery { capacity, input, output } => {
+          blocks.batteries.insert(id, custom.to_block(index, Battery { capacity, input, output }));
+        }
+        CustomBlockDetails::Reactor { power } => {
+          blocks.reactors.insert(id, custom.to_block(index, Reactor { max_power_generation: power, max_fuel_consumption: 0.0 }));
+        }
+        CustomBlockDetails::Renewable { power } => {
+          blocks.renewables.insert(id, custom.to_block(index, Renewable { max_power_generation: power }));
+        }
+        CustomBlockDetails::HydrogenEngine { power, fuel, fuel_capacity } => {
+          blocks.hydrogen_engines.insert(id, custom.to_block(index, HydrogenEngine { fuel_capacity, max_power_generation: power, max_fuel_consumption: fuel }));
+        }
+        CustomBlockDetails::HydrogenTank { capacity, power } => {
+          blocks.hydrogen_tanks.insert(id, custom.to_block(index, HydrogenTank { capacity, operational_power_consumption: power, idle_power_consumption: 0.0 }));
+        }
+        CustomBlockDetails::ShipTool { power } => {
+          blocks.ship_tools.insert(id, custom.to_block(index, ShipTool { operational_power_consumption: power }));
+        }
+        CustomBlockDetails::Utility { range, power } => {
+          blocks.utilities.insert(id, custom.to_block(index, Utility { max_range: range, max_power_consumption: power }));
+        }
+        CustomBlockDetails::Accessory { power } => {
+          blocks.accessories.insert(id, custom.to_block(index, Accessory { power_consumption: power }));
+        }
+        CustomBlockDetails::Automation { power } => {
+          blocks.automations.insert(id, custom.to_block(index, Automation { power_consumption: power }));
+        }
+        CustomBlockDetails::LifeSupport { power } => {
+          blocks.life_supports.insert(id, custom.to_block(index, LifeSupport { power_consumption: power }));
+        }
+        CustomBlockDetails::Gyroscope { torque, power } => {
+          blocks.gyroscopes.insert(id, custom.to_block(index, Gyroscope { torque, power_consumption: power }));
+        }
+        CustomBlockDetails::Weapon { volume, rate_of_fire, magazine_rounds, magazine_mass, magazine_volume } => {
+          let magazine = AmmoMagazine { id: format!("Custom.{}", id), rounds: magazine_rounds, mass: magazine_mass, volume: magazine_volume };
+          let weapon = Weapon { weapon_id: format!("Custom.{}", id), inventory_volume: volume, rate_of_fire, magazine: Some(magazine) };
+          blocks.weapons.insert(id, custom.to_block(index, weapon));
+        }
+      }
+    }
+    self.merge(Data {
+      blocks,
+      components: Components { components },
+      gas_properties: GasProperties { gas_properties: LinkedHashMap::new() },
+      localization: Localization { localization },
+    })
+  }
+}
+
+impl Thruster {
+  /// Creates a thruster of type `ty`, with the planetary influence behaviour of vanilla thrusters of that type.
+  /// `consumption` is in L/s of hydrogen for hydrogen thrusters, which is converted to energy with
+  /// `hydrogen_energy_density` if known.
+  fn custom(ty: ThrusterType, force: f64, consumption: f64, hydrogen_energy_density: Option<f64>) -> Self {
+    let (fuel_gas_id, max_consumption) = match (ty, hydrogen_energy_density) {
+      (ThrusterType::Hydrogen, Some(energy_density)) => (Some("Hydrogen".to_owned()), consumption * energy_density),
+      _ => (None, consumption),
+    };
+    let (min_planetary_influence, effectiveness_at_min_influence, effectiveness_at_max_influence, needs_atmosphere_for_influence) = match ty {
+      ThrusterType::Ion => (0.0, 1.0, 0.2, false),
+      ThrusterType::Atmospheric => (0.3, 0.0, 1.0, true),
+      ThrusterType::Hydrogen => (0.0, 1.0, 1.0, false),
+    };
+    Thruster {
+      ty,
+      fuel_gas_id,
+      force,
+      max_consumption,
+      min_consumption: 0.0,
+      min_planetary_influence,
+      max_planetary_influence: 1.0,
+      effectiveness_at_min_influence,
+      effectiveness_at_max_influence,
+      needs_atmosphere_for_influence,
+    }
+  }
+}
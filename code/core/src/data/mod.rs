@@ -1,18 +1,24 @@
 use std::io;
-use std::path::Path;
+use std::fs::File;
+use std::path::{Path, PathBuf};
 
+use linked_hash_map::LinkedHashMap;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use tracing::{debug_span, info_span};
 
-use blocks::Blocks;
+use blocks::{BlockId, Blocks};
 use components::Components;
 use gas_properties::GasProperties;
 use localization::Localization;
 
 pub mod blocks;
 pub mod components;
+pub mod custom;
 pub mod gas_properties;
 pub mod localization;
+pub mod query;
+pub mod validate;
 pub mod xml;
 
 #[derive(Error, Debug)]
@@ -31,12 +37,43 @@ pub enum ExtractError {
 pub enum ReadError {
   #[error("Could not read data from JSON")]
   FromJSON(#[from] serde_json::Error),
+  #[error("Could not read data from binary")]
+  FromBinary(#[from] bincode::Error),
+}
+
+#[derive(Error, Debug)]
+pub enum LoadError {
+  #[error("Could not open data file '{file}'")]
+  OpenFile { file: PathBuf, source: io::Error },
+  #[error("Could not read data file '{file}'")]
+  ReadFile { file: PathBuf, source: ReadError },
+  #[error("Could not extract data from Space Engineers directory '{dir}'")]
+  Extract { dir: PathBuf, source: ExtractError },
 }
 
 #[derive(Error, Debug)]
 pub enum WriteError {
   #[error("Could not write data to JSON")]
   ToJSON(#[from] serde_json::Error),
+  #[error("Could not write data to binary")]
+  ToBinary(#[from] bincode::Error),
+}
+
+/// Report of merging data with `Data::merge`.
+#[derive(Clone, Default, Debug)]
+pub struct MergeReport {
+  /// Identifiers of blocks that were overridden.
+  pub overridden_blocks: Vec<BlockId>,
+  /// Identifiers of components that were overridden.
+  pub overridden_components: Vec<String>,
+  /// Identifiers of gas properties that were overridden.
+  pub overridden_gas_properties: Vec<String>,
+}
+
+impl MergeReport {
+  pub fn is_empty(&self) -> bool {
+    self.overridden_blocks.is_empty() && self.overridden_components.is_empty() && self.overridden_gas_properties.is_empty()
+  }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -48,16 +85,62 @@ pub struct Data {
 }
 
 impl Data {
+  /// Name of the data file in a directory passed to `from_path`.
+  pub const FILE_NAME: &'static str = "data.json";
+  /// Name of the binary data file in a directory passed to `from_path`, which is preferred over the JSON data file.
+  pub const BINARY_FILE_NAME: &'static str = "data.bin";
+  /// Extension of binary data files, which are read in binary format by `from_path`.
+  pub const BINARY_EXTENSION: &'static str = "bin";
+
   pub fn extract_from_se_dir<P: AsRef<Path>>(se_dir_path: P) -> Result<Self, ExtractError> {
     let se_dir_path = se_dir_path.as_ref();
-    let blocks = Blocks::from_se_dir(se_dir_path)?;
-    let components = Components::from_se_dir(se_dir_path)?;
-    let gas_properties = GasProperties::from_se_dir(se_dir_path)?;
-    let localization = Localization::from_se_dir(se_dir_path)?;
+    let _span = info_span!("extract_data", dir = %se_dir_path.display()).entered();
+    let blocks = debug_span!("extract_blocks").in_scope(|| Blocks::from_se_dir(se_dir_path))?;
+    let components = debug_span!("extract_components").in_scope(|| Components::from_se_dir(se_dir_path))?;
+    let gas_properties = debug_span!("extract_gas_properties").in_scope(|| GasProperties::from_se_dir(se_dir_path))?;
+    let localization = debug_span!("extract_localization").in_scope(|| Localization::from_se_dir(se_dir_path))?;
     Ok(Self { blocks, components, gas_properties, localization })
   }
 
+  /// Loads data from `path`, which is either a data file (binary if it has the `bin` extension, JSON otherwise), a
+  /// directory containing a `data.bin` or `data.json` file, or a Space Engineers directory to extract data from.
+  pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
+    let path = path.as_ref();
+    let _span = info_span!("load_data", path = %path.display()).entered();
+    let file = if path.is_dir() {
+      let file = [Self::BINARY_FILE_NAME, Self::FILE_NAME].iter().map(|name| path.join(name)).find(|file| file.exists());
+      match file {
+        Some(file) => file,
+        None => return Self::extract_from_se_dir(path).map_err(|source| LoadError::Extract { dir: path.to_owned(), source }),
+      }
+    } else {
+      path.to_owned()
+    };
+    let reader = io::BufReader::new(File::open(&file).map_err(|source| LoadError::OpenFile { file: file.clone(), source })?);
+    let data = if Self::is_binary_file(&file) { Self::from_binary(reader) } else { Self::from_json(reader) };
+    data.map_err(|source| LoadError::ReadFile { file, source })
+  }
+
+  /// Returns true if `file` has the extension of binary data files.
+  pub fn is_binary_file<P: AsRef<Path>>(file: P) -> bool {
+    file.as_ref().extension().map_or(false, |e| e == Self::BINARY_EXTENSION)
+  }
+
+  /// Layers `other` (e.g., DLC or mod data) over this data, where `other` overrides blocks, components, gas properties,
+  /// and localizations with the same identifier. Overridden blocks keep their position, and new blocks are added after
+  /// existing blocks. Merging multiple datasets in order is deterministic: later datasets override earlier ones.
+  pub fn merge(&mut self, other: Data) -> MergeReport {
+    let report = MergeReport {
+      overridden_blocks: self.blocks.merge(other.blocks),
+      overridden_components: merge_map(&mut self.components.components, other.components.components),
+      overridden_gas_properties: merge_map(&mut self.gas_properties.gas_properties, other.gas_properties.gas_properties),
+    };
+    merge_map(&mut self.localization.localization, other.localization.localization);
+    report
+  }
+
   pub fn from_json<R: io::Read>(reader: R) -> Result<Self, ReadError> {
+    let _span = debug_span!("deserialize_data", format = "json").entered();
     let data = serde_json::from_reader(reader)?;
     Ok(data)
   }
@@ -67,56 +150,43 @@ impl Data {
     Ok(())
   }
 
+  /// Reads data in the compact binary format, which is much faster to read than JSON.
+  pub fn from_binary<R: io::Read>(reader: R) -> Result<Self, ReadError> {
+    let _span = debug_span!("deserialize_data", format = "binary").entered();
+    let data = bincode::deserialize_from(reader)?;
+    Ok(data)
+  }
+
+  pub fn to_binary<W: io::Write>(&self, writer: W) -> Result<(), WriteError> {
+    bincode::serialize_into(writer, self)?;
+    Ok(())
+  }
+
   pub fn debug_print(&self) {
     dbg!(&self.components);
     dbg!(&self.gas_properties);
 
-    for thruster in self.blocks.thrusters.values() {
-      dbg!(thruster);
-      dbg!(thruster.name(&self.localization));
-      dbg!(thruster.mass(&self.components));
-    }
-
-    for battery in self.blocks.batteries.values() {
-      dbg!(battery);
-      dbg!(battery.name(&self.localization));
-      dbg!(battery.mass(&self.components));
-    }
-
-    for hydrogen_engine in self.blocks.hydrogen_engines.values() {
-      dbg!(hydrogen_engine);
-      dbg!(hydrogen_engine.name(&self.localization));
-      dbg!(hydrogen_engine.mass(&self.components));
-    }
-
-    for reactor in self.blocks.reactors.values() {
-      dbg!(reactor);
-      dbg!(reactor.name(&self.localization));
-      dbg!(reactor.mass(&self.components));
-    }
-
-    for generator in self.blocks.generators.values() {
-      dbg!(generator);
-      dbg!(generator.name(&self.localization));
-      dbg!(generator.mass(&self.components));
-    }
-
-    for hydrogen_tank in self.blocks.hydrogen_tanks.values() {
-      dbg!(hydrogen_tank);
-      dbg!(hydrogen_tank.name(&self.localization));
-      dbg!(hydrogen_tank.mass(&self.components));
+    for block in self.blocks.iter() {
+      dbg!(block);
+      dbg!(block.name(&self.localization));
+      dbg!(block.mass(&self.components));
     }
+  }
+}
 
-    for container in self.blocks.containers.values() {
-      dbg!(container);
-      dbg!(container.name(&self.localization));
-      dbg!(container.mass(&self.components));
-    }
 
-    for cockpit in self.blocks.cockpits.values() {
-      dbg!(cockpit);
-      dbg!(cockpit.name(&self.localization));
-      dbg!(cockpit.mass(&self.components));
+/// Inserts the entries of `other` into `map`, overriding entries with the same key in place. Returns the keys of
+/// overridden entries.
+fn merge_map<V>(map: &mut LinkedHashMap<String, V>, other: LinkedHashMap<String, V>) -> Vec<String> {
+  let mut overridden = Vec::new();
+  for (key, value) in other {
+    match map.get_mut(&key) {
+      Some(existing) => {
+        *existing = value;
+        overridden.push(key);
+      }
+      None => { map.insert(key, value); }
     }
   }
+  overridden
 }
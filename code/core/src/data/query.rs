@@ -0,0 +1,277 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use super::blocks::{BlockId, BlockKind, BlockRef, Blocks, GridSize};
+use super::components::Components;
+use super::localization::Localization;
+
+impl BlockKind {
+  fn name(self) -> &'static str {
+    match self {
+      BlockKind::Battery => "battery",
+      BlockKind::Thruster => "thruster",
+      BlockKind::HydrogenEngine => "hydrogen-engine",
+      BlockKind::Reactor => "reactor",
+      BlockKind::Renewable => "renewable",
+      BlockKind::Generator => "generator",
+      BlockKind::HydrogenTank => "hydrogen-tank",
+      BlockKind::ShipTool => "ship-tool",
+      BlockKind::Utility => "utility",
+      BlockKind::Accessory => "accessory",
+      BlockKind::Automation => "automation",
+      BlockKind::LifeSupport => "life-support",
+      BlockKind::Gyroscope => "gyroscope",
+      BlockKind::Weapon => "weapon",
+      BlockKind::Container => "container",
+      BlockKind::Cockpit => "cockpit",
+    }
+  }
+}
+
+/// Numeric property of blocks that can be filtered and sorted on.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Hash, Debug)]
+pub enum BlockProperty {
+  /// Mass (kg)
+  Mass,
+  /// Maximum power output (MW)
+  PowerOutput,
+  /// Force (N)
+  Thrust,
+}
+
+impl BlockProperty {
+  fn name(self) -> &'static str {
+    match self {
+      BlockProperty::Mass => "mass",
+      BlockProperty::PowerOutput => "power",
+      BlockProperty::Thrust => "thrust",
+    }
+  }
+}
+
+/// Order of query results.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Hash, Debug)]
+pub enum BlockSort {
+  /// Order in which blocks were defined in the game data.
+  Index,
+  /// Localized name.
+  Name,
+  Property(BlockProperty),
+}
+
+impl Default for BlockSort { fn default() -> Self { BlockSort::Index } }
+
+#[derive(Error, Debug)]
+#[error("Unknown {kind} '{value}'")]
+pub struct ParseError {
+  kind: &'static str,
+  value: String,
+}
+
+macro_rules! impl_from_str_display {
+  ($type:ty, $kind:expr, $values:expr) => {
+    impl FromStr for $type {
+      type Err = ParseError;
+      fn from_str(s: &str) -> Result<Self, Self::Err> {
+        $values.find(|v| v.name().eq_ignore_ascii_case(s)).ok_or_else(|| ParseError { kind: $kind, value: s.to_owned() })
+      }
+    }
+
+    impl Display for $type {
+      fn fmt(&self, f: &mut Formatter) -> fmt::Result { f.write_str(self.name()) }
+    }
+  }
+}
+
+impl GridSize {
+  fn name(self) -> &'static str {
+    match self {
+      GridSize::Small => "small",
+      GridSize::Large => "large",
+    }
+  }
+}
+
+impl_from_str_display!(GridSize, "grid size", [GridSize::Small, GridSize::Large].iter().copied());
+impl_from_str_display!(BlockKind, "block kind", BlockKind::iter().copied());
+impl_from_str_display!(BlockProperty, "block property", [BlockProperty::Mass, BlockProperty::PowerOutput, BlockProperty::Thrust].iter().copied());
+
+impl FromStr for BlockSort {
+  type Err = ParseError;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.to_lowercase().as_str() {
+      "index" => Ok(BlockSort::Index),
+      "name" => Ok(BlockSort::Name),
+      _ => s.parse().map(BlockSort::Property).map_err(|_| ParseError { kind: "sort order", value: s.to_owned() }),
+    }
+  }
+}
+
+/// Block matching a query, with the properties it can be filtered and sorted on.
+#[derive(Clone, Debug)]
+pub struct BlockMatch<'a> {
+  pub id: &'a BlockId,
+  pub name: &'a str,
+  pub size: GridSize,
+  pub kind: BlockKind,
+  pub index: u64,
+  pub mass: f64,
+  pub power_output: f64,
+  pub thrust: f64,
+}
+
+impl BlockMatch<'_> {
+  pub fn property(&self, property: BlockProperty) -> f64 {
+    match property {
+      BlockProperty::Mass => self.mass,
+      BlockProperty::PowerOutput => self.power_output,
+      BlockProperty::Thrust => self.thrust,
+    }
+  }
+}
+
+/// Query over blocks, created with `Blocks::query`. Filters are combined: only blocks matching all of them are
+/// returned.
+#[derive(Clone, Debug)]
+pub struct BlockQuery<'a> {
+  blocks: &'a Blocks,
+  localization: &'a Localization,
+  components: &'a Components,
+  size: Option<GridSize>,
+  kinds: Vec<BlockKind>,
+  name_parts: Vec<String>,
+  thresholds: Vec<(BlockProperty, Ordering, f64)>,
+  sort: BlockSort,
+  descending: bool,
+}
+
+impl Blocks {
+  /// Creates a query over all blocks, which matches all blocks until filters are added.
+  pub fn query<'a>(&'a self, localization: &'a Localization, components: &'a Components) -> BlockQuery<'a> {
+    BlockQuery {
+      blocks: self,
+      localization,
+      components,
+      size: None,
+      kinds: Vec::new(),
+      name_parts: Vec::new(),
+      thresholds: Vec::new(),
+      sort: BlockSort::default(),
+      descending: false,
+    }
+  }
+}
+
+impl<'a> BlockQuery<'a> {
+  pub fn size(mut self, size: GridSize) -> Self {
+    self.size = Some(size);
+    self
+  }
+
+  /// Restricts results to blocks of `kind`. Can be called multiple times to allow multiple kinds.
+  pub fn kind(mut self, kind: BlockKind) -> Self {
+    self.kinds.push(kind);
+    self
+  }
+
+  /// Restricts results to blocks whose localized name contains `part`, ignoring case. Can be called multiple times to
+  /// require multiple parts.
+  pub fn name_contains(mut self, part: &str) -> Self {
+    let part = part.trim().to_lowercase();
+    if !part.is_empty() { self.name_parts.push(part); }
+    self
+  }
+
+  /// Restricts results to blocks whose `property` is greater than `value`.
+  pub fn greater_than(mut self, property: BlockProperty, value: f64) -> Self {
+    self.thresholds.push((property, Ordering::Greater, value));
+    self
+  }
+
+  /// Restricts results to blocks whose `property` is less than `value`.
+  pub fn less_than(mut self, property: BlockProperty, value: f64) -> Self {
+    self.thresholds.push((property, Ordering::Less, value));
+    self
+  }
+
+  pub fn sort_by(mut self, sort: BlockSort, descending: bool) -> Self {
+    self.sort = sort;
+    self.descending = descending;
+    self
+  }
+
+  /// Adds filters from search `text`, consisting of whitespace-separated terms: `small` or `large` restrict the grid
+  /// size, a kind such as `thruster` restricts the kind, `<property>` followed by `>` or `<` and a number (e.g.,
+  /// `thrust>100000`) adds a threshold, and all other terms must be contained in the name.
+  pub fn search(mut self, text: &str) -> Self {
+    for term in text.split_whitespace() {
+      self = if let Ok(size) = term.parse() {
+        self.size(size)
+      } else if let Ok(kind) = term.parse() {
+        self.kind(kind)
+      } else if let Some(threshold) = Self::parse_threshold(term) {
+        self.thresholds.push(threshold);
+        self
+      } else {
+        self.name_contains(term)
+      }
+    }
+    self
+  }
+
+  fn parse_threshold(term: &str) -> Option<(BlockProperty, Ordering, f64)> {
+    let (index, ordering) = term.char_indices().find_map(|(i, c)| match c {
+      '>' => Some((i, Ordering::Greater)),
+      '<' => Some((i, Ordering::Less)),
+      _ => None,
+    })?;
+    let property = term[..index].parse().ok()?;
+    let value = term[index + 1..].parse().ok()?;
+    Some((property, ordering, value))
+  }
+
+  /// Runs the query, returning matching blocks in the sort order, ties broken by definition order.
+  pub fn run(&self) -> Vec<BlockMatch<'a>> {
+    let mut matches: Vec<_> = self.blocks.iter().filter_map(|block| self.matches(block)).collect();
+    matches.sort_by(|a, b| {
+      let ordering = match self.sort {
+        BlockSort::Index => a.index.cmp(&b.index),
+        BlockSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        BlockSort::Property(p) => a.property(p).partial_cmp(&b.property(p)).unwrap_or(Ordering::Equal),
+      };
+      let ordering = if self.descending { ordering.reverse() } else { ordering };
+      ordering.then(a.index.cmp(&b.index))
+    });
+    matches
+  }
+
+  fn matches(&self, block: BlockRef<'a>) -> Option<BlockMatch<'a>> {
+    let kind = block.kind();
+    if !self.kinds.is_empty() && !self.kinds.contains(&kind) { return None; }
+    if self.size.map_or(false, |size| size != block.size()) { return None; }
+    let name = block.name(self.localization);
+    if !self.name_parts.is_empty() {
+      let lowercase_name = name.to_lowercase();
+      if !self.name_parts.iter().all(|part| lowercase_name.contains(part)) { return None; }
+    }
+    let details = block.details();
+    let m = BlockMatch {
+      id: block.id(),
+      name,
+      size: block.size(),
+      kind,
+      index: block.index(),
+      mass: block.mass(self.components),
+      power_output: details.power_output(),
+      thrust: details.thrust(),
+    };
+    if self.thresholds.iter().all(|(property, ordering, value)| m.property(*property).partial_cmp(value) == Some(*ordering)) {
+      Some(m)
+    } else {
+      None
+    }
+  }
+}
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use thiserror::Error;
+use tracing::debug_span;
+
+use super::blocks::{BlockId, BlockKind, BlockRef};
+use super::Data;
+
+/// Problem found in game data by `Data::validate`.
+#[derive(Error, Clone, Debug)]
+pub enum Diagnostic {
+  #[error("Block '{id}' has name '{key}' which is missing from the localization")]
+  MissingBlockLocalization { id: BlockId, key: String },
+  #[error("Component '{id}' has name '{key}' which is missing from the localization")]
+  MissingComponentLocalization { id: String, key: String },
+  #[error("Block '{id}' uses component '{component}' which does not exist")]
+  UnknownComponent { id: BlockId, component: String },
+  #[error("Block '{id}' has physics but a mass of {mass} kg")]
+  NonPositiveBlockMass { id: BlockId, mass: f64 },
+  #[error("Component '{id}' has a mass of {mass} kg")]
+  NonPositiveComponentMass { id: String, mass: f64 },
+  #[error("Thruster '{id}' has a force of {force} N")]
+  NonPositiveForce { id: BlockId, force: f64 },
+  #[error("Block identifier '{id}' is used by blocks of kinds {kinds:?}")]
+  DuplicateId { id: BlockId, kinds: Vec<BlockKind> },
+}
+
+impl Data {
+  /// Checks this data for missing localizations, unknown components, zero or negative masses, thrusters without force,
+  /// and identifiers used by blocks of multiple kinds. Returns all problems found, which is empty for valid data.
+  pub fn validate(&self) -> Vec<Diagnostic> {
+    let _span = debug_span!("validate_data").entered();
+    let mut diagnostics = Vec::new();
+    for (id, component) in &self.components.components {
+      if self.localization.get(&component.name).is_none() {
+        diagnostics.push(Diagnostic::MissingComponentLocalization { id: id.clone(), key: component.name.clone() });
+      }
+      if !(component.mass > 0.0) {
+        diagnostics.push(Diagnostic::NonPositiveComponentMass { id: id.clone(), mass: component.mass });
+      }
+    }
+
+    let mut kinds: HashMap<&BlockId, Vec<BlockKind>> = HashMap::new();
+    for block in self.blocks.iter() {
+      let id = block.id();
+      kinds.entry(id).or_default().push(block.kind());
+      let name = block.unlocalized_name();
+      if self.localization.get(name).is_none() {
+        diagnostics.push(Diagnostic::MissingBlockLocalization { id: id.clone(), key: name.to_owned() });
+      }
+      for component in block.components().keys() {
+        if self.components.get(component).is_none() {
+          diagnostics.push(Diagnostic::UnknownComponent { id: id.clone(), component: component.clone() });
+        }
+      }
+      let mass = block.mass(&self.components);
+      if block.has_physics() && !(mass > 0.0) {
+        diagnostics.push(Diagnostic::NonPositiveBlockMass { id: id.clone(), mass });
+      }
+      if let BlockRef::Thruster(thruster) = block {
+        if !(thruster.details.force > 0.0) {
+          diagnostics.push(Diagnostic::NonPositiveForce { id: id.clone(), force: thruster.details.force });
+        }
+      }
+    }
+
+    let mut duplicates: Vec<_> = kinds.into_iter().filter(|(_, kinds)| kinds.len() > 1).collect();
+    duplicates.sort_by(|(a, _), (b, _)| a.cmp(b));
+    diagnostics.extend(duplicates.into_iter().map(|(id, kinds)| Diagnostic::DuplicateId { id: id.clone(), kinds }));
+    diagnostics
+  }
+}
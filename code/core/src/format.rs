@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+use crate::precision::Quantity;
+use crate::units::{Kilograms, Litres, LitresPerSecond, MegaWattHours, MegaWatts, MetersPerSecondSquared, Minutes, Newtons, STANDARD_GRAVITY, Unit};
+
+#[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub enum MassUnit {
+  Kilogram,
+  Tonne,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub enum VolumeUnit {
+  Liter,
+  CubicMeter,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub enum PowerUnit {
+  Kilowatt,
+  Megawatt,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub enum AccelerationUnit {
+  MetersPerSecondSquared,
+  Gravity,
+}
+
+impl Default for MassUnit { fn default() -> Self { MassUnit::Kilogram } }
+
+impl Default for VolumeUnit { fn default() -> Self { VolumeUnit::Liter } }
+
+impl Default for PowerUnit { fn default() -> Self { PowerUnit::Megawatt } }
+
+impl Default for AccelerationUnit { fn default() -> Self { AccelerationUnit::MetersPerSecondSquared } }
+
+/// Units that the user prefers values to be shown in.
+#[derive(Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct UnitPreferences {
+  pub mass: MassUnit,
+  pub volume: VolumeUnit,
+  pub power: PowerUnit,
+  pub acceleration: AccelerationUnit,
+}
+
+impl UnitPreferences {
+  pub fn format_mass(&self, kg: Kilograms) -> String {
+    match self.mass {
+      MassUnit::Kilogram => format!("{} kg", kg.format()),
+      MassUnit::Tonne => format!("{} t", Quantity::Mass.format_scaled(kg.0 / 1000.0, 1000.0)),
+    }
+  }
+
+  pub fn format_volume(&self, liters: Litres) -> String {
+    match self.volume {
+      VolumeUnit::Liter => format!("{} L", liters.format()),
+      VolumeUnit::CubicMeter => format!("{} m³", Quantity::Volume.format_scaled(liters.0 / 1000.0, 1000.0)),
+    }
+  }
+
+  pub fn format_flow(&self, liters_per_second: LitresPerSecond) -> String {
+    match self.volume {
+      VolumeUnit::Liter => format!("{} L/s", liters_per_second.format()),
+      VolumeUnit::CubicMeter => format!("{} m³/s", Quantity::Flow.format_scaled(liters_per_second.0 / 1000.0, 1000.0)),
+    }
+  }
+
+  pub fn format_power(&self, megawatts: MegaWatts) -> String {
+    match self.power {
+      PowerUnit::Kilowatt => format!("{} kW", Quantity::Power.format_scaled(megawatts.0 * 1000.0, 0.001)),
+      PowerUnit::Megawatt => format!("{} MW", megawatts.format()),
+    }
+  }
+
+  pub fn format_energy(&self, megawatt_hours: MegaWattHours) -> String {
+    match self.power {
+      PowerUnit::Kilowatt => format!("{} kWh", Quantity::Energy.format_scaled(megawatt_hours.0 * 1000.0, 0.001)),
+      PowerUnit::Megawatt => format!("{} MWh", megawatt_hours.format()),
+    }
+  }
+
+  pub fn format_acceleration(&self, meters_per_second_squared: MetersPerSecondSquared) -> String {
+    match self.acceleration {
+      AccelerationUnit::MetersPerSecondSquared => format!("{} m/s²", meters_per_second_squared.format()),
+      AccelerationUnit::Gravity => format!("{} g", Quantity::Acceleration.format_scaled(meters_per_second_squared / STANDARD_GRAVITY, STANDARD_GRAVITY.0)),
+    }
+  }
+
+  pub fn format_force(&self, newtons: Newtons) -> String {
+    format!("{} N", newtons.format())
+  }
+
+  pub fn format_duration(&self, minutes: Minutes) -> String {
+    format!("{} min", minutes.format())
+  }
+
+  pub fn format_items(&self, items: f64) -> String {
+    format!("{} #", Quantity::Items.format(items))
+  }
+}
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use tracing::debug_span;
+
+use crate::data::blocks::{BlockId, BlockRef, ThrusterType};
+use crate::data::Data;
+use crate::units::{Kilograms, Litres, LitresPerSecond, MegaWatts, Newtons};
+
+use super::GridCalculator;
+
+/// Contribution of all blocks with the same identifier to a calculated grid. Consumption is maximum (operational)
+/// consumption. Force is summed over all directions.
+#[derive(Clone, Default, Debug)]
+pub struct BlockContribution {
+  pub id: BlockId,
+  pub count: u64,
+  pub mass: Kilograms,
+  /// Inventory volume
+  pub volume: Litres,
+  pub power_generation: MegaWatts,
+  pub power_consumption: MegaWatts,
+  pub hydrogen_generation: LitresPerSecond,
+  pub hydrogen_consumption: LitresPerSecond,
+  pub force: Newtons,
+}
+
+impl BlockContribution {
+  fn new(id: &BlockId) -> Self {
+    Self { id: id.clone(), ..Self::default() }
+  }
+}
+
+impl GridCalculator {
+  /// Calculates the contribution of each entered block, sorted by block identifier.
+  pub fn breakdown(&self, data: &Data) -> Vec<BlockContribution> {
+    let _span = debug_span!("breakdown").entered();
+    let mut contributions: HashMap<&BlockId, BlockContribution> = HashMap::default();
+    let components = &data.components;
+    let blocks = &data.blocks;
+
+    for (id, count) in self.blocks.iter().filter(|(_, count)| **count > 0) {
+      let c = contributions.entry(id).or_insert_with(|| BlockContribution::new(id));
+      c.count = c.count.saturating_add(*count);
+      let count = *count as f64;
+      // Thrusters are directional, and are handled below.
+      let block = match blocks.get(id) {
+        Some(BlockRef::Thruster(_)) | None => continue,
+        Some(block) => block,
+      };
+      c.mass += Kilograms(block.mass(components)) * count;
+      match block {
+        BlockRef::Container(block) => if block.store_any {
+          c.volume += Litres(block.details.capacity) * count * self.container_multiplier;
+        },
+        BlockRef::Cockpit(block) => if block.has_inventory {
+          c.volume += Litres(block.details.capacity) * count * self.container_multiplier;
+        },
+        BlockRef::HydrogenEngine(block) => {
+          c.power_generation += MegaWatts(block.details.max_power_generation) * count;
+          c.hydrogen_consumption += LitresPerSecond(block.details.max_fuel_consumption) * count;
+        }
+        BlockRef::Reactor(block) => {
+          c.power_generation += MegaWatts(block.details.max_power_generation) * count * self.reactor_output_multiplier;
+        }
+        BlockRef::Renewable(block) => {
+          c.power_generation += MegaWatts(block.details.max_power_generation) * count * (self.renewable_output / 100.0);
+        }
+        BlockRef::Battery(block) => {
+          c.power_generation += MegaWatts(block.details.output) * count;
+          c.power_consumption += MegaWatts(block.details.input) * count;
+        }
+        BlockRef::Generator(block) => {
+          c.volume += Litres(block.details.inventory_volume_ice) * count;
+          c.power_consumption += MegaWatts(block.details.operational_power_consumption) * count;
+          c.hydrogen_generation += LitresPerSecond(block.details.hydrogen_generation) * count * (self.generator_hydrogen_share / 100.0);
+        }
+        BlockRef::HydrogenTank(block) => {
+          c.power_consumption += MegaWatts(block.details.operational_power_consumption) * count;
+        }
+        BlockRef::ShipTool(block) => {
+          c.power_consumption += MegaWatts(block.details.operational_power_consumption) * count;
+        }
+        BlockRef::Utility(block) => {
+          c.power_consumption += MegaWatts(block.details.max_power_consumption) * count * (self.utility_range / 100.0);
+        }
+        BlockRef::Accessory(block) => {
+          c.power_consumption += MegaWatts(block.details.power_consumption) * count;
+        }
+        BlockRef::Automation(block) => {
+          c.power_consumption += MegaWatts(block.details.power_consumption) * count;
+        }
+        BlockRef::LifeSupport(block) => {
+          c.power_consumption += MegaWatts(block.details.power_consumption) * count;
+        }
+        BlockRef::Gyroscope(block) => {
+          c.power_consumption += MegaWatts(block.details.power_consumption) * count;
+        }
+        BlockRef::Weapon(block) => {
+          c.volume += Litres(block.details.inventory_volume) * count;
+        }
+        BlockRef::Thruster(_) => {}
+      }
+    }
+    for blocks_in_direction in self.directional_blocks.values() {
+      for (id, count) in blocks_in_direction.iter().filter(|(_, count)| **count > 0) {
+        if let Some(block) = blocks.thrusters.get(id) {
+          let c = contributions.entry(id).or_insert_with(|| BlockContribution::new(id));
+          c.count = c.count.saturating_add(*count);
+          let count = *count as f64;
+          let details = &block.details;
+          c.mass += Kilograms(block.mass(components)) * count;
+          c.force += Newtons(details.force) * details.effectiveness(self.planetary_influence) * count;
+          let consumption = details.actual_max_consumption(&data.gas_properties) * count;
+          match details.ty {
+            ThrusterType::Hydrogen => c.hydrogen_consumption += LitresPerSecond(consumption),
+            _ => c.power_consumption += MegaWatts(consumption),
+          }
+        }
+      }
+    }
+
+    let mut contributions: Vec<_> = contributions.into_iter().map(|(_, c)| c).collect();
+    contributions.sort_by(|a, b| a.id.cmp(&b.id));
+    contributions
+  }
+}
@@ -0,0 +1,162 @@
+use crate::data::blocks::BlockId;
+use crate::units::{Kilograms, Litres, LitresPerSecond, MegaWatts, Meters, MetersPerSecond};
+
+use super::{CustomConsumer, Direction, GridCalculator, PowerConsumer};
+use super::validate::ValidationError;
+
+/// Fluent builder for `GridCalculator`, validating options when building.
+#[derive(Clone, Debug)]
+pub struct GridCalculatorBuilder {
+  calculator: GridCalculator,
+  block_count_overflowed: bool,
+}
+
+impl GridCalculator {
+  pub fn builder() -> GridCalculatorBuilder {
+    GridCalculatorBuilder { calculator: GridCalculator::default(), block_count_overflowed: false }
+  }
+}
+
+impl GridCalculatorBuilder {
+  pub fn gravity(mut self, gravity_multiplier: f64) -> Self {
+    self.calculator.gravity_multiplier = gravity_multiplier;
+    self
+  }
+
+  pub fn container_multiplier(mut self, container_multiplier: f64) -> Self {
+    self.calculator.container_multiplier = container_multiplier;
+    self
+  }
+
+  pub fn planetary_influence(mut self, planetary_influence: f64) -> Self {
+    self.calculator.planetary_influence = planetary_influence;
+    self
+  }
+
+  pub fn additional_mass(mut self, additional_mass: Kilograms) -> Self {
+    self.calculator.additional_mass = additional_mass;
+    self
+  }
+
+  pub fn additional_volume(mut self, additional_volume: Litres) -> Self {
+    self.calculator.additional_volume = additional_volume;
+    self
+  }
+
+  pub fn max_speed(mut self, max_speed: MetersPerSecond) -> Self {
+    self.calculator.max_speed = max_speed;
+    self
+  }
+
+  pub fn descent_altitude(mut self, descent_altitude: Meters) -> Self {
+    self.calculator.descent_altitude = descent_altitude;
+    self
+  }
+
+  pub fn ice_only_fill(mut self, fill: f64) -> Self {
+    self.calculator.ice_only_fill = fill;
+    self
+  }
+
+  pub fn ore_only_fill(mut self, fill: f64) -> Self {
+    self.calculator.ore_only_fill = fill;
+    self
+  }
+
+  /// Sets the fill percentages of containers that accept any item, with ice, ore, and steel plates respectively.
+  pub fn any_fill(mut self, ice: f64, ore: f64, steel_plates: f64) -> Self {
+    self.calculator.any_fill_with_ice = ice;
+    self.calculator.any_fill_with_ore = ore;
+    self.calculator.any_fill_with_steel_plates = steel_plates;
+    self
+  }
+
+  /// Sets the percentage of the inventory volume of weapons that is filled with ammo magazines.
+  pub fn ammo_fill(mut self, fill: f64) -> Self {
+    self.calculator.ammo_fill = fill;
+    self
+  }
+
+  /// Sets the percentage of the ice throughput of O2/H2 generators that produces hydrogen.
+  pub fn generator_hydrogen_share(mut self, share: f64) -> Self {
+    self.calculator.generator_hydrogen_share = share;
+    self
+  }
+
+  /// Sets the percentage of the capacity of batteries that is charged at the start.
+  pub fn battery_charge(mut self, charge: f64) -> Self {
+    self.calculator.battery_charge = charge;
+    self
+  }
+
+  /// Sets the multiplier of the power generation of reactors.
+  pub fn reactor_output_multiplier(mut self, multiplier: f64) -> Self {
+    self.calculator.reactor_output_multiplier = multiplier;
+    self
+  }
+
+  /// Sets the percentage of time that solar panels and wind turbines are at full output.
+  pub fn renewable_output(mut self, output: f64) -> Self {
+    self.calculator.renewable_output = output;
+    self
+  }
+
+  pub fn utility_range(mut self, range: f64) -> Self {
+    self.calculator.utility_range = range;
+    self
+  }
+
+  /// Adds a named power consumer that is not a block of the game data.
+  pub fn custom_power_consumer(mut self, name: impl Into<String>, consumption: MegaWatts) -> Self {
+    self.calculator.custom_power_consumers.push(CustomConsumer { name: name.into(), consumption });
+    self
+  }
+
+  /// Adds a named hydrogen consumer that is not a block of the game data.
+  pub fn custom_hydrogen_consumer(mut self, name: impl Into<String>, consumption: LitresPerSecond) -> Self {
+    self.calculator.custom_hydrogen_consumers.push(CustomConsumer { name: name.into(), consumption });
+    self
+  }
+
+  /// Sets the order in which power consumers are cumulatively added, where omitted consumers are disabled.
+  pub fn power_priority(mut self, priority: Vec<PowerConsumer>) -> Self {
+    self.calculator.power_priority = priority;
+    self
+  }
+
+  pub fn idle_hydrogen_thrusters(mut self, idle_hydrogen_thrusters: bool) -> Self {
+    self.calculator.idle_hydrogen_thrusters = idle_hydrogen_thrusters;
+    self
+  }
+
+  /// Adds `count` blocks with `id`, on top of blocks with `id` that were already added.
+  pub fn block(mut self, id: impl Into<BlockId>, count: u64) -> Self {
+    let total = self.calculator.blocks.entry(id.into()).or_default();
+    self.block_count_overflowed |= Self::add_count(total, count);
+    self
+  }
+
+  /// Adds `count` thrusters with `id` facing `direction`, on top of thrusters with `id` that were already added in
+  /// that direction.
+  pub fn thruster(mut self, direction: Direction, id: impl Into<BlockId>, count: u64) -> Self {
+    let total = self.calculator.direction_mut(direction).entry(id.into()).or_default();
+    self.block_count_overflowed |= Self::add_count(total, count);
+    self
+  }
+
+  /// Adds `count` to `total`, saturating instead of overflowing. Returns whether it overflowed.
+  fn add_count(total: &mut u64, count: u64) -> bool {
+    let (sum, overflowed) = total.overflowing_add(count);
+    *total = if overflowed { u64::MAX } else { sum };
+    overflowed
+  }
+
+  /// Validates the options and builds the calculator.
+  pub fn build(self) -> Result<GridCalculator, ValidationError> {
+    if self.block_count_overflowed {
+      return Err(ValidationError::BlockCountOverflow);
+    }
+    self.calculator.validate()?;
+    Ok(self.calculator)
+  }
+}
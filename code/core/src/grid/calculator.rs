@@ -0,0 +1,642 @@
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, Sub};
+
+use tracing::{debug_span, trace_span};
+
+use crate::data::blocks::{BlockId, ThrusterType};
+use crate::data::Data;
+use crate::units::{Kilograms, Litres, LitresPerSecond, MegaWattHours, MegaWatts, Meters, MetersPerSecond, MetersPerSecondSquared, Minutes, Newtons, Seconds, STANDARD_GRAVITY};
+
+use super::{AccelerationCalculated, Direction, GridCalculated, GridCalculator, HydrogenCategory, HydrogenShare, PowerCategory, PowerConsumer, PowerShare, ThrusterConsumption};
+
+/// Calculates the results of a grid. Implement this to calculate with different rules, such as modded physics
+/// constants, balance patches, or other game versions.
+pub trait Calculator {
+  fn calculate(&self, grid: &GridCalculator, data: &Data) -> GridCalculated;
+}
+
+/// Calculator with the rules of the vanilla game.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct VanillaCalculator;
+
+impl Calculator for VanillaCalculator {
+  fn calculate(&self, grid: &GridCalculator, data: &Data) -> GridCalculated {
+    let _span = debug_span!("calculate").entered();
+    let inventory = Inventory::calculate(&grid.blocks, grid.container_multiplier, grid.ammo_fill, data);
+    let thrust = Thrust::calculate(&grid.directional_blocks, grid.planetary_influence, data);
+    let generation = Generation::calculate(&grid.blocks, data);
+    combine(grid, &inventory, &thrust, &generation)
+  }
+}
+
+/// Calculator with the rules of the vanilla game, which caches intermediate results of inventory, thrust, and
+/// generation, and only recalculates them when their inputs change. Block counts are compared by hash instead of
+/// keeping a copy of them. Call `clear` when calculating with different data.
+#[derive(Default, Debug)]
+pub struct IncrementalCalculator {
+  inventory: RefCell<Option<(InventoryKey, Inventory)>>,
+  thrust: RefCell<Option<((u64, f64), Thrust)>>,
+  generation: RefCell<Option<(u64, Generation)>>,
+}
+
+impl IncrementalCalculator {
+  pub fn new() -> Self { Self::default() }
+
+  /// Clears all cached intermediate results.
+  pub fn clear(&self) {
+    self.inventory.replace(None);
+    self.thrust.replace(None);
+    self.generation.replace(None);
+  }
+
+  /// Gets the cached value from `cache` if its key equals `key`, or calculates, caches, and returns a new value.
+  fn cached<K: PartialEq, V: Clone>(cache: &RefCell<Option<(K, V)>>, key: K, calculate: impl FnOnce() -> V) -> V {
+    let mut cache = cache.borrow_mut();
+    match cache.as_ref() {
+      Some((cached_key, value)) if *cached_key == key => value.clone(),
+      _ => {
+        let value = calculate();
+        *cache = Some((key, value.clone()));
+        value
+      }
+    }
+  }
+
+  /// Hashes the non-zero counts of `blocks` independently of their order.
+  fn hash_counts<'a, K: Hash + 'a>(blocks: impl Iterator<Item=(K, &'a BlockId, u64)>) -> u64 {
+    blocks
+      .filter(|(_, _, count)| *count != 0)
+      .map(|entry| {
+        let mut hasher = DefaultHasher::new();
+        entry.hash(&mut hasher);
+        hasher.finish()
+      })
+      .fold(0, u64::wrapping_add)
+  }
+}
+
+impl Calculator for IncrementalCalculator {
+  fn calculate(&self, grid: &GridCalculator, data: &Data) -> GridCalculated {
+    // Cached intermediate results are not recalculated, so their spans are missing from the trace.
+    let _span = debug_span!("calculate_incremental").entered();
+    let blocks = Self::hash_counts(grid.blocks.iter().map(|(id, count)| ((), id, *count)));
+    let directional_blocks = Self::hash_counts(grid.directional_blocks.iter()
+      .flat_map(|(direction, blocks)| blocks.iter().map(move |(id, count)| (*direction, id, *count))));
+    let inventory = Self::cached(&self.inventory, (blocks, grid.container_multiplier, grid.ammo_fill), || Inventory::calculate(&grid.blocks, grid.container_multiplier, grid.ammo_fill, data));
+    let thrust = Self::cached(&self.thrust, (directional_blocks, grid.planetary_influence), || Thrust::calculate(&grid.directional_blocks, grid.planetary_influence, data));
+    let generation = Self::cached(&self.generation, blocks, || Generation::calculate(&grid.blocks, data));
+    combine(grid, &inventory, &thrust, &generation)
+  }
+}
+
+
+/// Hash of the block counts, container multiplier, and ammo fill that inventory is calculated from.
+type InventoryKey = (u64, f64, f64);
+
+/// Intermediate results of containers, cockpits, and weapons.
+#[derive(Clone, Default, Debug)]
+struct Inventory {
+  mass: Kilograms,
+  volume_any: Litres,
+  /// Mass of the ammo in weapons, which only weighs when filled.
+  ammo_mass: Kilograms,
+}
+
+impl Inventory {
+  fn calculate(blocks: &HashMap<BlockId, u64>, container_multiplier: f64, ammo_fill: f64, data: &Data) -> Self {
+    let _span = trace_span!("inventory").entered();
+    let mut inventory = Self::default();
+    // Containers.
+    for (id, count) in blocks.iter() {
+      if let Some(block) = data.blocks.containers.get(id) {
+        let count = *count as f64;
+        inventory.mass += Kilograms(block.mass(&data.components)) * count;
+        if block.store_any {
+          inventory.volume_any += Litres(block.details.capacity) * count * container_multiplier;
+        }
+      }
+    }
+    // Cockpits.
+    for (id, count) in blocks.iter() {
+      if let Some(block) = data.blocks.cockpits.get(id) {
+        let count = *count as f64;
+        inventory.mass += Kilograms(block.mass(&data.components)) * count;
+        if block.has_inventory {
+          inventory.volume_any += Litres(block.details.capacity) * count * container_multiplier;
+        }
+      }
+    }
+    // Weapons.
+    for (id, count) in blocks.iter() {
+      if let Some(block) = data.blocks.weapons.get(id) {
+        let count = *count as f64;
+        inventory.mass += Kilograms(block.mass(&data.components)) * count;
+        inventory.ammo_mass += Kilograms(block.details.ammo_mass(ammo_fill)) * count;
+      }
+    }
+    inventory
+  }
+}
+
+/// Intermediate results of thrusters.
+#[derive(Clone, Default, Debug)]
+struct Thrust {
+  mass: Kilograms,
+  force: HashMap<Direction, Newtons>,
+  power_consumption: HashMap<Direction, ThrusterConsumption<MegaWatts>>,
+  hydrogen_consumption: HashMap<Direction, ThrusterConsumption<LitresPerSecond>>,
+}
+
+impl Thrust {
+  fn calculate(directional_blocks: &HashMap<Direction, HashMap<BlockId, u64>>, planetary_influence: f64, data: &Data) -> Self {
+    let _span = trace_span!("thrust").entered();
+    let mut thrust = Self::default();
+    for (side, blocks) in directional_blocks.iter() {
+      let mut force = Newtons(0.0);
+      for (id, count) in blocks {
+        if let Some(block) = data.blocks.thrusters.get(id) {
+          let count = *count as f64;
+          let details = &block.details;
+          thrust.mass += Kilograms(block.mass(&data.components)) * count;
+          let effectiveness = details.effectiveness(planetary_influence);
+          force += Newtons(details.force) * effectiveness * count;
+          match details.ty {
+            ThrusterType::Hydrogen => {
+              let c = thrust.hydrogen_consumption.entry(*side).or_default();
+              c.idle += LitresPerSecond(details.actual_min_consumption(&data.gas_properties)) * count;
+              c.max += LitresPerSecond(details.actual_max_consumption(&data.gas_properties)) * count;
+            },
+            _ => {
+              let c = thrust.power_consumption.entry(*side).or_default();
+              c.idle += MegaWatts(details.actual_min_consumption(&data.gas_properties)) * count;
+              c.max += MegaWatts(details.actual_max_consumption(&data.gas_properties)) * count;
+            },
+          }
+        }
+      }
+      thrust.force.insert(*side, force);
+    }
+    thrust
+  }
+
+  /// Gets the consumption of all thrusters when idle.
+  fn idle<R: Copy + Default + Add<Output=R>>(map: &HashMap<Direction, ThrusterConsumption<R>>) -> R {
+    map.values().fold(R::default(), |total, c| total + c.idle)
+  }
+
+  /// Gets the additional consumption, on top of idle consumption, of firing the axis of `side_1` and `side_2` at full
+  /// thrust. Only one side of an axis fires at once, so this takes the side with the highest additional consumption,
+  /// while the thrusters of the other side keep consuming their idle consumption.
+  fn firing<R: Copy + Default + PartialOrd + Sub<Output=R>>(map: &HashMap<Direction, ThrusterConsumption<R>>, side_1: Direction, side_2: Direction) -> R {
+    let c1 = map.get(&side_1).map_or(R::default(), |c| c.max - c.idle);
+    let c2 = map.get(&side_2).map_or(R::default(), |c| c.max - c.idle);
+    if c2 > c1 { c2 } else { c1 }
+  }
+}
+
+/// Intermediate results of power and hydrogen producers and storage.
+#[derive(Clone, Default, Debug)]
+struct Generation {
+  mass: Kilograms,
+  volume_ice_only: Litres,
+  power_generation: MegaWatts,
+  /// Power generation of reactors, which is not included in `power_generation` because it is multiplied by an option.
+  power_generation_reactor: MegaWatts,
+  /// Power generation of solar panels and wind turbines at full output, which is derated by an option.
+  power_generation_renewable: MegaWatts,
+  power_generation_battery: MegaWatts,
+  power_capacity_battery: MegaWattHours,
+  power_consumption_idle: MegaWatts,
+  power_consumption_misc: MegaWatts,
+  power_consumption_generator: MegaWatts,
+  power_consumption_battery: MegaWatts,
+  power_consumption_ship_tool: MegaWatts,
+  /// Power consumption of antennas, beacons, and ore detectors at maximum range, which is scaled by an option.
+  power_consumption_utility: MegaWatts,
+  hydrogen_generation: LitresPerSecond,
+  hydrogen_capacity_engine: Litres,
+  /// Capacity of hydrogen tanks per block, so that tanks in stockpile mode can be separated.
+  hydrogen_capacity_tanks: HashMap<BlockId, Litres>,
+  hydrogen_consumption_engine: LitresPerSecond,
+}
+
+impl Generation {
+  fn calculate(blocks: &HashMap<BlockId, u64>, data: &Data) -> Self {
+    let _span = trace_span!("generation").entered();
+    let mut g = Self::default();
+    // Hydrogen Engines.
+    for (id, count) in blocks.iter() {
+      if let Some(block) = data.blocks.hydrogen_engines.get(id) {
+        let count = *count as f64;
+        let details = &block.details;
+        g.mass += Kilograms(block.mass(&data.components)) * count;
+        g.power_generation += MegaWatts(details.max_power_generation) * count;
+        g.hydrogen_consumption_engine += LitresPerSecond(details.max_fuel_consumption) * count;
+        g.hydrogen_capacity_engine += Litres(details.fuel_capacity) * count;
+      }
+    }
+    // Reactors.
+    for (id, count) in blocks.iter() {
+      if let Some(block) = data.blocks.reactors.get(id) {
+        let count = *count as f64;
+        let details = &block.details;
+        g.mass += Kilograms(block.mass(&data.components)) * count;
+        g.power_generation_reactor += MegaWatts(details.max_power_generation) * count;
+        // TODO: fuel capacity/use
+      }
+    }
+    // Solar Panels and Wind Turbines.
+    for (id, count) in blocks.iter() {
+      if let Some(block) = data.blocks.renewables.get(id) {
+        let count = *count as f64;
+        g.mass += Kilograms(block.mass(&data.components)) * count;
+        g.power_generation_renewable += MegaWatts(block.details.max_power_generation) * count;
+      }
+    }
+    // Batteries.
+    for (id, count) in blocks.iter() {
+      if let Some(block) = data.blocks.batteries.get(id) {
+        let count = *count as f64;
+        let details = &block.details;
+        g.mass += Kilograms(block.mass(&data.components)) * count;
+        g.power_generation_battery += MegaWatts(details.output) * count;
+        g.power_consumption_battery += MegaWatts(details.input) * count;
+        g.power_capacity_battery += MegaWattHours(details.capacity) * count;
+      }
+    }
+    // Hydrogen Generators.
+    for (id, count) in blocks.iter() {
+      if let Some(block) = data.blocks.generators.get(id) {
+        let count = *count as f64;
+        let details = &block.details;
+        // Mass
+        g.mass += Kilograms(block.mass(&data.components)) * count;
+        // Volume
+        g.volume_ice_only += Litres(details.inventory_volume_ice) * count;
+        // Power consumption
+        g.power_consumption_idle += MegaWatts(details.idle_power_consumption) * count;
+        g.power_consumption_generator += MegaWatts(details.operational_power_consumption) * count;
+        g.hydrogen_generation += LitresPerSecond(details.hydrogen_generation) * count;
+        // TODO: ice consumption
+      }
+    }
+    // Hydrogen Tanks.
+    for (id, count) in blocks.iter() {
+      if let Some(block) = data.blocks.hydrogen_tanks.get(id) {
+        let count = *count as f64;
+        let details = &block.details;
+        // Mass
+        g.mass += Kilograms(block.mass(&data.components)) * count;
+        g.power_consumption_idle += MegaWatts(details.idle_power_consumption) * count;
+        g.power_consumption_misc += MegaWatts(details.operational_power_consumption) * count;
+        *g.hydrogen_capacity_tanks.entry(id.clone()).or_default() += Litres(details.capacity) * count;
+      }
+    }
+    // Ship Tools.
+    for (id, count) in blocks.iter() {
+      if let Some(block) = data.blocks.ship_tools.get(id) {
+        let count = *count as f64;
+        g.mass += Kilograms(block.mass(&data.components)) * count;
+        g.power_consumption_ship_tool += MegaWatts(block.details.operational_power_consumption) * count;
+      }
+    }
+    // Antennas, Beacons, and Ore Detectors.
+    for (id, count) in blocks.iter() {
+      if let Some(block) = data.blocks.utilities.get(id) {
+        let count = *count as f64;
+        g.mass += Kilograms(block.mass(&data.components)) * count;
+        g.power_consumption_utility += MegaWatts(block.details.max_power_consumption) * count;
+      }
+    }
+    // Lights and LCD Panels.
+    for (id, count) in blocks.iter() {
+      if let Some(block) = data.blocks.accessories.get(id) {
+        let count = *count as f64;
+        g.mass += Kilograms(block.mass(&data.components)) * count;
+        g.power_consumption_misc += MegaWatts(block.details.power_consumption) * count;
+      }
+    }
+    // Programmable Blocks, Timer Blocks, Event Controllers, and AI Blocks.
+    for (id, count) in blocks.iter() {
+      if let Some(block) = data.blocks.automations.get(id) {
+        let count = *count as f64;
+        g.mass += Kilograms(block.mass(&data.components)) * count;
+        g.power_consumption_misc += MegaWatts(block.details.power_consumption) * count;
+      }
+    }
+    // Medical Rooms, Survival Kits, and Cryo Chambers.
+    for (id, count) in blocks.iter() {
+      if let Some(block) = data.blocks.life_supports.get(id) {
+        let count = *count as f64;
+        g.mass += Kilograms(block.mass(&data.components)) * count;
+        g.power_consumption_misc += MegaWatts(block.details.power_consumption) * count;
+      }
+    }
+    // Gyroscopes.
+    for (id, count) in blocks.iter() {
+      if let Some(block) = data.blocks.gyroscopes.get(id) {
+        let count = *count as f64;
+        g.mass += Kilograms(block.mass(&data.components)) * count;
+        g.power_consumption_misc += MegaWatts(block.details.power_consumption) * count;
+      }
+    }
+
+    // TODO: add jump drive block
+
+    g
+  }
+}
+
+/// Combines intermediate results with the fill, mass, and gravity options of `grid` into the results of `grid`.
+fn combine(grid: &GridCalculator, inventory: &Inventory, thrust: &Thrust, generation: &Generation) -> GridCalculated {
+  let _span = trace_span!("combine").entered();
+  let ice_weight_per_volume = 1.0 / 0.37; // kg/L, TODO: derive from data
+  let ice_items_per_volume = 1.0 / 0.37; // #/L, TODO: derive from data
+  let ore_weight_per_volume = 1.0 / 0.37; // kg/L, TODO: derive from data
+  let ore_items_per_volume = 1.0 / 0.37; // #/L, TODO: derive from data
+  let steel_plate_weight_per_volume = 20.0 / 3.0; // kg/L, TODO: derive from data
+  let steel_plate_items_per_volume = 1.0 / 3.0; // #/L, TODO: derive from data
+  let power_consumption_jump_drive = MegaWatts(0.0);
+
+  let mut c = GridCalculated::default();
+
+  c.volume_mass.total_mass_empty = grid.additional_mass + inventory.mass + thrust.mass + generation.mass;
+  let volume_any = inventory.volume_any + grid.additional_volume;
+  c.volume_mass.total_volume_any = volume_any;
+  c.volume_mass.total_volume_ore = volume_any;
+  c.volume_mass.total_volume_ice = volume_any;
+  c.volume_mass.total_volume_ice_only = generation.volume_ice_only;
+  c.power.generation = generation.power_generation + generation.power_generation_reactor * grid.reactor_output_multiplier
+    + generation.power_generation_renewable * (grid.renewable_output / 100.0) + generation.power_generation_battery;
+  c.power.generation_battery = generation.power_generation_battery;
+  c.power.capacity_battery = generation.power_capacity_battery;
+  c.power.stored_battery = generation.power_capacity_battery * (grid.battery_charge / 100.0);
+  let uncharged_battery = c.power.capacity_battery - c.power.stored_battery;
+  c.power.recharge_duration = if uncharged_battery > MegaWattHours(0.0) { uncharged_battery / generation.power_consumption_battery } else { Minutes(0.0) };
+  c.hydrogen.generation = generation.hydrogen_generation * (grid.generator_hydrogen_share / 100.0);
+  c.hydrogen.capacity_engine = generation.hydrogen_capacity_engine;
+  for (id, capacity) in generation.hydrogen_capacity_tanks.iter() {
+    if grid.stockpile_hydrogen_tanks.contains(id) {
+      c.hydrogen.capacity_tank_stockpile += *capacity;
+    } else {
+      c.hydrogen.capacity_tank += *capacity;
+    }
+  }
+
+  // Calculate filled volumes.
+  let ice_only_volume = c.volume_mass.total_volume_ice_only * (grid.ice_only_fill / 100.0);
+  let ore_only_volume = c.volume_mass.total_volume_ore_only * (grid.ore_only_fill / 100.0);
+  let ice_in_any_volume = c.volume_mass.total_volume_any * (grid.any_fill_with_ice / 100.0);
+  let ore_in_any_volume = c.volume_mass.total_volume_any * (grid.any_fill_with_ore / 100.0);
+  let steel_plates_in_any_volume = c.volume_mass.total_volume_any * (grid.any_fill_with_steel_plates / 100.0);
+
+  // Calculate filled mass.
+  // TODO: container multiplier increases volume but keeps mass the same!
+  let ice_only_mass = Kilograms(ice_only_volume.0 * ice_weight_per_volume);
+  let ore_only_mass = Kilograms(ore_only_volume.0 * ore_weight_per_volume);
+  let any_mass = Kilograms((ice_in_any_volume.0 * ice_weight_per_volume) + (ore_in_any_volume.0 * ore_weight_per_volume) + (steel_plates_in_any_volume.0 * steel_plate_weight_per_volume));
+  c.volume_mass.total_mass_filled = c.volume_mass.total_mass_empty + ice_only_mass + ore_only_mass + any_mass + inventory.ammo_mass;
+
+  // Calculate filled items.
+  c.volume_mass.total_items_ice = (ice_only_volume + ice_in_any_volume).0 * ice_items_per_volume;
+  c.volume_mass.total_items_ore = (ore_only_volume + ore_in_any_volume).0 * ore_items_per_volume;
+  c.volume_mass.total_items_steel_plate = steel_plates_in_any_volume.0 * steel_plate_items_per_volume;
+
+  // Calculate Acceleration
+  let acceleration_span = trace_span!("acceleration").entered();
+  for (side, force) in thrust.force.iter() {
+    let mut a = AccelerationCalculated::default();
+    a.force = *force;
+    a.acceleration_empty_no_gravity = a.force / c.volume_mass.total_mass_empty;
+    a.acceleration_filled_no_gravity = a.force / c.volume_mass.total_mass_filled;
+    a.acceleration_empty_gravity = (a.force - (c.volume_mass.total_mass_empty * STANDARD_GRAVITY * grid.gravity_multiplier)) / c.volume_mass.total_mass_empty;
+    a.acceleration_filled_gravity = (a.force - (c.volume_mass.total_mass_filled * STANDARD_GRAVITY * grid.gravity_multiplier)) / c.volume_mass.total_mass_filled;
+    let (time, distance) = to_max_speed(grid.max_speed, a.acceleration_empty_no_gravity);
+    a.time_to_max_speed_empty = time;
+    a.distance_to_max_speed_empty = distance;
+    let (time, distance) = to_max_speed(grid.max_speed, a.acceleration_filled_no_gravity);
+    a.time_to_max_speed_filled = time;
+    a.distance_to_max_speed_filled = distance;
+    c.acceleration.insert(*side, a);
+  }
+  acceleration_span.exit();
+
+  {
+    let _span = trace_span!("power").entered();
+    c.power.thrusters = thrust.power_consumption.clone();
+    c.power.idle = c.power.resource(generation.power_consumption_idle + Thrust::idle(&thrust.power_consumption));
+    let misc = generation.power_consumption_misc + grid.custom_power_consumption();
+    let utility = generation.power_consumption_utility * (grid.utility_range / 100.0);
+    let mut budget = vec![(PowerCategory::Misc, misc), (PowerCategory::Utility, utility)];
+    let mut consumption = misc + utility;
+    c.power.misc = c.power.resource(consumption);
+    let mut thrusters_idle_added = false;
+    for consumer in grid.power_priority.iter() {
+      let added = match consumer.thruster_axis() {
+        Some((side_1, side_2)) => {
+          // Thrusters of axes that are not firing (yet) still consume their idle consumption.
+          if !thrusters_idle_added {
+            let idle = Thrust::idle(&thrust.power_consumption);
+            budget.push((PowerCategory::IdleThrusters, idle));
+            consumption += idle;
+            thrusters_idle_added = true;
+          }
+          Thrust::firing(&thrust.power_consumption, side_1, side_2)
+        }
+        None => match consumer {
+          PowerConsumer::JumpDrive => power_consumption_jump_drive,
+          PowerConsumer::Generator => generation.power_consumption_generator,
+          PowerConsumer::ShipTool => generation.power_consumption_ship_tool,
+          PowerConsumer::Battery => generation.power_consumption_battery,
+          _ => MegaWatts(0.0),
+        },
+      };
+      budget.push((PowerCategory::Consumer(*consumer), added));
+      consumption += added;
+      let resource = c.power.resource(consumption);
+      c.power.upto.push((*consumer, resource));
+    }
+    c.power.budget = budget.into_iter()
+      .map(|(category, added)| {
+        let share = if consumption > MegaWatts(0.0) { added / consumption * 100.0 } else { 0.0 };
+        PowerShare { category, consumption: added, share }
+      })
+      .collect();
+  }
+
+  {
+    let _span = trace_span!("hydrogen").entered();
+    c.hydrogen.thrusters = thrust.hydrogen_consumption.clone();
+    if !grid.idle_hydrogen_thrusters {
+      for consumption in c.hydrogen.thrusters.values_mut() {
+        consumption.idle = LitresPerSecond(0.0);
+      }
+    }
+    c.hydrogen.idle = c.hydrogen.resource(Thrust::idle(&c.hydrogen.thrusters));
+    let engine = generation.hydrogen_consumption_engine + grid.custom_hydrogen_consumption();
+    let idle = Thrust::idle(&c.hydrogen.thrusters);
+    let up_down = Thrust::firing(&c.hydrogen.thrusters, Direction::Up, Direction::Down);
+    let front_back = Thrust::firing(&c.hydrogen.thrusters, Direction::Front, Direction::Back);
+    let left_right = Thrust::firing(&c.hydrogen.thrusters, Direction::Left, Direction::Right);
+    let mut consumption = engine;
+    c.hydrogen.engine = c.hydrogen.resource(consumption);
+    consumption += idle + up_down;
+    c.hydrogen.upto_up_down_thruster = c.hydrogen.resource(consumption);
+    consumption += front_back;
+    c.hydrogen.upto_front_back_thruster = c.hydrogen.resource(consumption);
+    consumption += left_right;
+    c.hydrogen.upto_left_right_thruster = c.hydrogen.resource(consumption);
+    let share = |part: LitresPerSecond| if consumption > LitresPerSecond(0.0) { part / consumption * 100.0 } else { 0.0 };
+    c.hydrogen.budget.consumption = vec![
+      (HydrogenCategory::Engines, engine),
+      (HydrogenCategory::IdleThrusters, idle),
+      (HydrogenCategory::UpDownThrusters, up_down),
+      (HydrogenCategory::FrontBackThrusters, front_back),
+      (HydrogenCategory::LeftRightThrusters, left_right),
+    ].into_iter().map(|(category, part)| HydrogenShare { category, consumption: part, share: share(part) }).collect();
+    c.hydrogen.budget.generation = c.hydrogen.generation;
+    c.hydrogen.budget.generation_share = share(c.hydrogen.generation);
+    c.hydrogen.budget.net = c.hydrogen.generation - consumption;
+    // Directions without (non-zero counts of) hydrogen thrusters have nothing to burn.
+    for (side, consumption) in c.hydrogen.thrusters.iter().filter(|(_, c)| c.max > LitresPerSecond(0.0)) {
+      let net_consumption = consumption.max - c.hydrogen.generation;
+      let duration = if net_consumption > LitresPerSecond(0.0) {
+        c.hydrogen.capacity_tank / net_consumption
+      } else {
+        Seconds(f64::INFINITY)
+      };
+      c.hydrogen.burn_duration.insert(*side, duration);
+    }
+  }
+
+  {
+    let _span = trace_span!("climb").entered();
+    let weight = c.volume_mass.total_mass_filled * STANDARD_GRAVITY * grid.gravity_multiplier;
+    let force = thrust.force.get(&Direction::Up).copied().unwrap_or_default();
+    let throttle = if force > weight { weight / force } else { 1.0 };
+    c.climb.speed = if force > weight { grid.max_speed } else { MetersPerSecond(0.0) };
+    c.climb.thrust = throttle * 100.0;
+    let power_up = thrust.power_consumption.get(&Direction::Up).copied().unwrap_or_default();
+    c.climb.power = c.power.resource(c.power.misc.consumption + Thrust::idle(&thrust.power_consumption)
+      + (power_up.max - power_up.idle) * throttle);
+    let hydrogen_up = c.hydrogen.thrusters.get(&Direction::Up).copied().unwrap_or_default();
+    c.climb.hydrogen = c.hydrogen.resource(c.hydrogen.engine.consumption + Thrust::idle(&c.hydrogen.thrusters)
+      + (hydrogen_up.max - hydrogen_up.idle) * throttle);
+    // Stopping from speed v within altitude h at braking deceleration a requires v <= sqrt(2ah).
+    let safe_descent_speed = |braking: MetersPerSecondSquared| if braking > MetersPerSecondSquared(0.0) {
+      MetersPerSecond((2.0 * braking.0 * grid.descent_altitude.0).sqrt()).min(grid.max_speed)
+    } else {
+      MetersPerSecond(0.0)
+    };
+    if let Some(up) = c.acceleration.get(&Direction::Up) {
+      c.climb.safe_descent_speed_empty = safe_descent_speed(up.acceleration_empty_gravity);
+      c.climb.safe_descent_speed_filled = safe_descent_speed(up.acceleration_filled_gravity);
+    }
+    // Weight counters up thrusters when climbing, but adds to down thrusters when descending or braking a climb.
+    let up_force = thrust.force.get(&Direction::Up).copied().unwrap_or_default();
+    let down_force = thrust.force.get(&Direction::Down).copied().unwrap_or_default();
+    let weight_empty = c.volume_mass.total_mass_empty * STANDARD_GRAVITY * grid.gravity_multiplier;
+    c.climb.climb_acceleration_empty = (up_force - weight_empty) / c.volume_mass.total_mass_empty;
+    c.climb.climb_acceleration_filled = (up_force - weight) / c.volume_mass.total_mass_filled;
+    c.climb.descent_braking_acceleration_empty = (down_force + weight_empty) / c.volume_mass.total_mass_empty;
+    c.climb.descent_braking_acceleration_filled = (down_force + weight) / c.volume_mass.total_mass_filled;
+  }
+
+  c
+}
+
+/// Gets the time and distance to accelerate from standstill to `max_speed` at a constant `acceleration`, which are
+/// infinite if there is no acceleration.
+fn to_max_speed(max_speed: MetersPerSecond, acceleration: MetersPerSecondSquared) -> (Seconds, Meters) {
+  if !(acceleration > MetersPerSecondSquared(0.0)) {
+    return (Seconds(f64::INFINITY), Meters(f64::INFINITY));
+  }
+  let time = max_speed / acceleration;
+  (time, Meters(max_speed.0 * time.0 / 2.0))
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::data::Data;
+  use crate::grid::{Direction, GridCalculated, GridCalculator};
+
+  use super::{Calculator, IncrementalCalculator, VanillaCalculator};
+
+  type Edit = fn(&mut GridCalculator);
+
+  fn data() -> Data {
+    Data::from_path(concat!(env!("CARGO_MANIFEST_DIR"), "/../../data/data.json")).unwrap()
+  }
+
+  /// Asserts that all result values of `incremental` equal those of `vanilla`, up to rounding from summing in a
+  /// different order.
+  fn assert_same(step: &str, incremental: &GridCalculated, vanilla: &GridCalculated) {
+    let (incremental, vanilla) = (incremental.values(), vanilla.values());
+    assert_eq!(incremental.len(), vanilla.len(), "{}: different number of values", step);
+    for (i, v) in incremental.iter().zip(vanilla.iter()) {
+      assert_eq!(i.label, v.label, "{}: different values", step);
+      let same = i.value == v.value || (i.value.is_nan() && v.value.is_nan())
+        || (i.value - v.value).abs() <= 1e-9 * i.value.abs().max(v.value.abs());
+      assert!(same, "{}: '{}' is {} incrementally, but {} in vanilla", step, i.label, i.value, v.value);
+    }
+  }
+
+  #[test]
+  fn burn_duration_only_for_directions_with_hydrogen_thrusters() {
+    let data = data();
+    let mut grid = GridCalculator::default();
+    grid.blocks.insert("OxygenTank.LargeHydrogenTank".to_owned(), 1);
+    grid.direction_mut(Direction::Up).insert("Thrust.LargeBlockLargeHydrogenThrust".to_owned(), 2);
+    grid.direction_mut(Direction::Down).insert("Thrust.LargeBlockLargeHydrogenThrust".to_owned(), 0);
+    grid.direction_mut(Direction::Front).insert("Thrust.LargeBlockSmallThrust".to_owned(), 4);
+    let calculated = VanillaCalculator.calculate(&grid, &data);
+    let burn_duration = &calculated.hydrogen.burn_duration;
+    assert_eq!(burn_duration.len(), 1, "burn durations: {:?}", burn_duration);
+    let up = burn_duration[&Direction::Up];
+    assert!(up.0.is_finite() && up.0 > 0.0, "burn duration up: {:?}", up);
+  }
+
+  #[test]
+  fn incremental_equals_vanilla_after_each_edit() {
+    let data = data();
+    let incremental = IncrementalCalculator::new();
+    let mut grid = GridCalculator::default();
+    let steps: Vec<(&str, Edit)> = vec![
+      ("empty", |_| {}),
+      ("add blocks", |g| {
+        g.blocks.insert("CargoContainer.LargeBlockLockers".to_owned(), 4);
+        g.blocks.insert("Cockpit.LargeBlockCockpit".to_owned(), 1);
+        g.blocks.insert("Reactor.LargeBlockSmallGenerator".to_owned(), 2);
+        g.blocks.insert("BatteryBlock.LargeBlockBatteryBlock".to_owned(), 3);
+        g.blocks.insert("HydrogenEngine.LargeHydrogenEngine".to_owned(), 1);
+        g.blocks.insert("OxygenGenerator.".to_owned(), 2);
+        g.blocks.insert("OxygenTank.LargeHydrogenTank".to_owned(), 2);
+      }),
+      ("add thrusters", |g| {
+        g.direction_mut(Direction::Up).insert("Thrust.LargeBlockLargeHydrogenThrust".to_owned(), 4);
+        g.direction_mut(Direction::Down).insert("Thrust.LargeBlockSmallThrust".to_owned(), 6);
+        g.direction_mut(Direction::Front).insert("Thrust.LargeBlockLargeAtmosphericThrust".to_owned(), 2);
+      }),
+      ("block count to 0", |g| { g.blocks.insert("CargoContainer.LargeBlockLockers".to_owned(), 0); }),
+      ("reactor count to 0", |g| { g.blocks.insert("Reactor.LargeBlockSmallGenerator".to_owned(), 0); }),
+      ("block count from 0", |g| { g.blocks.insert("CargoContainer.LargeBlockLockers".to_owned(), 2); }),
+      ("move thrusters to another direction", |g| {
+        g.direction_mut(Direction::Up).insert("Thrust.LargeBlockLargeHydrogenThrust".to_owned(), 0);
+        g.direction_mut(Direction::Back).insert("Thrust.LargeBlockLargeHydrogenThrust".to_owned(), 4);
+      }),
+      ("thruster count to 0", |g| { g.direction_mut(Direction::Down).insert("Thrust.LargeBlockSmallThrust".to_owned(), 0); }),
+      ("container multiplier", |g| g.container_multiplier = 3.0),
+      ("planetary influence", |g| g.planetary_influence = 0.25),
+      ("container multiplier back", |g| g.container_multiplier = 1.0),
+      ("planetary influence back", |g| g.planetary_influence = 1.0),
+      ("unrelated option", |g| g.battery_charge = 50.0),
+    ];
+    for (step, edit) in steps {
+      edit(&mut grid);
+      assert_same(step, &incremental.calculate(&grid, &data), &VanillaCalculator.calculate(&grid, &data));
+    }
+  }
+}
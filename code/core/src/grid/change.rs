@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use crate::data::blocks::{BlockId, BlockRef, ThrusterType};
+use crate::data::Data;
+
+use super::{Direction, GridCalculator};
+
+/// Result sections of a calculated grid that are affected by changes to its calculator, so that frontends can update
+/// only those sections.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Debug)]
+pub struct ChangeSet {
+  pub volume: bool,
+  pub mass: bool,
+  pub items: bool,
+  pub acceleration: bool,
+  pub power: bool,
+  pub hydrogen: bool,
+}
+
+impl ChangeSet {
+  pub fn all() -> Self {
+    Self { volume: true, mass: true, items: true, acceleration: true, power: true, hydrogen: true }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    *self == Self::default()
+  }
+
+  fn union(&mut self, other: ChangeSet) {
+    self.volume |= other.volume;
+    self.mass |= other.mass;
+    self.items |= other.items;
+    self.acceleration |= other.acceleration;
+    self.power |= other.power;
+    self.hydrogen |= other.hydrogen;
+  }
+
+  /// Changes caused by filling containers: mass, items, and thereby acceleration.
+  fn fill() -> Self {
+    Self { mass: true, items: true, acceleration: true, ..Self::default() }
+  }
+
+  /// Changes caused by changing the count of block with `id`.
+  fn block(id: &BlockId, data: &Data) -> Self {
+    let mut changes = Self { mass: true, acceleration: true, ..Self::default() };
+    match data.blocks.get(id) {
+      Some(BlockRef::Container(_)) | Some(BlockRef::Cockpit(_)) => {
+        changes.volume = true;
+        changes.items = true;
+      }
+      Some(BlockRef::HydrogenEngine(_)) => {
+        changes.power = true;
+        changes.hydrogen = true;
+      }
+      Some(BlockRef::Generator(_)) => {
+        changes.volume = true;
+        changes.items = true;
+        changes.power = true;
+        changes.hydrogen = true;
+      }
+      Some(BlockRef::ShipTool(_)) | Some(BlockRef::Utility(_)) | Some(BlockRef::Accessory(_)) | Some(BlockRef::Automation(_))
+      | Some(BlockRef::LifeSupport(_)) | Some(BlockRef::Gyroscope(_)) => {
+        changes.power = true;
+      }
+      // Weapons only add mass, with their ammo when filled.
+      Some(BlockRef::Weapon(_)) => {}
+      Some(BlockRef::HydrogenTank(_)) => {
+        changes.power = true;
+        changes.hydrogen = true;
+      }
+      Some(BlockRef::Reactor(_)) | Some(BlockRef::Renewable(_)) | Some(BlockRef::Battery(_)) => changes.power = true,
+      Some(BlockRef::Thruster(block)) => match block.details.ty {
+        ThrusterType::Hydrogen => changes.hydrogen = true,
+        _ => changes.power = true,
+      },
+      None => return Self::default(),
+    }
+    changes
+  }
+}
+
+impl GridCalculator {
+  /// Determines which result sections are affected by changing `previous` into this calculator.
+  pub fn changes_from(&self, previous: &GridCalculator, data: &Data) -> ChangeSet {
+    let mut changes = ChangeSet::default();
+    if self.gravity_multiplier != previous.gravity_multiplier || self.planetary_influence != previous.planetary_influence
+      || self.max_speed != previous.max_speed || self.descent_altitude != previous.descent_altitude {
+      changes.acceleration = true;
+    }
+    if self.idle_hydrogen_thrusters != previous.idle_hydrogen_thrusters || self.generator_hydrogen_share != previous.generator_hydrogen_share
+      || self.stockpile_hydrogen_tanks != previous.stockpile_hydrogen_tanks || self.custom_hydrogen_consumers != previous.custom_hydrogen_consumers {
+      changes.hydrogen = true;
+    }
+    if self.battery_charge != previous.battery_charge || self.reactor_output_multiplier != previous.reactor_output_multiplier
+      || self.renewable_output != previous.renewable_output || self.utility_range != previous.utility_range
+      || self.power_priority != previous.power_priority || self.custom_power_consumers != previous.custom_power_consumers {
+      changes.power = true;
+    }
+    if self.container_multiplier != previous.container_multiplier {
+      changes.union(ChangeSet::fill());
+      changes.volume = true;
+    }
+    if self.additional_mass != previous.additional_mass || self.ammo_fill != previous.ammo_fill {
+      changes.mass = true;
+      changes.acceleration = true;
+    }
+    if self.additional_volume != previous.additional_volume {
+      changes.union(ChangeSet::fill());
+      changes.volume = true;
+    }
+    if self.ice_only_fill != previous.ice_only_fill || self.ore_only_fill != previous.ore_only_fill
+      || self.any_fill_with_ice != previous.any_fill_with_ice || self.any_fill_with_ore != previous.any_fill_with_ore
+      || self.any_fill_with_steel_plates != previous.any_fill_with_steel_plates {
+      changes.union(ChangeSet::fill());
+    }
+    Self::block_changes(&self.blocks, &previous.blocks, data, &mut changes);
+    for direction in Direction::iter() {
+      let empty = HashMap::default();
+      let blocks = self.directional_blocks.get(direction).unwrap_or(&empty);
+      let previous_blocks = previous.directional_blocks.get(direction).unwrap_or(&empty);
+      Self::block_changes(blocks, previous_blocks, data, &mut changes);
+    }
+    changes
+  }
+
+  fn block_changes(blocks: &HashMap<BlockId, u64>, previous: &HashMap<BlockId, u64>, data: &Data, changes: &mut ChangeSet) {
+    let count = |map: &HashMap<BlockId, u64>, id| map.get(id).copied().unwrap_or(0);
+    for id in blocks.keys().chain(previous.keys()) {
+      if count(blocks, id) != count(previous, id) {
+        changes.union(ChangeSet::block(id, data));
+      }
+    }
+  }
+}
@@ -0,0 +1,444 @@
+use std::collections::HashMap;
+
+use crate::data::blocks::BlockId;
+use crate::units::{Kilograms, Litres, LitresPerSecond, MegaWatts, Meters, MetersPerSecond};
+
+use super::{CustomConsumer, Direction, GridCalculator, PowerConsumer};
+
+/// Numeric option of a calculator.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum GridOption {
+  GravityMultiplier,
+  ContainerMultiplier,
+  PlanetaryInfluence,
+  AdditionalMass,
+  AdditionalVolume,
+  MaxSpeed,
+  DescentAltitude,
+  IceOnlyFill,
+  OreOnlyFill,
+  AnyFillWithIce,
+  AnyFillWithOre,
+  AnyFillWithSteelPlates,
+  AmmoFill,
+  GeneratorHydrogenShare,
+  BatteryCharge,
+  ReactorOutputMultiplier,
+  RenewableOutput,
+  UtilityRange,
+}
+
+impl GridOption {
+  pub fn iter() -> impl Iterator<Item=&'static GridOption> {
+    use self::GridOption::*;
+    static OPTIONS: [GridOption; 18] = [GravityMultiplier, ContainerMultiplier, PlanetaryInfluence, AdditionalMass, AdditionalVolume, MaxSpeed, DescentAltitude, IceOnlyFill, OreOnlyFill, AnyFillWithIce, AnyFillWithOre, AnyFillWithSteelPlates, AmmoFill, GeneratorHydrogenShare, BatteryCharge, ReactorOutputMultiplier, RenewableOutput, UtilityRange];
+    OPTIONS.iter()
+  }
+
+  pub fn get(self, calculator: &GridCalculator) -> f64 {
+    match self {
+      GridOption::GravityMultiplier => calculator.gravity_multiplier,
+      GridOption::ContainerMultiplier => calculator.container_multiplier,
+      GridOption::PlanetaryInfluence => calculator.planetary_influence,
+      GridOption::AdditionalMass => calculator.additional_mass.0,
+      GridOption::AdditionalVolume => calculator.additional_volume.0,
+      GridOption::MaxSpeed => calculator.max_speed.0,
+      GridOption::DescentAltitude => calculator.descent_altitude.0,
+      GridOption::IceOnlyFill => calculator.ice_only_fill,
+      GridOption::OreOnlyFill => calculator.ore_only_fill,
+      GridOption::AnyFillWithIce => calculator.any_fill_with_ice,
+      GridOption::AnyFillWithOre => calculator.any_fill_with_ore,
+      GridOption::AnyFillWithSteelPlates => calculator.any_fill_with_steel_plates,
+      GridOption::AmmoFill => calculator.ammo_fill,
+      GridOption::GeneratorHydrogenShare => calculator.generator_hydrogen_share,
+      GridOption::BatteryCharge => calculator.battery_charge,
+      GridOption::ReactorOutputMultiplier => calculator.reactor_output_multiplier,
+      GridOption::RenewableOutput => calculator.renewable_output,
+      GridOption::UtilityRange => calculator.utility_range,
+    }
+  }
+
+  pub fn set(self, calculator: &mut GridCalculator, value: f64) {
+    match self {
+      GridOption::GravityMultiplier => calculator.gravity_multiplier = value,
+      GridOption::ContainerMultiplier => calculator.container_multiplier = value,
+      GridOption::PlanetaryInfluence => calculator.planetary_influence = value,
+      GridOption::AdditionalMass => calculator.additional_mass = Kilograms(value),
+      GridOption::AdditionalVolume => calculator.additional_volume = Litres(value),
+      GridOption::MaxSpeed => calculator.max_speed = MetersPerSecond(value),
+      GridOption::DescentAltitude => calculator.descent_altitude = Meters(value),
+      GridOption::IceOnlyFill => calculator.ice_only_fill = value,
+      GridOption::OreOnlyFill => calculator.ore_only_fill = value,
+      GridOption::AnyFillWithIce => calculator.any_fill_with_ice = value,
+      GridOption::AnyFillWithOre => calculator.any_fill_with_ore = value,
+      GridOption::AnyFillWithSteelPlates => calculator.any_fill_with_steel_plates = value,
+      GridOption::AmmoFill => calculator.ammo_fill = value,
+      GridOption::GeneratorHydrogenShare => calculator.generator_hydrogen_share = value,
+      GridOption::BatteryCharge => calculator.battery_charge = value,
+      GridOption::ReactorOutputMultiplier => calculator.reactor_output_multiplier = value,
+      GridOption::RenewableOutput => calculator.renewable_output = value,
+      GridOption::UtilityRange => calculator.utility_range = value,
+    }
+  }
+}
+
+/// Boolean option of a calculator.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum GridFlag {
+  IdleHydrogenThrusters,
+}
+
+impl GridFlag {
+  pub fn iter() -> impl Iterator<Item=&'static GridFlag> {
+    static FLAGS: [GridFlag; 1] = [GridFlag::IdleHydrogenThrusters];
+    FLAGS.iter()
+  }
+
+  pub fn get(self, calculator: &GridCalculator) -> bool {
+    match self {
+      GridFlag::IdleHydrogenThrusters => calculator.idle_hydrogen_thrusters,
+    }
+  }
+
+  pub fn set(self, calculator: &mut GridCalculator, value: bool) {
+    match self {
+      GridFlag::IdleHydrogenThrusters => calculator.idle_hydrogen_thrusters = value,
+    }
+  }
+}
+
+/// Mutation of a calculator that can be applied and reverted. Commands store the previous value of what they change,
+/// so they must be created against the calculator they will be applied to.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Command {
+  SetOption { option: GridOption, value: f64, previous: f64 },
+  SetFlag { flag: GridFlag, value: bool, previous: bool },
+  /// Sets whether hydrogen tank `id` is in stockpile mode.
+  SetStockpile { id: BlockId, value: bool, previous: bool },
+  SetPowerPriority { value: Vec<PowerConsumer>, previous: Vec<PowerConsumer> },
+  SetCustomPowerConsumers { value: Vec<CustomConsumer<MegaWatts>>, previous: Vec<CustomConsumer<MegaWatts>> },
+  SetCustomHydrogenConsumers { value: Vec<CustomConsumer<LitresPerSecond>>, previous: Vec<CustomConsumer<LitresPerSecond>> },
+  /// Sets the count of block `id`, facing `direction` for thrusters.
+  SetBlockCount { id: BlockId, direction: Option<Direction>, count: u64, previous: u64 },
+  /// Commands applied in order, and reverted in reverse order.
+  Batch(Vec<Command>),
+}
+
+impl Command {
+  pub fn set_option(calculator: &GridCalculator, option: GridOption, value: f64) -> Self {
+    Command::SetOption { option, value, previous: option.get(calculator) }
+  }
+
+  pub fn set_flag(calculator: &GridCalculator, flag: GridFlag, value: bool) -> Self {
+    Command::SetFlag { flag, value, previous: flag.get(calculator) }
+  }
+
+  pub fn set_stockpile(calculator: &GridCalculator, id: BlockId, value: bool) -> Self {
+    let previous = calculator.stockpile_hydrogen_tanks.contains(&id);
+    Command::SetStockpile { id, value, previous }
+  }
+
+  pub fn set_power_priority(calculator: &GridCalculator, value: Vec<PowerConsumer>) -> Self {
+    Command::SetPowerPriority { value, previous: calculator.power_priority.clone() }
+  }
+
+  pub fn set_custom_power_consumers(calculator: &GridCalculator, value: Vec<CustomConsumer<MegaWatts>>) -> Self {
+    Command::SetCustomPowerConsumers { value, previous: calculator.custom_power_consumers.clone() }
+  }
+
+  pub fn set_custom_hydrogen_consumers(calculator: &GridCalculator, value: Vec<CustomConsumer<LitresPerSecond>>) -> Self {
+    Command::SetCustomHydrogenConsumers { value, previous: calculator.custom_hydrogen_consumers.clone() }
+  }
+
+  pub fn set_block_count(calculator: &GridCalculator, id: BlockId, direction: Option<Direction>, count: u64) -> Self {
+    let previous = match direction {
+      Some(direction) => calculator.direction_count(direction, &id),
+      None => calculator.blocks.get(&id).copied().unwrap_or(0),
+    };
+    Command::SetBlockCount { id, direction, count, previous }
+  }
+
+  /// Creates a command that changes `previous` into `current`, consisting of the options, flags, stockpile modes, power
+  /// priority, custom consumers, and block counts that differ.
+  /// This is used to record mutations made directly to a calculator, for example by data bindings.
+  pub fn between(previous: &GridCalculator, current: &GridCalculator) -> Self {
+    let mut commands = Vec::new();
+    for option in GridOption::iter() {
+      let (old, new) = (option.get(previous), option.get(current));
+      if old != new {
+        commands.push(Command::SetOption { option: *option, value: new, previous: old });
+      }
+    }
+    for flag in GridFlag::iter() {
+      let (old, new) = (flag.get(previous), flag.get(current));
+      if old != new {
+        commands.push(Command::SetFlag { flag: *flag, value: new, previous: old });
+      }
+    }
+    let mut stockpile_ids: Vec<_> = previous.stockpile_hydrogen_tanks.symmetric_difference(&current.stockpile_hydrogen_tanks).collect();
+    stockpile_ids.sort();
+    for id in stockpile_ids {
+      let value = current.stockpile_hydrogen_tanks.contains(id);
+      commands.push(Command::SetStockpile { id: id.clone(), value, previous: !value });
+    }
+    if previous.power_priority != current.power_priority {
+      commands.push(Command::SetPowerPriority { value: current.power_priority.clone(), previous: previous.power_priority.clone() });
+    }
+    if previous.custom_power_consumers != current.custom_power_consumers {
+      commands.push(Command::SetCustomPowerConsumers { value: current.custom_power_consumers.clone(), previous: previous.custom_power_consumers.clone() });
+    }
+    if previous.custom_hydrogen_consumers != current.custom_hydrogen_consumers {
+      commands.push(Command::SetCustomHydrogenConsumers { value: current.custom_hydrogen_consumers.clone(), previous: previous.custom_hydrogen_consumers.clone() });
+    }
+    let mut add_counts = |direction: Option<Direction>| {
+      let empty = HashMap::new();
+      let old_counts = Self::counts(previous, direction).unwrap_or(&empty);
+      let new_counts = Self::counts(current, direction).unwrap_or(&empty);
+      let mut ids: Vec<_> = old_counts.keys().chain(new_counts.keys()).collect();
+      ids.sort();
+      ids.dedup();
+      for id in ids {
+        let (old, new) = (old_counts.get(id).copied().unwrap_or(0), new_counts.get(id).copied().unwrap_or(0));
+        if old != new {
+          commands.push(Command::SetBlockCount { id: id.clone(), direction, count: new, previous: old });
+        }
+      }
+    };
+    add_counts(None);
+    for direction in Direction::iter() {
+      add_counts(Some(*direction));
+    }
+    Command::Batch(commands)
+  }
+
+  /// Returns true if this command does not change anything.
+  pub fn is_noop(&self) -> bool {
+    match self {
+      Command::SetOption { value, previous, .. } => value == previous,
+      Command::SetFlag { value, previous, .. } => value == previous,
+      Command::SetStockpile { value, previous, .. } => value == previous,
+      Command::SetPowerPriority { value, previous } => value == previous,
+      Command::SetCustomPowerConsumers { value, previous } => value == previous,
+      Command::SetCustomHydrogenConsumers { value, previous } => value == previous,
+      Command::SetBlockCount { count, previous, .. } => count == previous,
+      Command::Batch(commands) => commands.iter().all(|c| c.is_noop()),
+    }
+  }
+
+  pub fn apply(&self, calculator: &mut GridCalculator) {
+    match self {
+      Command::SetOption { option, value, .. } => option.set(calculator, *value),
+      Command::SetFlag { flag, value, .. } => flag.set(calculator, *value),
+      Command::SetStockpile { id, value, .. } => Self::set_stockpile_mode(calculator, id, *value),
+      Command::SetPowerPriority { value, .. } => calculator.power_priority = value.clone(),
+      Command::SetCustomPowerConsumers { value, .. } => calculator.custom_power_consumers = value.clone(),
+      Command::SetCustomHydrogenConsumers { value, .. } => calculator.custom_hydrogen_consumers = value.clone(),
+      Command::SetBlockCount { id, direction, count, .. } => Self::set_count(calculator, id, *direction, *count),
+      Command::Batch(commands) => for command in commands {
+        command.apply(calculator);
+      },
+    }
+  }
+
+  pub fn revert(&self, calculator: &mut GridCalculator) {
+    match self {
+      Command::SetOption { option, previous, .. } => option.set(calculator, *previous),
+      Command::SetFlag { flag, previous, .. } => flag.set(calculator, *previous),
+      Command::SetStockpile { id, previous, .. } => Self::set_stockpile_mode(calculator, id, *previous),
+      Command::SetPowerPriority { previous, .. } => calculator.power_priority = previous.clone(),
+      Command::SetCustomPowerConsumers { previous, .. } => calculator.custom_power_consumers = previous.clone(),
+      Command::SetCustomHydrogenConsumers { previous, .. } => calculator.custom_hydrogen_consumers = previous.clone(),
+      Command::SetBlockCount { id, direction, previous, .. } => Self::set_count(calculator, id, *direction, *previous),
+      Command::Batch(commands) => for command in commands.iter().rev() {
+        command.revert(calculator);
+      },
+    }
+  }
+
+  fn counts(calculator: &GridCalculator, direction: Option<Direction>) -> Option<&HashMap<BlockId, u64>> {
+    match direction {
+      Some(direction) => calculator.directional_blocks.get(&direction),
+      None => Some(&calculator.blocks),
+    }
+  }
+
+  fn set_stockpile_mode(calculator: &mut GridCalculator, id: &BlockId, stockpile: bool) {
+    if stockpile {
+      calculator.stockpile_hydrogen_tanks.insert(id.clone());
+    } else {
+      calculator.stockpile_hydrogen_tanks.remove(id);
+    }
+  }
+
+  /// Sets a count, removing the block instead if the count is 0, as missing blocks have a count of 0.
+  fn set_count(calculator: &mut GridCalculator, id: &BlockId, direction: Option<Direction>, count: u64) {
+    let counts = match direction {
+      Some(direction) => calculator.direction_mut(direction),
+      None => &mut calculator.blocks,
+    };
+    if count == 0 {
+      counts.remove(id);
+    } else {
+      counts.insert(id.clone(), count);
+    }
+  }
+}
+
+/// Undo and redo history of commands applied to a calculator.
+#[derive(Default, Debug)]
+pub struct CommandHistory {
+  undo: Vec<Command>,
+  redo: Vec<Command>,
+}
+
+impl CommandHistory {
+  /// Maximum number of commands that can be undone, to bound memory usage.
+  const MAX_UNDO: usize = 100;
+
+  /// Applies `command` to `calculator` and records it, clearing the redo history.
+  pub fn execute(&mut self, command: Command, calculator: &mut GridCalculator) {
+    command.apply(calculator);
+    self.push(command);
+  }
+
+  /// Records that the calculator was changed from `previous` to `current` outside of `execute`, clearing the redo
+  /// history. Does nothing if the calculator did not actually change.
+  pub fn record(&mut self, previous: &GridCalculator, current: &GridCalculator) {
+    self.push(Command::between(previous, current));
+  }
+
+  fn push(&mut self, command: Command) {
+    if command.is_noop() { return; }
+    if self.undo.len() >= Self::MAX_UNDO {
+      self.undo.remove(0);
+    }
+    self.undo.push(command);
+    self.redo.clear();
+  }
+
+  /// Reverts the last command from `calculator`, returning false if there is nothing to undo.
+  pub fn undo(&mut self, calculator: &mut GridCalculator) -> bool {
+    if let Some(command) = self.undo.pop() {
+      command.revert(calculator);
+      self.redo.push(command);
+      true
+    } else {
+      false
+    }
+  }
+
+  /// Applies the last undone command to `calculator` again, returning false if there is nothing to redo.
+  pub fn redo(&mut self, calculator: &mut GridCalculator) -> bool {
+    if let Some(command) = self.redo.pop() {
+      command.apply(calculator);
+      self.undo.push(command);
+      true
+    } else {
+      false
+    }
+  }
+
+  pub fn can_undo(&self) -> bool { !self.undo.is_empty() }
+
+  pub fn can_redo(&self) -> bool { !self.redo.is_empty() }
+
+  /// Clears the history, for when a different calculator is loaded.
+  pub fn clear(&mut self) {
+    self.undo.clear();
+    self.redo.clear();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::grid::{Direction, GridCalculator};
+
+  use super::{Command, CommandHistory, GridFlag, GridOption};
+
+  fn calculator() -> GridCalculator {
+    let mut calculator = GridCalculator::default();
+    calculator.blocks.insert("Reactor".to_owned(), 2);
+    calculator.blocks.insert("Battery".to_owned(), 3);
+    calculator.direction_mut(Direction::Up).insert("Thruster".to_owned(), 4);
+    calculator
+  }
+
+  /// Asserts that `between(a, b)` changes `a` into `b`, and that reverting it restores `a`.
+  fn assert_between(a: &GridCalculator, b: &GridCalculator) {
+    let command = Command::between(a, b);
+    let mut calculator = a.clone();
+    command.apply(&mut calculator);
+    assert_eq!(&calculator, b);
+    command.revert(&mut calculator);
+    assert_eq!(&calculator, a);
+  }
+
+  #[test]
+  fn between_every_option() {
+    let a = calculator();
+    for option in GridOption::iter() {
+      let mut b = a.clone();
+      option.set(&mut b, option.get(&a) + 1.0);
+      let command = Command::between(&a, &b);
+      assert!(!command.is_noop(), "{:?} is not changed", option);
+      assert_between(&a, &b);
+    }
+  }
+
+  #[test]
+  fn between_every_flag() {
+    let a = calculator();
+    for flag in GridFlag::iter() {
+      let mut b = a.clone();
+      flag.set(&mut b, !flag.get(&a));
+      assert_between(&a, &b);
+    }
+  }
+
+  #[test]
+  fn between_block_counts() {
+    let a = calculator();
+    let mut b = a.clone();
+    b.blocks.insert("Reactor".to_owned(), 5);
+    b.blocks.remove("Battery");
+    b.blocks.insert("Generator".to_owned(), 1);
+    b.direction_mut(Direction::Up).remove("Thruster");
+    b.direction_mut(Direction::Down).insert("Thruster".to_owned(), 2);
+    assert_between(&a, &b);
+  }
+
+  #[test]
+  fn between_everything_at_once() {
+    let a = calculator();
+    let mut b = a.clone();
+    for option in GridOption::iter() {
+      option.set(&mut b, option.get(&a) * 0.5);
+    }
+    for flag in GridFlag::iter() {
+      flag.set(&mut b, !flag.get(&a));
+    }
+    b.stockpile_hydrogen_tanks.insert("Tank".to_owned());
+    b.power_priority.reverse();
+    b.blocks.insert("Reactor".to_owned(), 1);
+    b.direction_mut(Direction::Left).insert("Thruster".to_owned(), 3);
+    assert_between(&a, &b);
+  }
+
+  #[test]
+  fn undo_drops_oldest_command_beyond_max() {
+    let original = GridCalculator::default();
+    let mut calculator = original.clone();
+    let mut history = CommandHistory::default();
+    for i in 0..=CommandHistory::MAX_UNDO {
+      let command = Command::set_option(&calculator, GridOption::AdditionalMass, (i + 1) as f64);
+      history.execute(command, &mut calculator);
+    }
+    for _ in 0..CommandHistory::MAX_UNDO {
+      assert!(history.undo(&mut calculator));
+    }
+    assert!(!history.undo(&mut calculator));
+    // The first command can no longer be undone.
+    assert_eq!(GridOption::AdditionalMass.get(&calculator), 1.0);
+    assert_ne!(calculator, original);
+  }
+}
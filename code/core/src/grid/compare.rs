@@ -0,0 +1,58 @@
+use crate::data::Data;
+use crate::precision::Quantity;
+use crate::units::{Newtons, STANDARD_GRAVITY};
+
+use super::{Direction, GridCalculated, GridCalculator, ResultValue};
+
+/// Comparison of a single result value between two calculated grids.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Comparison {
+  pub label: String,
+  pub unit: &'static str,
+  pub quantity: Quantity,
+  pub left: f64,
+  pub right: f64,
+}
+
+impl Comparison {
+  /// Gets the difference from the left to the right value.
+  pub fn delta(&self) -> f64 {
+    self.right - self.left
+  }
+
+  pub fn is_changed(&self) -> bool {
+    self.delta() != 0.0
+  }
+}
+
+/// Compares all result values of `left` and `right`, in the order they are shown in the GUIs for `left`. Values are
+/// matched by label, as power rows follow the power priority of each grid. Values that only one grid has, such as power
+/// rows of consumers that are disabled in the other grid, are skipped.
+pub fn compare(left: &GridCalculated, right: &GridCalculated) -> Vec<Comparison> {
+  let right = right.values();
+  left.values().into_iter()
+    .filter_map(|l| right.iter().find(|r| r.label == l.label).map(|r| Comparison { label: l.label, unit: l.unit, quantity: l.quantity, left: l.value, right: r.value }))
+    .collect()
+}
+
+/// Gets the key metrics of `calculator` for choosing between fleet candidates: mass, thrust-to-weight ratio per
+/// direction when filled under Earth gravity, power and hydrogen endurance at full consumption, and the number of
+/// components to build it. PCU is not included, as it is not in the game data.
+pub fn fleet_metrics(calculator: &GridCalculator, data: &Data) -> Vec<ResultValue> {
+  let calculated = calculator.calculate(data);
+  let mut values = vec![
+    ResultValue::new("Mass - Empty", calculated.volume_mass.total_mass_empty),
+    ResultValue::new("Mass - Filled", calculated.volume_mass.total_mass_filled),
+  ];
+  let weight = calculated.volume_mass.total_mass_filled * STANDARD_GRAVITY;
+  for direction in Direction::iter() {
+    let force = calculated.acceleration.get(direction).map_or(Newtons(0.0), |a| a.force);
+    let ratio = if weight > Newtons(0.0) { force / weight } else { 0.0 };
+    values.push(ResultValue { label: format!("{:?} Thrust-to-Weight", direction), value: ratio, unit: "", quantity: Quantity::Ratio });
+  }
+  values.push(ResultValue::new("Power Endurance", calculated.power.upto_all().duration));
+  values.push(ResultValue::new("Hydrogen Endurance", calculated.hydrogen.upto_left_right_thruster.duration));
+  let components = calculator.component_totals(data).iter().map(|(_, count)| count).sum();
+  values.push(ResultValue::items("Build Cost: Components", components));
+  values
+}
@@ -0,0 +1,154 @@
+use std::fmt::Write;
+
+use crate::precision::Quantity;
+use crate::units::Unit;
+
+use super::{Direction, GridCalculated, ResultValue};
+use super::gyroscope::TurnTarget;
+use super::results::ExportedResults;
+use super::planet::EnvironmentCalculated;
+use super::trip::Trip;
+use super::weapon::WeaponGroup;
+
+/// Formats all result values of `calculated` as CSV with a header row.
+pub fn to_csv(calculated: &GridCalculated) -> String {
+  let mut csv = String::from("Result,Value,Unit\n");
+  for value in calculated.values() {
+    writeln!(csv, "\"{}\",{},\"{}\"", value.label.replace('"', "\"\""), value.format(), value.unit).unwrap();
+  }
+  csv
+}
+
+/// Formats the fleet metrics (see `compare::fleet_metrics`) of named grids as a single Markdown table, with a column
+/// per grid. Values that are not finite, such as the hydrogen endurance of a grid without hydrogen, are shown as `-`.
+pub fn fleet_to_markdown(fleet: &[(String, Vec<ResultValue>)]) -> String {
+  let mut markdown = String::new();
+  let first = match fleet.first() {
+    Some((_, first)) => first,
+    None => return markdown,
+  };
+  markdown.push_str("| Metric |");
+  for (name, _) in fleet { write!(markdown, " {} |", name).unwrap(); }
+  markdown.push_str(" Unit |\n|---|");
+  for _ in fleet { markdown.push_str("---:|"); }
+  markdown.push_str("---|\n");
+  for (index, value) in first.iter().enumerate() {
+    write!(markdown, "| {} |", value.label).unwrap();
+    for (_, metrics) in fleet {
+      write!(markdown, " {} |", metrics.get(index).filter(|v| v.value.is_finite()).map_or_else(|| "-".to_owned(), |v| v.format())).unwrap();
+    }
+    writeln!(markdown, " {} |", value.unit).unwrap();
+  }
+  markdown
+}
+
+/// Formats component totals (see `GridCalculator::component_totals`) as one `Component/<id>=<count>` line per
+/// component, which projector, build planner, and assembler scripts accept as a list of items to build.
+pub fn to_component_list(totals: &[(String, f64)]) -> String {
+  let mut list = String::new();
+  for (id, count) in totals {
+    writeln!(list, "Component/{}={}", id, count.ceil()).unwrap();
+  }
+  list
+}
+
+/// Formats all result values of `calculated` as JSON in the stable layout of `ExportedResults`.
+pub fn to_json(calculated: &GridCalculated) -> String {
+  serde_json::to_string_pretty(&ExportedResults::from(calculated)).expect("[BUG] Could not serialize results to JSON")
+}
+
+/// Formats all result sections of `calculated` as Markdown tables under a heading, followed by a table with the
+/// contribution of each block if `calculated` has a breakdown.
+pub fn to_markdown(calculated: &GridCalculated) -> String {
+  let mut markdown = String::new();
+  for (i, section) in calculated.sections().into_iter().enumerate() {
+    if i > 0 { markdown.push('\n'); }
+    writeln!(markdown, "### {}\n\n| Result | Value | Unit |\n|---|---:|---|", section.title).unwrap();
+    for value in section.values {
+      writeln!(markdown, "| {} | {} | {} |", value.label, value.format(), value.unit).unwrap();
+    }
+  }
+  if let Some(breakdown) = &calculated.breakdown {
+    markdown.push_str("\n### Breakdown\n\n| Block | Count | Mass (kg) | Volume (L) | Power generation (MW) | Power consumption (MW) | Hydrogen generation (L/s) | Hydrogen consumption (L/s) | Force (N) |\n");
+    markdown.push_str("|---|---:|---:|---:|---:|---:|---:|---:|---:|\n");
+    for c in breakdown {
+      writeln!(markdown, "| {} | {} | {} | {} | {} | {} | {} | {} | {} |", c.id, Quantity::Items.format(c.count as f64), c.mass.format(), c.volume.format(), c.power_generation.format(), c.power_consumption.format(), c.hydrogen_generation.format(), c.hydrogen_consumption.format(), c.force.format()).unwrap();
+    }
+  }
+  markdown
+}
+
+/// Formats all result sections of `results` as Markdown tables under a heading, with a value column per environment.
+pub fn environments_to_markdown(results: &[EnvironmentCalculated]) -> String {
+  let mut markdown = String::new();
+  let sections: Vec<_> = results.iter().map(|r| r.calculated.sections()).collect();
+  let first = match sections.first() {
+    Some(first) => first,
+    None => return markdown,
+  };
+  for (i, section) in first.iter().enumerate() {
+    if i > 0 { markdown.push('\n'); }
+    write!(markdown, "### {}\n\n| Result |", section.title).unwrap();
+    for result in results { write!(markdown, " {} |", result.environment.name).unwrap(); }
+    markdown.push_str(" Unit |\n|---|");
+    for _ in results { markdown.push_str("---:|"); }
+    markdown.push_str("---|\n");
+    for (j, value) in section.values.iter().enumerate() {
+      write!(markdown, "| {} |", value.label).unwrap();
+      for environment_sections in &sections {
+        write!(markdown, " {} |", environment_sections[i].values[j].format()).unwrap();
+      }
+      writeln!(markdown, " {} |", value.unit).unwrap();
+    }
+  }
+  markdown
+}
+
+/// Formats trips of `distance` in `direction` with the `empty` and `filled` mass as a Markdown table under a heading,
+/// with `-` for trips that cannot be made.
+pub fn trip_to_markdown(direction: Direction, distance: f64, empty: Option<Trip>, filled: Option<Trip>) -> String {
+  let mut markdown = String::new();
+  writeln!(markdown, "\n### Trip of {} m {:?}\n\n| Result | Empty | Filled | Unit |\n|---|---:|---:|---|", Quantity::Distance.format(distance), direction).unwrap();
+  let rows: [(&str, fn(&Trip) -> String, &str); 7] = [
+    ("Top Speed", |t| t.top_speed.format(), "m/s"),
+    ("Accelerate", |t| t.accelerate_duration.format(), "s"),
+    ("Coast", |t| t.coast_duration.format(), "s"),
+    ("Brake", |t| t.brake_duration.format(), "s"),
+    ("Duration", |t| t.duration.format(), "s"),
+    ("Hydrogen", |t| t.hydrogen.format(), "L"),
+    ("Power", |t| t.power.format(), "MWh"),
+  ];
+  for (label, format, unit) in rows.iter() {
+    let empty = empty.as_ref().map_or_else(|| "-".to_owned(), format);
+    let filled = filled.as_ref().map_or_else(|| "-".to_owned(), format);
+    writeln!(markdown, "| {} | {} | {} | {} |", label, empty, filled, unit).unwrap();
+  }
+  markdown
+}
+
+/// Formats how many gyroscopes named `name` a grid requires in total and needs to add to reach `target`, with its empty
+/// and filled mass, as a Markdown section.
+pub fn gyroscopes_to_markdown(target: TurnTarget, name: &str, required: [Option<u64>; 2], to_add: [Option<u64>; 2]) -> String {
+  let mut markdown = String::new();
+  match target {
+    TurnTarget::HalfTurn(duration) => writeln!(markdown, "\n### Gyroscopes to Turn 180° in {} s", duration.format()),
+    TurnTarget::AngularAcceleration(a) => writeln!(markdown, "\n### Gyroscopes to Reach {} rad/s^2", Quantity::Acceleration.format(a)),
+  }.unwrap();
+  writeln!(markdown, "\n| Result | Empty | Filled | Unit |\n|---|---:|---:|---|").unwrap();
+  let format = |count: Option<u64>| count.map_or_else(|| "-".to_owned(), |count| count.to_string());
+  writeln!(markdown, "| {} Required | {} | {} | # |", name, format(required[0]), format(required[1])).unwrap();
+  writeln!(markdown, "| {} to Add | {} | {} | # |", name, format(to_add[0]), format(to_add[1])).unwrap();
+  markdown
+}
+
+/// Formats weapon groups (see `GridCalculator::weapon_groups`) as a Markdown table under a heading, with `-` for the
+/// endurance of weapons without ammo or rate of fire.
+pub fn weapons_to_markdown(groups: &[WeaponGroup]) -> String {
+  let mut markdown = String::new();
+  markdown.push_str("\n### Weapons\n\n| Block | Count | Rounds per weapon | Firing endurance (s) | Ammo mass (kg) |\n|---|---:|---:|---:|---:|\n");
+  for group in groups {
+    let endurance = group.endurance.map_or_else(|| "-".to_owned(), |endurance| endurance.format());
+    writeln!(markdown, "| {} | {} | {} | {} | {} |", group.id, Quantity::Items.format(group.count as f64), Quantity::Items.format(group.rounds), endurance, group.ammo_mass.format()).unwrap();
+  }
+  markdown
+}
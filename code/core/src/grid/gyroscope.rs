@@ -0,0 +1,84 @@
+use std::f64::consts::PI;
+
+use crate::data::blocks::{Block, GridSize, Gyroscope};
+use crate::data::Data;
+use crate::units::{Kilograms, Meters, Seconds};
+
+use super::GridCalculator;
+
+/// Rough outer dimensions of a grid, which is estimated as a solid cuboid of uniform density.
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub struct GridDimensions {
+  pub width: Meters,
+  pub height: Meters,
+  pub length: Meters,
+}
+
+impl GridDimensions {
+  /// Gets the moment of inertia (kg*m^2) of a solid cuboid of `mass` with these dimensions, around the axis for which
+  /// it is largest, so that turning around any other axis is at least as fast.
+  pub fn moment_of_inertia(&self, mass: Kilograms) -> f64 {
+    let (w, h, l) = (self.width.0 * self.width.0, self.height.0 * self.height.0, self.length.0 * self.length.0);
+    mass.0 * (h + l).max(w + l).max(w + h) / 12.0
+  }
+}
+
+/// Target for turning a grid with gyroscopes.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum TurnTarget {
+  /// Turn 180° from standstill to standstill within this duration.
+  HalfTurn(Seconds),
+  /// Reach this angular acceleration (rad/s^2).
+  AngularAcceleration(f64),
+}
+
+impl TurnTarget {
+  /// Gets the angular acceleration (rad/s^2) that reaches this target.
+  pub fn angular_acceleration(self) -> f64 {
+    match self {
+      // Accelerating for the first half of duration t and braking for the second half turns by a(t/2)^2, which must
+      // be π.
+      TurnTarget::HalfTurn(duration) => 4.0 * PI / (duration.0 * duration.0),
+      TurnTarget::AngularAcceleration(angular_acceleration) => angular_acceleration,
+    }
+  }
+}
+
+/// Gets the torque (N*m) that turns a grid of `mass` and `dimensions` as fast as `target`, ignoring the maximum angular
+/// speed of the game.
+pub fn required_torque(mass: Kilograms, dimensions: GridDimensions, target: TurnTarget) -> f64 {
+  dimensions.moment_of_inertia(mass) * target.angular_acceleration()
+}
+
+/// Calculates how many `gyroscope`s turn a grid of `mass` and `dimensions` as fast as `target`. Returns `None` if the
+/// gyroscope has no torque.
+pub fn gyroscopes_required(gyroscope: &Gyroscope, mass: Kilograms, dimensions: GridDimensions, target: TurnTarget) -> Option<u64> {
+  gyroscopes_for_torque(gyroscope, required_torque(mass, dimensions, target))
+}
+
+fn gyroscopes_for_torque(gyroscope: &Gyroscope, torque: f64) -> Option<u64> {
+  if !(gyroscope.torque > 0.0) { return None; }
+  Some((torque / gyroscope.torque).ceil().max(0.0) as u64)
+}
+
+/// Gets the gyroscope with the most torque in `size`, or `None` if there are no gyroscopes of that size.
+pub fn strongest_gyroscope(data: &Data, size: GridSize) -> Option<&Block<Gyroscope>> {
+  data.blocks.gyroscopes.values()
+    .filter(|block| block.size == size)
+    .max_by(|a, b| a.details.torque.partial_cmp(&b.details.torque).unwrap_or(std::cmp::Ordering::Equal))
+}
+
+impl GridCalculator {
+  /// Gets the total torque (N*m) of the gyroscopes of this grid.
+  pub fn gyroscope_torque(&self, data: &Data) -> f64 {
+    self.blocks.iter()
+      .filter_map(|(id, count)| data.blocks.gyroscopes.get(id).map(|block| block.details.torque * *count as f64))
+      .sum()
+  }
+
+  /// Calculates how many `gyroscope`s to add to this grid, with `mass` and `dimensions`, to turn it as fast as
+  /// `target`, counting the torque of the gyroscopes it already has. Returns `None` if the gyroscope has no torque.
+  pub fn gyroscopes_to_add(&self, data: &Data, gyroscope: &Gyroscope, mass: Kilograms, dimensions: GridDimensions, target: TurnTarget) -> Option<u64> {
+    gyroscopes_for_torque(gyroscope, required_torque(mass, dimensions, target) - self.gyroscope_torque(data))
+  }
+}
@@ -0,0 +1,85 @@
+use crate::data::blocks::BlockRef;
+use crate::data::Data;
+use crate::units::Kilograms;
+
+use super::{GridCalculated, GridCalculator};
+
+/// Category that the mass of a grid is attributed to.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Debug)]
+pub enum MassCategory {
+  /// Additional mass (such as armor), cockpits, containers, and other blocks that are not attributed elsewhere.
+  Structure,
+  Thrusters,
+  /// Power and hydrogen producers and storage.
+  Power,
+  Tools,
+  /// Weapons, and their ammo of the configured fill.
+  Weapons,
+  /// Ice, ore, and steel plates of the configured fill, which only weigh when filled.
+  Contents,
+}
+
+impl MassCategory {
+  pub fn iter() -> impl Iterator<Item=&'static MassCategory> {
+    use self::MassCategory::*;
+    static CATEGORIES: [MassCategory; 6] = [Structure, Thrusters, Power, Tools, Weapons, Contents];
+    CATEGORIES.iter()
+  }
+
+  fn of(block: BlockRef) -> Self {
+    use self::MassCategory::*;
+    match block {
+      BlockRef::Thruster(_) => Thrusters,
+      BlockRef::Battery(_) | BlockRef::HydrogenEngine(_) | BlockRef::Reactor(_) | BlockRef::Renewable(_) |
+      BlockRef::Generator(_) | BlockRef::HydrogenTank(_) => Power,
+      BlockRef::ShipTool(_) => Tools,
+      BlockRef::Weapon(_) => Weapons,
+      BlockRef::Utility(_) | BlockRef::Accessory(_) | BlockRef::Automation(_) | BlockRef::LifeSupport(_) |
+      BlockRef::Gyroscope(_) | BlockRef::Container(_) | BlockRef::Cockpit(_) => Structure,
+    }
+  }
+}
+
+/// Mass of a grid attributed to a category, when empty and when filled.
+#[derive(Copy, Clone, Debug)]
+pub struct MassShare {
+  pub category: MassCategory,
+  pub empty: Kilograms,
+  pub filled: Kilograms,
+}
+
+impl GridCalculator {
+  /// Attributes the total empty and filled mass of `calculated`, which must be calculated from this grid, to each
+  /// category, in the order of `MassCategory::iter`.
+  pub fn mass_budget(&self, data: &Data, calculated: &GridCalculated) -> Vec<MassShare> {
+    let mut shares: Vec<_> = MassCategory::iter()
+      .map(|category| MassShare { category: *category, empty: Kilograms(0.0), filled: Kilograms(0.0) })
+      .collect();
+    let mut add = |category: MassCategory, empty: Kilograms, filled: Kilograms| {
+      let share = &mut shares[category as usize];
+      share.empty += empty;
+      share.filled += filled;
+    };
+
+    add(MassCategory::Structure, self.additional_mass, self.additional_mass);
+    let owned_breakdown;
+    let breakdown = match &calculated.breakdown {
+      Some(breakdown) => breakdown,
+      None => {
+        owned_breakdown = self.breakdown(data);
+        &owned_breakdown
+      }
+    };
+    for contribution in breakdown {
+      if let Some(block) = data.blocks.get(&contribution.id) {
+        add(MassCategory::of(block), contribution.mass, contribution.mass);
+      }
+    }
+    let ammo_mass = self.ammo_mass(data);
+    add(MassCategory::Weapons, Kilograms(0.0), ammo_mass);
+    let contents = calculated.volume_mass.total_mass_filled - calculated.volume_mass.total_mass_empty - ammo_mass;
+    add(MassCategory::Contents, Kilograms(0.0), contents);
+
+    shares
+  }
+}
@@ -1,16 +1,49 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display, Formatter};
 use std::io;
+use std::ops::{Div, Sub};
+use std::str::FromStr;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
+use tracing::debug_span;
 
-use crate::data::blocks::{BlockId, ThrusterType};
+use crate::data::blocks::{BlockId, BlockKind, Blocks, GridSize};
 use crate::data::Data;
+use crate::precision::Quantity;
+use crate::units::{Kilograms, Litres, LitresPerSecond, MegaWattHours, MegaWatts, Meters, MetersPerSecond, MetersPerSecondSquared, Minutes, Newtons, Seconds, STANDARD_GRAVITY, Unit};
+
+use self::breakdown::BlockContribution;
+use self::calculator::{Calculator, VanillaCalculator};
+use self::validate::ValidationError;
+
+pub mod breakdown;
+pub mod builder;
+pub mod calculator;
+pub mod change;
+pub mod command;
+pub mod compare;
+pub mod export;
+pub mod gyroscope;
+pub mod mass_budget;
+pub mod planet;
+pub mod preset;
+pub mod recommendation;
+pub mod results;
+pub mod sweep;
+pub mod template;
+pub mod trip;
+pub mod validate;
+pub mod warning;
+pub mod watch;
+pub mod weapon;
 
 #[derive(Error, Debug)]
 pub enum ReadError {
   #[error("Could not read grid from JSON")]
   FromJSON(#[from] serde_json::Error),
+  #[error("Grid read from JSON is invalid")]
+  Invalid(#[from] ValidationError),
 }
 
 #[derive(Error, Debug)]
@@ -19,6 +52,14 @@ pub enum WriteError {
   ToJSON(#[from] serde_json::Error),
 }
 
+#[derive(Error, Debug)]
+#[error("Unknown power consumer '{0}', expected jump-drive, generator, up-down-thruster, front-back-thruster, left-right-thruster, ship-tool, or battery")]
+pub struct ParsePowerConsumerError(String);
+
+#[derive(Error, Debug)]
+#[error("Unknown direction '{0}', expected up, down, front, back, left, or right")]
+pub struct ParseDirectionError(String);
+
 #[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Hash, Serialize, Deserialize, Debug)]
 pub enum Direction {
   Up,
@@ -35,23 +76,247 @@ impl Direction {
     static SIDES: [Direction; 6] = [Up, Down, Front, Back, Left, Right];
     SIDES.iter()
   }
+
+  pub fn opposite(self) -> Direction {
+    use self::Direction::*;
+    match self {
+      Up => Down,
+      Down => Up,
+      Front => Back,
+      Back => Front,
+      Left => Right,
+      Right => Left,
+    }
+  }
+}
+
+impl FromStr for Direction {
+  type Err = ParseDirectionError;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Direction::iter().copied().find(|d| format!("{:?}", d).eq_ignore_ascii_case(s)).ok_or_else(|| ParseDirectionError(s.to_owned()))
+  }
+}
+
+/// Group of power consumers, which are cumulatively added to the power consumption rows in priority order.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Hash, Serialize, Deserialize, Debug)]
+pub enum PowerConsumer {
+  JumpDrive,
+  Generator,
+  UpDownThruster,
+  FrontBackThruster,
+  LeftRightThruster,
+  ShipTool,
+  Battery,
+}
+
+impl PowerConsumer {
+  pub fn iter() -> impl Iterator<Item=&'static PowerConsumer> {
+    use self::PowerConsumer::*;
+    static CONSUMERS: [PowerConsumer; 7] = [JumpDrive, Generator, UpDownThruster, FrontBackThruster, LeftRightThruster, ShipTool, Battery];
+    CONSUMERS.iter()
+  }
+
+  /// Gets the name of this consumer in textual power priorities.
+  pub fn name(self) -> &'static str {
+    match self {
+      PowerConsumer::JumpDrive => "jump-drive",
+      PowerConsumer::Generator => "generator",
+      PowerConsumer::UpDownThruster => "up-down-thruster",
+      PowerConsumer::FrontBackThruster => "front-back-thruster",
+      PowerConsumer::LeftRightThruster => "left-right-thruster",
+      PowerConsumer::ShipTool => "ship-tool",
+      PowerConsumer::Battery => "battery",
+    }
+  }
+
+  /// Gets the label of this consumer in result rows.
+  pub fn label(self) -> &'static str {
+    match self {
+      PowerConsumer::JumpDrive => "Charge Jump Drives",
+      PowerConsumer::Generator => "Generators",
+      PowerConsumer::UpDownThruster => "Up/Down Thrusters",
+      PowerConsumer::FrontBackThruster => "Front/Back Thrusters",
+      PowerConsumer::LeftRightThruster => "Left/Right Thrusters",
+      PowerConsumer::ShipTool => "Ship Tools",
+      PowerConsumer::Battery => "Charge Batteries",
+    }
+  }
+
+  /// Whether this consumer existed when power priorities were introduced. Grids from that version only store enabled
+  /// consumers, so such a consumer is disabled when it is missing, whereas newer consumers are enabled.
+  fn in_original_priority(self) -> bool {
+    match self {
+      PowerConsumer::ShipTool => false,
+      _ => true,
+    }
+  }
+
+  /// Gets the thruster axis of this consumer, or `None` if it is not a thruster consumer.
+  pub fn thruster_axis(self) -> Option<(Direction, Direction)> {
+    match self {
+      PowerConsumer::UpDownThruster => Some((Direction::Up, Direction::Down)),
+      PowerConsumer::FrontBackThruster => Some((Direction::Front, Direction::Back)),
+      PowerConsumer::LeftRightThruster => Some((Direction::Left, Direction::Right)),
+      _ => None,
+    }
+  }
+
+  /// Parses a comma-separated power priority, such as `generator, battery`. An empty string disables all consumers.
+  pub fn parse_priority(s: &str) -> Result<Vec<PowerConsumer>, ParsePowerConsumerError> {
+    s.split(',').map(str::trim).filter(|name| !name.is_empty()).map(str::parse).collect()
+  }
+
+  /// Formats `priority` as a comma-separated power priority that `parse_priority` parses.
+  pub fn format_priority(priority: &[PowerConsumer]) -> String {
+    priority.iter().map(|c| c.name()).collect::<Vec<_>>().join(", ")
+  }
+}
+
+impl FromStr for PowerConsumer {
+  type Err = ParsePowerConsumerError;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    PowerConsumer::iter().copied().find(|c| c.name().eq_ignore_ascii_case(s)).ok_or_else(|| ParsePowerConsumerError(s.to_owned()))
+  }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+impl Display for PowerConsumer {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result { f.write_str(self.name()) }
+}
+
+/// Consumer that is not a block of the game data, such as a block of a mod, entered by name with its consumption of
+/// resource `R`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct CustomConsumer<R> {
+  pub name: String,
+  pub consumption: R,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub struct GridCalculator {
   pub gravity_multiplier: f64,
   pub container_multiplier: f64,
   pub planetary_influence: f64,
-  pub additional_mass: f64,
+  pub additional_mass: Kilograms,
+  /// Inventory volume of blocks that are not in the game data, such as cargo containers of mods, which counts as
+  /// volume that can be filled with anything. Not affected by the container multiplier.
+  #[serde(default)]
+  pub additional_volume: Litres,
+  /// Maximum speed of the world, which is 100 m/s by default but often higher on modded servers.
+  #[serde(default = "default_max_speed")]
+  pub max_speed: MetersPerSecond,
+  /// Altitude above the ground at which a descending grid must have stopped, to determine the safe descent speed.
+  #[serde(default = "default_descent_altitude")]
+  pub descent_altitude: Meters,
   pub ice_only_fill: f64,
   pub ore_only_fill: f64,
   pub any_fill_with_ice: f64,
   pub any_fill_with_ore: f64,
   pub any_fill_with_steel_plates: f64,
+  /// Percentage of the inventory volume of weapons that is filled with ammo magazines.
+  #[serde(default = "default_ammo_fill")]
+  pub ammo_fill: f64,
+  /// Percentage of the ice throughput of O2/H2 generators that produces hydrogen, with the rest producing oxygen.
+  #[serde(default = "default_generator_hydrogen_share")]
+  pub generator_hydrogen_share: f64,
+  /// Percentage of the capacity of batteries that is charged at the start.
+  #[serde(default = "default_battery_charge")]
+  pub battery_charge: f64,
+  /// Multiplier of the power generation of reactors, for servers with balance mods.
+  #[serde(default = "default_reactor_output_multiplier")]
+  pub reactor_output_multiplier: f64,
+  /// Percentage of time that solar panels and wind turbines are at full output, to account for night and weather.
+  #[serde(default = "default_renewable_output")]
+  pub renewable_output: f64,
+  /// Percentage of the maximum broadcast range of antennas and beacons, and detection range of ore detectors, which
+  /// their power consumption scales with.
+  #[serde(default = "default_utility_range")]
+  pub utility_range: f64,
+  /// Whether hydrogen thrusters consume hydrogen when idle, which they do not with "thrust on demand" mods.
+  #[serde(default = "default_idle_hydrogen_thrusters")]
+  pub idle_hydrogen_thrusters: bool,
+  /// Hydrogen tanks in stockpile mode, which keep their hydrogen in reserve instead of feeding thrusters and engines.
+  #[serde(default)]
+  pub stockpile_hydrogen_tanks: HashSet<BlockId>,
+  /// Power consumers in the order that they are added to the cumulative power consumption rows. Consumers that are not
+  /// in this list are disabled.
+  #[serde(default = "default_power_priority", serialize_with = "serialize_power_priority", deserialize_with = "deserialize_power_priority")]
+  pub power_priority: Vec<PowerConsumer>,
+  /// Named power consumers that are added to the misc power consumption.
+  #[serde(default)]
+  pub custom_power_consumers: Vec<CustomConsumer<MegaWatts>>,
+  /// Named hydrogen consumers that are added to the hydrogen consumption of engines.
+  #[serde(default)]
+  pub custom_hydrogen_consumers: Vec<CustomConsumer<LitresPerSecond>>,
   pub blocks: HashMap<BlockId, u64>,
+  /// Thrusters per direction. Contains all directions after deserialization, but use `direction_mut` and
+  /// `iter_direction` to access directions without relying on that.
+  #[serde(deserialize_with = "deserialize_directional_blocks")]
   pub directional_blocks: HashMap<Direction, HashMap<BlockId, u64>>,
 }
 
+fn default_max_speed() -> MetersPerSecond { MetersPerSecond(100.0) }
+
+fn default_descent_altitude() -> Meters { Meters(100.0) }
+
+fn default_ammo_fill() -> f64 { 100.0 }
+
+fn default_generator_hydrogen_share() -> f64 { 100.0 }
+
+fn default_battery_charge() -> f64 { 100.0 }
+
+fn default_reactor_output_multiplier() -> f64 { 1.0 }
+
+fn default_renewable_output() -> f64 { 100.0 }
+
+fn default_utility_range() -> f64 { 100.0 }
+
+fn default_idle_hydrogen_thrusters() -> bool { true }
+
+fn default_power_priority() -> Vec<PowerConsumer> { PowerConsumer::iter().copied().collect() }
+
+/// Entry of a stored power priority. Disabled consumers are stored after the enabled ones, so that consumers added in
+/// later versions can be told apart from disabled consumers.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum PowerPriorityEntry {
+  Enabled(PowerConsumer),
+  Disabled { disabled: PowerConsumer },
+}
+
+fn serialize_power_priority<S: Serializer>(power_priority: &[PowerConsumer], serializer: S) -> Result<S::Ok, S::Error> {
+  let enabled = power_priority.iter().map(|consumer| PowerPriorityEntry::Enabled(*consumer));
+  let disabled = PowerConsumer::iter().filter(|consumer| !power_priority.contains(consumer))
+    .map(|consumer| PowerPriorityEntry::Disabled { disabled: *consumer });
+  serializer.collect_seq(enabled.chain(disabled))
+}
+
+/// Deserializes a power priority, appending consumers that did not exist yet when the grid was written.
+fn deserialize_power_priority<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<PowerConsumer>, D::Error> {
+  let mut power_priority = Vec::new();
+  let mut disabled = Vec::new();
+  for entry in Vec::<PowerPriorityEntry>::deserialize(deserializer)? {
+    match entry {
+      PowerPriorityEntry::Enabled(consumer) => power_priority.push(consumer),
+      PowerPriorityEntry::Disabled { disabled: consumer } => disabled.push(consumer),
+    }
+  }
+  for consumer in PowerConsumer::iter() {
+    if !consumer.in_original_priority() && !power_priority.contains(consumer) && !disabled.contains(consumer) {
+      power_priority.push(*consumer);
+    }
+  }
+  Ok(power_priority)
+}
+
+/// Deserializes directional blocks, adding directions that are missing from (e.g., hand-edited) files.
+fn deserialize_directional_blocks<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<Direction, HashMap<BlockId, u64>>, D::Error> {
+  let mut directional_blocks = HashMap::<Direction, HashMap<BlockId, u64>>::deserialize(deserializer)?;
+  for direction in Direction::iter() {
+    directional_blocks.entry(*direction).or_default();
+  }
+  Ok(directional_blocks)
+}
+
 impl Default for GridCalculator {
   fn default() -> Self {
     Self {
@@ -63,18 +328,23 @@ impl Default for GridCalculator {
       any_fill_with_ice: 0.0,
       any_fill_with_ore: 0.0,
       any_fill_with_steel_plates: 0.0,
-      additional_mass: 0.0,
+      ammo_fill: 100.0,
+      generator_hydrogen_share: 100.0,
+      battery_charge: 100.0,
+      reactor_output_multiplier: 1.0,
+      renewable_output: 100.0,
+      utility_range: 100.0,
+      additional_mass: Kilograms(0.0),
+      additional_volume: Litres(0.0),
+      max_speed: MetersPerSecond(100.0),
+      descent_altitude: Meters(100.0),
+      idle_hydrogen_thrusters: true,
+      stockpile_hydrogen_tanks: Default::default(),
+      power_priority: default_power_priority(),
+      custom_power_consumers: Vec::new(),
+      custom_hydrogen_consumers: Vec::new(),
       blocks: Default::default(),
-      directional_blocks: {
-        let mut map = HashMap::default();
-        map.insert(Direction::Up, HashMap::default());
-        map.insert(Direction::Down, HashMap::default());
-        map.insert(Direction::Front, HashMap::default());
-        map.insert(Direction::Back, HashMap::default());
-        map.insert(Direction::Left, HashMap::default());
-        map.insert(Direction::Right, HashMap::default());
-        map
-      },
+      directional_blocks: Direction::iter().map(|direction| (*direction, HashMap::default())).collect(),
     }
   }
 }
@@ -85,7 +355,9 @@ impl GridCalculator {
   }
 
   pub fn from_json<R: io::Read>(reader: R) -> Result<Self, ReadError> {
+    let _span = debug_span!("deserialize_grid").entered();
     let grid = serde_json::from_reader::<_, Self>(reader)?;
+    grid.validate()?;
     Ok(grid)
   }
 
@@ -98,288 +370,683 @@ impl GridCalculator {
     self.blocks.iter()
   }
 
-  pub fn calculate(&self, data: &Data) -> GridCalculated {
-    let ice_weight_per_volume = 1.0 / 0.37; // TODO: derive from data
-    let ice_items_per_volume = 1.0 / 0.37; // TODO: derive from data
-    let ore_weight_per_volume = 1.0 / 0.37; // TODO: derive from data
-    let ore_items_per_volume = 1.0 / 0.37; // TODO: derive from data
-    let steel_plate_weight_per_volume = 20.0 / 3.0; // TODO: derive from data
-    let steel_plate_items_per_volume = 1.0 / 3.0; // TODO: derive from data
-
-    let mut c = GridCalculated::default();
-
-    let mut power_consumption_idle = 0.0;
-    let mut power_consumption_misc = 0.0;
-    let mut power_consumption_generator = 0.0;
-    let power_consumption_jump_drive = 0.0;
-    let mut power_consumption_thruster: HashMap<Direction, f64> = HashMap::default();
-    let mut power_consumption_battery = 0.0;
-
-    let mut hydrogen_consumption_idle = 0.0;
-    let mut hydrogen_consumption_engine = 0.0;
-    let mut hydrogen_consumption_thruster: HashMap<Direction, f64> = HashMap::default();
-
-    c.total_mass_empty += self.additional_mass;
-
-    // Containers.
-    for (id, count) in self.blocks.iter() {
-      if let Some(block) = data.blocks.containers.get(id) {
-        let count = *count as f64;
-        c.total_mass_empty += block.mass(&data.components) * count;
-        if block.store_any {
-          let volume = block.details.capacity * count * self.container_multiplier;
-          c.total_volume_any += volume;
-          c.total_volume_ore += volume;
-          c.total_volume_ice += volume;
-        }
-      }
-    }
-    // Cockpits.
-    for (id, count) in self.blocks.iter() {
-      if let Some(block) = data.blocks.cockpits.get(id) {
-        let count = *count as f64;
-        c.total_mass_empty += block.mass(&data.components) * count;
-        if block.has_inventory {
-          let volume = block.details.capacity * count * self.container_multiplier;
-          c.total_volume_any += volume;
-          c.total_volume_ore += volume;
-          c.total_volume_ice += volume;
-        }
+  /// Gets the thrusters facing `direction`, inserting the direction if it is missing.
+  pub fn direction_mut(&mut self, direction: Direction) -> &mut HashMap<BlockId, u64> {
+    self.directional_blocks.entry(direction).or_default()
+  }
+
+  /// Iterates over the counts of thrusters facing `direction`, which is empty if the direction is missing.
+  pub fn iter_direction(&self, direction: Direction) -> impl Iterator<Item=(&BlockId, &u64)> {
+    self.directional_blocks.get(&direction).into_iter().flatten()
+  }
+
+  /// Gets the count of thruster `id` facing `direction`, which is 0 if it or the direction is missing.
+  pub fn direction_count(&self, direction: Direction, id: &BlockId) -> u64 {
+    self.directional_blocks.get(&direction).and_then(|blocks| blocks.get(id)).copied().unwrap_or(0)
+  }
+
+  /// Gets the total power consumption of custom power consumers.
+  pub fn custom_power_consumption(&self) -> MegaWatts {
+    self.custom_power_consumers.iter().fold(MegaWatts(0.0), |total, c| total + c.consumption)
+  }
+
+  /// Gets the total hydrogen consumption of custom hydrogen consumers.
+  pub fn custom_hydrogen_consumption(&self) -> LitresPerSecond {
+    self.custom_hydrogen_consumers.iter().fold(LitresPerSecond(0.0), |total, c| total + c.consumption)
+  }
+
+  /// Gets the total number of blocks, including thrusters in all directions, or `None` if it overflows.
+  pub fn total_block_count(&self) -> Option<u64> {
+    self.blocks.values()
+      .chain(self.directional_blocks.values().flat_map(|blocks| blocks.values()))
+      .try_fold(0u64, |total, count| total.checked_add(*count))
+  }
+
+  /// Gets the number of small-grid and large-grid blocks, including thrusters in all directions. Blocks that are not
+  /// in `data` are not counted.
+  pub fn block_count_by_size(&self, data: &Data) -> (u64, u64) {
+    let mut small = 0u64;
+    let mut large = 0u64;
+    let counts = self.blocks.iter().chain(self.directional_blocks.values().flat_map(|blocks| blocks.iter()));
+    for (id, count) in counts {
+      match data.blocks.get(id).map(|b| b.size()) {
+        Some(GridSize::Small) => small = small.saturating_add(*count),
+        Some(GridSize::Large) => large = large.saturating_add(*count),
+        None => {}
       }
     }
-    // Thrusters.
-    for (side, blocks) in self.directional_blocks.iter() {
-      let mut a = AccelerationCalculated::default();
-      for (id, count) in blocks {
-        if let Some(block) = data.blocks.thrusters.get(id) {
-          let count = *count as f64;
-          let details = &block.details;
-          c.total_mass_empty += block.mass(&data.components) * count;
-          // Clamp planetary influence value.
-          let planetary_influence = self.planetary_influence.clamp(details.min_planetary_influence, details.max_planetary_influence);
-          // Slope-intercept form equation: y = mx + b
-          // Calculate m: m = (y2 - y1) / (x2 - x1)
-          let m = (details.effectiveness_at_min_influence - details.effectiveness_at_max_influence) / (details.min_planetary_influence - details.max_planetary_influence);
-          // Calculate b: b = y + -mx (choose x,y on the line)
-          let b = details.effectiveness_at_max_influence + (-1.0 * m * details.max_planetary_influence);
-          // Calculate y: y = mx + b
-          let effectiveness = m * planetary_influence + b;
-          a.force += details.force * effectiveness * count;
-          match details.ty {
-            ThrusterType::Hydrogen => {
-              hydrogen_consumption_idle += details.actual_min_consumption(&data.gas_properties) * count;
-              let max_consumption = details.actual_max_consumption(&data.gas_properties) * count;
-              hydrogen_consumption_thruster.entry(*side).and_modify(|c| *c += max_consumption).or_insert(max_consumption);
-            },
-            _ => {
-              power_consumption_idle += details.actual_min_consumption(&data.gas_properties) * count;
-              let max_consumption = details.actual_max_consumption(&data.gas_properties) * count;
-              power_consumption_thruster.entry(*side).and_modify(|c| *c += max_consumption).or_insert(max_consumption);
-            },
-          }
+    (small, large)
+  }
+
+  /// Gets the total number of each component needed to build all blocks, in the order of the components of `data`,
+  /// followed by components that are not in `data` by identifier. Blocks that are not in `data` are not counted.
+  pub fn component_totals(&self, data: &Data) -> Vec<(String, f64)> {
+    let mut totals: HashMap<&str, f64> = HashMap::default();
+    let counts = self.blocks.iter().chain(self.directional_blocks.values().flat_map(|blocks| blocks.iter()));
+    for (id, count) in counts.filter(|(_, count)| **count > 0) {
+      if let Some(block) = data.blocks.get(id) {
+        for (component_id, component_count) in block.components() {
+          *totals.entry(component_id).or_default() += component_count * *count as f64;
         }
       }
-      c.acceleration.insert(*side, a);
-    }
-    // Hydrogen Engines.
-    for (id, count) in self.blocks.iter() {
-      if let Some(block) = data.blocks.hydrogen_engines.get(id) {
-        let count = *count as f64;
-        let details = &block.details;
-        c.total_mass_empty += block.mass(&data.components) * count;
-        c.power_generation += details.max_power_generation * count;
-        hydrogen_consumption_engine += details.max_fuel_consumption * count;
-        c.hydrogen_capacity_engine += details.fuel_capacity * count;
-      }
-    }
-    // Reactors.
-    for (id, count) in self.blocks.iter() {
-      if let Some(block) = data.blocks.reactors.get(id) {
-        let count = *count as f64;
-        let details = &block.details;
-        c.total_mass_empty += block.mass(&data.components) * count;
-        c.power_generation += details.max_power_generation * count;
-        // TODO: fuel capacity/use
-      }
-    }
-    // Batteries.
-    for (id, count) in self.blocks.iter() {
-      if let Some(block) = data.blocks.batteries.get(id) {
-        let count = *count as f64;
-        let details = &block.details;
-        c.total_mass_empty += block.mass(&data.components) * count;
-        c.power_generation += details.output * count;
-        power_consumption_battery += details.input * count;
-        c.power_capacity_battery += details.capacity * count;
-      }
-    }
-    // Hydrogen Generators.
-    for (id, count) in self.blocks.iter() {
-      if let Some(block) = data.blocks.generators.get(id) {
-        let count = *count as f64;
-        let details = &block.details;
-        // Mass
-        c.total_mass_empty += block.mass(&data.components) * count;
-        // Volume
-        c.total_volume_ice_only += details.inventory_volume_ice * count;
-        // Power consumption
-        power_consumption_idle += details.idle_power_consumption * count;
-        power_consumption_generator += details.operational_power_consumption * count;
-        c.hydrogen_generation += details.hydrogen_generation * count;
-        // TODO: ice consumption
-      }
-    }
-    // Hydrogen Tanks.
-    for (id, count) in self.blocks.iter() {
-      if let Some(block) = data.blocks.hydrogen_tanks.get(id) {
-        let count = *count as f64;
-        let details = &block.details;
-        // Mass
-        c.total_mass_empty += block.mass(&data.components) * count;
-        power_consumption_idle += details.idle_power_consumption * count;
-        power_consumption_misc += details.operational_power_consumption * count;
-        c.hydrogen_capacity_tank += details.capacity * count;
-      }
     }
+    let mut ordered: Vec<_> = data.components.components.keys()
+      .filter_map(|id| totals.remove(id.as_str()).map(|total| (id.clone(), total)))
+      .collect();
+    let mut unknown: Vec<_> = totals.into_iter().map(|(id, total)| (id.to_owned(), total)).collect();
+    unknown.sort_by(|(a, _), (b, _)| a.cmp(b));
+    ordered.extend(unknown);
+    ordered
+  }
 
-    // TODO: add jump drive block
-    // TODO: add gyroscopes
-    // TODO: add drills
-
-    // Calculate filled volumes.
-    let ice_only_volume = c.total_volume_ice_only * (self.ice_only_fill / 100.0);
-    let ore_only_volume = c.total_volume_ore_only * (self.ore_only_fill / 100.0);
-    let ice_in_any_volume = c.total_volume_any * (self.any_fill_with_ice / 100.0);
-    let ore_in_any_volume = c.total_volume_any * (self.any_fill_with_ore / 100.0);
-    let steel_plates_in_any_volume = c.total_volume_any * (self.any_fill_with_steel_plates / 100.0);
-
-    // Calculate filled mass.
-    // TODO: container multiplier increases volume but keeps mass the same!
-    let ice_only_mass = ice_only_volume * ice_weight_per_volume;
-    let ore_only_mass = ore_only_volume * ore_weight_per_volume;
-    let any_mass = (ice_in_any_volume * ice_weight_per_volume) + (ore_in_any_volume * ore_weight_per_volume) + (steel_plates_in_any_volume * steel_plate_weight_per_volume);
-    c.total_mass_filled = c.total_mass_empty + ice_only_mass + ore_only_mass + any_mass;
-
-    // Calculate filled items.
-    c.total_items_ice = (ice_only_volume + ice_in_any_volume) * ice_items_per_volume;
-    c.total_items_ore = (ore_only_volume + ore_in_any_volume) * ore_items_per_volume;
-    c.total_items_steel_plate = steel_plates_in_any_volume * steel_plate_items_per_volume;
-
-    // Calculate Acceleration
-    for a in c.acceleration.values_mut() {
-      a.acceleration_empty_no_gravity = a.force / c.total_mass_empty;
-      a.acceleration_filled_no_gravity = a.force / c.total_mass_filled;
-      a.acceleration_empty_gravity = (a.force - (c.total_mass_empty * 9.81 * self.gravity_multiplier)) / c.total_mass_empty;
-      a.acceleration_filled_gravity = (a.force - (c.total_mass_filled * 9.81 * self.gravity_multiplier)) / c.total_mass_filled;
+  /// Merges `other` into this grid, for composing a grid from modules (e.g., a mining arm and a tug chassis). Block
+  /// counts and additional mass are summed, and custom consumers are combined. Environment and fill options are kept
+  /// from this grid, as the grid being merged into defines where and how the whole grid is used. This grid is left
+  /// unchanged if block counts overflow.
+  pub fn merge(&mut self, other: &GridCalculator) -> Result<(), ValidationError> {
+    let mut blocks = self.blocks.clone();
+    Self::merge_counts(&mut blocks, &other.blocks)?;
+    let mut directional_blocks = self.directional_blocks.clone();
+    for (direction, other_blocks) in &other.directional_blocks {
+      Self::merge_counts(directional_blocks.entry(*direction).or_default(), other_blocks)?;
     }
+    let mut custom_power_consumers = self.custom_power_consumers.clone();
+    custom_power_consumers.extend(other.custom_power_consumers.iter().cloned());
+    let mut custom_hydrogen_consumers = self.custom_hydrogen_consumers.clone();
+    custom_hydrogen_consumers.extend(other.custom_hydrogen_consumers.iter().cloned());
+    let merged = Self {
+      blocks,
+      directional_blocks,
+      custom_power_consumers,
+      custom_hydrogen_consumers,
+      additional_mass: self.additional_mass + other.additional_mass,
+      additional_volume: self.additional_volume + other.additional_volume,
+      ..self.clone()
+    };
+    merged.total_block_count().ok_or(ValidationError::BlockCountOverflow)?;
+    *self = merged;
+    Ok(())
+  }
+
+  /// Multiplies all block counts by `factor`, rounding to the nearest count. This grid is left unchanged if `factor` is
+  /// negative or not finite, or if block counts overflow.
+  pub fn scale(&mut self, factor: f64) -> Result<(), ValidationError> {
+    self.scale_where(factor, |_| true)
+  }
 
-    {
-      c.power_idle = c.power_resource(power_consumption_idle);
-      let mut consumption = power_consumption_misc;
-      c.power_misc = c.power_resource(consumption);
-      consumption += power_consumption_jump_drive;
-      c.power_upto_jump_drive = c.power_resource(consumption);
-      consumption += power_consumption_generator;
-      c.power_upto_generator = c.power_resource(consumption);
-      consumption += Self::thruster_consumption_peak(&power_consumption_thruster, Direction::Up, Direction::Down);
-      c.power_upto_up_down_thruster = c.power_resource(consumption);
-      consumption += Self::thruster_consumption_peak(&power_consumption_thruster, Direction::Front, Direction::Back);
-      c.power_upto_front_back_thruster = c.power_resource(consumption);
-      consumption += Self::thruster_consumption_peak(&power_consumption_thruster, Direction::Left, Direction::Right);
-      c.power_upto_left_right_thruster = c.power_resource(consumption);
-      consumption += power_consumption_battery;
-      c.power_upto_battery = c.power_resource(consumption);
+  /// Multiplies counts of blocks of `kinds` by `factor` like `scale`, leaving counts of other blocks unchanged.
+  pub fn scale_kinds(&mut self, factor: f64, kinds: &[BlockKind], blocks: &Blocks) -> Result<(), ValidationError> {
+    self.scale_where(factor, |id| blocks.get(id).map_or(false, |block| kinds.contains(&block.kind())))
+  }
+
+  fn scale_where(&mut self, factor: f64, predicate: impl Fn(&BlockId) -> bool) -> Result<(), ValidationError> {
+    if !factor.is_finite() || factor < 0.0 {
+      return Err(ValidationError::InvalidScaleFactor(factor));
     }
+    let scale_counts = |counts: &HashMap<BlockId, u64>| -> Result<HashMap<BlockId, u64>, ValidationError> {
+      counts.iter().map(|(id, count)| {
+        if !predicate(id) { return Ok((id.clone(), *count)); }
+        let scaled = (*count as f64 * factor).round();
+        if scaled >= u64::MAX as f64 { return Err(ValidationError::BlockCountOverflow); }
+        Ok((id.clone(), scaled as u64))
+      }).collect()
+    };
+    let blocks = scale_counts(&self.blocks)?;
+    let directional_blocks = self.directional_blocks.iter()
+      .map(|(direction, counts)| Ok((*direction, scale_counts(counts)?)))
+      .collect::<Result<_, ValidationError>>()?;
+    let scaled = Self { blocks, directional_blocks, ..self.clone() };
+    scaled.total_block_count().ok_or(ValidationError::BlockCountOverflow)?;
+    *self = scaled;
+    Ok(())
+  }
 
-    {
-      c.hydrogen_idle = c.hydrogen_resource(hydrogen_consumption_idle);
-      let mut consumption = hydrogen_consumption_engine;
-      c.hydrogen_engine = c.hydrogen_resource(consumption);
-      consumption += Self::thruster_consumption_peak(&hydrogen_consumption_thruster, Direction::Up, Direction::Down);
-      c.hydrogen_upto_up_down_thruster = c.hydrogen_resource(consumption);
-      consumption += Self::thruster_consumption_peak(&hydrogen_consumption_thruster, Direction::Front, Direction::Back);
-      c.hydrogen_upto_front_back_thruster = c.hydrogen_resource(consumption);
-      consumption += Self::thruster_consumption_peak(&hydrogen_consumption_thruster, Direction::Left, Direction::Right);
-      c.hydrogen_upto_left_right_thruster = c.hydrogen_resource(consumption);
+  fn merge_counts(counts: &mut HashMap<BlockId, u64>, other: &HashMap<BlockId, u64>) -> Result<(), ValidationError> {
+    for (id, count) in other {
+      let existing = counts.entry(id.clone()).or_insert(0);
+      *existing = existing.checked_add(*count).ok_or(ValidationError::BlockCountOverflow)?;
     }
+    Ok(())
+  }
 
-    c
+  /// Calculates like `calculate`, and additionally calculates the contribution of each entered block.
+  pub fn calculate_with_breakdown(&self, data: &Data) -> GridCalculated {
+    let mut calculated = self.calculate(data);
+    calculated.breakdown = Some(self.breakdown(data));
+    calculated
+  }
+
+  /// Calculates the results of this grid under the rules of the vanilla game.
+  pub fn calculate(&self, data: &Data) -> GridCalculated {
+    self.calculate_with(&VanillaCalculator, data)
   }
 
-  fn thruster_consumption_peak(map: &HashMap<Direction, f64>, side_1: Direction, side2: Direction) -> f64 {
-    let c1 = map.get(&side_1).map(|c| *c).unwrap_or(0.0);
-    let c2 = map.get(&side2).map(|c| *c).unwrap_or(0.0);
-    c1.max(c2)
+  /// Calculates the results of this grid with `calculator`, for calculating with different rules.
+  pub fn calculate_with<C: Calculator + ?Sized>(&self, calculator: &C, data: &Data) -> GridCalculated {
+    calculator.calculate(self, data)
   }
 }
 
 
 #[derive(Default)]
 pub struct GridCalculated {
-  pub total_volume_any: f64,
-  pub total_volume_ore: f64,
-  pub total_volume_ice: f64,
-  pub total_volume_ore_only: f64,
-  pub total_volume_ice_only: f64,
-  pub total_mass_empty: f64,
-  pub total_mass_filled: f64,
+  pub volume_mass: VolumeMassCalculated,
+  pub acceleration: HashMap<Direction, AccelerationCalculated>,
+  pub power: PowerCalculated,
+  pub hydrogen: HydrogenCalculated,
+  pub climb: ClimbCalculated,
+
+  /// Contribution of each entered block, if calculated with `calculate_with_breakdown`.
+  pub breakdown: Option<Vec<BlockContribution>>,
+}
+
+#[derive(Default)]
+pub struct VolumeMassCalculated {
+  pub total_volume_any: Litres,
+  pub total_volume_ore: Litres,
+  pub total_volume_ice: Litres,
+  pub total_volume_ore_only: Litres,
+  pub total_volume_ice_only: Litres,
+  pub total_mass_empty: Kilograms,
+  pub total_mass_filled: Kilograms,
   pub total_items_ice: f64,
   pub total_items_ore: f64,
   pub total_items_steel_plate: f64,
+}
 
-  pub acceleration: HashMap<Direction, AccelerationCalculated>,
+#[derive(Default)]
+pub struct AccelerationCalculated {
+  pub force: Newtons,
+  pub acceleration_empty_no_gravity: MetersPerSecondSquared,
+  pub acceleration_empty_gravity: MetersPerSecondSquared,
+  pub acceleration_filled_no_gravity: MetersPerSecondSquared,
+  pub acceleration_filled_gravity: MetersPerSecondSquared,
+  /// Time and distance to accelerate from standstill to the maximum speed without gravity, which are infinite when
+  /// there is no acceleration.
+  pub time_to_max_speed_empty: Seconds,
+  pub time_to_max_speed_filled: Seconds,
+  pub distance_to_max_speed_empty: Meters,
+  pub distance_to_max_speed_filled: Meters,
+}
 
-  pub power_generation: f64,
-  pub power_capacity_battery: f64,
-  pub power_idle: ResourceCalculated,
-  pub power_misc: ResourceCalculated,
-  pub power_upto_generator: ResourceCalculated,
-  pub power_upto_jump_drive: ResourceCalculated,
-  pub power_upto_up_down_thruster: ResourceCalculated,
-  pub power_upto_front_back_thruster: ResourceCalculated,
-  pub power_upto_left_right_thruster: ResourceCalculated,
-  pub power_upto_battery: ResourceCalculated,
-
-  pub hydrogen_generation: f64,
-  pub hydrogen_capacity_tank: f64,
-  pub hydrogen_capacity_engine: f64,
-  pub hydrogen_idle: ResourceCalculated,
-  pub hydrogen_engine: ResourceCalculated,
-  pub hydrogen_upto_up_down_thruster: ResourceCalculated,
-  pub hydrogen_upto_front_back_thruster: ResourceCalculated,
-  pub hydrogen_upto_left_right_thruster: ResourceCalculated,
+#[derive(Default)]
+pub struct PowerCalculated {
+  pub generation: MegaWatts,
+  /// Output of batteries, which is included in `generation`.
+  pub generation_battery: MegaWatts,
+  pub capacity_battery: MegaWattHours,
+  /// Energy stored in batteries at the start, which durations are calculated with.
+  pub stored_battery: MegaWattHours,
+  /// Duration to fully charge batteries from the stored energy, at their maximum input.
+  pub recharge_duration: Minutes,
+  pub idle: ResourceCalculated<MegaWatts>,
+  pub misc: ResourceCalculated<MegaWatts>,
+  /// Cumulative consumption up to and including each enabled consumer, in priority order.
+  pub upto: Vec<(PowerConsumer, ResourceCalculated<MegaWatts>)>,
+  /// Power consumption of thrusters per direction.
+  pub thrusters: HashMap<Direction, ThrusterConsumption<MegaWatts>>,
+  /// Consumption of all enabled consumers attributed to categories, which sums up to the consumption of `upto_all`.
+  pub budget: Vec<PowerShare>,
+}
+
+/// Category that power consumption is attributed to in the power budget.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum PowerCategory {
+  /// Misc blocks and custom consumers.
+  Misc,
+  /// Antennas, beacons, and ore detectors at the configured range.
+  Utility,
+  /// Idle thrusters, which only consume once any thruster axis is enabled.
+  IdleThrusters,
+  /// Additional consumption of an enabled consumer.
+  Consumer(PowerConsumer),
+}
+
+impl PowerCategory {
+  /// Gets the label of this category in result rows.
+  pub fn label(self) -> &'static str {
+    match self {
+      PowerCategory::Misc => "Misc",
+      PowerCategory::Utility => "Utility",
+      PowerCategory::IdleThrusters => "Idle Thrusters",
+      PowerCategory::Consumer(consumer) => consumer.label(),
+    }
+  }
+}
+
+/// Power consumption attributed to a category, with its share (%) of the consumption of all enabled consumers.
+#[derive(Copy, Clone, Debug)]
+pub struct PowerShare {
+  pub category: PowerCategory,
+  pub consumption: MegaWatts,
+  pub share: f64,
 }
 
 #[derive(Default)]
-pub struct AccelerationCalculated {
-  pub force: f64,
-  pub acceleration_empty_no_gravity: f64,
-  pub acceleration_empty_gravity: f64,
-  pub acceleration_filled_no_gravity: f64,
-  pub acceleration_filled_gravity: f64,
+pub struct HydrogenCalculated {
+  pub generation: LitresPerSecond,
+  /// Capacity of tanks that feed thrusters and engines, which excludes tanks in stockpile mode.
+  pub capacity_tank: Litres,
+  /// Capacity of tanks in stockpile mode.
+  pub capacity_tank_stockpile: Litres,
+  pub capacity_engine: Litres,
+  pub idle: ResourceCalculated<LitresPerSecond>,
+  pub engine: ResourceCalculated<LitresPerSecond>,
+  pub upto_up_down_thruster: ResourceCalculated<LitresPerSecond>,
+  pub upto_front_back_thruster: ResourceCalculated<LitresPerSecond>,
+  pub upto_left_right_thruster: ResourceCalculated<LitresPerSecond>,
+  /// Hydrogen consumption of thrusters per direction.
+  pub thrusters: HashMap<Direction, ThrusterConsumption<LitresPerSecond>>,
+  /// Duration that tanks and generators sustain the hydrogen thrusters of a direction at full thrust, for directions
+  /// with hydrogen thrusters. Infinite if generators keep up.
+  pub burn_duration: HashMap<Direction, Seconds>,
+  pub budget: HydrogenBudget,
+}
+
+/// Category that hydrogen consumption is attributed to in the hydrogen budget.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum HydrogenCategory {
+  /// Hydrogen engines and custom consumers.
+  Engines,
+  IdleThrusters,
+  UpDownThrusters,
+  FrontBackThrusters,
+  LeftRightThrusters,
+}
+
+impl HydrogenCategory {
+  /// Gets the label of this category in result rows.
+  pub fn label(self) -> &'static str {
+    match self {
+      HydrogenCategory::Engines => "Engines",
+      HydrogenCategory::IdleThrusters => "Idle Thrusters",
+      HydrogenCategory::UpDownThrusters => "Up/Down Thrusters",
+      HydrogenCategory::FrontBackThrusters => "Front/Back Thrusters",
+      HydrogenCategory::LeftRightThrusters => "Left/Right Thrusters",
+    }
+  }
+}
+
+/// Hydrogen consumption attributed to a category, with its share (%) of the total consumption.
+#[derive(Copy, Clone, Debug)]
+pub struct HydrogenShare {
+  pub category: HydrogenCategory,
+  pub consumption: LitresPerSecond,
+  pub share: f64,
+}
+
+/// Hydrogen consumption at full thrust on all axes attributed to categories, against the generation of generators.
+#[derive(Default)]
+pub struct HydrogenBudget {
+  pub consumption: Vec<HydrogenShare>,
+  pub generation: LitresPerSecond,
+  /// Share (%) of the total consumption that generation covers.
+  pub generation_share: f64,
+  /// Generation minus total consumption, which is negative when draining tanks.
+  pub net: LitresPerSecond,
+}
+
+/// Consumption of the thrusters facing one direction, when idle and when firing at full thrust.
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub struct ThrusterConsumption<R> {
+  pub idle: R,
+  pub max: R,
+}
+
+/// Sustained climb out of gravity with the filled mass at a constant speed, where up thrusters only counter the weight.
+#[derive(Default)]
+pub struct ClimbCalculated {
+  /// Climb speed that can be sustained, which is the maximum speed if up thrusters lift the filled grid, or 0 otherwise.
+  pub speed: MetersPerSecond,
+  /// Share (%) of up thrust that counters the weight, which is 100 if up thrusters cannot lift the filled grid.
+  pub thrust: f64,
+  /// Power and hydrogen drain while climbing, including misc and engine consumption and idle thrusters.
+  pub power: ResourceCalculated<MegaWatts>,
+  pub hydrogen: ResourceCalculated<LitresPerSecond>,
+  /// Highest descent speed from which up thrusters can stop the grid within the descent altitude, up to the maximum
+  /// speed, or 0 if they cannot lift the grid.
+  pub safe_descent_speed_empty: MetersPerSecond,
+  pub safe_descent_speed_filled: MetersPerSecond,
+  /// Acceleration when climbing with up thrusters, which is their thrust minus the weight.
+  pub climb_acceleration_empty: MetersPerSecondSquared,
+  pub climb_acceleration_filled: MetersPerSecondSquared,
+  /// Acceleration when descending or braking a climb with down thrusters, which is their thrust plus the weight.
+  pub descent_braking_acceleration_empty: MetersPerSecondSquared,
+  pub descent_braking_acceleration_filled: MetersPerSecondSquared,
 }
 
+impl ClimbCalculated {
+  pub fn values(&self) -> Vec<ResultValue> {
+    let mut values = vec![
+      ResultValue::new("Climb Speed", self.speed),
+      ResultValue { label: "Climb Thrust".to_owned(), value: self.thrust, unit: "%", quantity: Quantity::Ratio },
+    ];
+    self.power.push_values(&mut values, "Power", "Climb");
+    self.hydrogen.push_values(&mut values, "Hydrogen", "Climb");
+    values.push(ResultValue::new("Safe Descent Speed - Empty", self.safe_descent_speed_empty));
+    values.push(ResultValue::new("Safe Descent Speed - Filled", self.safe_descent_speed_filled));
+    values.push(ResultValue::new("Climb Acceleration - Empty", self.climb_acceleration_empty));
+    values.push(ResultValue::new("Climb Acceleration - Filled", self.climb_acceleration_filled));
+    values.push(ResultValue::new("Descent Braking Acceleration - Empty", self.descent_braking_acceleration_empty));
+    values.push(ResultValue::new("Descent Braking Acceleration - Filled", self.descent_braking_acceleration_filled));
+    values
+  }
+}
+
+/// Consumption, balance (generation minus consumption), and duration until storage is depleted, of resource `R`.
 #[derive(Default)]
-pub struct ResourceCalculated {
-  pub consumption: f64,
-  pub balance: f64,
-  pub duration: f64,
+pub struct ResourceCalculated<R> {
+  pub consumption: R,
+  pub balance: R,
+  pub duration: Minutes,
 }
 
-impl ResourceCalculated {
-  fn new(consumption: f64, generation: f64, capacity: f64, conversion_rate: f64) -> Self {
+impl<R: Unit + Sub<Output=R>> ResourceCalculated<R> {
+  fn new<C: Div<R>>(consumption: R, generation: R, capacity: C) -> Self where C::Output: Into<Minutes> {
     let balance = generation - consumption;
-    let duration = (capacity / consumption) * conversion_rate;
+    let duration = (capacity / consumption).into();
     ResourceCalculated { consumption, balance, duration }
   }
+
+  fn push_values(&self, values: &mut Vec<ResultValue>, resource: &str, stage: &str) {
+    values.push(ResultValue::new(format!("{} Consumption: {}", resource, stage), self.consumption));
+    values.push(ResultValue::new(format!("{} Balance: {}", resource, stage), self.balance));
+    values.push(ResultValue::new(format!("{} Duration: {}", resource, stage), self.duration));
+  }
+}
+
+/// Single result value with its label, unit, and kind of quantity.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ResultValue {
+  pub label: String,
+  pub value: f64,
+  pub unit: &'static str,
+  pub quantity: Quantity,
+}
+
+impl ResultValue {
+  fn new<L: Into<String>, U: Unit>(label: L, value: U) -> Self {
+    Self { label: label.into(), value: value.into(), unit: U::SYMBOL, quantity: U::QUANTITY }
+  }
+
+  fn items<L: Into<String>>(label: L, value: f64) -> Self {
+    Self { label: label.into(), value, unit: "#", quantity: Quantity::Items }
+  }
+
+  /// Formats the value with the precision of its kind, without the unit.
+  pub fn format(&self) -> String {
+    self.quantity.format(self.value)
+  }
+}
+
+/// Section of result values with a title, for rendering results generically.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ResultSection {
+  pub title: &'static str,
+  pub values: Vec<ResultValue>,
 }
 
 impl GridCalculated {
-  fn power_resource(&self, consumption: f64) -> ResourceCalculated {
-    ResourceCalculated::new(consumption, self.power_generation, self.power_capacity_battery, 60.0 /* MWh to mins */)
+  /// Gets the peak hydrogen consumption of all thrusters, which is zero if there are no hydrogen thrusters.
+  pub fn hydrogen_thruster_consumption(&self) -> LitresPerSecond {
+    self.hydrogen.upto_left_right_thruster.consumption - self.hydrogen.engine.consumption
+  }
+
+  /// Gets the direction with the lowest thrust-to-weight ratio when filled, under `gravity_multiplier` times Earth
+  /// gravity, together with that ratio. Returns `None` if there is no mass or gravity.
+  pub fn worst_thrust_to_weight_ratio(&self, gravity_multiplier: f64) -> Option<(Direction, f64)> {
+    let weight = self.volume_mass.total_mass_filled * STANDARD_GRAVITY * gravity_multiplier;
+    if weight <= Newtons(0.0) { return None; }
+    Direction::iter()
+      .map(|d| (*d, self.acceleration.get(d).map_or(Newtons(0.0), |a| a.force) / weight))
+      .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
   }
 
-  fn hydrogen_resource(&self, consumption: f64) -> ResourceCalculated {
-    ResourceCalculated::new(consumption, self.hydrogen_generation, self.hydrogen_capacity_tank, 1.0 / 60.0 /* L/s to mins */)
+  /// Gets all result sections, in the order they are shown in the GUIs.
+  pub fn sections(&self) -> Vec<ResultSection> {
+    vec![
+      ResultSection { title: "Volume & Mass", values: self.volume_mass.values() },
+      ResultSection { title: "Force & Acceleration", values: self.acceleration_values() },
+      ResultSection { title: "Climb & Descent", values: self.climb.values() },
+      ResultSection { title: "Power", values: self.power.values() },
+      ResultSection { title: "Hydrogen", values: self.hydrogen.values() },
+    ]
+  }
+
+  /// Gets all result values with their label and unit, in the order they are shown in the GUIs.
+  pub fn values(&self) -> Vec<ResultValue> {
+    self.sections().into_iter().flat_map(|s| s.values).collect()
+  }
+
+  fn acceleration_values(&self) -> Vec<ResultValue> {
+    let mut values = Vec::new();
+    for direction in Direction::iter() {
+      let a = self.acceleration.get(direction);
+      let force = a.map_or(Newtons(0.0), |a| a.force);
+      let get = |f: fn(&AccelerationCalculated) -> MetersPerSecondSquared| a.map_or(MetersPerSecondSquared(0.0), f);
+      values.push(ResultValue::new(format!("{:?} Force", direction), force));
+      values.push(ResultValue::new(format!("{:?} Acceleration - Empty, No Gravity", direction), get(|a| a.acceleration_empty_no_gravity)));
+      values.push(ResultValue::new(format!("{:?} Acceleration - Filled, No Gravity", direction), get(|a| a.acceleration_filled_no_gravity)));
+      values.push(ResultValue::new(format!("{:?} Acceleration - Empty, Gravity", direction), get(|a| a.acceleration_empty_gravity)));
+      values.push(ResultValue::new(format!("{:?} Acceleration - Filled, Gravity", direction), get(|a| a.acceleration_filled_gravity)));
+      // Without thrusters in a direction, the maximum speed is never reached.
+      let time = |f: fn(&AccelerationCalculated) -> Seconds| a.map_or(Seconds(f64::INFINITY), f);
+      let distance = |f: fn(&AccelerationCalculated) -> Meters| a.map_or(Meters(f64::INFINITY), f);
+      values.push(ResultValue::new(format!("{:?} Time to Max Speed - Empty", direction), time(|a| a.time_to_max_speed_empty)));
+      values.push(ResultValue::new(format!("{:?} Time to Max Speed - Filled", direction), time(|a| a.time_to_max_speed_filled)));
+      values.push(ResultValue::new(format!("{:?} Distance to Max Speed - Empty", direction), distance(|a| a.distance_to_max_speed_empty)));
+      values.push(ResultValue::new(format!("{:?} Distance to Max Speed - Filled", direction), distance(|a| a.distance_to_max_speed_filled)));
+    }
+    values
+  }
+}
+
+impl VolumeMassCalculated {
+  pub fn values(&self) -> Vec<ResultValue> {
+    vec![
+      ResultValue::new("Total Volume - Any", self.total_volume_any),
+      ResultValue::new("Total Volume - Ore", self.total_volume_ore),
+      ResultValue::new("Total Volume - Ice", self.total_volume_ice),
+      ResultValue::new("Total Volume - Ore Only", self.total_volume_ore_only),
+      ResultValue::new("Total Volume - Ice Only", self.total_volume_ice_only),
+      ResultValue::new("Total Mass - Empty", self.total_mass_empty),
+      ResultValue::new("Total Mass - Filled", self.total_mass_filled),
+      ResultValue::items("Total Items - Ice", self.total_items_ice),
+      ResultValue::items("Total Items - Ore", self.total_items_ore),
+      ResultValue::items("Total Items - Steel Plates", self.total_items_steel_plate),
+    ]
+  }
+}
+
+impl PowerCalculated {
+  pub fn values(&self) -> Vec<ResultValue> {
+    let mut values = vec![
+      ResultValue::new("Power Generation", self.generation),
+      ResultValue::new("Power Capacity: Batteries", self.capacity_battery),
+      ResultValue::new("Power Stored: Batteries", self.stored_battery),
+      ResultValue::new("Power Recharge Duration: Batteries", self.recharge_duration),
+    ];
+    self.idle.push_values(&mut values, "Power", "Idle");
+    self.misc.push_values(&mut values, "Power", "Misc");
+    for (consumer, resource) in &self.upto {
+      resource.push_values(&mut values, "Power", &format!("+{}", consumer.label()));
+    }
+    for share in &self.budget {
+      values.push(ResultValue::new(format!("Power Budget: {}", share.category.label()), share.consumption));
+      values.push(ResultValue { label: format!("Power Budget Share: {}", share.category.label()), value: share.share, unit: "%", quantity: Quantity::Ratio });
+    }
+    values
+  }
+
+  /// Gets the cumulative consumption up to and including `consumer`, or `None` if `consumer` is disabled.
+  pub fn upto(&self, consumer: PowerConsumer) -> Option<&ResourceCalculated<MegaWatts>> {
+    self.upto.iter().find(|(c, _)| *c == consumer).map(|(_, r)| r)
+  }
+
+  /// Gets the cumulative consumption of all enabled consumers, which is the misc consumption if all are disabled.
+  pub fn upto_all(&self) -> &ResourceCalculated<MegaWatts> {
+    self.upto.last().map_or(&self.misc, |(_, r)| r)
+  }
+
+  /// Iterates over the consumption that each enabled consumer adds, in priority order.
+  pub fn added_consumption(&self) -> impl Iterator<Item=(PowerConsumer, MegaWatts)> + '_ {
+    let previous = std::iter::once(self.misc.consumption).chain(self.upto.iter().map(|(_, r)| r.consumption));
+    self.upto.iter().zip(previous).map(|((c, r), previous)| (*c, r.consumption - previous))
+  }
+
+  fn resource(&self, consumption: MegaWatts) -> ResourceCalculated<MegaWatts> {
+    ResourceCalculated::new(consumption, self.generation, self.stored_battery)
+  }
+}
+
+impl HydrogenCalculated {
+  pub fn values(&self) -> Vec<ResultValue> {
+    let mut values = vec![
+      ResultValue::new("Hydrogen Generation", self.generation),
+      ResultValue::new("Hydrogen Capacity: Engines", self.capacity_engine),
+      ResultValue::new("Hydrogen Capacity: Tanks", self.capacity_tank),
+      ResultValue::new("Hydrogen Capacity: Stockpiled Tanks", self.capacity_tank_stockpile),
+    ];
+    self.idle.push_values(&mut values, "Hydrogen", "Idle");
+    self.engine.push_values(&mut values, "Hydrogen", "Engines");
+    self.upto_up_down_thruster.push_values(&mut values, "Hydrogen", "+Up/Down Thrusters");
+    self.upto_front_back_thruster.push_values(&mut values, "Hydrogen", "+Front/Back Thrusters");
+    self.upto_left_right_thruster.push_values(&mut values, "Hydrogen", "+Left/Right Thrusters");
+    for direction in Direction::iter() {
+      if let Some(duration) = self.burn_duration.get(direction) {
+        values.push(ResultValue::new(format!("Hydrogen Full Thrust Burn: {:?}", direction), *duration));
+      }
+    }
+    for share in &self.budget.consumption {
+      values.push(ResultValue::new(format!("Hydrogen Budget: {}", share.category.label()), share.consumption));
+      values.push(ResultValue { label: format!("Hydrogen Budget Share: {}", share.category.label()), value: share.share, unit: "%", quantity: Quantity::Ratio });
+    }
+    values.push(ResultValue::new("Hydrogen Budget: Generators", self.budget.generation));
+    values.push(ResultValue { label: "Hydrogen Budget Share: Generators".to_owned(), value: self.budget.generation_share, unit: "%", quantity: Quantity::Ratio });
+    values.push(ResultValue::new("Hydrogen Budget: Net", self.budget.net));
+    values
+  }
+
+  fn resource(&self, consumption: LitresPerSecond) -> ResourceCalculated<LitresPerSecond> {
+    ResourceCalculated::new(consumption, self.generation, self.capacity_tank)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::data::blocks::BlockKind;
+  use crate::data::Data;
+  use crate::units::{Kilograms, Litres, MegaWatts};
+
+  use super::{CustomConsumer, Direction, GridCalculator};
+  use super::validate::ValidationError;
+
+  const REACTOR: &str = "Reactor.LargeBlockSmallGenerator";
+  const BATTERY: &str = "BatteryBlock.LargeBlockBatteryBlock";
+  const THRUSTER: &str = "Thrust.LargeBlockSmallThrust";
+
+  fn grid(reactors: u64, batteries: u64, thrusters_up: u64) -> GridCalculator {
+    let mut grid = GridCalculator::default();
+    grid.blocks.insert(REACTOR.to_owned(), reactors);
+    grid.blocks.insert(BATTERY.to_owned(), batteries);
+    grid.direction_mut(Direction::Up).insert(THRUSTER.to_owned(), thrusters_up);
+    grid
+  }
+
+  fn count(grid: &GridCalculator, id: &str) -> u64 {
+    grid.blocks.get(id).copied().unwrap_or(0)
+  }
+
+  #[test]
+  fn merge_sums_counts_and_mass_and_keeps_options() {
+    let mut grid = grid(2, 1, 4);
+    grid.additional_mass = Kilograms(1000.0);
+    grid.additional_volume = Litres(50.0);
+    grid.gravity_multiplier = 0.5;
+    grid.custom_power_consumers.push(CustomConsumer { name: "A".to_owned(), consumption: MegaWatts(1.0) });
+    let mut other = self::grid(1, 0, 2);
+    other.blocks.insert("Other".to_owned(), 3);
+    other.direction_mut(Direction::Down).insert(THRUSTER.to_owned(), 1);
+    other.additional_mass = Kilograms(500.0);
+    other.additional_volume = Litres(25.0);
+    other.gravity_multiplier = 2.0;
+    other.ice_only_fill = 10.0;
+    other.custom_power_consumers.push(CustomConsumer { name: "B".to_owned(), consumption: MegaWatts(2.0) });
+
+    grid.merge(&other).unwrap();
+    assert_eq!(count(&grid, REACTOR), 3);
+    assert_eq!(count(&grid, BATTERY), 1);
+    assert_eq!(count(&grid, "Other"), 3);
+    assert_eq!(grid.direction_count(Direction::Up, &THRUSTER.to_owned()), 6);
+    assert_eq!(grid.direction_count(Direction::Down, &THRUSTER.to_owned()), 1);
+    assert_eq!(grid.additional_mass, Kilograms(1500.0));
+    assert_eq!(grid.additional_volume, Litres(75.0));
+    assert_eq!(grid.gravity_multiplier, 0.5);
+    assert_eq!(grid.ice_only_fill, GridCalculator::default().ice_only_fill);
+    let names: Vec<_> = grid.custom_power_consumers.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["A", "B"]);
+  }
+
+  #[test]
+  fn merge_overflow_leaves_grid_unchanged() {
+    // Overflow of a single count, in directional blocks after the other blocks merged fine.
+    let mut grid = grid(1, 1, u64::MAX);
+    let original = grid.clone();
+    match grid.merge(&self::grid(1, 1, 1)) {
+      Err(ValidationError::BlockCountOverflow) => {}
+      r => panic!("unexpected result: {:?}", r),
+    }
+    assert_eq!(grid, original);
+    // Overflow of the total count, while each count fits.
+    let mut grid = self::grid(u64::MAX / 2, 0, 0);
+    let original = grid.clone();
+    match grid.merge(&self::grid(0, u64::MAX / 2 + 2, 0)) {
+      Err(ValidationError::BlockCountOverflow) => {}
+      r => panic!("unexpected result: {:?}", r),
+    }
+    assert_eq!(grid, original);
+  }
+
+  #[test]
+  fn scale_rounds_to_nearest_count() {
+    let mut grid = grid(3, 5, 1);
+    grid.scale(0.5).unwrap();
+    assert_eq!(count(&grid, REACTOR), 2);
+    assert_eq!(count(&grid, BATTERY), 3);
+    assert_eq!(grid.direction_count(Direction::Up, &THRUSTER.to_owned()), 1);
+    grid.scale(0.2).unwrap();
+    assert_eq!(count(&grid, REACTOR), 0);
+    assert_eq!(count(&grid, BATTERY), 1);
+    assert_eq!(grid.direction_count(Direction::Up, &THRUSTER.to_owned()), 0);
+  }
+
+  #[test]
+  fn scale_kinds_leaves_other_kinds_untouched() {
+    let data = Data::from_path(concat!(env!("CARGO_MANIFEST_DIR"), "/../../data/data.json")).unwrap();
+    let mut grid = grid(2, 3, 4);
+    grid.scale_kinds(2.0, &[BlockKind::Reactor, BlockKind::Thruster], &data.blocks).unwrap();
+    assert_eq!(count(&grid, REACTOR), 4);
+    assert_eq!(count(&grid, BATTERY), 3);
+    assert_eq!(grid.direction_count(Direction::Up, &THRUSTER.to_owned()), 8);
+  }
+
+  #[test]
+  fn scale_rejects_invalid_factors_and_overflow() {
+    let mut grid = grid(2, 3, 4);
+    let original = grid.clone();
+    for &factor in &[-1.0, f64::NAN, f64::INFINITY] {
+      match grid.scale(factor) {
+        Err(ValidationError::InvalidScaleFactor(_)) => {}
+        r => panic!("unexpected result for {}: {:?}", factor, r),
+      }
+      assert_eq!(grid, original);
+    }
+    let mut grid = self::grid(1, 1, u64::MAX / 2);
+    let original = grid.clone();
+    match grid.scale(3.0) {
+      Err(ValidationError::BlockCountOverflow) => {}
+      r => panic!("unexpected result: {:?}", r),
+    }
+    assert_eq!(grid, original);
   }
 }
@@ -0,0 +1,93 @@
+use crate::data::Data;
+use crate::precision::Quantity;
+
+use super::{GridCalculated, GridCalculator};
+
+/// Gravity and atmosphere at the surface of a planet or moon, or in space.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PlanetPreset {
+  pub name: &'static str,
+  pub gravity_multiplier: f64,
+  pub planetary_influence: f64,
+}
+
+/// Presets for space and the surfaces of the planets and moons of the default solar system.
+pub const PLANET_PRESETS: [PlanetPreset; 5] = [
+  PlanetPreset { name: "Space", gravity_multiplier: 0.0, planetary_influence: 0.0 },
+  PlanetPreset { name: "Earthlike", gravity_multiplier: 1.0, planetary_influence: 1.0 },
+  PlanetPreset { name: "Mars", gravity_multiplier: 0.9, planetary_influence: 1.0 },
+  PlanetPreset { name: "Alien", gravity_multiplier: 1.1, planetary_influence: 1.0 },
+  PlanetPreset { name: "Moon", gravity_multiplier: 0.25, planetary_influence: 0.0 },
+];
+
+/// Environments that grids are evaluated in by default with `GridCalculator::calculate_in_environments`: space, the
+/// Moon, and the surface of Earthlike planets.
+pub const DEFAULT_ENVIRONMENTS: [PlanetPreset; 3] = [PLANET_PRESETS[0], PLANET_PRESETS[4], PLANET_PRESETS[1]];
+
+impl PlanetPreset {
+  /// Sets the gravity multiplier and planetary influence of `calculator` to those of this preset.
+  pub fn apply(&self, calculator: &mut GridCalculator) {
+    calculator.gravity_multiplier = self.gravity_multiplier;
+    calculator.planetary_influence = self.planetary_influence;
+  }
+
+  /// Finds the preset with the same gravity multiplier and planetary influence as `calculator`, if any.
+  pub fn find(calculator: &GridCalculator) -> Option<&'static PlanetPreset> {
+    PLANET_PRESETS.iter().find(|p| p.gravity_multiplier == calculator.gravity_multiplier && p.planetary_influence == calculator.planetary_influence)
+  }
+
+  /// Finds the preset named `name`, ignoring case.
+  pub fn find_by_name(name: &str) -> Option<&'static PlanetPreset> {
+    PLANET_PRESETS.iter().find(|p| p.name.eq_ignore_ascii_case(name))
+  }
+}
+
+/// Grid calculated in an environment.
+pub struct EnvironmentCalculated {
+  pub environment: PlanetPreset,
+  pub calculated: GridCalculated,
+}
+
+impl GridCalculator {
+  /// Calculates this grid once in each of `environments`, with all other options unchanged. Results are returned in
+  /// the order of `environments`.
+  pub fn calculate_in_environments(&self, data: &Data, environments: &[PlanetPreset]) -> Vec<EnvironmentCalculated> {
+    let mut calculator = self.clone();
+    environments.iter().map(|environment| {
+      environment.apply(&mut calculator);
+      EnvironmentCalculated { environment: *environment, calculated: calculator.calculate(data) }
+    }).collect()
+  }
+}
+
+/// Single result value of a grid calculated in multiple environments.
+#[derive(Clone, PartialEq, Debug)]
+pub struct EnvironmentValues {
+  pub label: String,
+  pub unit: &'static str,
+  pub quantity: Quantity,
+  /// Value in each environment, in the order of the environments.
+  pub values: Vec<f64>,
+}
+
+impl EnvironmentValues {
+  /// Returns true if the value differs between environments.
+  pub fn is_varying(&self) -> bool {
+    self.values.windows(2).any(|w| w[0] != w[1])
+  }
+}
+
+/// Gets all result values of `results` with their value in each environment, in the order they are shown in the GUIs.
+pub fn values_per_environment(results: &[EnvironmentCalculated]) -> Vec<EnvironmentValues> {
+  let per_environment: Vec<_> = results.iter().map(|r| r.calculated.values()).collect();
+  let first = match per_environment.first() {
+    Some(first) => first,
+    None => return Vec::new(),
+  };
+  first.iter().enumerate().map(|(index, value)| EnvironmentValues {
+    label: value.label.clone(),
+    unit: value.unit,
+    quantity: value.quantity,
+    values: per_environment.iter().map(|values| values[index].value).collect(),
+  }).collect()
+}
@@ -0,0 +1,39 @@
+use super::{GridCalculator, ReadError};
+
+/// Example calculator bundled with the calculator, serving as a starting point and as documentation of typical values.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct GridPreset {
+  pub name: &'static str,
+  pub description: &'static str,
+  json: &'static str,
+}
+
+pub const GRID_PRESETS: [GridPreset; 4] = [
+  GridPreset {
+    name: "Small Miner",
+    description: "Small grid atmospheric miner with batteries, filled with ore",
+    json: include_str!("../../../../grids/presets/small_miner.grid.json"),
+  },
+  GridPreset {
+    name: "Large Hauler",
+    description: "Large grid ion hauler for use in space, powered by reactors, filled with ore",
+    json: include_str!("../../../../grids/presets/large_hauler.grid.json"),
+  },
+  GridPreset {
+    name: "Atmospheric Fighter",
+    description: "Small grid battery powered atmospheric fighter",
+    json: include_str!("../../../../grids/presets/atmospheric_fighter.grid.json"),
+  },
+  GridPreset {
+    name: "Hydrogen Lander",
+    description: "Large grid hydrogen lander for earthlike planets, with hydrogen generators and tanks",
+    json: include_str!("../../../../grids/presets/hydrogen_lander.grid.json"),
+  },
+];
+
+impl GridPreset {
+  /// Creates a calculator from this preset.
+  pub fn load(&self) -> Result<GridCalculator, ReadError> {
+    GridCalculator::from_json(self.json.as_bytes())
+  }
+}
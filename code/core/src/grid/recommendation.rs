@@ -0,0 +1,110 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::data::blocks::{Block, BlockId, GridSize, Thruster};
+use crate::data::Data;
+use crate::units::{MegaWatts, Minutes, Newtons, Seconds, STANDARD_GRAVITY, Unit};
+
+use super::{Direction, GridCalculated, GridCalculator, PowerConsumer};
+
+/// Full thrust burn duration that hydrogen tank recommendations aim for.
+pub const TARGET_BURN_DURATION: Seconds = Seconds(600.0);
+
+/// Block to add to a grid, with the number of blocks to add.
+#[derive(Clone, PartialEq, Debug)]
+pub struct BlockAddition {
+  pub id: BlockId,
+  /// Localized name of the block.
+  pub name: String,
+  pub size: GridSize,
+  pub count: u64,
+}
+
+impl Display for BlockAddition {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "{} {:?} {}", self.count, self.size, self.name)
+  }
+}
+
+/// Suggestion for solving a problem with a calculated grid.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Recommendation {
+  /// Add batteries to cover a power `deficit` of all enabled consumers except battery charging.
+  AddBatteries { addition: BlockAddition, deficit: MegaWatts },
+  /// Add hydrogen tanks to sustain a full thrust burn in `direction` for `TARGET_BURN_DURATION`.
+  AddHydrogenTanks { addition: BlockAddition, direction: Direction },
+  /// Add upward thrusters to lift the filled grid in gravity.
+  AddUpThrusters { addition: BlockAddition },
+}
+
+impl Display for Recommendation {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Recommendation::AddBatteries { addition, deficit } => write!(f, "Add ~{} to cover the power deficit of {} MW", addition, deficit.format()),
+      Recommendation::AddHydrogenTanks { addition, direction } => write!(f, "Add ~{} for {} min of full thrust burn in {:?} direction", addition, Minutes::from(TARGET_BURN_DURATION).format(), direction),
+      Recommendation::AddUpThrusters { addition } => write!(f, "Add ~{} facing up to lift the filled grid", addition),
+    }
+  }
+}
+
+
+/// Generates recommendations for `calculated`, which must be calculated from `grid`. Blocks are recommended in the
+/// grid size that most of the entered blocks have.
+pub fn recommend(calculated: &GridCalculated, grid: &GridCalculator, data: &Data) -> Vec<Recommendation> {
+  let mut recommendations = Vec::new();
+  let (small, large) = grid.block_count_by_size(data);
+  let size = if small > large { GridSize::Small } else { GridSize::Large };
+
+  // Battery charging is excluded, as adding batteries also adds to their charging.
+  let charging = calculated.power.added_consumption()
+    .find(|(consumer, _)| *consumer == PowerConsumer::Battery)
+    .map_or(MegaWatts(0.0), |(_, added)| added);
+  let deficit = calculated.power.upto_all().consumption - charging - calculated.power.generation;
+  if deficit > MegaWatts(0.0) {
+    let battery = best(data.blocks.batteries.values(), size, |b| b.details.output);
+    if let Some(addition) = battery.and_then(|b| addition(b, data, deficit.0, b.details.output)) {
+      recommendations.push(Recommendation::AddBatteries { addition, deficit });
+    }
+  }
+
+  let shortest_burn = calculated.hydrogen.burn_duration.iter()
+    .filter(|(_, duration)| **duration < TARGET_BURN_DURATION)
+    .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+  if let Some((direction, _)) = shortest_burn {
+    let consumption = calculated.hydrogen.thrusters.get(direction).map_or(Default::default(), |c| c.max);
+    let required = (consumption - calculated.hydrogen.generation) * TARGET_BURN_DURATION - calculated.hydrogen.capacity_tank;
+    let tank = best(data.blocks.hydrogen_tanks.values(), size, |b| b.details.capacity);
+    if let Some(addition) = tank.and_then(|b| addition(b, data, required.0, b.details.capacity)) {
+      recommendations.push(Recommendation::AddHydrogenTanks { addition, direction: *direction });
+    }
+  }
+
+  let weight = calculated.volume_mass.total_mass_filled * STANDARD_GRAVITY * grid.gravity_multiplier;
+  let force = calculated.acceleration.get(&Direction::Up).map_or(Newtons(0.0), |a| a.force);
+  if weight > force {
+    // Prefer thrusters that the grid already has, as those suit its fuel and environment.
+    let entered = |b: &&Block<_>| grid.directional_blocks.values().any(|blocks| blocks.get(&b.id).map_or(false, |c| *c > 0));
+    let effective_force = |b: &Block<Thruster>| b.details.force * b.details.effectiveness(grid.planetary_influence);
+    let thruster = best(data.blocks.thrusters.values().filter(entered), size, effective_force)
+      .or_else(|| best(data.blocks.thrusters.values(), size, effective_force));
+    if let Some(addition) = thruster.and_then(|b| addition(b, data, (weight - force).0, effective_force(b))) {
+      recommendations.push(Recommendation::AddUpThrusters { addition });
+    }
+  }
+
+  recommendations
+}
+
+/// Gets the block of `size` with the highest positive `value`.
+fn best<'a, T: 'a>(blocks: impl Iterator<Item=&'a Block<T>>, size: GridSize, value: impl Fn(&Block<T>) -> f64) -> Option<&'a Block<T>> {
+  blocks
+    .filter(|b| b.size == size && value(b) > 0.0)
+    .max_by(|a, b| value(a).partial_cmp(&value(b)).unwrap_or(std::cmp::Ordering::Equal))
+}
+
+/// Solves the number of `block`s that provide at least `required` when each provides `per_block`.
+fn addition<T>(block: &Block<T>, data: &Data, required: f64, per_block: f64) -> Option<BlockAddition> {
+  if !(required > 0.0 && per_block > 0.0) { return None; }
+  let count = (required / per_block).ceil();
+  if !count.is_finite() { return None; }
+  Some(BlockAddition { id: block.id.clone(), name: block.name(&data.localization).to_owned(), size: block.size, count: count as u64 })
+}
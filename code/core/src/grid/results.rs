@@ -0,0 +1,468 @@
+//! Stable external JSON layout of calculated results, for downstream tools. This layout is separate from
+//! `GridCalculated` so that its fields can be refactored freely: every field here has an explicit name that must not be
+//! changed. Fields may be added, but existing fields are only removed or changed together with an increment of
+//! `FORMAT_VERSION`.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use super::{AccelerationCalculated, Direction, GridCalculated, PowerCalculated, PowerConsumer, ResourceCalculated, ThrusterConsumption};
+use crate::units::Unit;
+
+/// Version of the JSON layout, incremented on incompatible changes.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Calculated results. Volumes are in L, masses in kg, forces in N, accelerations in m/s^2, power in MW, energy in
+/// MWh, hydrogen flows in L/s, and durations in minutes.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct ExportedResults {
+  #[serde(rename = "format_version")]
+  pub format_version: u32,
+  #[serde(rename = "volume_mass")]
+  pub volume_mass: ExportedVolumeMass,
+  /// Acceleration in each direction, keyed by `up`, `down`, `front`, `back`, `left`, and `right`.
+  #[serde(rename = "acceleration")]
+  pub acceleration: BTreeMap<String, ExportedAcceleration>,
+  #[serde(rename = "power")]
+  pub power: ExportedPower,
+  #[serde(rename = "hydrogen")]
+  pub hydrogen: ExportedHydrogen,
+  #[serde(rename = "climb", default)]
+  pub climb: ExportedClimb,
+  /// Contribution of each entered block, if calculated with a breakdown.
+  #[serde(rename = "breakdown", default, skip_serializing_if = "Option::is_none")]
+  pub breakdown: Option<Vec<ExportedContribution>>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct ExportedVolumeMass {
+  #[serde(rename = "total_volume_any")]
+  pub total_volume_any: f64,
+  #[serde(rename = "total_volume_ore")]
+  pub total_volume_ore: f64,
+  #[serde(rename = "total_volume_ice")]
+  pub total_volume_ice: f64,
+  #[serde(rename = "total_volume_ore_only")]
+  pub total_volume_ore_only: f64,
+  #[serde(rename = "total_volume_ice_only")]
+  pub total_volume_ice_only: f64,
+  #[serde(rename = "total_mass_empty")]
+  pub total_mass_empty: f64,
+  #[serde(rename = "total_mass_filled")]
+  pub total_mass_filled: f64,
+  #[serde(rename = "total_items_ice")]
+  pub total_items_ice: f64,
+  #[serde(rename = "total_items_ore")]
+  pub total_items_ore: f64,
+  #[serde(rename = "total_items_steel_plate")]
+  pub total_items_steel_plate: f64,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct ExportedAcceleration {
+  #[serde(rename = "force")]
+  pub force: f64,
+  #[serde(rename = "empty_no_gravity")]
+  pub empty_no_gravity: f64,
+  #[serde(rename = "empty_gravity")]
+  pub empty_gravity: f64,
+  #[serde(rename = "filled_no_gravity")]
+  pub filled_no_gravity: f64,
+  #[serde(rename = "filled_gravity")]
+  pub filled_gravity: f64,
+  /// Time (s) and distance (m) to reach the maximum speed without gravity, which are `null` if it is never reached.
+  #[serde(rename = "time_to_max_speed_empty")]
+  pub time_to_max_speed_empty: Option<f64>,
+  #[serde(rename = "time_to_max_speed_filled")]
+  pub time_to_max_speed_filled: Option<f64>,
+  #[serde(rename = "distance_to_max_speed_empty")]
+  pub distance_to_max_speed_empty: Option<f64>,
+  #[serde(rename = "distance_to_max_speed_filled")]
+  pub distance_to_max_speed_filled: Option<f64>,
+}
+
+/// Sustained climb with the filled mass, with the speed (m/s), share (%) of up thrust, and power and hydrogen drain.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+pub struct ExportedClimb {
+  #[serde(rename = "speed")]
+  pub speed: f64,
+  #[serde(rename = "thrust")]
+  pub thrust: f64,
+  #[serde(rename = "power")]
+  pub power: ExportedResource,
+  #[serde(rename = "hydrogen")]
+  pub hydrogen: ExportedResource,
+  /// Highest descent speeds (m/s) from which the grid can stop within the descent altitude.
+  #[serde(rename = "safe_descent_speed_empty", default)]
+  pub safe_descent_speed_empty: f64,
+  #[serde(rename = "safe_descent_speed_filled", default)]
+  pub safe_descent_speed_filled: f64,
+  /// Accelerations (m/s^2) when climbing with up thrusters against the weight, and when descending or braking a climb
+  /// with down thrusters aided by the weight.
+  #[serde(rename = "climb_acceleration_empty", default)]
+  pub climb_acceleration_empty: f64,
+  #[serde(rename = "climb_acceleration_filled", default)]
+  pub climb_acceleration_filled: f64,
+  #[serde(rename = "descent_braking_acceleration_empty", default)]
+  pub descent_braking_acceleration_empty: f64,
+  #[serde(rename = "descent_braking_acceleration_filled", default)]
+  pub descent_braking_acceleration_filled: f64,
+}
+
+/// Consumption and balance of a resource, and the duration until its storage is depleted, which is `null` if it is
+/// never depleted.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+pub struct ExportedResource {
+  #[serde(rename = "consumption")]
+  pub consumption: f64,
+  #[serde(rename = "balance")]
+  pub balance: f64,
+  #[serde(rename = "duration")]
+  pub duration: Option<f64>,
+}
+
+/// Power results. The `upto_` fields are cumulative in the order of `priority`, where consumers that are disabled by
+/// being absent from `priority` add no consumption and come after all enabled consumers.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct ExportedPower {
+  #[serde(rename = "generation")]
+  pub generation: f64,
+  #[serde(rename = "battery_capacity")]
+  pub battery_capacity: f64,
+  /// Energy stored in batteries at the start.
+  #[serde(rename = "battery_stored", default)]
+  pub battery_stored: f64,
+  #[serde(rename = "battery_recharge_duration", default)]
+  pub battery_recharge_duration: Option<f64>,
+  #[serde(rename = "idle")]
+  pub idle: ExportedResource,
+  #[serde(rename = "misc")]
+  pub misc: ExportedResource,
+  #[serde(rename = "upto_generator")]
+  pub upto_generator: ExportedResource,
+  #[serde(rename = "upto_jump_drive")]
+  pub upto_jump_drive: ExportedResource,
+  #[serde(rename = "upto_up_down_thruster")]
+  pub upto_up_down_thruster: ExportedResource,
+  #[serde(rename = "upto_front_back_thruster")]
+  pub upto_front_back_thruster: ExportedResource,
+  #[serde(rename = "upto_left_right_thruster")]
+  pub upto_left_right_thruster: ExportedResource,
+  #[serde(rename = "upto_ship_tool", default)]
+  pub upto_ship_tool: ExportedResource,
+  #[serde(rename = "upto_battery")]
+  pub upto_battery: ExportedResource,
+  /// Enabled consumers in priority order, named like `jump-drive` or `up-down-thruster`.
+  #[serde(rename = "priority", default)]
+  pub priority: Vec<String>,
+  /// Consumption of thrusters per direction, keyed like `ExportedResults::acceleration`.
+  #[serde(rename = "thrusters", default)]
+  pub thrusters: BTreeMap<String, ExportedThrusterConsumption>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct ExportedHydrogen {
+  #[serde(rename = "generation")]
+  pub generation: f64,
+  /// Capacity of tanks that feed thrusters and engines.
+  #[serde(rename = "tank_capacity")]
+  pub tank_capacity: f64,
+  /// Capacity of tanks in stockpile mode.
+  #[serde(rename = "tank_capacity_stockpile", default)]
+  pub tank_capacity_stockpile: f64,
+  #[serde(rename = "engine_capacity")]
+  pub engine_capacity: f64,
+  #[serde(rename = "idle")]
+  pub idle: ExportedResource,
+  #[serde(rename = "engine")]
+  pub engine: ExportedResource,
+  #[serde(rename = "upto_up_down_thruster")]
+  pub upto_up_down_thruster: ExportedResource,
+  #[serde(rename = "upto_front_back_thruster")]
+  pub upto_front_back_thruster: ExportedResource,
+  #[serde(rename = "upto_left_right_thruster")]
+  pub upto_left_right_thruster: ExportedResource,
+  /// Consumption of thrusters per direction, keyed like `ExportedResults::acceleration`.
+  #[serde(rename = "thrusters", default)]
+  pub thrusters: BTreeMap<String, ExportedThrusterConsumption>,
+  /// Duration (s) of a full thrust burn per direction with hydrogen thrusters, which is `null` if generators keep up.
+  #[serde(rename = "burn_duration", default)]
+  pub burn_duration: BTreeMap<String, Option<f64>>,
+}
+
+/// Consumption of the thrusters facing one direction, when idle and when firing at full thrust.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct ExportedThrusterConsumption {
+  #[serde(rename = "idle")]
+  pub idle: f64,
+  #[serde(rename = "max")]
+  pub max: f64,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct ExportedContribution {
+  #[serde(rename = "block_id")]
+  pub block_id: String,
+  #[serde(rename = "count")]
+  pub count: u64,
+  #[serde(rename = "mass")]
+  pub mass: f64,
+  #[serde(rename = "volume")]
+  pub volume: f64,
+  #[serde(rename = "power_generation")]
+  pub power_generation: f64,
+  #[serde(rename = "power_consumption")]
+  pub power_consumption: f64,
+  #[serde(rename = "hydrogen_generation")]
+  pub hydrogen_generation: f64,
+  #[serde(rename = "hydrogen_consumption")]
+  pub hydrogen_consumption: f64,
+  #[serde(rename = "force")]
+  pub force: f64,
+}
+
+impl Direction {
+  /// Gets the stable name of this direction in exported results.
+  fn export_name(self) -> &'static str {
+    match self {
+      Direction::Up => "up",
+      Direction::Down => "down",
+      Direction::Front => "front",
+      Direction::Back => "back",
+      Direction::Left => "left",
+      Direction::Right => "right",
+    }
+  }
+}
+
+impl From<&GridCalculated> for ExportedResults {
+  fn from(c: &GridCalculated) -> Self {
+    let v = &c.volume_mass;
+    let volume_mass = ExportedVolumeMass {
+      total_volume_any: v.total_volume_any.0,
+      total_volume_ore: v.total_volume_ore.0,
+      total_volume_ice: v.total_volume_ice.0,
+      total_volume_ore_only: v.total_volume_ore_only.0,
+      total_volume_ice_only: v.total_volume_ice_only.0,
+      total_mass_empty: v.total_mass_empty.0,
+      total_mass_filled: v.total_mass_filled.0,
+      total_items_ice: v.total_items_ice,
+      total_items_ore: v.total_items_ore,
+      total_items_steel_plate: v.total_items_steel_plate,
+    };
+    let default_acceleration = AccelerationCalculated::default();
+    let acceleration = Direction::iter().map(|direction| {
+      let a = c.acceleration.get(direction).unwrap_or(&default_acceleration);
+      (direction.export_name().to_owned(), ExportedAcceleration {
+        force: a.force.0,
+        empty_no_gravity: a.acceleration_empty_no_gravity.0,
+        empty_gravity: a.acceleration_empty_gravity.0,
+        filled_no_gravity: a.acceleration_filled_no_gravity.0,
+        filled_gravity: a.acceleration_filled_gravity.0,
+        time_to_max_speed_empty: finite(a.time_to_max_speed_empty.0),
+        time_to_max_speed_filled: finite(a.time_to_max_speed_filled.0),
+        distance_to_max_speed_empty: finite(a.distance_to_max_speed_empty.0),
+        distance_to_max_speed_filled: finite(a.distance_to_max_speed_filled.0),
+      })
+    }).collect();
+    let p = &c.power;
+    let power = ExportedPower {
+      generation: p.generation.0,
+      battery_capacity: p.capacity_battery.0,
+      battery_stored: p.stored_battery.0,
+      battery_recharge_duration: if p.recharge_duration.0.is_finite() { Some(p.recharge_duration.0) } else { None },
+      idle: (&p.idle).into(),
+      misc: (&p.misc).into(),
+      upto_generator: export_upto(p, PowerConsumer::Generator),
+      upto_jump_drive: export_upto(p, PowerConsumer::JumpDrive),
+      upto_up_down_thruster: export_upto(p, PowerConsumer::UpDownThruster),
+      upto_front_back_thruster: export_upto(p, PowerConsumer::FrontBackThruster),
+      upto_left_right_thruster: export_upto(p, PowerConsumer::LeftRightThruster),
+      upto_ship_tool: export_upto(p, PowerConsumer::ShipTool),
+      upto_battery: export_upto(p, PowerConsumer::Battery),
+      priority: p.upto.iter().map(|(consumer, _)| consumer.name().to_owned()).collect(),
+      thrusters: export_thrusters(&p.thrusters),
+    };
+    let h = &c.hydrogen;
+    let hydrogen = ExportedHydrogen {
+      generation: h.generation.0,
+      tank_capacity: h.capacity_tank.0,
+      tank_capacity_stockpile: h.capacity_tank_stockpile.0,
+      engine_capacity: h.capacity_engine.0,
+      idle: (&h.idle).into(),
+      engine: (&h.engine).into(),
+      upto_up_down_thruster: (&h.upto_up_down_thruster).into(),
+      upto_front_back_thruster: (&h.upto_front_back_thruster).into(),
+      upto_left_right_thruster: (&h.upto_left_right_thruster).into(),
+      thrusters: export_thrusters(&h.thrusters),
+      burn_duration: h.burn_duration.iter().map(|(d, duration)| (d.export_name().to_owned(), finite(duration.0))).collect(),
+    };
+    let breakdown = c.breakdown.as_ref().map(|breakdown| breakdown.iter().map(|b| ExportedContribution {
+      block_id: b.id.clone(),
+      count: b.count,
+      mass: b.mass.0,
+      volume: b.volume.0,
+      power_generation: b.power_generation.0,
+      power_consumption: b.power_consumption.0,
+      hydrogen_generation: b.hydrogen_generation.0,
+      hydrogen_consumption: b.hydrogen_consumption.0,
+      force: b.force.0,
+    }).collect());
+    let climb = ExportedClimb {
+      speed: c.climb.speed.0,
+      thrust: c.climb.thrust,
+      power: (&c.climb.power).into(),
+      hydrogen: (&c.climb.hydrogen).into(),
+      safe_descent_speed_empty: c.climb.safe_descent_speed_empty.0,
+      safe_descent_speed_filled: c.climb.safe_descent_speed_filled.0,
+      climb_acceleration_empty: c.climb.climb_acceleration_empty.0,
+      climb_acceleration_filled: c.climb.climb_acceleration_filled.0,
+      descent_braking_acceleration_empty: c.climb.descent_braking_acceleration_empty.0,
+      descent_braking_acceleration_filled: c.climb.descent_braking_acceleration_filled.0,
+    };
+    Self { format_version: FORMAT_VERSION, volume_mass, acceleration, power, hydrogen, climb, breakdown }
+  }
+}
+
+/// Exports `value` if it is finite, or `None` otherwise.
+fn finite(value: f64) -> Option<f64> {
+  if value.is_finite() { Some(value) } else { None }
+}
+
+/// Exports the cumulative consumption up to `consumer`, which is that of all enabled consumers if it is disabled.
+fn export_upto(power: &PowerCalculated, consumer: PowerConsumer) -> ExportedResource {
+  power.upto(consumer).unwrap_or_else(|| power.upto_all()).into()
+}
+
+/// Exports thruster consumption of all directions, with 0 consumption for directions without thrusters.
+fn export_thrusters<R: Unit + Default>(thrusters: &HashMap<Direction, ThrusterConsumption<R>>) -> BTreeMap<String, ExportedThrusterConsumption> {
+  Direction::iter().map(|direction| {
+    let c = thrusters.get(direction).copied().unwrap_or_default();
+    (direction.export_name().to_owned(), ExportedThrusterConsumption { idle: c.idle.into(), max: c.max.into() })
+  }).collect()
+}
+
+impl<R: Unit> From<&ResourceCalculated<R>> for ExportedResource {
+  fn from(r: &ResourceCalculated<R>) -> Self {
+    let duration = r.duration.0;
+    Self {
+      consumption: r.consumption.into(),
+      balance: r.balance.into(),
+      duration: if duration.is_finite() { Some(duration) } else { None },
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use crate::grid::breakdown::BlockContribution;
+  use crate::grid::{AccelerationCalculated, Direction, GridCalculated, PowerConsumer, ResourceCalculated, ThrusterConsumption};
+  use crate::units::{Kilograms, Litres, LitresPerSecond, MegaWattHours, MegaWatts, Meters, MetersPerSecond, MetersPerSecondSquared, Minutes, Newtons, Seconds};
+
+  use super::{ExportedResults, FORMAT_VERSION};
+
+  fn resource<R>(consumption: R, balance: R, duration: f64) -> ResourceCalculated<R> {
+    ResourceCalculated { consumption, balance, duration: Minutes(duration) }
+  }
+
+  /// Creates results with distinct values in every exported field, including non-finite durations.
+  fn calculated() -> GridCalculated {
+    let mut c = GridCalculated::default();
+    let v = &mut c.volume_mass;
+    v.total_volume_any = Litres(15625.0);
+    v.total_volume_ore = Litres(15625.0);
+    v.total_volume_ice = Litres(15625.0);
+    v.total_volume_ore_only = Litres(3375.0);
+    v.total_volume_ice_only = Litres(0.0);
+    v.total_mass_empty = Kilograms(12000.0);
+    v.total_mass_filled = Kilograms(20000.0);
+    v.total_items_ice = 250.0;
+    v.total_items_ore = 3000.0;
+    v.total_items_steel_plate = 0.0;
+    c.acceleration.insert(Direction::Up, AccelerationCalculated {
+      force: Newtons(480000.0),
+      acceleration_empty_no_gravity: MetersPerSecondSquared(40.0),
+      acceleration_empty_gravity: MetersPerSecondSquared(30.19),
+      acceleration_filled_no_gravity: MetersPerSecondSquared(24.0),
+      acceleration_filled_gravity: MetersPerSecondSquared(14.19),
+      time_to_max_speed_empty: Seconds(2.5),
+      time_to_max_speed_filled: Seconds(4.0),
+      distance_to_max_speed_empty: Meters(125.0),
+      distance_to_max_speed_filled: Meters(200.0),
+    });
+    c.acceleration.insert(Direction::Front, AccelerationCalculated {
+      force: Newtons(0.0),
+      time_to_max_speed_empty: Seconds(f64::INFINITY),
+      time_to_max_speed_filled: Seconds(f64::INFINITY),
+      distance_to_max_speed_empty: Meters(f64::INFINITY),
+      distance_to_max_speed_filled: Meters(f64::INFINITY),
+      ..AccelerationCalculated::default()
+    });
+    let p = &mut c.power;
+    p.generation = MegaWatts(12.0);
+    p.capacity_battery = MegaWattHours(3.0);
+    p.stored_battery = MegaWattHours(1.5);
+    p.recharge_duration = Minutes(f64::INFINITY);
+    p.idle = resource(MegaWatts(0.1), MegaWatts(11.9), f64::INFINITY);
+    p.misc = resource(MegaWatts(0.5), MegaWatts(11.5), f64::INFINITY);
+    p.upto = vec![
+      (PowerConsumer::Generator, resource(MegaWatts(1.5), MegaWatts(10.5), f64::INFINITY)),
+      (PowerConsumer::UpDownThruster, resource(MegaWatts(14.0), MegaWatts(-2.0), 45.0)),
+      (PowerConsumer::Battery, resource(MegaWatts(16.0), MegaWatts(-4.0), 22.5)),
+    ];
+    p.thrusters.insert(Direction::Up, ThrusterConsumption { idle: MegaWatts(0.002), max: MegaWatts(12.5) });
+    let h = &mut c.hydrogen;
+    h.generation = LitresPerSecond(200.0);
+    h.capacity_tank = Litres(40000.0);
+    h.capacity_tank_stockpile = Litres(15000.0);
+    h.capacity_engine = Litres(16000.0);
+    h.idle = resource(LitresPerSecond(0.0), LitresPerSecond(200.0), f64::INFINITY);
+    h.engine = resource(LitresPerSecond(100.0), LitresPerSecond(100.0), f64::INFINITY);
+    h.upto_up_down_thruster = resource(LitresPerSecond(1100.0), LitresPerSecond(-900.0), 0.74);
+    h.upto_front_back_thruster = resource(LitresPerSecond(1100.0), LitresPerSecond(-900.0), 0.74);
+    h.upto_left_right_thruster = resource(LitresPerSecond(1100.0), LitresPerSecond(-900.0), 0.74);
+    h.thrusters.insert(Direction::Up, ThrusterConsumption { idle: LitresPerSecond(0.0), max: LitresPerSecond(1000.0) });
+    h.burn_duration = vec![(Direction::Up, Seconds(44.4)), (Direction::Down, Seconds(f64::INFINITY))].into_iter().collect::<HashMap<_, _>>();
+    let climb = &mut c.climb;
+    climb.speed = MetersPerSecond(100.0);
+    climb.thrust = 40.9;
+    climb.power = resource(MegaWatts(5.6), MegaWatts(6.4), f64::INFINITY);
+    climb.hydrogen = resource(LitresPerSecond(509.0), LitresPerSecond(-309.0), 2.16);
+    climb.safe_descent_speed_empty = MetersPerSecond(77.7);
+    climb.safe_descent_speed_filled = MetersPerSecond(53.3);
+    climb.climb_acceleration_empty = MetersPerSecondSquared(30.19);
+    climb.climb_acceleration_filled = MetersPerSecondSquared(14.19);
+    climb.descent_braking_acceleration_empty = MetersPerSecondSquared(9.81);
+    climb.descent_braking_acceleration_filled = MetersPerSecondSquared(9.81);
+    c.breakdown = Some(vec![BlockContribution {
+      id: "Thrust.SmallBlockSmallHydrogenThrust".to_owned(),
+      count: 4,
+      mass: Kilograms(1336.0),
+      volume: Litres(0.0),
+      power_generation: MegaWatts(0.0),
+      power_consumption: MegaWatts(0.0),
+      hydrogen_generation: LitresPerSecond(0.0),
+      hydrogen_consumption: LitresPerSecond(1000.0),
+      force: Newtons(480000.0),
+    }]);
+    c
+  }
+
+  #[test]
+  fn serializes_to_golden_v1_json() {
+    let actual = serde_json::to_value(ExportedResults::from(&calculated())).unwrap();
+    let golden: serde_json::Value = serde_json::from_str(include_str!("../../testdata/results_v1_golden.json")).unwrap();
+    assert_eq!(actual, golden);
+  }
+
+  #[test]
+  fn deserializes_old_v1_json() {
+    for json in &[include_str!("../../testdata/results_v1_initial.json"), include_str!("../../testdata/results_v1_climb.json")] {
+      let results: ExportedResults = serde_json::from_str(json).unwrap();
+      assert_eq!(results.format_version, FORMAT_VERSION);
+    }
+  }
+}
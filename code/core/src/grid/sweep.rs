@@ -0,0 +1,60 @@
+use crate::data::Data;
+use crate::units::MegaWatts;
+
+use super::{Direction, GridCalculated, GridCalculator, PowerConsumer};
+
+/// Calculates a grid for `steps` + 1 evenly spaced values from `from` to `to` (inclusive), setting each value into a
+/// copy of `calculator` with `set`. Returns the values with their calculated grid.
+pub fn sweep<S: Fn(&mut GridCalculator, f64)>(calculator: &GridCalculator, data: &Data, from: f64, to: f64, steps: usize, set: S) -> Vec<(f64, GridCalculated)> {
+  let steps = steps.max(1);
+  let mut calculator = calculator.clone();
+  (0..=steps).map(|step| {
+    let value = from + (to - from) * (step as f64 / steps as f64);
+    set(&mut calculator, value);
+    (value, calculator.calculate(data))
+  }).collect()
+}
+
+/// Sets the fill of all containers to `fill` percent. Containers that store any item keep the ratio between ice, ore,
+/// and steel plates, or are filled with ore if they were empty.
+pub fn set_cargo_fill(calculator: &mut GridCalculator, fill: f64) {
+  calculator.ice_only_fill = fill;
+  calculator.ore_only_fill = fill;
+  let any_fill = calculator.any_fill_with_ice + calculator.any_fill_with_ore + calculator.any_fill_with_steel_plates;
+  if any_fill > 0.0 {
+    let factor = fill / any_fill;
+    calculator.any_fill_with_ice *= factor;
+    calculator.any_fill_with_ore *= factor;
+    calculator.any_fill_with_steel_plates *= factor;
+  } else {
+    calculator.any_fill_with_ore = fill;
+  }
+}
+
+/// Gets the filled acceleration under gravity in `direction` for cargo fills from 0 to 100 percent, in `steps` + 1
+/// points.
+pub fn acceleration_by_cargo_fill(calculator: &GridCalculator, data: &Data, direction: Direction, steps: usize) -> Vec<(f64, f64)> {
+  sweep(calculator, data, 0.0, 100.0, steps, set_cargo_fill).into_iter()
+    .map(|(fill, c)| (fill, c.acceleration.get(&direction).map_or(0.0, |a| a.acceleration_filled_gravity.0)))
+    .collect()
+}
+
+/// Gets the power balance for thruster utilizations from 0 to 100 percent, in `steps` + 1 points. Thruster power
+/// consumption scales linearly with utilization; batteries are not charged. Only enabled power consumers are included.
+pub fn power_balance_by_thruster_utilization(calculated: &GridCalculated, steps: usize) -> Vec<(f64, f64)> {
+  let steps = steps.max(1);
+  let mut base_consumption = calculated.power.misc.consumption;
+  let mut thruster_consumption = MegaWatts(0.0);
+  for (consumer, added) in calculated.power.added_consumption() {
+    if consumer.thruster_axis().is_some() {
+      thruster_consumption += added;
+    } else if consumer != PowerConsumer::Battery {
+      base_consumption += added;
+    }
+  }
+  let base_balance = calculated.power.generation - base_consumption;
+  (0..=steps).map(|step| {
+    let utilization = step as f64 / steps as f64;
+    (utilization * 100.0, (base_balance - thruster_consumption * utilization).0)
+  }).collect()
+}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::data::blocks::BlockId;
+use crate::units::{Kilograms, Litres};
+
+use super::{Direction, GridCalculator};
+use super::validate::ValidationError;
+
+/// Named fragment of a grid, such as a standard power plant of 4 batteries and 2 reactors, that can be applied onto a
+/// calculator to add its blocks.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct GridTemplate {
+  pub name: String,
+  pub description: String,
+  pub additional_mass: Kilograms,
+  pub additional_volume: Litres,
+  pub blocks: HashMap<BlockId, u64>,
+  pub directional_blocks: HashMap<Direction, HashMap<BlockId, u64>>,
+}
+
+impl GridTemplate {
+  /// Creates a template named `name` from the blocks, additional mass, and additional volume of `calculator`, leaving
+  /// out blocks with a count of 0.
+  pub fn from_calculator(name: String, description: String, calculator: &GridCalculator) -> Self {
+    fn non_zero(counts: &HashMap<BlockId, u64>) -> HashMap<BlockId, u64> {
+      counts.iter().filter(|(_, count)| **count > 0).map(|(id, count)| (id.clone(), *count)).collect()
+    }
+    let directional_blocks = calculator.directional_blocks.iter()
+      .map(|(direction, counts)| (*direction, non_zero(counts)))
+      .filter(|(_, counts)| !counts.is_empty())
+      .collect();
+    Self {
+      name,
+      description,
+      additional_mass: calculator.additional_mass,
+      additional_volume: calculator.additional_volume,
+      blocks: non_zero(&calculator.blocks),
+      directional_blocks,
+    }
+  }
+
+  /// Applies this template onto `calculator` with `GridCalculator::merge`, adding its block counts, additional mass, and
+  /// additional volume.
+  pub fn apply(&self, calculator: &mut GridCalculator) -> Result<(), ValidationError> {
+    let mut fragment = GridCalculator::default();
+    fragment.additional_mass = self.additional_mass;
+    fragment.additional_volume = self.additional_volume;
+    fragment.blocks = self.blocks.clone();
+    for (direction, counts) in &self.directional_blocks {
+      *fragment.direction_mut(*direction) = counts.clone();
+    }
+    calculator.merge(&fragment)
+  }
+}
+
+/// Templates stored in the user configuration, in the order they were added.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct GridTemplates {
+  templates: Vec<GridTemplate>,
+}
+
+impl GridTemplates {
+  pub fn iter(&self) -> impl Iterator<Item=&GridTemplate> {
+    self.templates.iter()
+  }
+
+  pub fn get(&self, name: &str) -> Option<&GridTemplate> {
+    self.templates.iter().find(|t| t.name == name)
+  }
+
+  /// Adds `template`, replacing the template with the same name in place if it exists.
+  pub fn insert(&mut self, template: GridTemplate) {
+    match self.templates.iter_mut().find(|t| t.name == template.name) {
+      Some(existing) => *existing = template,
+      None => self.templates.push(template),
+    }
+  }
+
+  /// Removes the template named `name`, returning it if it existed.
+  pub fn remove(&mut self, name: &str) -> Option<GridTemplate> {
+    let index = self.templates.iter().position(|t| t.name == name)?;
+    Some(self.templates.remove(index))
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.templates.is_empty()
+  }
+}
@@ -0,0 +1,55 @@
+use crate::units::{Litres, MegaWattHours, Meters, MetersPerSecond, MetersPerSecondSquared, Seconds};
+
+use super::{Direction, GridCalculated};
+
+/// Trip over a distance in space that accelerates up to a top speed with the thrusters of the travel direction, coasts
+/// at the top speed, and brakes with the thrusters of the opposite direction.
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub struct Trip {
+  pub top_speed: MetersPerSecond,
+  pub accelerate_duration: Seconds,
+  pub coast_duration: Seconds,
+  pub brake_duration: Seconds,
+  pub duration: Seconds,
+  /// Hydrogen consumed by the thrusters while accelerating and braking.
+  pub hydrogen: Litres,
+  /// Energy consumed by the thrusters while accelerating and braking.
+  pub power: MegaWattHours,
+}
+
+/// Calculates a trip over `distance` in `direction`, reaching at most `max_speed`, for the filled mass if `filled` or
+/// the empty mass otherwise. Returns `None` if the grid cannot accelerate in `direction` or brake in its opposite
+/// direction.
+pub fn trip(calculated: &GridCalculated, max_speed: MetersPerSecond, direction: Direction, distance: Meters, filled: bool) -> Option<Trip> {
+  let acceleration = |direction: Direction| calculated.acceleration.get(&direction)
+    .map(|a| if filled { a.acceleration_filled_no_gravity } else { a.acceleration_empty_no_gravity })
+    .filter(|a| *a > MetersPerSecondSquared(0.0));
+  let accelerate = acceleration(direction)?;
+  let brake = acceleration(direction.opposite())?;
+  // Distance to reach, or brake from, `max_speed` is v^2 / 2a. Without room to reach it, the top speed is where the
+  // accelerating and braking distances add up to `distance`.
+  let full_distance = max_speed.0 * max_speed.0 / 2.0 * (1.0 / accelerate.0 + 1.0 / brake.0);
+  let top_speed = if full_distance <= distance.0 {
+    max_speed
+  } else {
+    MetersPerSecond((2.0 * distance.0 * accelerate.0 * brake.0 / (accelerate.0 + brake.0)).sqrt())
+  };
+  let accelerate_duration = top_speed / accelerate;
+  let brake_duration = top_speed / brake;
+  let coast_duration = if top_speed.0 > 0.0 {
+    Seconds((distance.0 - full_distance).max(0.0) / top_speed.0)
+  } else {
+    Seconds(0.0)
+  };
+  let hydrogen_max = |direction: Direction| calculated.hydrogen.thrusters.get(&direction).map(|c| c.max).unwrap_or_default();
+  let power_max = |direction: Direction| calculated.power.thrusters.get(&direction).map(|c| c.max).unwrap_or_default();
+  Some(Trip {
+    top_speed,
+    accelerate_duration,
+    coast_duration,
+    brake_duration,
+    duration: accelerate_duration + coast_duration + brake_duration,
+    hydrogen: hydrogen_max(direction) * accelerate_duration + hydrogen_max(direction.opposite()) * brake_duration,
+    power: power_max(direction) * accelerate_duration + power_max(direction.opposite()) * brake_duration,
+  })
+}
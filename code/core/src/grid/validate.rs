@@ -0,0 +1,215 @@
+use thiserror::Error;
+
+use crate::units::{Kilograms, Litres, Meters, MetersPerSecond};
+
+use super::{GridCalculator, PowerConsumer};
+
+#[derive(Error, Debug)]
+pub enum ValidationError {
+  #[error("{0} must be a finite number")]
+  NotFinite(&'static str),
+  #[error("Gravity multiplier {0} must not be negative")]
+  NegativeGravityMultiplier(f64),
+  #[error("Container multiplier {0} must be positive")]
+  NonPositiveContainerMultiplier(f64),
+  #[error("Planetary influence {0} must be between 0 and 1")]
+  PlanetaryInfluenceOutOfRange(f64),
+  #[error("Additional mass {0} must not be negative")]
+  NegativeAdditionalMass(Kilograms),
+  #[error("Additional volume {0} must not be negative")]
+  NegativeAdditionalVolume(Litres),
+  #[error("Max speed {0} must be positive")]
+  NonPositiveMaxSpeed(MetersPerSecond),
+  #[error("Descent altitude {0} must not be negative")]
+  NegativeDescentAltitude(Meters),
+  #[error("Fill percentage {0} of '{1}' must be between 0 and 100")]
+  FillOutOfRange(f64, &'static str),
+  #[error("Fill percentages of any-fill containers add up to {0}, which exceeds 100")]
+  AnyFillExceeded(f64),
+  #[error("Hydrogen share {0} of O2/H2 generators must be between 0 and 100")]
+  GeneratorHydrogenShareOutOfRange(f64),
+  #[error("Battery charge {0} must be between 0 and 100")]
+  BatteryChargeOutOfRange(f64),
+  #[error("Reactor output multiplier {0} must not be negative")]
+  NegativeReactorOutputMultiplier(f64),
+  #[error("Renewable output {0} must be between 0 and 100")]
+  RenewableOutputOutOfRange(f64),
+  #[error("Utility range {0} must be between 0 and 100")]
+  UtilityRangeOutOfRange(f64),
+  #[error("Consumption {1} of custom consumer '{0}' must be a finite number that is not negative")]
+  InvalidCustomConsumption(String, f64),
+  #[error("Power consumer '{0}' occurs more than once in the power priority")]
+  DuplicatePowerConsumer(PowerConsumer),
+  #[error("Scale factor {0} must be a finite number that is not negative")]
+  InvalidScaleFactor(f64),
+  #[error("Total number of blocks exceeds {}", u64::MAX)]
+  BlockCountOverflow,
+}
+
+impl GridCalculator {
+  /// Checks that all options are finite and within their valid range.
+  pub fn validate(&self) -> Result<(), ValidationError> {
+    for &(value, name) in &[
+      (self.gravity_multiplier, "Gravity multiplier"),
+      (self.container_multiplier, "Container multiplier"),
+      (self.planetary_influence, "Planetary influence"),
+      (self.additional_mass.0, "Additional mass"),
+      (self.additional_volume.0, "Additional volume"),
+      (self.max_speed.0, "Max speed"),
+      (self.descent_altitude.0, "Descent altitude"),
+      (self.ice_only_fill, "Ice-only fill"),
+      (self.ore_only_fill, "Ore-only fill"),
+      (self.any_fill_with_ice, "Any-fill with ice"),
+      (self.any_fill_with_ore, "Any-fill with ore"),
+      (self.any_fill_with_steel_plates, "Any-fill with steel plates"),
+      (self.ammo_fill, "Ammo fill"),
+      (self.generator_hydrogen_share, "Generator hydrogen share"),
+      (self.battery_charge, "Battery charge"),
+      (self.reactor_output_multiplier, "Reactor output multiplier"),
+      (self.renewable_output, "Renewable output"),
+      (self.utility_range, "Utility range"),
+    ] {
+      if !value.is_finite() {
+        return Err(ValidationError::NotFinite(name));
+      }
+    }
+    if self.gravity_multiplier < 0.0 {
+      return Err(ValidationError::NegativeGravityMultiplier(self.gravity_multiplier));
+    }
+    if self.container_multiplier <= 0.0 {
+      return Err(ValidationError::NonPositiveContainerMultiplier(self.container_multiplier));
+    }
+    if !(0.0..=1.0).contains(&self.planetary_influence) {
+      return Err(ValidationError::PlanetaryInfluenceOutOfRange(self.planetary_influence));
+    }
+    if self.additional_mass < Kilograms(0.0) {
+      return Err(ValidationError::NegativeAdditionalMass(self.additional_mass));
+    }
+    if self.additional_volume < Litres(0.0) {
+      return Err(ValidationError::NegativeAdditionalVolume(self.additional_volume));
+    }
+    if self.max_speed <= MetersPerSecond(0.0) {
+      return Err(ValidationError::NonPositiveMaxSpeed(self.max_speed));
+    }
+    if self.descent_altitude < Meters(0.0) {
+      return Err(ValidationError::NegativeDescentAltitude(self.descent_altitude));
+    }
+    for &(fill, name) in &[
+      (self.ice_only_fill, "ice only"),
+      (self.ore_only_fill, "ore only"),
+      (self.any_fill_with_ice, "any with ice"),
+      (self.any_fill_with_ore, "any with ore"),
+      (self.any_fill_with_steel_plates, "any with steel plates"),
+      (self.ammo_fill, "ammo"),
+    ] {
+      if !(0.0..=100.0).contains(&fill) {
+        return Err(ValidationError::FillOutOfRange(fill, name));
+      }
+    }
+    let any_fill = self.any_fill_with_ice + self.any_fill_with_ore + self.any_fill_with_steel_plates;
+    if any_fill > 100.0 {
+      return Err(ValidationError::AnyFillExceeded(any_fill));
+    }
+    if !(0.0..=100.0).contains(&self.generator_hydrogen_share) {
+      return Err(ValidationError::GeneratorHydrogenShareOutOfRange(self.generator_hydrogen_share));
+    }
+    if !(0.0..=100.0).contains(&self.battery_charge) {
+      return Err(ValidationError::BatteryChargeOutOfRange(self.battery_charge));
+    }
+    if self.reactor_output_multiplier < 0.0 {
+      return Err(ValidationError::NegativeReactorOutputMultiplier(self.reactor_output_multiplier));
+    }
+    if !(0.0..=100.0).contains(&self.renewable_output) {
+      return Err(ValidationError::RenewableOutputOutOfRange(self.renewable_output));
+    }
+    if !(0.0..=100.0).contains(&self.utility_range) {
+      return Err(ValidationError::UtilityRangeOutOfRange(self.utility_range));
+    }
+    let custom_consumptions = self.custom_power_consumers.iter().map(|c| (&c.name, c.consumption.0))
+      .chain(self.custom_hydrogen_consumers.iter().map(|c| (&c.name, c.consumption.0)));
+    for (name, consumption) in custom_consumptions {
+      if !consumption.is_finite() || consumption < 0.0 {
+        return Err(ValidationError::InvalidCustomConsumption(name.clone(), consumption));
+      }
+    }
+    for (i, consumer) in self.power_priority.iter().enumerate() {
+      if self.power_priority[..i].contains(consumer) {
+        return Err(ValidationError::DuplicatePowerConsumer(*consumer));
+      }
+    }
+    self.total_block_count().ok_or(ValidationError::BlockCountOverflow)?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::grid::{GridCalculator, PowerConsumer};
+  use crate::units::Kilograms;
+
+  use super::ValidationError;
+
+  fn validate(modify: impl FnOnce(&mut GridCalculator)) -> Result<(), ValidationError> {
+    let mut calculator = GridCalculator::default();
+    modify(&mut calculator);
+    calculator.validate()
+  }
+
+  #[test]
+  fn default_is_valid() {
+    validate(|_| {}).unwrap();
+  }
+
+  #[test]
+  fn rejects_nan() {
+    match validate(|c| c.gravity_multiplier = f64::NAN) {
+      Err(ValidationError::NotFinite("Gravity multiplier")) => {}
+      r => panic!("unexpected result: {:?}", r),
+    }
+    match validate(|c| c.any_fill_with_ore = f64::NAN) {
+      Err(ValidationError::NotFinite("Any-fill with ore")) => {}
+      r => panic!("unexpected result: {:?}", r),
+    }
+  }
+
+  #[test]
+  fn rejects_negative_values() {
+    match validate(|c| c.additional_mass = Kilograms(-1.0)) {
+      Err(ValidationError::NegativeAdditionalMass(mass)) => assert_eq!(mass, Kilograms(-1.0)),
+      r => panic!("unexpected result: {:?}", r),
+    }
+    match validate(|c| c.ice_only_fill = -10.0) {
+      Err(ValidationError::FillOutOfRange(fill, "ice only")) => assert_eq!(fill, -10.0),
+      r => panic!("unexpected result: {:?}", r),
+    }
+    match validate(|c| c.reactor_output_multiplier = -0.5) {
+      Err(ValidationError::NegativeReactorOutputMultiplier(multiplier)) => assert_eq!(multiplier, -0.5),
+      r => panic!("unexpected result: {:?}", r),
+    }
+  }
+
+  #[test]
+  fn any_fill_sum_must_not_exceed_100() {
+    validate(|c| {
+      c.any_fill_with_ice = 50.0;
+      c.any_fill_with_ore = 30.0;
+      c.any_fill_with_steel_plates = 20.0;
+    }).unwrap();
+    match validate(|c| {
+      c.any_fill_with_ice = 50.0;
+      c.any_fill_with_ore = 30.0;
+      c.any_fill_with_steel_plates = 30.0;
+    }) {
+      Err(ValidationError::AnyFillExceeded(sum)) => assert_eq!(sum, 110.0),
+      r => panic!("unexpected result: {:?}", r),
+    }
+  }
+
+  #[test]
+  fn rejects_duplicate_power_priority() {
+    match validate(|c| c.power_priority = vec![PowerConsumer::Battery, PowerConsumer::ShipTool, PowerConsumer::Battery]) {
+      Err(ValidationError::DuplicatePowerConsumer(PowerConsumer::Battery)) => {}
+      r => panic!("unexpected result: {:?}", r),
+    }
+  }
+}
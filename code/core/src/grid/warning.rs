@@ -0,0 +1,112 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::units::{Kilograms, Litres, LitresPerSecond, MegaWattHours, MegaWatts, MetersPerSecondSquared, Newtons, Unit};
+
+use crate::data::Data;
+
+use super::{Direction, GridCalculated, GridCalculator, PowerConsumer};
+
+/// Stage in the power consumption rows, which (except for `Idle`) cumulatively add consumers in priority order.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Hash, Debug)]
+pub enum PowerStage {
+  Idle,
+  Misc,
+  Consumer(PowerConsumer),
+}
+
+impl Display for PowerStage {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    f.write_str(match self {
+      PowerStage::Idle => "Idle",
+      PowerStage::Misc => "Misc",
+      PowerStage::Consumer(consumer) => consumer.label(),
+    })
+  }
+}
+
+
+/// Problem with a calculated grid.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Warning {
+  /// Power generation does not cover consumption at `stage`. For cumulative stages, only the first stage with a
+  /// negative balance is reported, as all later stages are negative as well.
+  NegativePowerBalance { stage: PowerStage, balance: MegaWatts },
+  /// There are hydrogen thrusters, but no hydrogen tanks or generators to supply them.
+  HydrogenThrustersWithoutSource,
+  /// There are batteries, but nothing other than batteries generates power to charge them.
+  BatteriesWithoutCharger,
+  /// There is thrust in some direction, but none in `direction`.
+  NoThrust { direction: Direction },
+  /// There is gravity, but no upward thrust to oppose it. Reported instead of `NoThrust` for the up direction.
+  NoLift,
+  /// Upward thrust cannot lift the filled grid in gravity, resulting in a negative upward `acceleration`.
+  InsufficientLift { acceleration: MetersPerSecondSquared },
+  /// Blocks of both grid sizes are entered, which are summed as if they were on the same grid.
+  MixedGridSizes { small: u64, large: u64 },
+}
+
+impl Display for Warning {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Warning::NegativePowerBalance { stage: PowerStage::Idle, balance } => write!(f, "Power balance is negative ({} MW) when idle", balance.format()),
+      Warning::NegativePowerBalance { stage, balance } => write!(f, "Power balance is negative ({} MW) from '{}' onwards", balance.format(), stage),
+      Warning::HydrogenThrustersWithoutSource => write!(f, "Hydrogen thrusters have no hydrogen tanks or generators to supply them"),
+      Warning::BatteriesWithoutCharger => write!(f, "Batteries have no power generation other than batteries to charge them"),
+      Warning::NoThrust { direction } => write!(f, "No thrust in {:?} direction", direction),
+      Warning::NoLift => write!(f, "No upward thrust to oppose gravity"),
+      Warning::InsufficientLift { acceleration } => write!(f, "Upward thrust cannot lift the filled grid in gravity ({} m/s^2)", acceleration.format()),
+      Warning::MixedGridSizes { small, large } => write!(f, "Small grid ({}) and large grid ({}) blocks are mixed, which sums blocks of separate grids", small, large),
+    }
+  }
+}
+
+
+/// Checks `calculated`, which must be calculated from `grid`, for problems.
+pub fn check(calculated: &GridCalculated, grid: &GridCalculator, data: &Data) -> Vec<Warning> {
+  let mut warnings = Vec::new();
+
+  let (small, large) = grid.block_count_by_size(data);
+  if small > 0 && large > 0 {
+    warnings.push(Warning::MixedGridSizes { small, large });
+  }
+
+  if calculated.power.idle.balance < MegaWatts(0.0) {
+    warnings.push(Warning::NegativePowerBalance { stage: PowerStage::Idle, balance: calculated.power.idle.balance });
+  }
+  let cumulative_power_stages = std::iter::once((PowerStage::Misc, &calculated.power.misc))
+    .chain(calculated.power.upto.iter().map(|(consumer, r)| (PowerStage::Consumer(*consumer), r)));
+  if let Some((stage, resource)) = cumulative_power_stages.into_iter().find(|(_, r)| r.balance < MegaWatts(0.0)) {
+    warnings.push(Warning::NegativePowerBalance { stage, balance: resource.balance });
+  }
+
+  if calculated.hydrogen_thruster_consumption() > LitresPerSecond(0.0) && calculated.hydrogen.capacity_tank <= Litres(0.0) && calculated.hydrogen.generation <= LitresPerSecond(0.0) {
+    warnings.push(Warning::HydrogenThrustersWithoutSource);
+  }
+
+  if calculated.power.capacity_battery > MegaWattHours(0.0) && calculated.power.generation - calculated.power.generation_battery <= MegaWatts(0.0) {
+    warnings.push(Warning::BatteriesWithoutCharger);
+  }
+
+  let has_thrust = |direction: &Direction| calculated.acceleration.get(direction).map_or(false, |a| a.force > Newtons(0.0));
+  let no_lift = grid.gravity_multiplier > 0.0 && calculated.volume_mass.total_mass_filled > Kilograms(0.0) && !has_thrust(&Direction::Up);
+  if no_lift {
+    warnings.push(Warning::NoLift);
+  }
+  if Direction::iter().any(has_thrust) {
+    for direction in Direction::iter() {
+      if !has_thrust(direction) && !(no_lift && *direction == Direction::Up) {
+        warnings.push(Warning::NoThrust { direction: *direction });
+      }
+    }
+  }
+
+  // Without upward thrust, `NoThrust` is reported instead. Accelerations with and without gravity only differ when
+  // there is gravity.
+  if let Some(up) = calculated.acceleration.get(&Direction::Up) {
+    if up.force > Newtons(0.0) && up.acceleration_filled_gravity < MetersPerSecondSquared(0.0) && up.acceleration_filled_gravity != up.acceleration_filled_no_gravity {
+      warnings.push(Warning::InsufficientLift { acceleration: up.acceleration_filled_gravity });
+    }
+  }
+
+  warnings
+}
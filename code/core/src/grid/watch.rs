@@ -0,0 +1,80 @@
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use thiserror::Error;
+
+use super::{GridCalculator, ReadError};
+use super::validate::ValidationError;
+
+/// Interval at which watched files are checked for changes by default.
+pub const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Error, Debug)]
+pub enum WatchError {
+  #[error("Could not open grid file")]
+  Open(#[from] io::Error),
+  #[error("Could not read grid")]
+  Read(#[from] ReadError),
+  #[error("Could not merge module into grid")]
+  Merge(#[from] ValidationError),
+}
+
+/// Detects changes to files by polling their modification times, which works with every editor and file system
+/// without platform-specific notification APIs.
+#[derive(Clone, Debug)]
+pub struct Watcher {
+  files: Vec<(PathBuf, Option<SystemTime>)>,
+  polled: bool,
+}
+
+impl Watcher {
+  pub fn new<I: IntoIterator<Item=P>, P: Into<PathBuf>>(paths: I) -> Self {
+    let files = paths.into_iter().map(|path| (path.into(), None)).collect();
+    Self { files, polled: false }
+  }
+
+  /// Checks whether any file was modified, created, or removed since the previous poll. The first poll always reports
+  /// a change, so that watchers calculate once before waiting for changes.
+  pub fn poll(&mut self) -> bool {
+    let mut changed = !self.polled;
+    self.polled = true;
+    for (path, modified) in &mut self.files {
+      let current = fs::metadata(&path).and_then(|m| m.modified()).ok();
+      if current != *modified {
+        *modified = current;
+        changed = true;
+      }
+    }
+    changed
+  }
+}
+
+/// Calls `on_change` initially and whenever any of `paths` changes, checking every `interval`, until `on_change`
+/// returns false.
+pub fn watch<I: IntoIterator<Item=P>, P: Into<PathBuf>>(paths: I, interval: Duration, mut on_change: impl FnMut() -> bool) {
+  let mut watcher = Watcher::new(paths);
+  loop {
+    if watcher.poll() && !on_change() { return; }
+    thread::sleep(interval);
+  }
+}
+
+/// Reads the grid at `grid_file` and merges the grids at `module_files` into it.
+pub fn read_grid_with_modules<G: AsRef<Path>, M: AsRef<Path>>(grid_file: G, module_files: &[M]) -> Result<GridCalculator, WatchError> {
+  let mut calculator = GridCalculator::from_json(File::open(grid_file)?)?;
+  for module_file in module_files {
+    calculator.merge(&GridCalculator::from_json(File::open(module_file)?)?)?;
+  }
+  Ok(calculator)
+}
+
+/// Calls `on_change` with the grid at `grid_file`, with `module_files` merged into it, initially and whenever any of
+/// these files changes, until `on_change` returns false. Errors are passed to `on_change` instead of stopping the watch,
+/// as files are often briefly invalid while an external editor saves them.
+pub fn watch_grid<G: AsRef<Path>, M: AsRef<Path>>(grid_file: G, module_files: &[M], interval: Duration, mut on_change: impl FnMut(Result<GridCalculator, WatchError>) -> bool) {
+  let paths = std::iter::once(grid_file.as_ref()).chain(module_files.iter().map(|p| p.as_ref())).map(Path::to_path_buf);
+  watch(paths.collect::<Vec<_>>(), interval, || on_change(read_grid_with_modules(grid_file.as_ref(), module_files)));
+}
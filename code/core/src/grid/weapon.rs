@@ -0,0 +1,47 @@
+use crate::data::blocks::BlockId;
+use crate::data::Data;
+use crate::units::{Kilograms, Seconds};
+
+use super::GridCalculator;
+
+/// Weapons with the same identifier on a grid, loaded with the ammo of the configured fill.
+#[derive(Clone, Debug)]
+pub struct WeaponGroup {
+  pub id: BlockId,
+  pub count: u64,
+  /// Rounds per weapon
+  pub rounds: f64,
+  /// Duration that each weapon can fire continuously, or `None` if it has no ammo magazine or rate of fire.
+  pub endurance: Option<Seconds>,
+  /// Mass of the ammo of all weapons in this group.
+  pub ammo_mass: Kilograms,
+}
+
+impl GridCalculator {
+  /// Gets the weapon groups of this grid with the ammo of the configured fill, sorted by block identifier.
+  pub fn weapon_groups(&self, data: &Data) -> Vec<WeaponGroup> {
+    let mut groups: Vec<_> = self.blocks.iter()
+      .filter(|(_, count)| **count > 0)
+      .filter_map(|(id, count)| data.blocks.weapons.get(id).map(|block| {
+        let weapon = &block.details;
+        let rounds = weapon.rounds(self.ammo_fill);
+        let endurance = if weapon.magazine.is_some() && weapon.rate_of_fire > 0.0 {
+          Some(Seconds(rounds / (weapon.rate_of_fire / 60.0)))
+        } else {
+          None
+        };
+        let ammo_mass = Kilograms(weapon.ammo_mass(self.ammo_fill)) * *count as f64;
+        WeaponGroup { id: id.clone(), count: *count, rounds, endurance, ammo_mass }
+      }))
+      .collect();
+    groups.sort_by(|a, b| a.id.cmp(&b.id));
+    groups
+  }
+
+  /// Gets the total mass of the ammo in the weapons of this grid with the configured fill.
+  pub fn ammo_mass(&self, data: &Data) -> Kilograms {
+    self.blocks.iter()
+      .filter_map(|(id, count)| data.blocks.weapons.get(id).map(|block| Kilograms(block.details.ammo_mass(self.ammo_fill)) * *count as f64))
+      .sum()
+  }
+}
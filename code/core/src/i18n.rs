@@ -0,0 +1,32 @@
+use std::collections::HashMap;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ReadError {
+  #[error("Could not read translations from JSON")]
+  FromJSON(#[from] serde_json::Error),
+}
+
+/// Translations of user interface texts into a single language, shared between frontends. Texts are keyed by their
+/// English text, so that untranslated texts are shown in English.
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct Translations {
+  /// Name of the language, in that language.
+  pub language: String,
+  texts: HashMap<String, String>,
+}
+
+impl Translations {
+  pub fn from_json<R: io::Read>(reader: R) -> Result<Self, ReadError> {
+    let translations = serde_json::from_reader(reader)?;
+    Ok(translations)
+  }
+
+  /// Gets the translation of English `text`, or `text` itself if there is no translation.
+  pub fn translate<'a>(&'a self, text: &'a str) -> &'a str {
+    self.texts.get(text).map_or(text, |t| t.as_str())
+  }
+}
@@ -2,6 +2,11 @@
 #![feature(clamp)]
 #![feature(backtrace)]
 
+pub mod config;
 pub mod grid;
 pub mod data;
 pub mod error;
+pub mod i18n;
+pub mod format;
+pub mod precision;
+pub mod units;
@@ -0,0 +1,75 @@
+/// Kind of quantity, which determines the precision its values are shown with. All frontends and exporters format
+/// values through this, so that they show identical numbers for the same calculation.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Quantity {
+  Mass,
+  Volume,
+  Flow,
+  Power,
+  Energy,
+  Acceleration,
+  Force,
+  Duration,
+  Speed,
+  Distance,
+  Items,
+  Ratio,
+}
+
+impl Quantity {
+  /// Gets the number of decimals shown for values of this kind, in the units of the calculator: kg, L, L/s, MW, MWh,
+  /// m/s², N, minutes, m/s, and m.
+  pub fn decimals(self) -> usize {
+    match self {
+      Quantity::Mass => 0,
+      Quantity::Volume => 0,
+      Quantity::Flow => 2,
+      Quantity::Power => 3,
+      Quantity::Energy => 2,
+      Quantity::Acceleration => 2,
+      Quantity::Force => 0,
+      Quantity::Duration => 2,
+      Quantity::Speed => 1,
+      Quantity::Distance => 0,
+      Quantity::Items => 0,
+      Quantity::Ratio => 2,
+    }
+  }
+
+  /// Gets the number of decimals shown for values of this kind in a unit that equals `factor` calculator units, such
+  /// as 1000 for tonnes, keeping the same absolute precision.
+  pub fn decimals_scaled(self, factor: f64) -> usize {
+    let decimals = self.decimals() as i32 + factor.log10().round() as i32;
+    decimals.max(0) as usize
+  }
+
+  /// Formats `value`, given in the units of the calculator, with the precision of this kind.
+  pub fn format(self, value: f64) -> String {
+    format_decimals(value, self.decimals())
+  }
+
+  /// Formats `value` like `format`, with a plus sign for positive values, for showing differences.
+  pub fn format_signed(self, value: f64) -> String {
+    let formatted = self.format(value);
+    if round(value, self.decimals()) > 0.0 { format!("+{}", formatted) } else { formatted }
+  }
+
+  /// Formats `value`, given in a unit that equals `factor` calculator units, with the precision of this kind.
+  pub fn format_scaled(self, value: f64, factor: f64) -> String {
+    format_decimals(value, self.decimals_scaled(factor))
+  }
+}
+
+/// Rounds `value` to `decimals` decimals, with ties rounding away from zero.
+pub fn round(value: f64, decimals: usize) -> f64 {
+  let scale = 10f64.powi(decimals as i32);
+  (value * scale).round() / scale
+}
+
+/// Formats `value` rounded to `decimals` decimals, with ties rounding away from zero, and without a minus sign for
+/// values that round to zero.
+pub fn format_decimals(value: f64, decimals: usize) -> String {
+  let rounded = round(value, decimals);
+  let rounded = if rounded == 0.0 { 0.0 } else { rounded };
+  format!("{:.*}", decimals, rounded)
+}
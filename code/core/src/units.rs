@@ -0,0 +1,156 @@
+use std::fmt::{self, Display, Formatter};
+use std::iter::Sum;
+use std::num::ParseFloatError;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::precision::Quantity;
+
+/// Standard gravity in m/s², used to convert accelerations to g and masses to weights.
+pub const STANDARD_GRAVITY: MetersPerSecondSquared = MetersPerSecondSquared(9.81);
+
+/// Physical unit wrapping an `f64` value.
+pub trait Unit: Copy + Into<f64> {
+  /// Symbol of this unit, such as "kg".
+  const SYMBOL: &'static str;
+  /// Kind of quantity of this unit, determining the precision its values are shown with.
+  const QUANTITY: Quantity;
+
+  /// Formats the value with the precision of its kind, without the unit symbol.
+  fn format(self) -> String { Self::QUANTITY.format(self.into()) }
+}
+
+macro_rules! create_unit {
+  ($(#[$meta:meta])* $name:ident, $symbol:expr, $quantity:ident) => {
+    $(#[$meta])*
+    #[derive(Copy, Clone, Default, PartialEq, PartialOrd, Serialize, Deserialize, Debug)]
+    #[serde(transparent)]
+    pub struct $name(pub f64);
+
+    impl Unit for $name {
+      const SYMBOL: &'static str = $symbol;
+      const QUANTITY: Quantity = Quantity::$quantity;
+    }
+
+    impl $name {
+      pub fn max(self, other: Self) -> Self { Self(self.0.max(other.0)) }
+      pub fn min(self, other: Self) -> Self { Self(self.0.min(other.0)) }
+    }
+
+    impl Add for $name {
+      type Output = Self;
+      fn add(self, rhs: Self) -> Self { Self(self.0 + rhs.0) }
+    }
+
+    impl AddAssign for $name {
+      fn add_assign(&mut self, rhs: Self) { self.0 += rhs.0 }
+    }
+
+    impl Sub for $name {
+      type Output = Self;
+      fn sub(self, rhs: Self) -> Self { Self(self.0 - rhs.0) }
+    }
+
+    impl SubAssign for $name {
+      fn sub_assign(&mut self, rhs: Self) { self.0 -= rhs.0 }
+    }
+
+    impl Neg for $name {
+      type Output = Self;
+      fn neg(self) -> Self { Self(-self.0) }
+    }
+
+    impl Mul<f64> for $name {
+      type Output = Self;
+      fn mul(self, rhs: f64) -> Self { Self(self.0 * rhs) }
+    }
+
+    impl Div<f64> for $name {
+      type Output = Self;
+      fn div(self, rhs: f64) -> Self { Self(self.0 / rhs) }
+    }
+
+    /// Dividing quantities of the same unit results in a ratio.
+    impl Div for $name {
+      type Output = f64;
+      fn div(self, rhs: Self) -> f64 { self.0 / rhs.0 }
+    }
+
+    impl From<$name> for f64 {
+      fn from(value: $name) -> f64 { value.0 }
+    }
+
+    impl Sum for $name {
+      fn sum<I: Iterator<Item=Self>>(iter: I) -> Self { Self(iter.map(|v| v.0).sum()) }
+    }
+
+    /// Formats the bare number, passing on the precision and other format options, without the unit symbol.
+    impl Display for $name {
+      fn fmt(&self, f: &mut Formatter) -> fmt::Result { Display::fmt(&self.0, f) }
+    }
+
+    /// Parses a bare number, without the unit symbol.
+    impl FromStr for $name {
+      type Err = ParseFloatError;
+      fn from_str(s: &str) -> Result<Self, Self::Err> { s.parse().map(Self) }
+    }
+  }
+}
+
+create_unit!(Kilograms, "kg", Mass);
+create_unit!(Newtons, "N", Force);
+create_unit!(MetersPerSecondSquared, "m/s^2", Acceleration);
+create_unit!(MegaWatts, "MW", Power);
+create_unit!(MegaWattHours, "MWh", Energy);
+create_unit!(Litres, "L", Volume);
+create_unit!(LitresPerSecond, "L/s", Flow);
+create_unit!(Seconds, "s", Duration);
+create_unit!(Minutes, "min", Duration);
+create_unit!(MetersPerSecond, "m/s", Speed);
+create_unit!(Meters, "m", Distance);
+
+impl Mul<MetersPerSecondSquared> for Kilograms {
+  type Output = Newtons;
+  fn mul(self, rhs: MetersPerSecondSquared) -> Newtons { Newtons(self.0 * rhs.0) }
+}
+
+impl Div<Kilograms> for Newtons {
+  type Output = MetersPerSecondSquared;
+  fn div(self, rhs: Kilograms) -> MetersPerSecondSquared { MetersPerSecondSquared(self.0 / rhs.0) }
+}
+
+/// Duration that `self` of energy lasts at a consumption of `rhs`.
+impl Div<MegaWatts> for MegaWattHours {
+  type Output = Minutes;
+  fn div(self, rhs: MegaWatts) -> Minutes { Minutes((self.0 / rhs.0) * 60.0) }
+}
+
+/// Duration that `self` of hydrogen lasts at a consumption of `rhs`.
+impl Div<LitresPerSecond> for Litres {
+  type Output = Seconds;
+  fn div(self, rhs: LitresPerSecond) -> Seconds { Seconds(self.0 / rhs.0) }
+}
+
+/// Duration to reach a speed of `self` from standstill at a constant acceleration of `rhs`.
+impl Div<MetersPerSecondSquared> for MetersPerSecond {
+  type Output = Seconds;
+  fn div(self, rhs: MetersPerSecondSquared) -> Seconds { Seconds(self.0 / rhs.0) }
+}
+
+/// Hydrogen consumed at a consumption of `self` during `rhs`.
+impl Mul<Seconds> for LitresPerSecond {
+  type Output = Litres;
+  fn mul(self, rhs: Seconds) -> Litres { Litres(self.0 * rhs.0) }
+}
+
+/// Energy consumed at a consumption of `self` during `rhs`.
+impl Mul<Seconds> for MegaWatts {
+  type Output = MegaWattHours;
+  fn mul(self, rhs: Seconds) -> MegaWattHours { MegaWattHours(self.0 * rhs.0 / 3600.0) }
+}
+
+impl From<Seconds> for Minutes {
+  fn from(seconds: Seconds) -> Self { Minutes(seconds.0 / 60.0) }
+}
@@ -0,0 +1,98 @@
+use glib::object::IsA;
+use glib::Type;
+use gtk::{CellRendererText, ListStore, PolicyType, ScrolledWindow, SortType, TreeView, TreeViewColumn, Window, WindowType};
+use gtk::prelude::*;
+
+use secalc_core::data::Data;
+use secalc_core::format::UnitPreferences;
+use secalc_core::grid::breakdown::BlockContribution;
+use secalc_core::units::{Kilograms, Litres, LitresPerSecond, MegaWatts, Newtons};
+
+/// Window listing the contribution of each entered block, sortable by column.
+pub struct BreakdownWindow {
+  window: Window,
+  store: ListStore,
+}
+
+/// Numeric columns: (title, getter, formatter). Each is stored in the model as a formatted string column for display,
+/// followed by a value column for sorting.
+const VALUE_COLUMNS: [(&str, fn(&BlockContribution) -> f64, fn(&UnitPreferences, f64) -> String); 8] = [
+  ("Count", |c| c.count as f64, |_, v| format!("{:.0}", v)),
+  ("Mass", |c| c.mass.0, |u, v| u.format_mass(Kilograms(v))),
+  ("Volume", |c| c.volume.0, |u, v| u.format_volume(Litres(v))),
+  ("Power generation", |c| c.power_generation.0, |u, v| u.format_power(MegaWatts(v))),
+  ("Power consumption", |c| c.power_consumption.0, |u, v| u.format_power(MegaWatts(v))),
+  ("Hydrogen generation", |c| c.hydrogen_generation.0, |u, v| u.format_flow(LitresPerSecond(v))),
+  ("Hydrogen consumption", |c| c.hydrogen_consumption.0, |u, v| u.format_flow(LitresPerSecond(v))),
+  ("Force", |c| c.force.0, |u, v| u.format_force(Newtons(v))),
+];
+
+impl BreakdownWindow {
+  pub fn new<W: IsA<Window>>(parent: &W) -> Self {
+    let window = Window::new(WindowType::Toplevel);
+    window.set_title("Block Breakdown");
+    window.set_transient_for(Some(parent));
+    window.set_destroy_with_parent(true);
+    window.set_default_size(900, 400);
+    // Keep the window around when closed, so that it can be shown again.
+    window.connect_delete_event(|window, _| {
+      window.hide();
+      Inhibit(true)
+    });
+
+    let mut types = vec![Type::String];
+    for _ in VALUE_COLUMNS.iter() {
+      types.push(Type::String);
+      types.push(Type::F64);
+    }
+    let store = ListStore::new(&types);
+    let tree_view = TreeView::new_with_model(&store);
+    Self::append_column(&tree_view, "Block", 0, 0, 0.0);
+    for (i, (title, _, _)) in VALUE_COLUMNS.iter().enumerate() {
+      let text_column = 1 + i as i32 * 2;
+      Self::append_column(&tree_view, title, text_column, text_column + 1, 1.0);
+    }
+    // Sort by mass by default, as it usually dominates the budget.
+    store.set_sort_column_id(gtk::SortColumn::Index(4), SortType::Descending);
+
+    let scrolled_window = ScrolledWindow::new(None::<&gtk::Adjustment>, None::<&gtk::Adjustment>);
+    scrolled_window.set_policy(PolicyType::Automatic, PolicyType::Automatic);
+    scrolled_window.add(&tree_view);
+    window.add(&scrolled_window);
+
+    Self { window, store }
+  }
+
+  fn append_column(tree_view: &TreeView, title: &str, text_column: i32, sort_column: i32, xalign: f32) {
+    let cell = CellRendererText::new();
+    cell.set_property_xalign(xalign);
+    let column = TreeViewColumn::new();
+    column.set_title(title);
+    column.set_resizable(true);
+    column.pack_start(&cell, true);
+    column.add_attribute(&cell, "text", text_column);
+    column.set_sort_column_id(sort_column);
+    tree_view.append_column(&column);
+  }
+
+  /// Replaces the listed contributions with `contributions`.
+  pub fn update(&self, contributions: &[BlockContribution], data: &Data, units: &UnitPreferences) {
+    self.store.clear();
+    for contribution in contributions {
+      let name = data.blocks.localized_name(&contribution.id, &data.localization).unwrap_or(&contribution.id).to_string();
+      let iter = self.store.append();
+      self.store.set_value(&iter, 0, &name.to_value());
+      for (i, (_, get, format)) in VALUE_COLUMNS.iter().enumerate() {
+        let value = get(contribution);
+        let text_column = 1 + i as u32 * 2;
+        self.store.set_value(&iter, text_column, &format(units, value).to_value());
+        self.store.set_value(&iter, text_column + 1, &value.to_value());
+      }
+    }
+  }
+
+  pub fn show(&self) {
+    self.window.show_all();
+    self.window.present();
+  }
+}
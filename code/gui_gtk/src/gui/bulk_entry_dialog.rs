@@ -0,0 +1,109 @@
+use glib::object::IsA;
+use gtk::{Align, ComboBoxText, Dialog, DialogFlags, Grid, Label, PolicyType, ResponseType, ScrolledWindow, TextView, Window, WrapMode};
+use gtk::prelude::*;
+
+use secalc_core::data::blocks::{BlockId, GridSize};
+use secalc_core::data::Data;
+use secalc_core::grid::Direction;
+
+/// Block count entered in bulk, matched to a block.
+pub struct BulkEntry {
+  pub id: BlockId,
+  pub direction: Option<Direction>,
+  pub count: u64,
+}
+
+/// Dialog where users paste lines of "block name, count[, direction]", separated by commas or tabs.
+pub struct BulkEntryDialog {
+  dialog: Dialog,
+  size: ComboBoxText,
+  text: TextView,
+}
+
+impl BulkEntryDialog {
+  pub fn new<W: IsA<Window>>(parent: &W) -> Self {
+    let dialog = Dialog::new_with_buttons(Some("Bulk Block Entry"), Some(parent), DialogFlags::MODAL | DialogFlags::DESTROY_WITH_PARENT, &[("Cancel", ResponseType::Cancel), ("Apply", ResponseType::Apply)]);
+    dialog.set_default_size(500, 400);
+    let grid = Grid::new();
+    grid.set_row_spacing(2);
+    grid.set_column_spacing(5);
+    grid.set_border_width(5);
+
+    let help = Label::new(Some("Enter one block per line as 'name, count', or 'name, count, direction' for thrusters.\nTab-separated lines copied from a spreadsheet are also accepted."));
+    help.set_halign(Align::Start);
+    grid.attach(&help, 0, 0, 2, 1);
+
+    let size_label = Label::new(Some("Grid size"));
+    size_label.set_halign(Align::Start);
+    grid.attach(&size_label, 0, 1, 1, 1);
+    let size = ComboBoxText::new();
+    size.append(Some("large"), "Large");
+    size.append(Some("small"), "Small");
+    size.set_active_id(Some("large"));
+    grid.attach(&size, 1, 1, 1, 1);
+
+    let text = TextView::new();
+    text.set_monospace(true);
+    text.set_wrap_mode(WrapMode::None);
+    let scrolled_window = ScrolledWindow::new(None::<&gtk::Adjustment>, None::<&gtk::Adjustment>);
+    scrolled_window.set_policy(PolicyType::Automatic, PolicyType::Automatic);
+    scrolled_window.set_hexpand(true);
+    scrolled_window.set_vexpand(true);
+    scrolled_window.add(&text);
+    grid.attach(&scrolled_window, 0, 2, 2, 1);
+
+    dialog.get_content_area().add(&grid);
+    dialog.show_all();
+    Self { dialog, size, text }
+  }
+
+  /// Runs the dialog, returning the matched entries and a message for each line that could not be matched if the user
+  /// applied, or `None` if the user cancelled.
+  pub fn run(&self, data: &Data) -> Option<(Vec<BulkEntry>, Vec<String>)> {
+    if self.dialog.run() != ResponseType::Apply { return None; }
+    let size = match self.size.get_active_id().as_ref().map(|id| id.as_str()) {
+      Some("small") => GridSize::Small,
+      _ => GridSize::Large,
+    };
+    let buffer = self.text.get_buffer()?;
+    let (start, end) = buffer.get_bounds();
+    let text = buffer.get_text(&start, &end, false).map(|t| t.to_string()).unwrap_or_default();
+
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    for line in text.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+      match Self::parse_line(line, size, data) {
+        Ok(entry) => entries.push(entry),
+        Err(message) => errors.push(format!("{}: {}", line, message)),
+      }
+    }
+    Some((entries, errors))
+  }
+
+  fn parse_line(line: &str, size: GridSize, data: &Data) -> Result<BulkEntry, String> {
+    let fields: Vec<&str> = (if line.contains('\t') { line.split('\t') } else { line.split(',') })
+      .map(|f| f.trim())
+      .collect();
+    let (name, count, direction) = match fields.as_slice() {
+      [name, count] => (*name, *count, None),
+      [name, count, direction] => (*name, *count, Some(*direction)),
+      _ => return Err("expected a name and count, and optionally a direction".to_owned()),
+    };
+    let count = count.parse().map_err(|_| format!("'{}' is not a valid count", count))?;
+    let direction = match direction {
+      Some(direction) => Some(Direction::iter().find(|d| format!("{:?}", d).eq_ignore_ascii_case(direction)).copied()
+        .ok_or_else(|| format!("'{}' is not a valid direction", direction))?),
+      None => None,
+    };
+    let id = data.blocks.find_by_name(name, Some(size), &data.localization)
+      .ok_or_else(|| format!("no block named like '{}'", name))?
+      .clone();
+    Ok(BulkEntry { id, direction, count })
+  }
+}
+
+impl Drop for BulkEntryDialog {
+  fn drop(&mut self) {
+    self.dialog.destroy();
+  }
+}
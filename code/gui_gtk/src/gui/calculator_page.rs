@@ -0,0 +1,1873 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::str::FromStr;
+
+use gio::{Menu, SimpleAction, SimpleActionGroup};
+use gio::prelude::*;
+use glib::VariantTy;
+use gtk::{Align, ApplicationWindow, Box as GtkBox, Button, CheckButton, ComboBoxText, Entry, EntryIconPosition, Expander, Frame, Grid, InputPurpose, Label, MenuButton, Orientation, ReliefStyle, SpinButton, SpinButtonUpdatePolicy, ToggleButton, Widget};
+use gtk::prelude::*;
+use snafu::{ResultExt, Snafu};
+
+use secalc_core::grid::{CustomConsumer, GridCalculated, GridCalculator, Direction, HydrogenCategory, PowerCategory, PowerConsumer, PowerShare, ReadError, ResultValue};
+use secalc_core::grid::calculator::IncrementalCalculator;
+use secalc_core::grid::change::ChangeSet;
+use secalc_core::grid::command::CommandHistory;
+use secalc_core::grid::compare::fleet_metrics;
+use secalc_core::grid::planet::{EnvironmentCalculated, PlanetPreset};
+use secalc_core::grid::preset::GridPreset;
+use secalc_core::grid::template::GridTemplate;
+use secalc_core::grid::trip::{trip, Trip};
+use secalc_core::grid::validate::ValidationError;
+use secalc_core::grid::recommendation::{self, Recommendation};
+use secalc_core::grid::warning::{self, Warning};
+use secalc_core::data::blocks::{Block, BlockId, Blocks, DetailStats};
+use secalc_core::data::Data;
+use secalc_core::precision::Quantity;
+use secalc_core::units::{Kilograms, Litres, LitresPerSecond, MegaWatts, Meters, MetersPerSecond, Unit};
+
+use crate::gui::breakdown_window::BreakdownWindow;
+use crate::gui::preferences::Preferences;
+
+#[derive(Debug, Snafu)]
+pub enum OpenError {
+  #[snafu(display("Could not open file '{}' for reading: {}", file_path.display(), source))]
+  OpenFile { file_path: PathBuf, source: std::io::Error, },
+  #[snafu(display("Could not deserialize data from file '{}': {}", file_path.display(), source))]
+  OpenDeserialize { file_path: PathBuf, source: secalc_core::grid::ReadError, },
+  #[snafu(display("Could not merge grid from file '{}': {}", file_path.display(), source))]
+  OpenMerge { file_path: PathBuf, source: ValidationError, },
+}
+
+#[derive(Debug, Snafu)]
+pub enum SaveError {
+  #[snafu(display("Could not open file '{}' for writing: {}", file_path.display(), source))]
+  SaveFile { file_path: PathBuf, source: std::io::Error, },
+  #[snafu(display("Could not serialize data to file '{}': {}", file_path.display(), source))]
+  SaveSerialize { file_path: PathBuf, source: secalc_core::grid::WriteError, },
+}
+
+/// Calculator inputs and results for a single grid, shown as a page (tab) in the main window.
+pub struct CalculatorPage {
+  root: GtkBox,
+  tab_label: Label,
+
+  gravity_multiplier: Entry,
+  container_multiplier: Entry,
+  planetary_influence: Entry,
+  additional_mass: Entry,
+  additional_volume: Entry,
+  max_speed: Entry,
+  descent_altitude: Entry,
+  ice_only_fill: Entry,
+  ore_only_fill: Entry,
+  any_fill_with_ice: Entry,
+  any_fill_with_ore: Entry,
+  any_fill_with_steel_plates: Entry,
+  ammo_fill: Entry,
+  ammo_fill_label: Label,
+  generator_hydrogen_share: Entry,
+  battery_charge: Entry,
+  reactor_output_multiplier: Entry,
+  renewable_output: Entry,
+  renewable_output_label: Label,
+  utility_range: Entry,
+  utility_range_label: Label,
+  idle_hydrogen_thrusters: CheckButton,
+  power_priority: Entry,
+  custom_consumer_name: Entry,
+  custom_consumer_consumption: Entry,
+  custom_consumer_unit: ComboBoxText,
+  custom_consumer_add: Button,
+  custom_consumers: ComboBoxText,
+  custom_consumer_remove: Button,
+
+  warnings_frame: Frame,
+  warnings: GtkBox,
+
+  sections: Vec<(&'static str, Expander)>,
+
+  volume_mass_input_small: Grid,
+  volume_mass_input_large: Grid,
+  total_volume_any: Label,
+  total_volume_ore: Label,
+  total_volume_ice: Label,
+  total_volume_ore_only: Label,
+  total_volume_ice_only: Label,
+  total_mass_empty: Label,
+  total_mass_filled: Label,
+  total_items_ice: Label,
+  total_items_ore: Label,
+  total_items_steel_plates: Label,
+
+  acceleration_input_small: Grid,
+  acceleration_input_large: Grid,
+  thrusters: HashMap<Direction, ThrusterWidgets>,
+  trip_distance: Entry,
+  trip_direction: ComboBoxText,
+  trip_empty: TripWidgets,
+  trip_filled: TripWidgets,
+  climb_speed: Label,
+  climb_thrust: Label,
+  climb_power_consumption: Label,
+  climb_power_balance: Label,
+  climb_power_duration: Label,
+  climb_hydrogen_consumption: Label,
+  climb_hydrogen_balance: Label,
+  climb_hydrogen_duration: Label,
+  climb_safe_descent_speed_empty: Label,
+  climb_safe_descent_speed_filled: Label,
+  climb_acceleration_empty: Label,
+  climb_acceleration_filled: Label,
+  climb_descent_braking_acceleration_empty: Label,
+  climb_descent_braking_acceleration_filled: Label,
+
+  power_input_small: Grid,
+  power_input_large: Grid,
+  power_generation: Label,
+  power_capacity_battery: Label,
+  power_consumption_idle: Label,
+  power_consumption_misc: Label,
+  power_consumption_upto_jump_drive: Label,
+  power_consumption_upto_generator: Label,
+  power_consumption_upto_up_down_thruster: Label,
+  power_consumption_upto_front_back_thruster: Label,
+  power_consumption_upto_left_right_thruster: Label,
+  power_label_upto_ship_tool: Label,
+  power_consumption_upto_ship_tool: Label,
+  power_consumption_upto_battery: Label,
+  power_balance_idle: Label,
+  power_balance_misc: Label,
+  power_balance_upto_jump_drive: Label,
+  power_balance_upto_generator: Label,
+  power_balance_upto_up_down_thruster: Label,
+  power_balance_upto_front_back_thruster: Label,
+  power_balance_upto_left_right_thruster: Label,
+  power_balance_upto_ship_tool: Label,
+  power_balance_upto_battery: Label,
+  power_duration_idle: Label,
+  power_duration_misc: Label,
+  power_duration_upto_jump_drive: Label,
+  power_duration_upto_generator: Label,
+  power_duration_upto_up_down_thruster: Label,
+  power_duration_upto_front_back_thruster: Label,
+  power_duration_upto_left_right_thruster: Label,
+  power_duration_upto_ship_tool: Label,
+  power_duration_upto_battery: Label,
+
+  hydrogen_input_small: Grid,
+  hydrogen_input_large: Grid,
+  hydrogen_generation: Label,
+  hydrogen_capacity_engine: Label,
+  hydrogen_capacity_tank: Label,
+  hydrogen_consumption_idle: Label,
+  hydrogen_consumption_engine: Label,
+  hydrogen_consumption_upto_up_down_thruster: Label,
+  hydrogen_consumption_upto_front_back_thruster: Label,
+  hydrogen_consumption_upto_left_right_thruster: Label,
+  hydrogen_balance_idle: Label,
+  hydrogen_balance_engine: Label,
+  hydrogen_balance_upto_up_down_thruster: Label,
+  hydrogen_balance_upto_front_back_thruster: Label,
+  hydrogen_balance_upto_left_right_thruster: Label,
+  hydrogen_duration_idle: Label,
+  hydrogen_duration_engine: Label,
+  hydrogen_duration_upto_up_down_thruster: Label,
+  hydrogen_duration_upto_front_back_thruster: Label,
+  hydrogen_duration_upto_left_right_thruster: Label,
+
+  data: Rc<Data>,
+  preferences: Rc<RefCell<Preferences>>,
+  breakdown_window: BreakdownWindow,
+  /// Caches intermediate results, as the calculator is recalculated on every input change.
+  incremental_calculator: IncrementalCalculator,
+  state: RefCell<State> /* RefCell to support mutability for Rc<Self> in closures. */,
+  block_inputs: RefCell<BlockInputs> /* RefCell to support mutability for Rc<Self>. */,
+  block_rows: RefCell<Vec<BlockRow>> /* RefCell to support mutability for Rc<Self>. */,
+  recalculated_handler: RefCell<Option<Box<dyn Fn(&CalculatorPage, &GridCalculated)>>> /* RefCell to support mutability for Rc<Self>. */,
+}
+
+struct ThrusterWidgets {
+  force: Label,
+  acceleration_empty_no_gravity: Label,
+  acceleration_filled_no_gravity: Label,
+  acceleration_empty_gravity: Label,
+  acceleration_filled_gravity: Label,
+  time_to_max_speed_empty: Label,
+  time_to_max_speed_filled: Label,
+  distance_to_max_speed_empty: Label,
+  distance_to_max_speed_filled: Label,
+  burn_duration: Label,
+}
+
+struct TripWidgets {
+  top_speed: Label,
+  accelerate: Label,
+  coast: Label,
+  brake: Label,
+  duration: Label,
+  hydrogen: Label,
+  power: Label,
+}
+
+impl TripWidgets {
+  fn new(builder: &gtk::Builder, id_prefix: &str) -> Self {
+    let get = |id: &str| -> Label { builder.get_object(&format!("{}_{}", id_prefix, id)).unwrap() };
+    Self {
+      top_speed: get("top_speed"),
+      accelerate: get("accelerate"),
+      coast: get("coast"),
+      brake: get("brake"),
+      duration: get("duration"),
+      hydrogen: get("hydrogen"),
+      power: get("power"),
+    }
+  }
+
+  /// Shows `trip`, or `-` for all results if the trip cannot be made.
+  fn set(&self, trip: Option<Trip>) {
+    match trip {
+      Some(trip) => {
+        self.top_speed.set(trip.top_speed);
+        self.accelerate.set(trip.accelerate_duration);
+        self.coast.set(trip.coast_duration);
+        self.brake.set(trip.brake_duration);
+        self.duration.set(trip.duration);
+        self.hydrogen.set(trip.hydrogen);
+        self.power.set(trip.power);
+      }
+      None => for label in &[&self.top_speed, &self.accelerate, &self.coast, &self.brake, &self.duration, &self.hydrogen, &self.power] {
+        label.set_text("-");
+      },
+    }
+  }
+}
+
+struct State {
+  current_file_path: Option<PathBuf>,
+  /// Whether inputs were changed since the last open or save.
+  modified: bool,
+  calculator: GridCalculator,
+  /// Calculator that the shown results were calculated from, if any, to only update sections affected by changes.
+  shown_calculator: Option<GridCalculator>,
+  /// Changes to the calculator that can be undone.
+  history: CommandHistory,
+  /// Whether inputs are being set from a calculator, during which their changes are not recorded in the history.
+  restoring: bool,
+}
+
+struct BlockInputs {
+  inputs: HashMap<BlockId, SpinButton>,
+  up_inputs: HashMap<BlockId, SpinButton>,
+  down_inputs: HashMap<BlockId, SpinButton>,
+  front_inputs: HashMap<BlockId, SpinButton>,
+  back_inputs: HashMap<BlockId, SpinButton>,
+  left_inputs: HashMap<BlockId, SpinButton>,
+  right_inputs: HashMap<BlockId, SpinButton>,
+  stockpile_inputs: HashMap<BlockId, CheckButton>,
+}
+
+impl BlockInputs {
+  fn iter_inputs(&self) -> impl Iterator<Item=&SpinButton> {
+    vec![
+      self.inputs.values(),
+      self.up_inputs.values(),
+      self.down_inputs.values(),
+      self.front_inputs.values(),
+      self.back_inputs.values(),
+      self.left_inputs.values(),
+      self.right_inputs.values(),
+    ].into_iter().flat_map(|it| it)
+  }
+
+  fn directional_inputs(&self, direction: Direction) -> &HashMap<BlockId, SpinButton> {
+    match direction {
+      Direction::Up => &self.up_inputs,
+      Direction::Down => &self.down_inputs,
+      Direction::Front => &self.front_inputs,
+      Direction::Back => &self.back_inputs,
+      Direction::Left => &self.left_inputs,
+      Direction::Right => &self.right_inputs,
+    }
+  }
+}
+
+/// Widgets of a single block input row, used to show, hide, or sort the row.
+struct BlockRow {
+  id: BlockId,
+  /// Lowercase localized block name, to sort by.
+  name: String,
+  /// Index in creation order, which is the default order.
+  index: usize,
+  mass: f64,
+  power_output: f64,
+  thrust: f64,
+  grid: Grid,
+  /// Current row in `grid`.
+  row: i32,
+  /// Widgets of the row, in column order.
+  widgets: Vec<Widget>,
+  inputs: Vec<SpinButton>,
+}
+
+impl BlockRow {
+  fn has_nonzero_count(&self) -> bool {
+    self.inputs.iter().any(|input| input.count() > 0)
+  }
+
+  fn compare(&self, other: &BlockRow, key: BlockSortKey, descending: bool) -> Ordering {
+    let ordering = match key {
+      BlockSortKey::Default => self.index.cmp(&other.index),
+      BlockSortKey::Name => self.name.cmp(&other.name),
+      BlockSortKey::Mass => self.mass.partial_cmp(&other.mass).unwrap_or(Ordering::Equal),
+      BlockSortKey::PowerOutput => self.power_output.partial_cmp(&other.power_output).unwrap_or(Ordering::Equal),
+      BlockSortKey::Thrust => self.thrust.partial_cmp(&other.thrust).unwrap_or(Ordering::Equal),
+    };
+    let ordering = if descending { ordering.reverse() } else { ordering };
+    ordering.then(self.index.cmp(&other.index))
+  }
+}
+
+/// Keys that block rows can be sorted by.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum BlockSortKey {
+  Default,
+  Name,
+  Mass,
+  PowerOutput,
+  Thrust,
+}
+
+impl BlockSortKey {
+  /// All keys with their combo box identifier and label.
+  const ALL: [(BlockSortKey, &'static str, &'static str); 5] = [
+    (BlockSortKey::Default, "default", "Default"),
+    (BlockSortKey::Name, "name", "Name"),
+    (BlockSortKey::Mass, "mass", "Mass"),
+    (BlockSortKey::PowerOutput, "power_output", "Power output"),
+    (BlockSortKey::Thrust, "thrust", "Thrust"),
+  ];
+
+  fn from_id(id: &str) -> Option<Self> {
+    Self::ALL.iter().find(|(_, i, _)| *i == id).map(|(key, _, _)| *key)
+  }
+}
+
+/// Maximum block count that can be entered into a block count spin button.
+const BLOCK_COUNT_MAX: f64 = 100_000.0;
+
+impl CalculatorPage {
+  pub fn new(data: Rc<Data>, preferences: Rc<RefCell<Preferences>>, window: &ApplicationWindow) -> Rc<Self> {
+    let glade_src = include_str!("calculator_page.glade");
+    let builder = gtk::Builder::new_from_string(glade_src);
+
+    let root = builder.get_object("calculator_page").unwrap();
+    let tab_label = Label::new(None);
+
+    let gravity_multiplier = builder.get_object("gravity_multiplier").unwrap();
+    let container_multiplier = builder.get_object("container_multiplier").unwrap();
+    let planetary_influence = builder.get_object("planetary_influence").unwrap();
+    let additional_mass = builder.get_object("additional_mass").unwrap();
+    let additional_volume = builder.get_object("additional_volume").unwrap();
+    let max_speed = builder.get_object("max_speed").unwrap();
+    let descent_altitude = builder.get_object("descent_altitude").unwrap();
+    let ice_only_fill = builder.get_object("ice_only_fill").unwrap();
+    let ore_only_fill = builder.get_object("ore_only_fill").unwrap();
+    let any_fill_with_ice = builder.get_object("any_fill_with_ice").unwrap();
+    let any_fill_with_ore = builder.get_object("any_fill_with_ore").unwrap();
+    let any_fill_with_steel_plates = builder.get_object("any_fill_with_steel_plates").unwrap();
+    let ammo_fill = builder.get_object("ammo_fill").unwrap();
+    let ammo_fill_label = builder.get_object("ammo_fill_label").unwrap();
+    let generator_hydrogen_share = builder.get_object("generator_hydrogen_share").unwrap();
+    let battery_charge = builder.get_object("battery_charge").unwrap();
+    let reactor_output_multiplier = builder.get_object("reactor_output_multiplier").unwrap();
+    let renewable_output = builder.get_object("renewable_output").unwrap();
+    let renewable_output_label = builder.get_object("renewable_output_label").unwrap();
+    let utility_range = builder.get_object("utility_range").unwrap();
+    let utility_range_label = builder.get_object("utility_range_label").unwrap();
+    let idle_hydrogen_thrusters = builder.get_object("idle_hydrogen_thrusters").unwrap();
+    let power_priority = builder.get_object("power_priority").unwrap();
+    let custom_consumer_name = builder.get_object("custom_consumer_name").unwrap();
+    let custom_consumer_consumption = builder.get_object("custom_consumer_consumption").unwrap();
+    let custom_consumer_unit = builder.get_object("custom_consumer_unit").unwrap();
+    let custom_consumer_add = builder.get_object("custom_consumer_add").unwrap();
+    let custom_consumers = builder.get_object("custom_consumers").unwrap();
+    let custom_consumer_remove = builder.get_object("custom_consumer_remove").unwrap();
+
+    let warnings_frame = builder.get_object("warnings_frame").unwrap();
+    let warnings = builder.get_object("warnings").unwrap();
+
+    let sections: Vec<(&'static str, Expander)> = ["volume_mass", "acceleration", "power", "hydrogen"].iter()
+      .map(|id| (*id, builder.get_object(&format!("{}_section", id)).unwrap()))
+      .collect();
+
+    let volume_mass_input_small = builder.get_object("volume_mass_input_small").unwrap();
+    Self::cleanup_glade_grid(&volume_mass_input_small);
+    let volume_mass_input_large = builder.get_object("volume_mass_input_large").unwrap();
+    Self::cleanup_glade_grid(&volume_mass_input_large);
+    let total_volume_any = builder.get_object("total_volume_any").unwrap();
+    let total_volume_ore = builder.get_object("total_volume_ore").unwrap();
+    let total_volume_ice = builder.get_object("total_volume_ice").unwrap();
+    let total_volume_ore_only = builder.get_object("total_volume_ore_only").unwrap();
+    let total_volume_ice_only = builder.get_object("total_volume_ice_only").unwrap();
+    let total_mass_empty = builder.get_object("total_mass_empty").unwrap();
+    let total_mass_filled = builder.get_object("total_mass_filled").unwrap();
+    let total_items_ice = builder.get_object("total_items_ice").unwrap();
+    let total_items_ore = builder.get_object("total_items_ore").unwrap();
+    let total_items_steel_plates = builder.get_object("total_items_steel_plates").unwrap();
+
+    let acceleration_input_small = builder.get_object("acceleration_input_small").unwrap();
+    let acceleration_input_large = builder.get_object("acceleration_input_large").unwrap();
+    let mut thrusters = HashMap::default();
+    for side in Direction::iter() {
+      let side = *side;
+      let id_prefix = match side {
+        Direction::Up => "up",
+        Direction::Down => "down",
+        Direction::Front => "front",
+        Direction::Back => "back",
+        Direction::Left => "left",
+        Direction::Right => "right",
+      };
+      let force = builder.get_object(&(id_prefix.to_string() + "_force")).unwrap();
+      let acceleration_empty_no_gravity = builder.get_object(&(id_prefix.to_string() + "_acceleration_empty_no_gravity")).unwrap();
+      let acceleration_filled_no_gravity = builder.get_object(&(id_prefix.to_string() + "_acceleration_filled_no_gravity")).unwrap();
+      let acceleration_empty_gravity = builder.get_object(&(id_prefix.to_string() + "_acceleration_empty_gravity")).unwrap();
+      let acceleration_filled_gravity = builder.get_object(&(id_prefix.to_string() + "_acceleration_filled_gravity")).unwrap();
+      let time_to_max_speed_empty = builder.get_object(&(id_prefix.to_string() + "_time_to_max_speed_empty")).unwrap();
+      let time_to_max_speed_filled = builder.get_object(&(id_prefix.to_string() + "_time_to_max_speed_filled")).unwrap();
+      let distance_to_max_speed_empty = builder.get_object(&(id_prefix.to_string() + "_distance_to_max_speed_empty")).unwrap();
+      let distance_to_max_speed_filled = builder.get_object(&(id_prefix.to_string() + "_distance_to_max_speed_filled")).unwrap();
+      let burn_duration = builder.get_object(&(id_prefix.to_string() + "_burn_duration")).unwrap();
+      let thruster_widgets = ThrusterWidgets {
+        force,
+        acceleration_empty_no_gravity,
+        acceleration_filled_no_gravity,
+        acceleration_empty_gravity,
+        acceleration_filled_gravity,
+        time_to_max_speed_empty,
+        time_to_max_speed_filled,
+        distance_to_max_speed_empty,
+        distance_to_max_speed_filled,
+        burn_duration,
+      };
+      thrusters.insert(side, thruster_widgets);
+    }
+    let trip_distance = builder.get_object("trip_distance").unwrap();
+    let trip_direction = builder.get_object("trip_direction").unwrap();
+    let trip_empty = TripWidgets::new(&builder, "trip_empty");
+    let trip_filled = TripWidgets::new(&builder, "trip_filled");
+    let climb_speed = builder.get_object("climb_speed").unwrap();
+    let climb_thrust = builder.get_object("climb_thrust").unwrap();
+    let climb_power_consumption = builder.get_object("climb_power_consumption").unwrap();
+    let climb_power_balance = builder.get_object("climb_power_balance").unwrap();
+    let climb_power_duration = builder.get_object("climb_power_duration").unwrap();
+    let climb_hydrogen_consumption = builder.get_object("climb_hydrogen_consumption").unwrap();
+    let climb_hydrogen_balance = builder.get_object("climb_hydrogen_balance").unwrap();
+    let climb_hydrogen_duration = builder.get_object("climb_hydrogen_duration").unwrap();
+    let climb_safe_descent_speed_empty = builder.get_object("climb_safe_descent_speed_empty").unwrap();
+    let climb_safe_descent_speed_filled = builder.get_object("climb_safe_descent_speed_filled").unwrap();
+    let climb_acceleration_empty = builder.get_object("climb_acceleration_empty").unwrap();
+    let climb_acceleration_filled = builder.get_object("climb_acceleration_filled").unwrap();
+    let climb_descent_braking_acceleration_empty = builder.get_object("climb_descent_braking_acceleration_empty").unwrap();
+    let climb_descent_braking_acceleration_filled = builder.get_object("climb_descent_braking_acceleration_filled").unwrap();
+
+    let power_input_small = builder.get_object("power_input_small").unwrap();
+    Self::cleanup_glade_grid(&power_input_small);
+    let power_input_large = builder.get_object("power_input_large").unwrap();
+    Self::cleanup_glade_grid(&power_input_large);
+    let power_generation = builder.get_object("power_generation").unwrap();
+    let power_capacity_battery = builder.get_object("power_capacity_battery").unwrap();
+    let power_consumption_idle = builder.get_object("power_consumption_idle").unwrap();
+    let power_consumption_misc = builder.get_object("power_consumption_misc").unwrap();
+    let power_consumption_upto_jump_drive = builder.get_object("power_consumption_upto_jump_drive").unwrap();
+    let power_consumption_upto_generator = builder.get_object("power_consumption_upto_generator").unwrap();
+    let power_consumption_upto_up_down_thruster = builder.get_object("power_consumption_upto_up_down_thruster").unwrap();
+    let power_consumption_upto_front_back_thruster = builder.get_object("power_consumption_upto_front_back_thruster").unwrap();
+    let power_consumption_upto_left_right_thruster = builder.get_object("power_consumption_upto_left_right_thruster").unwrap();
+    let power_label_upto_ship_tool = builder.get_object("power_label_upto_ship_tool").unwrap();
+    let power_consumption_upto_ship_tool = builder.get_object("power_consumption_upto_ship_tool").unwrap();
+    let power_consumption_upto_battery = builder.get_object("power_consumption_upto_battery").unwrap();
+    let power_balance_idle = builder.get_object("power_balance_idle").unwrap();
+    let power_balance_misc = builder.get_object("power_balance_misc").unwrap();
+    let power_balance_upto_jump_drive = builder.get_object("power_balance_upto_jump_drive").unwrap();
+    let power_balance_upto_generator = builder.get_object("power_balance_upto_generator").unwrap();
+    let power_balance_upto_up_down_thruster = builder.get_object("power_balance_upto_up_down_thruster").unwrap();
+    let power_balance_upto_front_back_thruster = builder.get_object("power_balance_upto_front_back_thruster").unwrap();
+    let power_balance_upto_left_right_thruster = builder.get_object("power_balance_upto_left_right_thruster").unwrap();
+    let power_balance_upto_ship_tool = builder.get_object("power_balance_upto_ship_tool").unwrap();
+    let power_balance_upto_battery = builder.get_object("power_balance_upto_battery").unwrap();
+    let power_duration_idle = builder.get_object("power_duration_idle").unwrap();
+    let power_duration_misc = builder.get_object("power_duration_misc").unwrap();
+    let power_duration_upto_jump_drive = builder.get_object("power_duration_upto_jump_drive").unwrap();
+    let power_duration_upto_generator = builder.get_object("power_duration_upto_generator").unwrap();
+    let power_duration_upto_up_down_thruster = builder.get_object("power_duration_upto_up_down_thruster").unwrap();
+    let power_duration_upto_front_back_thruster = builder.get_object("power_duration_upto_front_back_thruster").unwrap();
+    let power_duration_upto_left_right_thruster = builder.get_object("power_duration_upto_left_right_thruster").unwrap();
+    let power_duration_upto_ship_tool = builder.get_object("power_duration_upto_ship_tool").unwrap();
+    let power_duration_upto_battery = builder.get_object("power_duration_upto_battery").unwrap();
+
+    let hydrogen_input_small = builder.get_object("hydrogen_input_small").unwrap();
+    Self::cleanup_glade_grid(&hydrogen_input_small);
+    let hydrogen_input_large = builder.get_object("hydrogen_input_large").unwrap();
+    Self::cleanup_glade_grid(&hydrogen_input_large);
+    let hydrogen_generation = builder.get_object("hydrogen_generation").unwrap();
+    let hydrogen_capacity_engine = builder.get_object("hydrogen_capacity_engine").unwrap();
+    let hydrogen_capacity_tank = builder.get_object("hydrogen_capacity_tank").unwrap();
+    let hydrogen_consumption_idle = builder.get_object("hydrogen_consumption_idle").unwrap();
+    let hydrogen_consumption_engine = builder.get_object("hydrogen_consumption_engine").unwrap();
+    let hydrogen_consumption_upto_up_down_thruster = builder.get_object("hydrogen_consumption_upto_up_down_thruster").unwrap();
+    let hydrogen_consumption_upto_front_back_thruster = builder.get_object("hydrogen_consumption_upto_front_back_thruster").unwrap();
+    let hydrogen_consumption_upto_left_right_thruster = builder.get_object("hydrogen_consumption_upto_left_right_thruster").unwrap();
+    let hydrogen_balance_idle = builder.get_object("hydrogen_balance_idle").unwrap();
+    let hydrogen_balance_engine = builder.get_object("hydrogen_balance_engine").unwrap();
+    let hydrogen_balance_upto_up_down_thruster = builder.get_object("hydrogen_balance_upto_up_down_thruster").unwrap();
+    let hydrogen_balance_upto_front_back_thruster = builder.get_object("hydrogen_balance_upto_front_back_thruster").unwrap();
+    let hydrogen_balance_upto_left_right_thruster = builder.get_object("hydrogen_balance_upto_left_right_thruster").unwrap();
+    let hydrogen_duration_idle = builder.get_object("hydrogen_duration_idle").unwrap();
+    let hydrogen_duration_engine = builder.get_object("hydrogen_duration_engine").unwrap();
+    let hydrogen_duration_upto_up_down_thruster = builder.get_object("hydrogen_duration_upto_up_down_thruster").unwrap();
+    let hydrogen_duration_upto_front_back_thruster = builder.get_object("hydrogen_duration_upto_front_back_thruster").unwrap();
+    let hydrogen_duration_upto_left_right_thruster = builder.get_object("hydrogen_duration_upto_left_right_thruster").unwrap();
+
+    let breakdown_window = BreakdownWindow::new(window);
+    let calculator = preferences.borrow().config.default_calculator();
+    let state = RefCell::new(State {
+      current_file_path: None,
+      modified: false,
+      calculator: calculator.clone(),
+      shown_calculator: None,
+      history: CommandHistory::default(),
+      restoring: false,
+    });
+    let block_inputs = RefCell::new(BlockInputs {
+      inputs: Default::default(),
+      up_inputs: Default::default(),
+      down_inputs: Default::default(),
+      front_inputs: Default::default(),
+      back_inputs: Default::default(),
+      left_inputs: Default::default(),
+      right_inputs: Default::default(),
+      stockpile_inputs: Default::default(),
+    });
+    let block_rows = RefCell::new(Vec::new());
+
+    let page = Rc::new(CalculatorPage {
+      root,
+      tab_label,
+
+      gravity_multiplier,
+      container_multiplier,
+      planetary_influence,
+      additional_mass,
+      additional_volume,
+      max_speed,
+      descent_altitude,
+      ice_only_fill,
+      ore_only_fill,
+      any_fill_with_ice,
+      any_fill_with_ore,
+      any_fill_with_steel_plates,
+      ammo_fill,
+      ammo_fill_label,
+      generator_hydrogen_share,
+      battery_charge,
+      reactor_output_multiplier,
+      renewable_output,
+      renewable_output_label,
+      utility_range,
+      utility_range_label,
+      idle_hydrogen_thrusters,
+      power_priority,
+      custom_consumer_name,
+      custom_consumer_consumption,
+      custom_consumer_unit,
+      custom_consumer_add,
+      custom_consumers,
+      custom_consumer_remove,
+
+      warnings_frame,
+      warnings,
+
+      sections,
+
+      volume_mass_input_small,
+      volume_mass_input_large,
+      total_volume_any,
+      total_volume_ore,
+      total_volume_ice,
+      total_volume_ore_only,
+      total_volume_ice_only,
+      total_mass_empty,
+      total_mass_filled,
+      total_items_ice,
+      total_items_ore,
+      total_items_steel_plates,
+
+      acceleration_input_small,
+      acceleration_input_large,
+      thrusters,
+      trip_distance,
+      trip_direction,
+      trip_empty,
+      trip_filled,
+      climb_speed,
+      climb_thrust,
+      climb_power_consumption,
+      climb_power_balance,
+      climb_power_duration,
+      climb_hydrogen_consumption,
+      climb_hydrogen_balance,
+      climb_hydrogen_duration,
+      climb_safe_descent_speed_empty,
+      climb_safe_descent_speed_filled,
+      climb_acceleration_empty,
+      climb_acceleration_filled,
+      climb_descent_braking_acceleration_empty,
+      climb_descent_braking_acceleration_filled,
+
+      power_input_small,
+      power_input_large,
+      power_generation,
+      power_capacity_battery,
+      power_consumption_idle,
+      power_consumption_misc,
+      power_consumption_upto_jump_drive,
+      power_consumption_upto_generator,
+      power_consumption_upto_up_down_thruster,
+      power_consumption_upto_front_back_thruster,
+      power_consumption_upto_left_right_thruster,
+      power_label_upto_ship_tool,
+      power_consumption_upto_ship_tool,
+      power_consumption_upto_battery,
+      power_balance_idle,
+      power_balance_misc,
+      power_balance_upto_jump_drive,
+      power_balance_upto_generator,
+      power_balance_upto_up_down_thruster,
+      power_balance_upto_front_back_thruster,
+      power_balance_upto_left_right_thruster,
+      power_balance_upto_ship_tool,
+      power_balance_upto_battery,
+      power_duration_idle,
+      power_duration_misc,
+      power_duration_upto_jump_drive,
+      power_duration_upto_generator,
+      power_duration_upto_up_down_thruster,
+      power_duration_upto_front_back_thruster,
+      power_duration_upto_left_right_thruster,
+      power_duration_upto_ship_tool,
+      power_duration_upto_battery,
+
+      hydrogen_input_small,
+      hydrogen_input_large,
+      hydrogen_generation,
+      hydrogen_capacity_engine,
+      hydrogen_capacity_tank,
+      hydrogen_consumption_idle,
+      hydrogen_consumption_engine,
+      hydrogen_consumption_upto_up_down_thruster,
+      hydrogen_consumption_upto_front_back_thruster,
+      hydrogen_consumption_upto_left_right_thruster,
+      hydrogen_balance_idle,
+      hydrogen_balance_engine,
+      hydrogen_balance_upto_up_down_thruster,
+      hydrogen_balance_upto_front_back_thruster,
+      hydrogen_balance_upto_left_right_thruster,
+      hydrogen_duration_idle,
+      hydrogen_duration_engine,
+      hydrogen_duration_upto_up_down_thruster,
+      hydrogen_duration_upto_front_back_thruster,
+      hydrogen_duration_upto_left_right_thruster,
+
+      data,
+      preferences,
+      breakdown_window,
+      incremental_calculator: IncrementalCalculator::new(),
+      state,
+      block_inputs,
+      block_rows,
+      recalculated_handler: RefCell::new(None),
+    });
+    page.clone().initialize();
+    page.show_calculator(&calculator);
+    page.update_tab_label();
+    page.recalculate();
+    page
+  }
+
+  fn initialize(self: Rc<Self>) {
+    for (id, expander) in &self.sections {
+      let id = *id;
+      expander.set_expanded(!self.preferences.borrow().collapsed_sections.contains(id));
+      let self_cloned = self.clone();
+      expander.connect_property_expanded_notify(move |expander| {
+        let collapsed_sections = &mut self_cloned.preferences.borrow_mut().collapsed_sections;
+        if expander.get_expanded() {
+          collapsed_sections.remove(id);
+        } else {
+          collapsed_sections.insert(id.to_string());
+        }
+      });
+    }
+
+    self.gravity_multiplier.set_and_recalc_on_change(&self, 1.0, |c| &mut c.gravity_multiplier);
+    self.container_multiplier.set_and_recalc_on_change(&self, 1.0, |c| &mut c.container_multiplier);
+    self.planetary_influence.set_and_recalc_on_change(&self, 1.0, |c| &mut c.planetary_influence);
+    self.additional_mass.set_and_recalc_on_change(&self, Kilograms(0.0), |c| &mut c.additional_mass);
+    self.additional_volume.set_and_recalc_on_change(&self, Litres(0.0), |c| &mut c.additional_volume);
+    self.max_speed.set_and_recalc_on_change(&self, MetersPerSecond(100.0), |c| &mut c.max_speed);
+    self.descent_altitude.set_and_recalc_on_change(&self, Meters(100.0), |c| &mut c.descent_altitude);
+    self.ice_only_fill.set_and_recalc_on_change(&self, 100.0, |c| &mut c.ice_only_fill);
+    self.ore_only_fill.set_and_recalc_on_change(&self, 100.0, |c| &mut c.ore_only_fill);
+    self.clone().create_any_fill_inputs();
+    self.ammo_fill.set_and_recalc_on_change(&self, 100.0, |c| &mut c.ammo_fill);
+    self.generator_hydrogen_share.set_and_recalc_on_change(&self, 100.0, |c| &mut c.generator_hydrogen_share);
+    self.battery_charge.set_and_recalc_on_change(&self, 100.0, |c| &mut c.battery_charge);
+    self.reactor_output_multiplier.set_and_recalc_on_change(&self, 1.0, |c| &mut c.reactor_output_multiplier);
+    self.renewable_output.set_and_recalc_on_change(&self, 100.0, |c| &mut c.renewable_output);
+    self.utility_range.set_and_recalc_on_change(&self, 100.0, |c| &mut c.utility_range);
+    // Data extracted before these block kinds were supported has none of them, so hide their inputs and results.
+    Self::hide_unless(!self.data.blocks.renewables.is_empty(), &[self.renewable_output_label.upcast_ref(), self.renewable_output.upcast_ref()]);
+    Self::hide_unless(!self.data.blocks.utilities.is_empty(), &[self.utility_range_label.upcast_ref(), self.utility_range.upcast_ref()]);
+    Self::hide_unless(!self.data.blocks.weapons.is_empty(), &[self.ammo_fill_label.upcast_ref(), self.ammo_fill.upcast_ref()]);
+    Self::hide_unless(!self.data.blocks.ship_tools.is_empty(), &[
+      self.power_label_upto_ship_tool.upcast_ref(),
+      self.power_consumption_upto_ship_tool.upcast_ref(),
+      self.power_balance_upto_ship_tool.upcast_ref(),
+      self.power_duration_upto_ship_tool.upcast_ref(),
+    ]);
+    {
+      let page = self.clone();
+      self.idle_hydrogen_thrusters.connect_toggled(move |check_button| {
+        page.apply_input_change(|c| c.idle_hydrogen_thrusters = check_button.get_active());
+      });
+    }
+    {
+      let page = self.clone();
+      self.power_priority.connect_changed(move |entry| {
+        // Like numeric options, keep the last valid priority on errors.
+        let text = entry.get_text().map(|t| t.to_string()).unwrap_or_default();
+        let mut calculator = page.state.borrow().calculator.clone();
+        let result = PowerConsumer::parse_priority(&text).map_err(|e| e.to_string()).and_then(|priority| {
+          calculator.power_priority = priority;
+          calculator.validate().map_err(|e| e.to_string())
+        });
+        if let Err(message) = result {
+          entry.set_error(Some(&message));
+          return;
+        }
+        entry.set_error(None);
+        page.apply_input_change(|c| c.power_priority = calculator.power_priority);
+      });
+    }
+    {
+      let page = self.clone();
+      self.custom_consumer_add.connect_clicked(move |_| {
+        let name = page.custom_consumer_name.get_text().map(|t| t.trim().to_owned()).unwrap_or_default();
+        if name.is_empty() { return; }
+        let consumption = match page.custom_consumer_consumption.parse(0.0) {
+          Ok(consumption) => consumption,
+          Err(message) => {
+            page.custom_consumer_consumption.set_error(Some(&message));
+            return;
+          }
+        };
+        let hydrogen = page.custom_consumer_unit.get_active_id().map_or(false, |id| id == "hydrogen");
+        let result = page.modify_calculator(|c| {
+          if hydrogen {
+            c.custom_hydrogen_consumers.push(CustomConsumer { name, consumption: LitresPerSecond(consumption) });
+          } else {
+            c.custom_power_consumers.push(CustomConsumer { name, consumption: MegaWatts(consumption) });
+          }
+          c.validate()
+        });
+        match result {
+          Ok(()) => {
+            page.custom_consumer_consumption.set_error(None);
+            page.custom_consumer_name.set_text("");
+            page.custom_consumer_consumption.set_text("");
+          }
+          Err(e) => page.custom_consumer_consumption.set_error(Some(&e.to_string())),
+        }
+      });
+    }
+    {
+      let page = self.clone();
+      self.custom_consumer_remove.connect_clicked(move |_| {
+        if let Some(index) = page.custom_consumers.get_active() {
+          // Power consumers are listed before hydrogen consumers.
+          let index = index as usize;
+          let _: Result<(), ()> = page.modify_calculator(|c| {
+            let power_count = c.custom_power_consumers.len();
+            if index < power_count {
+              c.custom_power_consumers.remove(index);
+            } else if index - power_count < c.custom_hydrogen_consumers.len() {
+              c.custom_hydrogen_consumers.remove(index - power_count);
+            }
+            Ok(())
+          });
+        }
+      });
+    }
+
+    {
+      let page = self.clone();
+      self.trip_distance.connect_changed(move |_| page.update_trip(&page.calculate()));
+      let page = self.clone();
+      self.trip_direction.connect_changed(move |_| page.update_trip(&page.calculate()));
+    }
+
+    // Volume & Mass
+    self.clone().create_block_inputs(self.data.blocks.containers.values().filter(|c| c.details.store_any), &self.volume_mass_input_small, &self.volume_mass_input_large, |c| &mut c.blocks);
+    self.clone().create_block_inputs(self.data.blocks.cockpits.values().filter(|c| c.details.has_inventory), &self.volume_mass_input_small, &self.volume_mass_input_large, |c| &mut c.blocks);
+    self.clone().create_block_inputs(self.data.blocks.weapons.values(), &self.volume_mass_input_small, &self.volume_mass_input_large, |c| &mut c.blocks);
+    // Acceleration
+    self.clone().create_acceleration_block_inputs(self.data.blocks.thrusters.values(), &self.acceleration_input_small, &self.acceleration_input_large);
+    self.clone().create_thruster_copy_menus();
+    // Power
+    self.clone().create_block_inputs(self.data.blocks.hydrogen_engines.values(), &self.power_input_small, &self.power_input_large, |c| &mut c.blocks);
+    self.clone().create_block_inputs(self.data.blocks.reactors.values(), &self.power_input_small, &self.power_input_large, |c| &mut c.blocks);
+    self.clone().create_block_inputs(self.data.blocks.renewables.values(), &self.power_input_small, &self.power_input_large, |c| &mut c.blocks);
+    self.clone().create_block_inputs(self.data.blocks.batteries.values(), &self.power_input_small, &self.power_input_large, |c| &mut c.blocks);
+    self.clone().create_block_inputs(self.data.blocks.ship_tools.values(), &self.power_input_small, &self.power_input_large, |c| &mut c.blocks);
+    self.clone().create_block_inputs(self.data.blocks.utilities.values(), &self.power_input_small, &self.power_input_large, |c| &mut c.blocks);
+    self.clone().create_block_inputs(self.data.blocks.accessories.values(), &self.power_input_small, &self.power_input_large, |c| &mut c.blocks);
+    self.clone().create_block_inputs(self.data.blocks.automations.values(), &self.power_input_small, &self.power_input_large, |c| &mut c.blocks);
+    self.clone().create_block_inputs(self.data.blocks.life_supports.values(), &self.power_input_small, &self.power_input_large, |c| &mut c.blocks);
+    self.clone().create_block_inputs(self.data.blocks.gyroscopes.values(), &self.power_input_small, &self.power_input_large, |c| &mut c.blocks);
+    // Hydrogen
+    self.clone().create_block_inputs(self.data.blocks.generators.values(), &self.hydrogen_input_small, &self.hydrogen_input_large, |c| &mut c.blocks);
+    self.clone().create_hydrogen_tank_inputs();
+
+    self.create_sort_controls();
+  }
+
+
+  pub fn root(&self) -> &GtkBox {
+    &self.root
+  }
+
+  pub fn tab_label(&self) -> &Label {
+    &self.tab_label
+  }
+
+  pub fn current_file_path(&self) -> Option<PathBuf> {
+    self.state.borrow().current_file_path.clone()
+  }
+
+  /// Returns true if this page has no file and its inputs were never changed.
+  pub fn is_pristine(&self) -> bool {
+    let state = self.state.borrow();
+    state.current_file_path.is_none() && !state.modified
+  }
+
+  pub fn calculate(&self) -> GridCalculated {
+    let state = self.state.borrow();
+    let mut calculated = state.calculator.calculate_with(&self.incremental_calculator, &self.data);
+    calculated.breakdown = Some(state.calculator.breakdown(&self.data));
+    calculated
+  }
+
+  /// Gets the total number of each component needed to build the grid.
+  pub fn component_totals(&self) -> Vec<(String, f64)> {
+    self.state.borrow().calculator.component_totals(&self.data)
+  }
+
+  /// Gets the key metrics of the grid for comparing it against other grids of a fleet.
+  pub fn fleet_metrics(&self) -> Vec<ResultValue> {
+    fleet_metrics(&self.state.borrow().calculator, &self.data)
+  }
+
+  /// Calculates like `calculate` once in each of `environments`.
+  pub fn calculate_in_environments(&self, environments: &[PlanetPreset]) -> Vec<EnvironmentCalculated> {
+    self.state.borrow().calculator.calculate_in_environments(&self.data, environments)
+  }
+
+  pub fn show_breakdown(&self) {
+    self.breakdown_window.show();
+  }
+
+  fn update_tab_label(&self) {
+    let title = self.state.borrow().current_file_path.as_ref()
+      .and_then(|p| p.file_name())
+      .map(|n| n.to_string_lossy().into_owned())
+      .unwrap_or_else(|| "Untitled".to_owned());
+    self.tab_label.set_text(&title);
+  }
+
+
+  fn hide_unless(show: bool, widgets: &[&Widget]) {
+    if show { return; }
+    for widget in widgets {
+      // Prevent `show_all` of the page from showing the widget again.
+      widget.set_no_show_all(true);
+      widget.hide();
+    }
+  }
+
+  fn cleanup_glade_grid(grid: &Grid) {
+    // Remove a column and 3 rows, because Glade always creates 3x3 grids.
+    grid.remove_column(2);
+    grid.remove_row(2);
+    grid.remove_row(1);
+    grid.remove_row(0);
+  }
+
+  fn create_block_inputs<'a, T: DetailStats + 'a, I, F>(
+    self: Rc<Self>,
+    iter: I,
+    small_grid: &Grid,
+    large_grid: &Grid,
+    calculator_func: F
+  ) where
+    F: (Fn(&mut GridCalculator) -> &mut HashMap<BlockId, u64>) + 'static + Copy,
+    I: Iterator<Item=&'a Block<T>>
+  {
+    let (small, large) = Blocks::small_and_large_sorted(iter);
+    self.clone().create_block_input_grid(small, small_grid, calculator_func);
+    self.create_block_input_grid(large, large_grid, calculator_func);
+  }
+
+  fn create_block_input_grid<T: DetailStats, F>(
+    self: Rc<Self>,
+    blocks: Vec<&Block<T>>,
+    grid: &Grid,
+    calculator_func: F
+  ) where
+    F: (Fn(&mut GridCalculator) -> &mut HashMap<BlockId, u64>) + 'static + Copy
+  {
+    let index_offset = grid.get_children().len() as i32;
+    for (index, block) in blocks.into_iter().enumerate() {
+      let index = index as i32 + index_offset;
+      grid.insert_row(index as i32);
+      let label = self.create_block_label(block);
+      grid.attach(&label, 0, index, 1, 1);
+      let input = Self::create_block_count_input();
+      input.insert_and_recalc_on_change(&self, block.id.clone(), calculator_func);
+      grid.attach(&input, 1, index, 1, 1);
+      self.block_inputs.borrow_mut().inputs.insert(block.id.clone(), input.clone());
+      self.add_block_row(block, grid, index, label, vec![input]);
+    }
+  }
+
+
+  /// Connects the any-fill entries, which are parsed and validated together because their sum must not exceed 100%, so
+  /// that correcting one of them also applies the others and clears their errors.
+  fn create_any_fill_inputs(self: Rc<Self>) {
+    let fills: [(Entry, fn(&mut GridCalculator) -> &mut f64); 3] = [
+      (self.any_fill_with_ice.clone(), |c| &mut c.any_fill_with_ice),
+      (self.any_fill_with_ore.clone(), |c| &mut c.any_fill_with_ore),
+      (self.any_fill_with_steel_plates.clone(), |c| &mut c.any_fill_with_steel_plates),
+    ];
+    for (entry, _) in &fills {
+      let page = self.clone();
+      let fills = fills.clone();
+      entry.connect_changed(move |_| {
+        let mut calculator = page.state.borrow().calculator.clone();
+        let mut valid = true;
+        for (entry, func) in &fills {
+          match entry.parse(0.0) {
+            Ok(value) => *func(&mut calculator) = value,
+            Err(message) => {
+              entry.set_error(Some(&message));
+              valid = false;
+            }
+          }
+        }
+        if !valid { return; }
+        let error = calculator.validate().err().map(|e| e.to_string());
+        for (entry, _) in &fills {
+          entry.set_error(error.as_deref());
+        }
+        if error.is_none() {
+          page.apply_input_change(|c| *c = calculator);
+        }
+      });
+    }
+  }
+
+  /// Creates hydrogen tank inputs like `create_block_inputs`, with an additional toggle for stockpile mode per tank.
+  fn create_hydrogen_tank_inputs(self: Rc<Self>) {
+    self.clone().create_block_inputs(self.data.blocks.hydrogen_tanks.values(), &self.hydrogen_input_small, &self.hydrogen_input_large, |c| &mut c.blocks);
+    let mut block_rows = self.block_rows.borrow_mut();
+    for row in block_rows.iter_mut().filter(|r| self.data.blocks.hydrogen_tanks.contains_key(&r.id)) {
+      let stockpile = CheckButton::new_with_label("Stockpile");
+      stockpile.set_tooltip_text(Some("Tanks in stockpile mode keep their hydrogen in reserve instead of feeding thrusters and engines"));
+      row.grid.attach(&stockpile, 2, row.row, 1, 1);
+      stockpile.show();
+      {
+        let page = self.clone();
+        let id = row.id.clone();
+        stockpile.connect_toggled(move |stockpile| {
+          let active = stockpile.get_active();
+          page.apply_input_change(|c| if active {
+            c.stockpile_hydrogen_tanks.insert(id.clone());
+          } else {
+            c.stockpile_hydrogen_tanks.remove(&id);
+          });
+        });
+      }
+      self.block_inputs.borrow_mut().stockpile_inputs.insert(row.id.clone(), stockpile.clone());
+      row.widgets.push(stockpile.upcast());
+    }
+  }
+
+  fn create_acceleration_block_inputs<'a, T: DetailStats + 'a, I>(
+    self: Rc<Self>,
+    iter: I,
+    small_grid: &Grid,
+    large_grid: &Grid,
+  ) where
+    I: Iterator<Item=&'a Block<T>>
+  {
+    let (small, large) = Blocks::small_and_large_sorted(iter);
+    self.clone().create_acceleration_block_input_grid(small, small_grid);
+    self.create_acceleration_block_input_grid(large, large_grid);
+  }
+
+  fn create_acceleration_block_input_grid<T: DetailStats>(
+    self: Rc<Self>,
+    blocks: Vec<&Block<T>>,
+    grid: &Grid,
+  ) {
+    let mut block_inputs = self.block_inputs.borrow_mut();
+    for (index, block) in blocks.into_iter().enumerate() {
+      let index = index as i32 + 1;
+      grid.insert_row(index as i32);
+      let label = self.create_block_label(block);
+      grid.attach(&label, 0, index, 1, 1);
+
+      let input_up = Self::create_block_count_input();
+      input_up.insert_and_recalc_on_change(&self, block.id.clone(), |c| c.direction_mut(Direction::Up));
+      grid.attach(&input_up, 1, index, 1, 1);
+      block_inputs.up_inputs.insert(block.id.clone(), input_up.clone());
+
+      let input_down = Self::create_block_count_input();
+      input_down.insert_and_recalc_on_change(&self, block.id.clone(), |c| c.direction_mut(Direction::Down));
+      grid.attach(&input_down, 2, index, 1, 1);
+      block_inputs.down_inputs.insert(block.id.clone(), input_down.clone());
+
+      let input_front = Self::create_block_count_input();
+      input_front.insert_and_recalc_on_change(&self, block.id.clone(), |c| c.direction_mut(Direction::Front));
+      grid.attach(&input_front, 3, index, 1, 1);
+      block_inputs.front_inputs.insert(block.id.clone(), input_front.clone());
+
+      let input_back = Self::create_block_count_input();
+      input_back.insert_and_recalc_on_change(&self, block.id.clone(), |c| c.direction_mut(Direction::Back));
+      grid.attach(&input_back, 4, index, 1, 1);
+      block_inputs.back_inputs.insert(block.id.clone(), input_back.clone());
+
+      let input_left = Self::create_block_count_input();
+      input_left.insert_and_recalc_on_change(&self, block.id.clone(), |c| c.direction_mut(Direction::Left));
+      grid.attach(&input_left, 5, index, 1, 1);
+      block_inputs.left_inputs.insert(block.id.clone(), input_left.clone());
+
+      let input_right = Self::create_block_count_input();
+      input_right.insert_and_recalc_on_change(&self, block.id.clone(), |c| c.direction_mut(Direction::Right));
+      grid.attach(&input_right, 6, index, 1, 1);
+      block_inputs.right_inputs.insert(block.id.clone(), input_right.clone());
+
+      self.add_block_row(block, grid, index, label, vec![input_up, input_down, input_front, input_back, input_left, input_right]);
+    }
+  }
+
+
+  /// Replaces the direction column headers of the acceleration grids with menu buttons to copy thruster counts from
+  /// that direction to other directions.
+  fn create_thruster_copy_menus(self: Rc<Self>) {
+    let action = SimpleAction::new("copy-thrusters", Some(VariantTy::new("s").unwrap()));
+    let self_cloned = self.clone();
+    action.connect_activate(move |_, parameter| {
+      // Parameter is formatted as '<from>:<to>', where `to` is empty to clear `from`.
+      let parameter = parameter.and_then(|p| p.get_str()).unwrap_or_default();
+      let mut split = parameter.splitn(2, ':');
+      let from = split.next().and_then(Self::parse_direction);
+      let to = split.next().and_then(Self::parse_direction);
+      match (from, to) {
+        (Some(from), Some(to)) => self_cloned.copy_thrusters(from, to),
+        (Some(from), None) => self_cloned.clear_thrusters(from),
+        _ => {}
+      }
+    });
+    let action_group = SimpleActionGroup::new();
+    action_group.add_action(&action);
+    self.root.insert_action_group("page", Some(&action_group));
+
+    for grid in &[&self.acceleration_input_small, &self.acceleration_input_large] {
+      for (index, direction) in Direction::iter().enumerate() {
+        let column = index as i32 + 1;
+        if let Some(header) = grid.get_child_at(column, 0) {
+          grid.remove(&header);
+        }
+        let menu = Menu::new();
+        for other in Direction::iter().filter(|d| *d != direction) {
+          menu.append(Some(&format!("Copy to {:?}", other)), Some(&format!("page.copy-thrusters('{:?}:{:?}')", direction, other)));
+        }
+        menu.append(Some("Clear"), Some(&format!("page.copy-thrusters('{:?}:')", direction)));
+        let button = MenuButton::new();
+        button.set_label(&format!("{:?}", direction));
+        button.set_relief(ReliefStyle::None);
+        button.set_tooltip_text(Some("Copy thruster counts to another direction"));
+        button.set_menu_model(Some(&menu));
+        grid.attach(&button, column, 0, 1, 1);
+      }
+    }
+  }
+
+  fn parse_direction(text: &str) -> Option<Direction> {
+    Direction::iter().find(|d| format!("{:?}", d) == text).copied()
+  }
+
+  /// Sets the thruster counts of direction `to` to those of direction `from`.
+  fn copy_thrusters(&self, from: Direction, to: Direction) {
+    // PERF: setting SpinButtons will trigger their signals, each which mutably borrow `state` and recalculates.
+    let block_inputs = self.block_inputs.borrow();
+    let to_inputs = block_inputs.directional_inputs(to);
+    for (id, from_input) in block_inputs.directional_inputs(from) {
+      if let Some(to_input) = to_inputs.get(id) {
+        to_input.set_count(from_input.count());
+      }
+    }
+  }
+
+  /// Sets the count of block `id` in `direction`, or of a non-directional block if `direction` is `None`. Returns false
+  /// if there is no such input.
+  pub fn set_block_count(&self, id: &BlockId, direction: Option<Direction>, count: u64) -> bool {
+    let block_inputs = self.block_inputs.borrow();
+    let inputs = match direction {
+      Some(direction) => block_inputs.directional_inputs(direction),
+      None => &block_inputs.inputs,
+    };
+    match inputs.get(id) {
+      Some(input) => {
+        input.set_count(count);
+        true
+      }
+      None => false,
+    }
+  }
+
+  fn clear_thrusters(&self, direction: Direction) {
+    for input in self.block_inputs.borrow().directional_inputs(direction).values() {
+      input.set_count(0);
+    }
+  }
+
+
+  fn add_block_row<T: DetailStats>(&self, block: &Block<T>, grid: &Grid, row: i32, label: Label, inputs: Vec<SpinButton>) {
+    let name = block.name(&self.data.localization).to_lowercase();
+    let mut widgets: Vec<Widget> = vec![label.upcast()];
+    widgets.extend(inputs.iter().map(|input| input.clone().upcast()));
+    let mut block_rows = self.block_rows.borrow_mut();
+    let index = block_rows.len();
+    block_rows.push(BlockRow {
+      id: block.id.clone(),
+      name,
+      index,
+      mass: block.mass(&self.data.components),
+      power_output: block.details.power_output(),
+      thrust: block.details.thrust(),
+      grid: grid.clone(),
+      row,
+      widgets,
+      inputs,
+    });
+  }
+
+  fn section_grids(&self, section: &str) -> [&Grid; 2] {
+    match section {
+      "volume_mass" => [&self.volume_mass_input_small, &self.volume_mass_input_large],
+      "acceleration" => [&self.acceleration_input_small, &self.acceleration_input_large],
+      "power" => [&self.power_input_small, &self.power_input_large],
+      _ => [&self.hydrogen_input_small, &self.hydrogen_input_large],
+    }
+  }
+
+  /// Adds controls for sorting the block rows above the contents of each section.
+  fn create_sort_controls(self: Rc<Self>) {
+    for (id, expander) in &self.sections {
+      let id = *id;
+      let content = match expander.get_child() {
+        Some(content) => content,
+        None => continue,
+      };
+      expander.remove(&content);
+
+      let key = ComboBoxText::new();
+      for (_, key_id, label) in BlockSortKey::ALL.iter() {
+        key.append(Some(key_id), label);
+      }
+      key.set_active_id(Some("default"));
+      let descending = ToggleButton::new_with_label("Descending");
+      let controls = GtkBox::new(Orientation::Horizontal, 5);
+      controls.pack_start(&Label::new(Some("Sort blocks by")), false, false, 0);
+      controls.pack_start(&key, false, false, 0);
+      controls.pack_start(&descending, false, false, 0);
+
+      let self_cloned = self.clone();
+      let descending_cloned = descending.clone();
+      key.connect_changed(move |key| {
+        self_cloned.sort_section(id, key, &descending_cloned);
+      });
+      let self_cloned = self.clone();
+      let key_cloned = key.clone();
+      descending.connect_toggled(move |descending| {
+        self_cloned.sort_section(id, &key_cloned, descending);
+      });
+
+      let section_box = GtkBox::new(Orientation::Vertical, 5);
+      section_box.pack_start(&controls, false, false, 0);
+      section_box.pack_start(&content, true, true, 0);
+      section_box.show_all();
+      expander.add(&section_box);
+    }
+  }
+
+  fn sort_section(&self, section: &str, key: &ComboBoxText, descending: &ToggleButton) {
+    let key = key.get_active_id().and_then(|id| BlockSortKey::from_id(id.as_str())).unwrap_or(BlockSortKey::Default);
+    for grid in self.section_grids(section).iter() {
+      self.sort_block_rows(grid, key, descending.get_active());
+    }
+  }
+
+  /// Reorders the block rows in `grid` by `key`, reusing the rows they currently occupy.
+  fn sort_block_rows(&self, grid: &Grid, key: BlockSortKey, descending: bool) {
+    let mut block_rows = self.block_rows.borrow_mut();
+    let mut rows: Vec<&mut BlockRow> = block_rows.iter_mut().filter(|r| r.grid == *grid).collect();
+    let mut positions: Vec<i32> = rows.iter().map(|r| r.row).collect();
+    positions.sort();
+    rows.sort_by(|a, b| a.compare(b, key, descending));
+    // Remove all widgets first, so that re-attaching them does not overlap rows that have not moved yet.
+    for row in rows.iter() {
+      for widget in &row.widgets {
+        grid.remove(widget);
+      }
+    }
+    for (row, position) in rows.into_iter().zip(positions) {
+      for (column, widget) in row.widgets.iter().enumerate() {
+        grid.attach(widget, column as i32, position, 1, 1);
+      }
+      row.row = position;
+    }
+  }
+
+  /// Shows only block rows matching search `query` (see `BlockQuery::search`), and which have a nonzero count if
+  /// `hide_zero_count` is true.
+  pub fn update_block_row_visibility(&self, query: &str, hide_zero_count: bool) {
+    let matching: Option<HashSet<BlockId>> = if query.is_empty() {
+      None
+    } else {
+      Some(self.data.blocks.query(&self.data.localization, &self.data.components).search(query).run().into_iter().map(|m| m.id.clone()).collect())
+    };
+    for row in self.block_rows.borrow().iter() {
+      let matches_query = matching.as_ref().map_or(true, |ids| ids.contains(&row.id));
+      let visible = matches_query && (!hide_zero_count || row.has_nonzero_count());
+      for widget in &row.widgets {
+        widget.set_visible(visible);
+      }
+    }
+  }
+
+
+  fn create_block_label<T: DetailStats>(&self, block: &Block<T>) -> Label {
+    let name = block.name(&self.data.localization);
+    let label = Self::create_static_label(name);
+    let mut tooltip = name.to_string();
+    for stat in block.stats(&self.data.components, &self.data.gas_properties) {
+      tooltip.push_str(&format!("\n{}: {}", stat.label, stat.format()));
+    }
+    label.set_tooltip_text(Some(&tooltip));
+    label
+  }
+
+  fn create_static_label(label: &str) -> Label {
+    let label = Label::new(Some(label));
+    label.set_halign(Align::Start);
+    label
+  }
+
+  fn create_block_count_input() -> SpinButton {
+    let input = SpinButton::new_with_range(0.0, BLOCK_COUNT_MAX, 1.0);
+    input.set_digits(0);
+    input.set_numeric(true);
+    input.set_increments(1.0, 10.0);
+    input.set_update_policy(SpinButtonUpdatePolicy::IfValid);
+    input.set_input_purpose(InputPurpose::Digits);
+    input.set_width_chars(3);
+    input
+  }
+
+
+  pub fn recalculate(&self) {
+    let calculated = self.calculate();
+    let changes = {
+      let mut state = self.state.borrow_mut(); // Scoped borrow.
+      let changes = state.shown_calculator.as_ref().map_or_else(ChangeSet::all, |shown| state.calculator.changes_from(shown, &self.data));
+      state.shown_calculator = Some(state.calculator.clone());
+      changes
+    };
+
+    // Volume & Mass
+    if changes.volume || changes.mass || changes.items {
+      self.total_volume_any.set(calculated.volume_mass.total_volume_any);
+      self.total_volume_ore.set(calculated.volume_mass.total_volume_ore);
+      self.total_volume_ice.set(calculated.volume_mass.total_volume_ice);
+      self.total_volume_ore_only.set(calculated.volume_mass.total_volume_ore_only);
+      self.total_volume_ice_only.set(calculated.volume_mass.total_volume_ice_only);
+      self.total_mass_empty.set(calculated.volume_mass.total_mass_empty);
+      self.total_mass_filled.set(calculated.volume_mass.total_mass_filled);
+      self.total_items_ice.set_items(calculated.volume_mass.total_items_ice);
+      self.total_items_ore.set_items(calculated.volume_mass.total_items_ore);
+      self.total_items_steel_plates.set_items(calculated.volume_mass.total_items_steel_plate);
+    }
+    // Force & Acceleration
+    if changes.acceleration {
+      for (side, a) in calculated.acceleration.iter() {
+        let widgets = self.thrusters.get(side).unwrap();
+        widgets.force.set(a.force);
+        widgets.acceleration_empty_no_gravity.set(a.acceleration_empty_no_gravity);
+        widgets.acceleration_filled_no_gravity.set(a.acceleration_filled_no_gravity);
+        widgets.acceleration_empty_gravity.set(a.acceleration_empty_gravity);
+        widgets.acceleration_filled_gravity.set(a.acceleration_filled_gravity);
+        widgets.time_to_max_speed_empty.set(a.time_to_max_speed_empty);
+        widgets.time_to_max_speed_filled.set(a.time_to_max_speed_filled);
+        widgets.distance_to_max_speed_empty.set(a.distance_to_max_speed_empty);
+        widgets.distance_to_max_speed_filled.set(a.distance_to_max_speed_filled);
+      }
+    }
+    if changes.acceleration || changes.hydrogen {
+      for (side, widgets) in self.thrusters.iter() {
+        match calculated.hydrogen.burn_duration.get(side) {
+          Some(duration) => widgets.burn_duration.set(*duration),
+          None => widgets.burn_duration.set_text("-"),
+        }
+      }
+    }
+    if changes.acceleration || changes.power || changes.hydrogen {
+      self.update_trip(&calculated);
+      let climb = &calculated.climb;
+      self.climb_speed.set(climb.speed);
+      self.climb_thrust.set_text(&Quantity::Ratio.format(climb.thrust));
+      self.climb_power_consumption.set(climb.power.consumption);
+      self.climb_power_balance.set_balance(climb.power.balance);
+      self.climb_power_duration.set(climb.power.duration);
+      self.climb_hydrogen_consumption.set(climb.hydrogen.consumption);
+      self.climb_hydrogen_balance.set_balance(climb.hydrogen.balance);
+      self.climb_hydrogen_duration.set(climb.hydrogen.duration);
+      self.climb_safe_descent_speed_empty.set(climb.safe_descent_speed_empty);
+      self.climb_safe_descent_speed_filled.set(climb.safe_descent_speed_filled);
+      self.climb_acceleration_empty.set(climb.climb_acceleration_empty);
+      self.climb_acceleration_filled.set(climb.climb_acceleration_filled);
+      self.climb_descent_braking_acceleration_empty.set(climb.descent_braking_acceleration_empty);
+      self.climb_descent_braking_acceleration_filled.set(climb.descent_braking_acceleration_filled);
+    }
+    // Power
+    if changes.power {
+      self.power_generation.set(calculated.power.generation);
+      self.power_capacity_battery.set(calculated.power.capacity_battery);
+      self.power_capacity_battery.set_tooltip_text(Some(&format!("{} MWh stored at the start, fully recharged in {} min", calculated.power.stored_battery.format(), calculated.power.recharge_duration.format())));
+      self.power_consumption_idle.set(calculated.power.idle.consumption);
+      self.power_consumption_misc.set(calculated.power.misc.consumption);
+      self.power_balance_idle.set_balance(calculated.power.idle.balance);
+      self.power_balance_misc.set_balance(calculated.power.misc.balance);
+      self.power_duration_idle.set(calculated.power.idle.duration);
+      self.power_duration_misc.set(calculated.power.misc.duration);
+      // Show the power budget as tooltips on the rows that add its consumption, with idle thrusters on the first
+      // thruster row.
+      let share_text = |share: &PowerShare| format!("{}: {} MW ({} %)", share.category.label(), share.consumption.format(), Quantity::Ratio.format(share.share));
+      let mut misc_budget = Vec::new();
+      let mut pending_budget = Vec::new();
+      let mut upto_budget = HashMap::new();
+      for share in &calculated.power.budget {
+        match share.category {
+          PowerCategory::Misc | PowerCategory::Utility => misc_budget.push(share_text(share)),
+          PowerCategory::IdleThrusters => pending_budget.push(share_text(share)),
+          PowerCategory::Consumer(consumer) => {
+            pending_budget.push(share_text(share));
+            upto_budget.insert(consumer, pending_budget.join("\n"));
+            pending_budget.clear();
+          }
+        }
+      }
+      self.power_consumption_misc.set_tooltip_text(Some(&misc_budget.join("\n")));
+      let set_upto = |consumer: PowerConsumer, consumption: &Label, balance: &Label, duration: &Label| {
+        consumption.set_tooltip_text(upto_budget.get(&consumer).map(String::as_str));
+        match calculated.power.upto(consumer) {
+          Some(resource) => {
+            consumption.set(resource.consumption);
+            balance.set_balance(resource.balance);
+            duration.set(resource.duration);
+          }
+          // Disabled consumers are not part of the cumulative consumption.
+          None => for label in &[consumption, balance, duration] {
+            label.set_highlighted(false);
+            label.set_text("-");
+          },
+        }
+      };
+      set_upto(PowerConsumer::JumpDrive, &self.power_consumption_upto_jump_drive, &self.power_balance_upto_jump_drive, &self.power_duration_upto_jump_drive);
+      set_upto(PowerConsumer::Generator, &self.power_consumption_upto_generator, &self.power_balance_upto_generator, &self.power_duration_upto_generator);
+      set_upto(PowerConsumer::UpDownThruster, &self.power_consumption_upto_up_down_thruster, &self.power_balance_upto_up_down_thruster, &self.power_duration_upto_up_down_thruster);
+      set_upto(PowerConsumer::FrontBackThruster, &self.power_consumption_upto_front_back_thruster, &self.power_balance_upto_front_back_thruster, &self.power_duration_upto_front_back_thruster);
+      set_upto(PowerConsumer::LeftRightThruster, &self.power_consumption_upto_left_right_thruster, &self.power_balance_upto_left_right_thruster, &self.power_duration_upto_left_right_thruster);
+      set_upto(PowerConsumer::ShipTool, &self.power_consumption_upto_ship_tool, &self.power_balance_upto_ship_tool, &self.power_duration_upto_ship_tool);
+      set_upto(PowerConsumer::Battery, &self.power_consumption_upto_battery, &self.power_balance_upto_battery, &self.power_duration_upto_battery);
+    }
+    // Hydrogen
+    if changes.hydrogen {
+      self.hydrogen_generation.set(calculated.hydrogen.generation);
+      self.hydrogen_capacity_engine.set(calculated.hydrogen.capacity_engine);
+      self.hydrogen_capacity_tank.set(calculated.hydrogen.capacity_tank);
+      let stockpile = calculated.hydrogen.capacity_tank_stockpile;
+      let stockpile_tooltip = format!("Excludes {} L in tanks in stockpile mode", stockpile.format());
+      self.hydrogen_capacity_tank.set_tooltip_text(if stockpile > Litres(0.0) { Some(&stockpile_tooltip) } else { None });
+      self.hydrogen_consumption_idle.set(calculated.hydrogen.idle.consumption);
+      self.hydrogen_consumption_engine.set(calculated.hydrogen.engine.consumption);
+      self.hydrogen_consumption_upto_up_down_thruster.set(calculated.hydrogen.upto_up_down_thruster.consumption);
+      self.hydrogen_consumption_upto_front_back_thruster.set(calculated.hydrogen.upto_front_back_thruster.consumption);
+      self.hydrogen_consumption_upto_left_right_thruster.set(calculated.hydrogen.upto_left_right_thruster.consumption);
+      self.hydrogen_balance_idle.set_balance(calculated.hydrogen.idle.balance);
+      self.hydrogen_balance_engine.set_balance(calculated.hydrogen.engine.balance);
+      self.hydrogen_balance_upto_up_down_thruster.set_balance(calculated.hydrogen.upto_up_down_thruster.balance);
+      self.hydrogen_balance_upto_front_back_thruster.set_balance(calculated.hydrogen.upto_front_back_thruster.balance);
+      self.hydrogen_balance_upto_left_right_thruster.set_balance(calculated.hydrogen.upto_left_right_thruster.balance);
+      self.hydrogen_duration_idle.set(calculated.hydrogen.idle.duration);
+      self.hydrogen_duration_engine.set(calculated.hydrogen.engine.duration);
+      self.hydrogen_duration_upto_up_down_thruster.set(calculated.hydrogen.upto_up_down_thruster.duration);
+      self.hydrogen_duration_upto_front_back_thruster.set(calculated.hydrogen.upto_front_back_thruster.duration);
+      self.hydrogen_duration_upto_left_right_thruster.set(calculated.hydrogen.upto_left_right_thruster.duration);
+      // Show the hydrogen budget as tooltips on the rows that add its consumption, and on generation.
+      let budget = &calculated.hydrogen.budget;
+      let budget_tooltip = |categories: &[HydrogenCategory]| budget.consumption.iter()
+        .filter(|share| categories.contains(&share.category))
+        .map(|share| format!("{}: {} L/s ({} %)", share.category.label(), share.consumption.format(), Quantity::Ratio.format(share.share)))
+        .collect::<Vec<_>>()
+        .join("\n");
+      self.hydrogen_generation.set_tooltip_text(Some(&format!("Covers {} % of consumption at full thrust, with a net flow of {} L/s", Quantity::Ratio.format(budget.generation_share), budget.net.format())));
+      self.hydrogen_consumption_engine.set_tooltip_text(Some(&budget_tooltip(&[HydrogenCategory::Engines])));
+      self.hydrogen_consumption_upto_up_down_thruster.set_tooltip_text(Some(&budget_tooltip(&[HydrogenCategory::IdleThrusters, HydrogenCategory::UpDownThrusters])));
+      self.hydrogen_consumption_upto_front_back_thruster.set_tooltip_text(Some(&budget_tooltip(&[HydrogenCategory::FrontBackThrusters])));
+      self.hydrogen_consumption_upto_left_right_thruster.set_tooltip_text(Some(&budget_tooltip(&[HydrogenCategory::LeftRightThrusters])));
+    }
+    // Warnings
+    let (warnings, recommendations) = {
+      let calculator = &self.state.borrow().calculator;
+      (warning::check(&calculated, calculator, &self.data), recommendation::recommend(&calculated, calculator, &self.data))
+    };
+    self.update_warnings(&warnings, &recommendations);
+    // Breakdown
+    if let Some(breakdown) = &calculated.breakdown {
+      self.breakdown_window.update(breakdown, &self.data, &self.preferences.borrow().config.units);
+    }
+
+    if let Some(handler) = self.recalculated_handler.borrow().as_ref() {
+      handler(self, &calculated);
+    }
+  }
+
+  /// Shows the trip of the entered distance and direction for `calculated`.
+  fn update_trip(&self, calculated: &GridCalculated) {
+    let distance = match self.trip_distance.parse(Meters(10000.0)) {
+      Ok(distance) if distance >= Meters(0.0) => distance,
+      Ok(_) => {
+        self.trip_distance.set_error(Some("Distance must not be negative"));
+        return;
+      }
+      Err(message) => {
+        self.trip_distance.set_error(Some(&message));
+        return;
+      }
+    };
+    self.trip_distance.set_error(None);
+    let direction = self.trip_direction.get_active_id().and_then(|id| Self::parse_direction(&id)).unwrap_or(Direction::Front);
+    let max_speed = self.state.borrow().calculator.max_speed;
+    self.trip_empty.set(trip(calculated, max_speed, direction, distance, false));
+    self.trip_filled.set(trip(calculated, max_speed, direction, distance, true));
+  }
+
+  /// Sets `handler` to be called with the results each time this page is recalculated.
+  pub fn connect_recalculated<F: Fn(&CalculatorPage, &GridCalculated) + 'static>(&self, handler: F) {
+    self.recalculated_handler.replace(Some(Box::new(handler)));
+  }
+
+  pub fn gravity_multiplier(&self) -> f64 {
+    self.state.borrow().calculator.gravity_multiplier
+  }
+
+  fn update_warnings(&self, warnings: &[Warning], recommendations: &[Recommendation]) {
+    for child in self.warnings.get_children() {
+      self.warnings.remove(&child);
+    }
+    for widgets in self.thrusters.values() {
+      widgets.force.set_highlighted(false);
+      widgets.acceleration_filled_gravity.set_highlighted(false);
+    }
+    self.hydrogen_capacity_tank.set_highlighted(false);
+    self.power_capacity_battery.set_highlighted(false);
+
+    for warning in warnings {
+      let label = Self::create_static_label(&format!("{}", warning));
+      label.set_highlighted(true);
+      label.show();
+      self.warnings.add(&label);
+      match warning {
+        Warning::NoThrust { direction } => if let Some(widgets) = self.thrusters.get(direction) {
+          widgets.force.set_highlighted(true);
+        },
+        Warning::HydrogenThrustersWithoutSource => self.hydrogen_capacity_tank.set_highlighted(true),
+        Warning::BatteriesWithoutCharger => self.power_capacity_battery.set_highlighted(true),
+        Warning::NoLift => if let Some(widgets) = self.thrusters.get(&Direction::Up) {
+          widgets.force.set_highlighted(true);
+        },
+        Warning::InsufficientLift { .. } => if let Some(widgets) = self.thrusters.get(&Direction::Up) {
+          widgets.acceleration_filled_gravity.set_highlighted(true);
+        },
+        // Negative balances are highlighted when setting their value.
+        Warning::NegativePowerBalance { .. } => {}
+        Warning::MixedGridSizes { .. } => {}
+      }
+    }
+    for recommendation in recommendations {
+      let label = Self::create_static_label(&format!("{}", recommendation));
+      label.show();
+      self.warnings.add(&label);
+    }
+    self.warnings_frame.set_visible(!warnings.is_empty() || !recommendations.is_empty());
+  }
+
+
+  pub fn process_open<P: AsRef<Path>>(&self, file_path: P) -> Result<(), OpenError> {
+    let file_path = file_path.as_ref();
+    let reader = OpenOptions::new().read(true).open(file_path).context(self::OpenFile { file_path })?;
+    let calculator = GridCalculator::from_json(reader).context(self::OpenDeserialize { file_path })?;
+    self.set_calculator(calculator, Some(file_path.to_owned()));
+    Ok(())
+  }
+
+  /// Merges the calculator in `file_path` into the calculator of this page, summing block counts and additional mass.
+  pub fn process_merge<P: AsRef<Path>>(&self, file_path: P) -> Result<(), OpenError> {
+    let file_path = file_path.as_ref();
+    let reader = OpenOptions::new().read(true).open(file_path).context(self::OpenFile { file_path })?;
+    let other = GridCalculator::from_json(reader).context(self::OpenDeserialize { file_path })?;
+    self.modify_calculator(|calculator| calculator.merge(&other)).context(self::OpenMerge { file_path })
+  }
+
+  /// Applies `template` onto the calculator of this page, adding its block counts and additional mass.
+  pub fn apply_template(&self, template: &GridTemplate) -> Result<(), ValidationError> {
+    self.modify_calculator(|calculator| template.apply(calculator))
+  }
+
+  /// Creates a template named `name` from the blocks and additional mass of this page.
+  pub fn create_template(&self, name: String, description: String) -> GridTemplate {
+    GridTemplate::from_calculator(name, description, &self.state.borrow().calculator)
+  }
+
+  /// Applies `modify` to the calculator of this page in response to an input change, recording it in the history unless
+  /// inputs are being restored, and recalculates.
+  fn apply_input_change(&self, modify: impl FnOnce(&mut GridCalculator)) {
+    {
+      let mut state = self.state.borrow_mut(); // Scoped borrow.
+      let state = &mut *state;
+      let previous = state.calculator.clone();
+      modify(&mut state.calculator);
+      if !state.restoring {
+        state.history.record(&previous, &state.calculator);
+      }
+      state.modified = true;
+    }
+    self.recalculate();
+  }
+
+  /// Modifies a copy of the calculator of this page with `modify`, and sets it as the calculator if that succeeds. The
+  /// modification is recorded in the history as a single change.
+  fn modify_calculator<E>(&self, modify: impl FnOnce(&mut GridCalculator) -> Result<(), E>) -> Result<(), E> {
+    let previous = self.state.borrow().calculator.clone();
+    let mut calculator = previous.clone();
+    modify(&mut calculator)?;
+    self.show_calculator(&calculator);
+    {
+      let mut state = self.state.borrow_mut(); // Scoped borrow.
+      state.history.record(&previous, &calculator);
+      state.calculator = calculator;
+      state.modified = true;
+    }
+    self.update_tab_label();
+    self.recalculate();
+    Ok(())
+  }
+
+  /// Undoes the last change to the calculator of this page, returning false if there is nothing to undo.
+  pub fn undo(&self) -> bool {
+    self.restore(|history, calculator| history.undo(calculator))
+  }
+
+  /// Redoes the last undone change to the calculator of this page, returning false if there is nothing to redo.
+  pub fn redo(&self) -> bool {
+    self.restore(|history, calculator| history.redo(calculator))
+  }
+
+  fn restore(&self, restore: impl FnOnce(&mut CommandHistory, &mut GridCalculator) -> bool) -> bool {
+    let calculator = {
+      let mut state = self.state.borrow_mut(); // Scoped borrow.
+      let state = &mut *state;
+      let mut calculator = state.calculator.clone();
+      if !restore(&mut state.history, &mut calculator) { return false; }
+      calculator
+    };
+    self.show_calculator(&calculator);
+    {
+      let mut state = self.state.borrow_mut(); // Scoped borrow.
+      state.calculator = calculator;
+      state.modified = true;
+    }
+    self.update_tab_label();
+    self.recalculate();
+    true
+  }
+
+  /// Replaces the calculator of this page with the calculator of `preset`, as a new unsaved calculator.
+  pub fn load_preset(&self, preset: &GridPreset) -> Result<(), ReadError> {
+    let calculator = preset.load()?;
+    self.set_calculator(calculator, None);
+    self.state.borrow_mut().modified = true;
+    self.update_tab_label();
+    Ok(())
+  }
+
+  /// Replaces the calculator of this page with `calculator` from `file_path`, clearing the history.
+  fn set_calculator(&self, calculator: GridCalculator, file_path: Option<PathBuf>) {
+    self.show_calculator(&calculator);
+    {
+      let mut state = self.state.borrow_mut(); // Scoped borrow.
+      state.current_file_path = file_path;
+      state.modified = false;
+      state.calculator = calculator;
+      state.history.clear();
+    }
+    self.update_tab_label();
+  }
+
+  /// Sets the inputs of this page to the values of `calculator`, without recording their changes in the history.
+  fn show_calculator(&self, calculator: &GridCalculator) {
+    // PERF: setting Entries and SpinButtons will trigger their signals, each which mutably borrow `state` and recalculates.
+    self.state.borrow_mut().restoring = true;
+
+    self.gravity_multiplier.set(calculator.gravity_multiplier);
+    self.container_multiplier.set(calculator.container_multiplier);
+    self.planetary_influence.set(calculator.planetary_influence);
+    self.additional_mass.set(calculator.additional_mass);
+    self.additional_volume.set(calculator.additional_volume);
+    self.max_speed.set(calculator.max_speed);
+    self.descent_altitude.set(calculator.descent_altitude);
+    self.ice_only_fill.set(calculator.ice_only_fill);
+    self.ore_only_fill.set(calculator.ore_only_fill);
+    self.any_fill_with_ice.set(calculator.any_fill_with_ice);
+    self.any_fill_with_ore.set(calculator.any_fill_with_ore);
+    self.any_fill_with_steel_plates.set(calculator.any_fill_with_steel_plates);
+    self.ammo_fill.set(calculator.ammo_fill);
+    self.generator_hydrogen_share.set(calculator.generator_hydrogen_share);
+    self.battery_charge.set(calculator.battery_charge);
+    self.reactor_output_multiplier.set(calculator.reactor_output_multiplier);
+    self.renewable_output.set(calculator.renewable_output);
+    self.utility_range.set(calculator.utility_range);
+    self.idle_hydrogen_thrusters.set_active(calculator.idle_hydrogen_thrusters);
+    self.power_priority.set_text(&PowerConsumer::format_priority(&calculator.power_priority));
+    self.custom_consumers.remove_all();
+    for consumer in &calculator.custom_power_consumers {
+      self.custom_consumers.append_text(&format!("{}: {} MW", consumer.name, consumer.consumption.format()));
+    }
+    for consumer in &calculator.custom_hydrogen_consumers {
+      self.custom_consumers.append_text(&format!("{}: {} L/s", consumer.name, consumer.consumption.format()));
+    }
+    let has_custom_consumers = !calculator.custom_power_consumers.is_empty() || !calculator.custom_hydrogen_consumers.is_empty();
+    self.custom_consumers.set_active(if has_custom_consumers { Some(0) } else { None });
+    self.custom_consumer_remove.set_sensitive(has_custom_consumers);
+    {
+      fn set_inputs_from<'a>(inputs: &HashMap<BlockId, SpinButton>, iter: impl Iterator<Item=(&'a BlockId, &'a u64)>) {
+        for (block_id, count) in iter {
+          if let Some(input) = inputs.get(block_id) {
+            input.set_count(*count);
+          }
+        }
+      }
+      let block_inputs = self.block_inputs.borrow(); // Scoped borrow.
+      for input in block_inputs.iter_inputs() {
+        input.set_count(0);
+      }
+      set_inputs_from(&block_inputs.inputs, calculator.iter_block_counts());
+      set_inputs_from(&block_inputs.up_inputs, calculator.iter_direction(Direction::Up));
+      set_inputs_from(&block_inputs.down_inputs, calculator.iter_direction(Direction::Down));
+      set_inputs_from(&block_inputs.front_inputs, calculator.iter_direction(Direction::Front));
+      set_inputs_from(&block_inputs.back_inputs, calculator.iter_direction(Direction::Back));
+      set_inputs_from(&block_inputs.left_inputs, calculator.iter_direction(Direction::Left));
+      set_inputs_from(&block_inputs.right_inputs, calculator.iter_direction(Direction::Right));
+      for (block_id, input) in &block_inputs.stockpile_inputs {
+        input.set_active(calculator.stockpile_hydrogen_tanks.contains(block_id));
+      }
+    }
+    self.state.borrow_mut().restoring = false;
+  }
+
+  pub fn process_save<P: AsRef<Path>>(&self, file_path: P) -> Result<(), SaveError> {
+    let file_path = file_path.as_ref();
+    let writer = OpenOptions::new().write(true).create(true).open(file_path).context(self::SaveFile { file_path })?;
+
+    let mut state = self.state.borrow_mut();
+    state.calculator.to_json(writer).context(self::SaveSerialize { file_path })?;
+
+    state.current_file_path = Some(file_path.to_owned());
+    state.modified = false;
+    drop(state);
+    self.update_tab_label();
+
+    Ok(())
+  }
+
+
+trait MyEntryExt: EntryExt {
+  fn parse<T: FromStr + Copy>(&self, default: T) -> Result<T, String> where T::Err: Display;
+  fn set<T: Display>(&self, value: T);
+  fn set_error(&self, message: Option<&str>);
+
+  fn set_and_recalc_on_change<T: FromStr + Copy + 'static, F: (Fn(&mut GridCalculator) -> &mut T) + 'static>(&self, page: &Rc<CalculatorPage>, default: T, func: F) where T::Err: Display;
+}
+
+impl MyEntryExt for Entry {
+  /// Parses the text of this entry, returning `default` if it is empty, or a message describing the problem if it
+  /// cannot be parsed.
+  fn parse<T: FromStr + Copy>(&self, default: T) -> Result<T, String> where T::Err: Display {
+    let text = self.get_text().map(|t| t.trim().to_owned()).unwrap_or_default();
+    if text.is_empty() { return Ok(default); }
+    text.parse().map_err(|e| {
+      let mut message = format!("'{}' is not a valid number: {}", text, e);
+      if text.contains(',') {
+        message.push_str(". Use '.' as the decimal separator");
+      }
+      message
+    })
+  }
+
+  fn set<T: Display>(&self, value: T) {
+    self.set_text(&format!("{:.2}", value));
+  }
+
+  /// Highlights this entry and shows `message` in a tooltip and icon if it is `Some`, or clears the highlight otherwise.
+  fn set_error(&self, message: Option<&str>) {
+    let style_context = self.get_style_context();
+    if let Some(message) = message {
+      style_context.add_class("error");
+      self.set_icon_from_icon_name(EntryIconPosition::Secondary, Some("dialog-warning-symbolic"));
+      self.set_icon_tooltip_text(EntryIconPosition::Secondary, Some(message));
+      self.set_tooltip_text(Some(message));
+    } else {
+      style_context.remove_class("error");
+      self.set_icon_from_icon_name(EntryIconPosition::Secondary, None);
+      self.set_tooltip_text(None);
+    }
+  }
+
+  fn set_and_recalc_on_change<T: FromStr + Copy + 'static, F: (Fn(&mut GridCalculator) -> &mut T) + 'static>(&self, page: &Rc<CalculatorPage>, default: T, func: F) where T::Err: Display {
+    let rc_clone = page.clone();
+    self.connect_changed(move |entry| {
+      // Keep the last valid value on parse errors, so that typos do not silently change results.
+      let value = match entry.parse(default) {
+        Ok(value) => value,
+        Err(message) => {
+          entry.set_error(Some(&message));
+          return;
+        }
+      };
+      // Likewise, keep the last valid value when the new value is out of range, such as a negative multiplier.
+      let mut calculator = rc_clone.state.borrow().calculator.clone();
+      *func(&mut calculator) = value;
+      if let Err(e) = calculator.validate() {
+        entry.set_error(Some(&e.to_string()));
+        return;
+      }
+      entry.set_error(None);
+      {
+        let mut state = rc_clone.state.borrow_mut(); // Scoped borrow.
+        let state = &mut *state;
+        let previous = std::mem::replace(&mut state.calculator, calculator);
+        if !state.restoring {
+          state.history.record(&previous, &state.calculator);
+        }
+        state.modified = true;
+      }
+      rc_clone.recalculate();
+    });
+  }
+}
+
+
+trait MySpinButtonExt: SpinButtonExt {
+  fn count(&self) -> u64;
+  fn set_count(&self, count: u64);
+
+  fn insert_and_recalc_on_change<F: (Fn(&mut GridCalculator) -> &mut HashMap<BlockId, u64>) + 'static>(&self, page: &Rc<CalculatorPage>, id: BlockId, func: F);
+}
+
+impl MySpinButtonExt for SpinButton {
+  fn count(&self) -> u64 {
+    self.get_value().max(0.0) as u64
+  }
+
+  fn set_count(&self, count: u64) {
+    self.set_value(count as f64);
+  }
+
+  fn insert_and_recalc_on_change<F: (Fn(&mut GridCalculator) -> &mut HashMap<BlockId, u64>) + 'static>(&self, page: &Rc<CalculatorPage>, id: BlockId, func: F) {
+    let rc_clone = page.clone();
+    self.connect_value_changed(move |input| {
+      {
+        let mut state = rc_clone.state.borrow_mut(); // Scoped borrow.
+        let state = &mut *state;
+        let previous = if state.restoring { None } else { Some(state.calculator.clone()) };
+        func(&mut state.calculator).insert(id.clone(), input.count());
+        if let Some(previous) = previous {
+          state.history.record(&previous, &state.calculator);
+        }
+        state.modified = true;
+      }
+      rc_clone.recalculate();
+    });
+  }
+}
+
+
+trait MyLabelExt {
+  fn set<T: Unit>(&self, value: T);
+  fn set_items(&self, value: f64);
+  fn set_balance<T: Unit + PartialOrd + Default>(&self, value: T);
+  fn set_highlighted(&self, highlighted: bool);
+}
+
+impl MyLabelExt for Label {
+  fn set<T: Unit>(&self, value: T) {
+    self.set_text(&value.format());
+  }
+
+  fn set_items(&self, value: f64) {
+    self.set_text(&Quantity::Items.format(value));
+  }
+
+  fn set_balance<T: Unit + PartialOrd + Default>(&self, value: T) {
+    self.set_highlighted(value < T::default());
+    self.set(value);
+  }
+
+  fn set_highlighted(&self, highlighted: bool) {
+    let style_context = self.get_style_context();
+    if highlighted {
+      style_context.add_class("negative");
+    } else {
+      style_context.remove_class("negative");
+    }
+  }
+}
\ No newline at end of file
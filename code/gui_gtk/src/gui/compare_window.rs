@@ -0,0 +1,133 @@
+use std::rc::Rc;
+
+use gtk::{Align, ComboBoxText, Grid, Label, Orientation, PolicyType, ScrolledWindow, Window, WindowType};
+use gtk::prelude::*;
+use glib::object::IsA;
+
+use secalc_core::grid::compare::compare;
+
+use crate::gui::calculator_page::CalculatorPage;
+
+/// Window showing the results of two calculator pages side by side, with highlighted differences, and the key metrics
+/// of all calculator pages as a fleet.
+pub struct CompareWindow {
+  window: Window,
+  left: ComboBoxText,
+  right: ComboBoxText,
+  results: Grid,
+  pages: Vec<Rc<CalculatorPage>>,
+}
+
+impl CompareWindow {
+  pub fn new<W: IsA<Window>>(parent: &W, pages: Vec<Rc<CalculatorPage>>) -> Rc<Self> {
+    let window = Window::new(WindowType::Toplevel);
+    window.set_title("Compare");
+    window.set_transient_for(Some(parent));
+    window.set_destroy_with_parent(true);
+    window.set_default_size(700, 600);
+
+    let left = ComboBoxText::new();
+    let right = ComboBoxText::new();
+    for (index, page) in pages.iter().enumerate() {
+      let id = index.to_string();
+      let title = page.tab_label().get_text().map(|t| t.to_string()).unwrap_or_default();
+      left.append(Some(&id), &title);
+      right.append(Some(&id), &title);
+    }
+    left.set_active(Some(0));
+    right.set_active(Some(if pages.len() > 1 { 1 } else { 0 }));
+
+    let results = Grid::new();
+    results.set_row_spacing(2);
+    results.set_column_spacing(10);
+    results.set_border_width(5);
+
+    let fleet = Grid::new();
+    fleet.set_row_spacing(2);
+    fleet.set_column_spacing(10);
+    fleet.set_border_width(5);
+    fleet.attach(&Self::create_label("Fleet", false), 0, 0, 1, 1);
+    for (column, page) in pages.iter().enumerate() {
+      let column = column as i32 + 1;
+      let title = page.tab_label().get_text().map(|t| t.to_string()).unwrap_or_default();
+      fleet.attach(&Self::create_label(&title, true), column, 0, 1, 1);
+      for (index, value) in page.fleet_metrics().into_iter().enumerate() {
+        let row = index as i32 + 1;
+        if column == 1 {
+          fleet.attach(&Self::create_label(&format!("{} ({})", value.label, value.unit), false), 0, row, 1, 1);
+        }
+        let text = if value.value.is_finite() { value.format() } else { "-".to_owned() };
+        fleet.attach(&Self::create_label(&text, true), column, row, 1, 1);
+      }
+    }
+
+    let selection = gtk::Box::new(Orientation::Horizontal, 5);
+    selection.set_border_width(5);
+    selection.pack_start(&left, true, true, 0);
+    selection.pack_start(&right, true, true, 0);
+    let scrolled_window = ScrolledWindow::new(None::<&gtk::Adjustment>, None::<&gtk::Adjustment>);
+    scrolled_window.set_policy(PolicyType::Automatic, PolicyType::Automatic);
+    let scrolled_content = gtk::Box::new(Orientation::Vertical, 10);
+    scrolled_content.pack_start(&results, false, false, 0);
+    scrolled_content.pack_start(&fleet, false, false, 0);
+    scrolled_window.add(&scrolled_content);
+    let root = gtk::Box::new(Orientation::Vertical, 0);
+    root.pack_start(&selection, false, false, 0);
+    root.pack_start(&scrolled_window, true, true, 0);
+    window.add(&root);
+
+    let compare_window = Rc::new(Self { window, left, right, results, pages });
+    let self_cloned = compare_window.clone();
+    compare_window.left.connect_changed(move |_| self_cloned.update());
+    let self_cloned = compare_window.clone();
+    compare_window.right.connect_changed(move |_| self_cloned.update());
+    compare_window.update();
+    compare_window
+  }
+
+  fn selected_page(&self, combo_box: &ComboBoxText) -> Option<&Rc<CalculatorPage>> {
+    combo_box.get_active().and_then(|index| self.pages.get(index as usize))
+  }
+
+  fn update(&self) {
+    for child in self.results.get_children() {
+      self.results.remove(&child);
+    }
+    let (left, right) = match (self.selected_page(&self.left), self.selected_page(&self.right)) {
+      (Some(left), Some(right)) => (left, right),
+      _ => return,
+    };
+    for (column, header) in ["Result", "Left", "Right", "Delta"].iter().enumerate() {
+      self.results.attach(&Self::create_label(header, column != 0), column as i32, 0, 1, 1);
+    }
+    for (index, comparison) in compare(&left.calculate(), &right.calculate()).into_iter().enumerate() {
+      let row = index as i32 + 1;
+      let label = format!("{} ({})", comparison.label, comparison.unit);
+      self.results.attach(&Self::create_label(&label, false), 0, row, 1, 1);
+      self.results.attach(&Self::create_label(&comparison.quantity.format(comparison.left), true), 1, row, 1, 1);
+      self.results.attach(&Self::create_label(&comparison.quantity.format(comparison.right), true), 2, row, 1, 1);
+      let delta = Self::create_label(&comparison.quantity.format_signed(comparison.delta()), true);
+      if comparison.is_changed() {
+        delta.get_style_context().add_class(if comparison.delta() < 0.0 { "negative" } else { "positive" });
+      }
+      self.results.attach(&delta, 3, row, 1, 1);
+    }
+    self.results.show_all();
+  }
+
+  fn create_label(text: &str, is_value: bool) -> Label {
+    let label = Label::new(Some(text));
+    if is_value {
+      label.set_halign(Align::End);
+      label.get_style_context().add_class("result");
+    } else {
+      label.set_halign(Align::Start);
+    }
+    label
+  }
+
+  pub fn show(&self) {
+    self.window.show_all();
+    self.window.present();
+  }
+}
@@ -18,6 +18,7 @@ impl FileDialog {
     cancel_label: &str,
     current_dir_path: Option<P>,
     current_file_path: Option<P>,
+    pattern: &str,
   ) -> Self {
     let chooser = FileChooserNative::new(Some(title), Some(parent), action, Some(accept_label), Some(cancel_label));
     if let Some(current_dir) = current_dir_path {
@@ -27,9 +28,9 @@ impl FileDialog {
       chooser.set_current_name(current_file)
     }
     chooser.set_do_overwrite_confirmation(true);
-    let grid_filter = FileFilter::new();
-    grid_filter.add_pattern("*.grid.json");
-    chooser.add_filter(&grid_filter);
+    let filter = FileFilter::new();
+    filter.add_pattern(pattern);
+    chooser.add_filter(&filter);
     let any_filter = FileFilter::new();
     any_filter.add_pattern("*");
     chooser.add_filter(&any_filter);
@@ -40,7 +41,7 @@ impl FileDialog {
     parent: &W,
     current_dir_path: Option<P>,
   ) -> Self {
-    Self::new("Open", parent, FileChooserAction::Open, "Open", "Cancel", current_dir_path, None)
+    Self::new("Open", parent, FileChooserAction::Open, "Open", "Cancel", current_dir_path, None, "*.grid.json")
   }
 
   pub fn new_save<W: IsA<Window>, P: AsRef<Path>>(
@@ -48,7 +49,16 @@ impl FileDialog {
     current_dir_path: Option<P>,
     current_file_path: Option<P>,
   ) -> Self {
-    Self::new("Open", parent, FileChooserAction::Save, "Save", "Cancel", current_dir_path, current_file_path)
+    Self::new("Open", parent, FileChooserAction::Save, "Save", "Cancel", current_dir_path, current_file_path, "*.grid.json")
+  }
+
+  pub fn new_export<W: IsA<Window>, P: AsRef<Path>>(
+    parent: &W,
+    current_dir_path: Option<P>,
+    current_file_path: Option<P>,
+    pattern: &str,
+  ) -> Self {
+    Self::new("Export", parent, FileChooserAction::Save, "Export", "Cancel", current_dir_path, current_file_path, pattern)
   }
 
 
@@ -0,0 +1,63 @@
+use gtk::{Align, Grid, Label, PolicyType, ScrolledWindow, Window, WindowType};
+use gtk::prelude::*;
+use glib::object::IsA;
+
+use secalc_core::grid::planet::{EnvironmentCalculated, values_per_environment};
+
+/// Window showing the results of a calculator page with a column per environment, with values that differ between
+/// environments highlighted.
+pub struct EnvironmentsWindow {
+  window: Window,
+}
+
+impl EnvironmentsWindow {
+  pub fn new<W: IsA<Window>>(parent: &W, title: &str, results: &[EnvironmentCalculated]) -> Self {
+    let window = Window::new(WindowType::Toplevel);
+    window.set_title(&format!("Environments - {}", title));
+    window.set_transient_for(Some(parent));
+    window.set_destroy_with_parent(true);
+    window.set_default_size(800, 600);
+
+    let grid = Grid::new();
+    grid.set_row_spacing(2);
+    grid.set_column_spacing(10);
+    grid.set_border_width(5);
+    grid.attach(&Self::create_label("Result", false), 0, 0, 1, 1);
+    for (column, result) in results.iter().enumerate() {
+      grid.attach(&Self::create_label(result.environment.name, true), column as i32 + 1, 0, 1, 1);
+    }
+    for (index, values) in values_per_environment(results).into_iter().enumerate() {
+      let row = index as i32 + 1;
+      let label = Self::create_label(&format!("{} ({})", values.label, values.unit), false);
+      if values.is_varying() {
+        label.get_style_context().add_class("varying");
+      }
+      grid.attach(&label, 0, row, 1, 1);
+      for (column, value) in values.values.iter().enumerate() {
+        grid.attach(&Self::create_label(&values.quantity.format(*value), true), column as i32 + 1, row, 1, 1);
+      }
+    }
+
+    let scrolled_window = ScrolledWindow::new(None::<&gtk::Adjustment>, None::<&gtk::Adjustment>);
+    scrolled_window.set_policy(PolicyType::Automatic, PolicyType::Automatic);
+    scrolled_window.add(&grid);
+    window.add(&scrolled_window);
+    Self { window }
+  }
+
+  fn create_label(text: &str, is_value: bool) -> Label {
+    let label = Label::new(Some(text));
+    if is_value {
+      label.set_halign(Align::End);
+      label.get_style_context().add_class("result");
+    } else {
+      label.set_halign(Align::Start);
+    }
+    label
+  }
+
+  pub fn show(&self) {
+    self.window.show_all();
+    self.window.present();
+  }
+}
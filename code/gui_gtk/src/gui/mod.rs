@@ -1,16 +1,60 @@
+use std::fs::File;
+
 use gio::prelude::*;
 use gtk::Application;
+use tracing::warn;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
 
+use secalc_core::data::custom::CustomBlocks;
 use secalc_core::data::Data;
 
 use self::main_window::MainWindow;
+use self::preferences::Preferences;
 
 pub mod main_window;
+pub mod breakdown_window;
+pub mod bulk_entry_dialog;
+pub mod calculator_page;
+pub mod compare_window;
 pub mod dialog;
+pub mod environments_window;
+pub mod preferences;
+pub mod preferences_dialog;
+pub mod status_bar;
+pub mod template_dialog;
+pub mod theme;
 
-pub fn run(data: Data) {
+/// Runs the GUI, with data loaded from the data path in the preferences, or from binary `built_in_data` if there is
+/// none.
+pub fn run(built_in_data: &[u8]) {
+  // Prints tracing spans, such as data loading and calculation timings, filtered by the SECALC_LOG environment variable.
+  // Warnings are printed when it is not set.
+  tracing_subscriber::fmt()
+    .with_env_filter(EnvFilter::try_from_env("SECALC_LOG").unwrap_or_else(|_| EnvFilter::new("warn")))
+    .with_span_events(FmtSpan::CLOSE)
+    .with_writer(std::io::stderr)
+    .init();
   let application = Application::new(None, Default::default()).expect("failed to initialize GTK application");
-  let main_window = MainWindow::new(data);
+  let preferences = Preferences::load().unwrap_or_else(|e| {
+    warn!("Could not load preferences, using defaults: {}", e);
+    Preferences::default()
+  });
+  let mut data = preferences.config.data_path.as_ref()
+    .and_then(|data_path| Data::from_path(data_path).map_err(|e| warn!("Could not load data, using built-in data instead: {}", e)).ok())
+    .unwrap_or_else(|| Data::from_binary(built_in_data).expect("Cannot read built-in data"));
+  if let Some(custom_blocks_path) = &preferences.config.custom_blocks_path {
+    match File::open(custom_blocks_path).map_err(|e| e.to_string()).and_then(|reader| CustomBlocks::from_json(reader).map_err(|e| e.to_string())) {
+      Ok(custom_blocks) => { data.add_custom_blocks(&custom_blocks); }
+      Err(e) => warn!("Could not load custom blocks file '{}', ignoring custom blocks: {}", custom_blocks_path.display(), e),
+    }
+  }
+  if cfg!(debug_assertions) {
+    for diagnostic in data.validate() {
+      warn!("Game data problem: {}", diagnostic);
+    }
+  }
+  let main_window = MainWindow::new(data, preferences);
   application.connect_activate(move |app| {
     main_window.set_application(app);
     main_window.show();
@@ -0,0 +1,91 @@
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+
+use secalc_core::config::{self, Config};
+
+#[derive(Debug, Snafu)]
+pub enum LoadError {
+  #[snafu(display("Could not open preferences file '{}' for reading: {}", file_path.display(), source))]
+  LoadFile { file_path: PathBuf, source: std::io::Error, },
+  #[snafu(display("Could not deserialize preferences from file '{}': {}", file_path.display(), source))]
+  LoadDeserialize { file_path: PathBuf, source: serde_json::Error, },
+  #[snafu(display("Could not load shared configuration: {}", source))]
+  LoadConfig { source: config::LoadError, },
+}
+
+#[derive(Debug, Snafu)]
+pub enum SaveError {
+  #[snafu(display("Could not find a configuration directory to store preferences in"))]
+  NoConfigDir,
+  #[snafu(display("Could not create preferences directory '{}': {}", dir_path.display(), source))]
+  CreateDir { dir_path: PathBuf, source: std::io::Error, },
+  #[snafu(display("Could not open preferences file '{}' for writing: {}", file_path.display(), source))]
+  SaveFile { file_path: PathBuf, source: std::io::Error, },
+  #[snafu(display("Could not serialize preferences to file '{}': {}", file_path.display(), source))]
+  SaveSerialize { file_path: PathBuf, source: serde_json::Error, },
+  #[snafu(display("Could not save shared configuration: {}", source))]
+  SaveConfig { source: config::SaveError, },
+}
+
+/// User preferences of the GTK GUI, persisted between runs. Settings shared with the other frontends are stored in
+/// `config` instead.
+#[derive(Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Preferences {
+  /// Configuration shared with the other frontends, which is stored in its own file.
+  #[serde(skip)]
+  pub config: Config,
+  /// Identifiers of sections that are collapsed.
+  pub collapsed_sections: HashSet<String>,
+  /// Last known window size (width, height).
+  pub window_size: Option<(i32, i32)>,
+  /// Last known window position (x, y).
+  pub window_position: Option<(i32, i32)>,
+  pub window_maximized: bool,
+  /// Directory that was last used to open or save a calculator.
+  pub last_dir_path: Option<PathBuf>,
+}
+
+impl Preferences {
+  const FILE_NAME: &'static str = "gtk_preferences.json";
+
+  /// Loads preferences and the shared configuration from the configuration directory, or returns defaults if they were
+  /// never saved.
+  pub fn load() -> Result<Self, LoadError> {
+    let config_exists = Config::file_path().map_or(false, |p| p.exists());
+    let config = Config::load().context(self::LoadConfig)?;
+    let file_path = match Self::file_path() {
+      Some(file_path) if file_path.exists() => file_path,
+      _ => return Ok(Self { config, ..Self::default() }),
+    };
+    let file_path = &file_path;
+    let json = fs::read_to_string(file_path).context(self::LoadFile { file_path })?;
+    let mut preferences: Self = serde_json::from_str(&json).context(self::LoadDeserialize { file_path })?;
+    // Preferences files from before the shared configuration contain its theme, units, data paths, and templates.
+    preferences.config = if config_exists { config } else {
+      serde_json::from_str(&json).context(self::LoadDeserialize { file_path })?
+    };
+    Ok(preferences)
+  }
+
+  pub fn save(&self) -> Result<(), SaveError> {
+    let file_path = Self::file_path().ok_or(SaveError::NoConfigDir)?;
+    if let Some(dir_path) = file_path.parent() {
+      fs::create_dir_all(dir_path).context(self::CreateDir { dir_path })?;
+    }
+    let file_path = &file_path;
+    let writer = OpenOptions::new().write(true).create(true).truncate(true).open(file_path).context(self::SaveFile { file_path })?;
+    serde_json::to_writer_pretty(writer, self).context(self::SaveSerialize { file_path })?;
+    self.config.save().context(self::SaveConfig)?;
+    Ok(())
+  }
+
+  /// Path of the preferences file, next to the configuration file shared with the other frontends.
+  fn file_path() -> Option<PathBuf> {
+    Config::file_path().map(|file_path| file_path.with_file_name(Self::FILE_NAME))
+  }
+}
@@ -0,0 +1,153 @@
+use std::path::PathBuf;
+
+use glib::object::IsA;
+use gtk::{Align, Box as GtkBox, Button, ComboBoxText, Dialog, DialogFlags, FileChooserAction, FileChooserButton, Grid, Label, Orientation, ResponseType, SpinButton, Window};
+use gtk::prelude::*;
+
+use secalc_core::config::Theme;
+use secalc_core::format::{AccelerationUnit, MassUnit, PowerUnit, VolumeUnit};
+
+use crate::gui::preferences::Preferences;
+
+pub struct PreferencesDialog {
+  dialog: Dialog,
+  theme: ComboBoxText,
+  mass_unit: ComboBoxText,
+  volume_unit: ComboBoxText,
+  power_unit: ComboBoxText,
+  acceleration_unit: ComboBoxText,
+  data_path: FileChooserButton,
+  custom_blocks_path: FileChooserButton,
+  gravity_multiplier: SpinButton,
+  container_multiplier: SpinButton,
+  planetary_influence: SpinButton,
+}
+
+impl PreferencesDialog {
+  pub fn new<W: IsA<Window>>(parent: &W, preferences: &Preferences) -> Self {
+    let dialog = Dialog::new_with_buttons(Some("Preferences"), Some(parent), DialogFlags::MODAL | DialogFlags::DESTROY_WITH_PARENT, &[("Cancel", ResponseType::Cancel), ("Apply", ResponseType::Apply)]);
+    let grid = Grid::new();
+    grid.set_row_spacing(2);
+    grid.set_column_spacing(5);
+    grid.set_border_width(5);
+
+    let theme = Self::add_row(&grid, 0, "Theme");
+    for t in Theme::iter() {
+      theme.append(Some(t.id()), t.label());
+    }
+    theme.set_active_id(Some(preferences.config.theme.id()));
+
+    let units = &preferences.config.units;
+    let mass_unit = Self::add_row(&grid, 1, "Mass unit");
+    mass_unit.append(Some("kg"), "Kilograms (kg)");
+    mass_unit.append(Some("t"), "Tonnes (t)");
+    mass_unit.set_active_id(Some(match units.mass { MassUnit::Kilogram => "kg", MassUnit::Tonne => "t" }));
+    let volume_unit = Self::add_row(&grid, 2, "Volume unit");
+    volume_unit.append(Some("L"), "Liters (L)");
+    volume_unit.append(Some("m3"), "Cubic meters (m³)");
+    volume_unit.set_active_id(Some(match units.volume { VolumeUnit::Liter => "L", VolumeUnit::CubicMeter => "m3" }));
+    let power_unit = Self::add_row(&grid, 3, "Power unit");
+    power_unit.append(Some("kW"), "Kilowatts (kW)");
+    power_unit.append(Some("MW"), "Megawatts (MW)");
+    power_unit.set_active_id(Some(match units.power { PowerUnit::Kilowatt => "kW", PowerUnit::Megawatt => "MW" }));
+    let acceleration_unit = Self::add_row(&grid, 4, "Acceleration unit");
+    acceleration_unit.append(Some("m/s2"), "Meters per second squared (m/s²)");
+    acceleration_unit.append(Some("g"), "Standard gravity (g)");
+    acceleration_unit.set_active_id(Some(match units.acceleration { AccelerationUnit::MetersPerSecondSquared => "m/s2", AccelerationUnit::Gravity => "g" }));
+
+    let data_path = Self::add_file_row(&grid, 5, "Data file (restart to apply)", "Use built-in", &preferences.config.data_path);
+    let custom_blocks_path = Self::add_file_row(&grid, 6, "Custom blocks file (restart to apply)", "Use none", &preferences.config.custom_blocks_path);
+
+    let config = &preferences.config;
+    let gravity_multiplier = Self::add_number_row(&grid, 7, "Default gravity multiplier", 0.0, 100.0, config.gravity_multiplier);
+    let container_multiplier = Self::add_number_row(&grid, 8, "Default container multiplier", 0.01, 100.0, config.container_multiplier);
+    let planetary_influence = Self::add_number_row(&grid, 9, "Default planetary influence", 0.0, 1.0, config.planetary_influence);
+
+    dialog.get_content_area().add(&grid);
+    dialog.show_all();
+    Self { dialog, theme, mass_unit, volume_unit, power_unit, acceleration_unit, data_path, custom_blocks_path, gravity_multiplier, container_multiplier, planetary_influence }
+  }
+
+  fn add_row(grid: &Grid, row: i32, label: &str) -> ComboBoxText {
+    let label = Label::new(Some(label));
+    label.set_halign(Align::Start);
+    grid.attach(&label, 0, row, 1, 1);
+    let combo_box = ComboBoxText::new();
+    grid.attach(&combo_box, 1, row, 1, 1);
+    combo_box
+  }
+
+  /// Adds a row for entering a number between `min` and `max`, initially `value`.
+  fn add_number_row(grid: &Grid, row: i32, label: &str, min: f64, max: f64, value: f64) -> SpinButton {
+    let label = Label::new(Some(label));
+    label.set_halign(Align::Start);
+    grid.attach(&label, 0, row, 1, 1);
+    let spin_button = SpinButton::new_with_range(min, max, 0.1);
+    spin_button.set_digits(2);
+    spin_button.set_value(value);
+    grid.attach(&spin_button, 1, row, 1, 1);
+    spin_button
+  }
+
+  /// Adds a row for choosing a file, initially `path`, with a button labelled `clear_label` to unset the file.
+  fn add_file_row(grid: &Grid, row: i32, label: &str, clear_label: &str, path: &Option<PathBuf>) -> FileChooserButton {
+    let label = Label::new(Some(label));
+    label.set_halign(Align::Start);
+    grid.attach(&label, 0, row, 1, 1);
+    let file_chooser = FileChooserButton::new("Choose file", FileChooserAction::Open);
+    if let Some(path) = path {
+      file_chooser.set_filename(path);
+    }
+    let clear_button = Button::new_with_label(clear_label);
+    {
+      let file_chooser = file_chooser.clone();
+      clear_button.connect_clicked(move |_| file_chooser.unselect_all());
+    }
+    let file_box = GtkBox::new(Orientation::Horizontal, 5);
+    file_box.pack_start(&file_chooser, true, true, 0);
+    file_box.pack_start(&clear_button, false, false, 0);
+    grid.attach(&file_box, 1, row, 1, 1);
+    file_chooser
+  }
+
+  /// Runs the dialog, updating `preferences` and returning true if the user applied changes.
+  pub fn run(&self, preferences: &mut Preferences) -> bool {
+    if self.dialog.run() != ResponseType::Apply { return false; }
+    if let Some(theme) = self.theme.get_active_id().and_then(|id| Theme::from_id(&id)) {
+      preferences.config.theme = theme;
+    }
+    let units = &mut preferences.config.units;
+    match self.mass_unit.get_active_id().as_deref() {
+      Some("kg") => units.mass = MassUnit::Kilogram,
+      Some("t") => units.mass = MassUnit::Tonne,
+      _ => {}
+    }
+    match self.volume_unit.get_active_id().as_deref() {
+      Some("L") => units.volume = VolumeUnit::Liter,
+      Some("m3") => units.volume = VolumeUnit::CubicMeter,
+      _ => {}
+    }
+    match self.power_unit.get_active_id().as_deref() {
+      Some("kW") => units.power = PowerUnit::Kilowatt,
+      Some("MW") => units.power = PowerUnit::Megawatt,
+      _ => {}
+    }
+    match self.acceleration_unit.get_active_id().as_deref() {
+      Some("m/s2") => units.acceleration = AccelerationUnit::MetersPerSecondSquared,
+      Some("g") => units.acceleration = AccelerationUnit::Gravity,
+      _ => {}
+    }
+    preferences.config.data_path = self.data_path.get_filename();
+    preferences.config.custom_blocks_path = self.custom_blocks_path.get_filename();
+    preferences.config.gravity_multiplier = self.gravity_multiplier.get_value();
+    preferences.config.container_multiplier = self.container_multiplier.get_value();
+    preferences.config.planetary_influence = self.planetary_influence.get_value();
+    true
+  }
+}
+
+impl Drop for PreferencesDialog {
+  fn drop(&mut self) {
+    self.dialog.destroy();
+  }
+}
@@ -0,0 +1,73 @@
+use gtk::{Box as GtkBox, Label, Orientation, Separator};
+use gtk::prelude::*;
+
+use secalc_core::format::UnitPreferences;
+use secalc_core::grid::GridCalculated;
+use secalc_core::precision::Quantity;
+use secalc_core::units::MegaWatts;
+
+/// Bar showing the key figures of the current calculator page, so that they are visible while editing any section.
+pub struct StatusBar {
+  root: GtkBox,
+  mass: Label,
+  thrust_to_weight: Label,
+  power_balance: Label,
+  hydrogen_duration: Label,
+}
+
+impl StatusBar {
+  pub fn new() -> Self {
+    let root = GtkBox::new(Orientation::Horizontal, 10);
+    root.set_border_width(3);
+    let mass = Self::add_figure(&root, "Filled mass", "Total mass when all containers and tanks are filled");
+    let thrust_to_weight = Self::add_figure(&root, "Worst TWR", "Lowest filled thrust-to-weight ratio of all directions under the gravity multiplier");
+    let power_balance = Self::add_figure(&root, "Power balance", "Power balance with all consumers active, including battery charging");
+    let hydrogen_duration = Self::add_figure(&root, "Hydrogen endurance", "Duration until hydrogen tanks are empty when all thrusters are active");
+    Self { root, mass, thrust_to_weight, power_balance, hydrogen_duration }
+  }
+
+  fn add_figure(root: &GtkBox, title: &str, tooltip: &str) -> Label {
+    if !root.get_children().is_empty() {
+      root.pack_start(&Separator::new(Orientation::Vertical), false, false, 0);
+    }
+    let title = Label::new(Some(&format!("{}:", title)));
+    title.set_tooltip_text(Some(tooltip));
+    root.pack_start(&title, false, false, 0);
+    let value = Label::new(None);
+    value.set_tooltip_text(Some(tooltip));
+    value.get_style_context().add_class("result");
+    root.pack_start(&value, false, false, 0);
+    value
+  }
+
+  pub fn root(&self) -> &GtkBox {
+    &self.root
+  }
+
+  pub fn update(&self, calculated: &GridCalculated, gravity_multiplier: f64, units: &UnitPreferences) {
+    self.mass.set_text(&units.format_mass(calculated.volume_mass.total_mass_filled));
+    match calculated.worst_thrust_to_weight_ratio(gravity_multiplier) {
+      Some((direction, ratio)) => {
+        self.thrust_to_weight.set_text(&format!("{} ({:?})", Quantity::Ratio.format(ratio), direction));
+        Self::set_highlighted(&self.thrust_to_weight, ratio < 1.0);
+      }
+      None => {
+        self.thrust_to_weight.set_text("-");
+        Self::set_highlighted(&self.thrust_to_weight, false);
+      }
+    }
+    let power_balance = calculated.power.upto_all().balance;
+    self.power_balance.set_text(&units.format_power(power_balance));
+    Self::set_highlighted(&self.power_balance, power_balance < MegaWatts(0.0));
+    self.hydrogen_duration.set_text(&units.format_duration(calculated.hydrogen.upto_left_right_thruster.duration));
+  }
+
+  fn set_highlighted(label: &Label, highlighted: bool) {
+    let style_context = label.get_style_context();
+    if highlighted {
+      style_context.add_class("negative");
+    } else {
+      style_context.remove_class("negative");
+    }
+  }
+}
@@ -0,0 +1,137 @@
+use glib::object::IsA;
+use gtk::{Align, ComboBoxText, Dialog, DialogFlags, Entry, Grid, Label, ResponseType, Window};
+use gtk::prelude::*;
+
+use secalc_core::grid::template::GridTemplates;
+
+/// Action chosen in the `ApplyTemplateDialog`, with the name of the chosen template.
+pub enum TemplateAction {
+  Apply(String),
+  Delete(String),
+}
+
+/// Dialog where users choose a template to apply onto the current calculator, or to delete.
+pub struct ApplyTemplateDialog {
+  dialog: Dialog,
+  template: ComboBoxText,
+}
+
+impl ApplyTemplateDialog {
+  pub fn new<W: IsA<Window>>(parent: &W, templates: &GridTemplates) -> Self {
+    let dialog = Dialog::new_with_buttons(Some("Apply Template"), Some(parent), DialogFlags::MODAL | DialogFlags::DESTROY_WITH_PARENT, &[("Cancel", ResponseType::Cancel), ("Delete", ResponseType::Reject), ("Apply", ResponseType::Apply)]);
+    let grid = Grid::new();
+    grid.set_row_spacing(2);
+    grid.set_column_spacing(5);
+    grid.set_border_width(5);
+
+    let template_label = Label::new(Some("Template"));
+    template_label.set_halign(Align::Start);
+    grid.attach(&template_label, 0, 0, 1, 1);
+    let template = ComboBoxText::new();
+    for t in templates.iter() {
+      template.append(Some(&t.name), &t.name);
+    }
+    template.set_hexpand(true);
+    grid.attach(&template, 1, 0, 1, 1);
+
+    let description = Label::new(None);
+    description.set_halign(Align::Start);
+    description.set_line_wrap(true);
+    grid.attach(&description, 0, 1, 2, 1);
+    {
+      let templates = templates.clone();
+      let dialog = dialog.clone();
+      template.connect_changed(move |template| {
+        let selected = template.get_active_id().and_then(|name| templates.get(name.as_str()));
+        description.set_text(selected.map_or("", |t| t.description.as_str()));
+        dialog.set_response_sensitive(ResponseType::Apply, selected.is_some());
+        dialog.set_response_sensitive(ResponseType::Reject, selected.is_some());
+      });
+    }
+    dialog.set_response_sensitive(ResponseType::Apply, false);
+    dialog.set_response_sensitive(ResponseType::Reject, false);
+    template.set_active(Some(0));
+
+    dialog.get_content_area().add(&grid);
+    dialog.show_all();
+    Self { dialog, template }
+  }
+
+  /// Runs the dialog, returning the chosen action, or `None` if the user cancelled.
+  pub fn run(&self) -> Option<TemplateAction> {
+    let response = self.dialog.run();
+    let name = self.template.get_active_id()?.to_string();
+    match response {
+      ResponseType::Apply => Some(TemplateAction::Apply(name)),
+      ResponseType::Reject => Some(TemplateAction::Delete(name)),
+      _ => None,
+    }
+  }
+}
+
+impl Drop for ApplyTemplateDialog {
+  fn drop(&mut self) {
+    self.dialog.destroy();
+  }
+}
+
+/// Dialog where users enter the name and description of a template to create from the current calculator.
+pub struct SaveTemplateDialog {
+  dialog: Dialog,
+  name: Entry,
+  description: Entry,
+}
+
+impl SaveTemplateDialog {
+  pub fn new<W: IsA<Window>>(parent: &W) -> Self {
+    let dialog = Dialog::new_with_buttons(Some("Save as Template"), Some(parent), DialogFlags::MODAL | DialogFlags::DESTROY_WITH_PARENT, &[("Cancel", ResponseType::Cancel), ("Save", ResponseType::Accept)]);
+    let grid = Grid::new();
+    grid.set_row_spacing(2);
+    grid.set_column_spacing(5);
+    grid.set_border_width(5);
+
+    let help = Label::new(Some("Saves the blocks and additional mass of the current tab as a template.\nA template with the same name is replaced."));
+    help.set_halign(Align::Start);
+    grid.attach(&help, 0, 0, 2, 1);
+
+    let name_label = Label::new(Some("Name"));
+    name_label.set_halign(Align::Start);
+    grid.attach(&name_label, 0, 1, 1, 1);
+    let name = Entry::new();
+    name.set_hexpand(true);
+    grid.attach(&name, 1, 1, 1, 1);
+
+    let description_label = Label::new(Some("Description"));
+    description_label.set_halign(Align::Start);
+    grid.attach(&description_label, 0, 2, 1, 1);
+    let description = Entry::new();
+    grid.attach(&description, 1, 2, 1, 1);
+
+    {
+      let dialog = dialog.clone();
+      name.connect_changed(move |name| {
+        let has_name = name.get_text().map_or(false, |t| !t.trim().is_empty());
+        dialog.set_response_sensitive(ResponseType::Accept, has_name);
+      });
+    }
+    dialog.set_response_sensitive(ResponseType::Accept, false);
+
+    dialog.get_content_area().add(&grid);
+    dialog.show_all();
+    Self { dialog, name, description }
+  }
+
+  /// Runs the dialog, returning the entered name and description, or `None` if the user cancelled.
+  pub fn run(&self) -> Option<(String, String)> {
+    if self.dialog.run() != ResponseType::Accept { return None; }
+    let name = self.name.get_text().map(|t| t.trim().to_owned()).unwrap_or_default();
+    let description = self.description.get_text().map(|t| t.trim().to_owned()).unwrap_or_default();
+    Some((name, description))
+  }
+}
+
+impl Drop for SaveTemplateDialog {
+  fn drop(&mut self) {
+    self.dialog.destroy();
+  }
+}
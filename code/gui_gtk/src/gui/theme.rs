@@ -0,0 +1,35 @@
+use gdk::Screen;
+use gtk::{CssProvider, Settings, StyleContext};
+use gtk::prelude::*;
+
+use secalc_core::config::Theme;
+
+/// Applies themes, remembering the system preference so that switching back to `Theme::System` restores it.
+pub struct ThemeApplier {
+  system_prefers_dark: bool,
+}
+
+impl ThemeApplier {
+  /// Loads the included CSS into the default screen and captures the system theme preference.
+  pub fn new() -> Self {
+    let provider = CssProvider::new();
+    provider.load_from_data(include_bytes!("theme.css")).expect("[BUG] included theme CSS is invalid");
+    if let Some(screen) = Screen::get_default() {
+      StyleContext::add_provider_for_screen(&screen, &provider, gtk::STYLE_PROVIDER_PRIORITY_APPLICATION);
+    }
+    let system_prefers_dark = Settings::get_default()
+      .map_or(false, |s| s.get_property_gtk_application_prefer_dark_theme());
+    Self { system_prefers_dark }
+  }
+
+  pub fn apply(&self, theme: Theme) {
+    let prefer_dark = match theme {
+      Theme::System => self.system_prefers_dark,
+      Theme::Light => false,
+      Theme::Dark => true,
+    };
+    if let Some(settings) = Settings::get_default() {
+      settings.set_property_gtk_application_prefer_dark_theme(prefer_dark);
+    }
+  }
+}
@@ -0,0 +1,15 @@
+use std::env;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+
+use secalc_core::data::Data;
+
+/// Converts the built-in JSON data into the binary format, which is included in the binary and is much faster to load.
+fn main() {
+  let json_path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("../../data/data.json");
+  println!("cargo:rerun-if-changed={}", json_path.display());
+  let data = Data::from_json(BufReader::new(File::open(&json_path).expect("Cannot open built-in data"))).expect("Cannot read built-in data");
+  let binary_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join(Data::BINARY_FILE_NAME);
+  data.to_binary(BufWriter::new(File::create(binary_path).expect("Cannot create binary data file"))).expect("Cannot write binary data");
+}
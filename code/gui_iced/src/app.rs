@@ -1,31 +1,62 @@
 use std::fmt::Debug;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
 
-use iced::{Application, Command, Element, executor};
+use iced::{Application, Command, Element, executor, Subscription};
+#[cfg(not(target_arch = "wasm32"))]
+use iced_native::{Event, keyboard, mouse, window};
 use log::error;
 
 use secalc_core::data::Data;
 use secalc_core::grid::GridCalculator;
+use secalc_core::grid::command::CommandHistory;
+use secalc_core::grid::template::GridTemplate;
 
-use crate::page::{grid_calc, load, load_confirm_discard, save_as, save_overwrite_confirm};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::page::charts;
+use crate::config::{self, Config};
+use crate::i18n::{self, tr, tr_format};
+use crate::page::{breakdown, compare, environments, grid_calc, load, load_confirm_discard, presets, save_as, save_overwrite_confirm, settings, start, templates};
 use crate::storage::Storage;
+use crate::theme::Theme;
+use crate::view::root;
 
 pub struct App {
+  config: Config,
   data: Data,
   storage: Storage,
+  /// History of each tab, in tab order.
+  histories: Vec<CommandHistory>,
   current_page: Page,
   grid_calc_page: grid_calc::Page,
 }
 
 #[derive(Debug)]
 pub enum Page {
+  Start(start::Page),
   GridCalc,
   SaveAs(save_as::Page),
   SaveAsOverwriteConfirm(save_overwrite_confirm::Page),
   LoadConfirmDiscard(load_confirm_discard::Page),
+  CloseTabConfirmDiscard(load_confirm_discard::Page),
   Load(load::Page),
+  Presets(presets::Page),
+  Templates(templates::Page),
+  Settings(settings::Page),
+  Compare(compare::Page),
+  Breakdown(breakdown::Page),
+  Environments(environments::Page),
+  #[cfg(not(target_arch = "wasm32"))]
+  Charts(charts::Page),
 }
 
 impl Page {
+  fn start(storage: &Storage) -> Page {
+    Page::Start(start::Page::new(storage))
+  }
+
   fn save_as(name: Option<String>) -> Page {
     Page::SaveAs(save_as::Page::new(name))
   }
@@ -45,33 +76,97 @@ impl Page {
 
 #[derive(Clone, Debug)]
 pub enum Message {
+  StartPage(start::Message),
   GridCalcPage(grid_calc::Message),
   SaveAsPage(save_as::Message),
   SaveAsOverwriteConfirmPage(save_overwrite_confirm::Message),
   LoadConfirmDiscardPage(load_confirm_discard::Message),
+  CloseTabConfirmDiscardPage(load_confirm_discard::Message),
   LoadPage(load::Message),
+  PresetsPage(presets::Message),
+  TemplatesPage(templates::Message),
+  SettingsPage(settings::Message),
+  ComparePage(compare::Message),
+  BreakdownPage(breakdown::Message),
+  EnvironmentsPage(environments::Message),
+  #[cfg(not(target_arch = "wasm32"))]
+  ChartsPage(charts::Message),
+  #[cfg(not(target_arch = "wasm32"))]
+  Event(Event),
 }
 
 impl Default for App {
   fn default() -> Self {
-    let data = {
-      let bytes: &[u8] = include_bytes!("../../../data/data.json");
-      Data::from_json(bytes).expect("Cannot read data")
-    };
+    let config = config::load()
+      .unwrap_or_else(|e| {
+        error!("Could not load config, using defaults instead: {}", e);
+        Config::default()
+      });
+    Theme::from(config.theme).set_current();
+    i18n::set_current(config::load_translations(&config)
+      .unwrap_or_else(|e| {
+        error!("Could not load translations file, using English instead: {}", e);
+        Default::default()
+      }));
+    let data = config::load_data(&config)
+      .unwrap_or_else(|e| {
+        error!("Could not load data file, using built-in data instead: {}", e);
+        config::load_data(&Config::default()).expect("Cannot read data")
+      });
     let storage = Storage::load()
       .unwrap_or_else(|e| panic!("[BUG] Could not load storage: {}", e))
       .unwrap_or_default();
-    let current_page = Page::GridCalc;
-    let grid_calc_page = grid_calc::Page::new(&data, &GridCalculator::default(), &storage.calculator);
+    let current_page = Page::start(&storage);
+    let histories = (0..storage.tab_count()).map(|_| CommandHistory::default()).collect();
+    let grid_calc_page = grid_calc::Page::new(&data, &config.default_calculator(), &storage.calculator, &storage.collapsed_panels, config.units);
     Self {
+      config,
       data,
       storage,
+      histories,
       current_page,
       grid_calc_page,
     }
   }
 }
 
+impl App {
+  /// Opens the calculator JSON file at `path` as the current calculator. Replacing the calculator is recorded in the
+  /// history, so that it can be undone.
+  #[cfg(not(target_arch = "wasm32"))]
+  fn open_file(&mut self, path: &Path) -> anyhow::Result<()> {
+    let calculator = GridCalculator::from_json(File::open(path)?)?;
+    let name = path.file_stem().map_or_else(|| "Untitled".to_owned(), |stem| stem.to_string_lossy().into_owned());
+    let previous = std::mem::replace(&mut self.storage.calculator, calculator.clone());
+    self.histories[self.storage.current_tab()].record(&previous, &calculator);
+    self.storage.open_calculator(name, calculator)?;
+    self.config.push_recent_file(path.to_owned());
+    config::save(&self.config)?;
+    self.grid_calc_page.reload_input(&self.storage.calculator, &self.data);
+    self.current_page = Page::GridCalc;
+    Ok(())
+  }
+
+  /// Opens `calculator` of a preset named `name` in a new tab, so that the current calculator is kept.
+  fn open_preset(&mut self, name: String, calculator: GridCalculator) {
+    self.storage.new_tab(calculator.clone())
+      .unwrap_or_else(|e| error!("[BUG] Could not save storage: {}", e));
+    self.histories.push(CommandHistory::default());
+    self.storage.open_calculator(name, calculator)
+      .unwrap_or_else(|e| error!("[BUG] Could not save storage: {}", e));
+    self.grid_calc_page.reload_input(&self.storage.calculator, &self.data);
+  }
+
+  /// Closes the current tab, discarding its calculator and history.
+  fn close_tab(&mut self) {
+    if self.storage.tab_count() <= 1 { return; }
+    self.histories.remove(self.storage.current_tab());
+    self.storage.close_tab()
+      .unwrap_or_else(|e| error!("[BUG] Could not save storage: {}", e));
+    self.grid_calc_page.reload_input(&self.storage.calculator, &self.data);
+  }
+}
+
 impl Application for App {
   type Executor = executor::Null;
   type Message = Message;
@@ -82,27 +177,99 @@ impl Application for App {
   }
 
   fn title(&self) -> String {
-    String::from("Space Engineers Calculator")
+    let name = self.storage.calculator_name.clone().unwrap_or_else(|| tr("Untitled"));
+    let modified = if self.storage.calculator_modified { "*" } else { "" };
+    format!("{}{} - {}", name, modified, tr("Space Engineers Calculator"))
   }
 
   fn update(&mut self, message: Message) -> Command<Message> {
     match (&mut self.current_page, message) {
-      (Page::GridCalc, Message::GridCalcPage(m)) => match self.grid_calc_page.update(m, &mut self.storage.calculator, &self.data) {
-        Some(grid_calc::Action::CalculatorModified) => self.storage.calculator_modified = true,
-        Some(grid_calc::Action::Save) => if let Some(name) = self.storage.calculator_name.clone() {
-          self.storage.save_calculator(name)
+      (Page::Start(page), Message::StartPage(m)) => match page.update(m) {
+        start::Action::Continue => self.current_page = Page::GridCalc,
+        start::Action::New => {
+          self.storage.new_calculator(self.config.default_calculator())
             .unwrap_or_else(|e| error!("[BUG] Could not save storage: {}", e));
+          self.histories[self.storage.current_tab()].clear();
+          self.grid_calc_page.reload_input(&self.storage.calculator, &self.data);
           self.current_page = Page::GridCalc;
-        } else {
-          self.current_page = Page::save_as(self.storage.calculator_name.clone());
         },
-        Some(grid_calc::Action::SaveAs) => self.current_page = Page::save_as(self.storage.calculator_name.clone()),
-        Some(grid_calc::Action::Load) => if self.storage.calculator_modified {
-          self.current_page = Page::load_confirm_discard();
-        } else {
-          self.current_page = Page::load(&self.storage);
+        start::Action::Open(name) => {
+          self.storage.load_calculator(name)
+            .unwrap_or_else(|e| error!("[BUG] Could not load calculator: {}", e));
+          self.histories[self.storage.current_tab()].clear();
+          self.grid_calc_page.reload_input(&self.storage.calculator, &self.data);
+          self.current_page = Page::GridCalc;
         },
-        None => {},
+      },
+      (Page::GridCalc, Message::GridCalcPage(m)) => {
+        let previous = self.storage.calculator.clone();
+        match self.grid_calc_page.update(m, &mut self.storage.calculator, &self.data) {
+          Some(grid_calc::Action::CalculatorModified) => {
+            self.histories[self.storage.current_tab()].record(&previous, &self.storage.calculator);
+            self.storage.calculator_modified = true;
+          },
+          Some(grid_calc::Action::Undo) => if self.histories[self.storage.current_tab()].undo(&mut self.storage.calculator) {
+            self.grid_calc_page.reload_input(&self.storage.calculator, &self.data);
+            self.storage.calculator_modified = true;
+          },
+          Some(grid_calc::Action::Redo) => if self.histories[self.storage.current_tab()].redo(&mut self.storage.calculator) {
+            self.grid_calc_page.reload_input(&self.storage.calculator, &self.data);
+            self.storage.calculator_modified = true;
+          },
+          Some(grid_calc::Action::Save) => if let Some(name) = self.storage.calculator_name.clone() {
+            self.storage.save_calculator(name)
+              .unwrap_or_else(|e| error!("[BUG] Could not save storage: {}", e));
+            self.current_page = Page::GridCalc;
+          } else {
+            self.current_page = Page::save_as(self.storage.calculator_name.clone());
+          },
+          Some(grid_calc::Action::ToggleTheme) => {
+            let theme = Theme::current().toggled();
+            theme.set_current();
+            self.config.theme = theme.into();
+            config::save(&self.config)
+              .unwrap_or_else(|e| error!("[BUG] Could not save config: {}", e));
+          },
+          Some(grid_calc::Action::Compare) => self.current_page = Page::Compare(compare::Page::new(&self.storage, &self.data)),
+          Some(grid_calc::Action::Breakdown) => self.current_page = Page::Breakdown(breakdown::Page::new(&self.storage.calculator, &self.data, self.config.units)),
+          Some(grid_calc::Action::Environments) => self.current_page = Page::Environments(environments::Page::new(&self.storage.calculator, &self.data)),
+          Some(grid_calc::Action::Settings) => self.current_page = Page::Settings(settings::Page::new(&self.config)),
+          Some(grid_calc::Action::CollapsedPanelsChanged(collapsed_panels)) => {
+            self.storage.collapsed_panels = collapsed_panels;
+            self.storage.save()
+              .unwrap_or_else(|e| error!("[BUG] Could not save storage: {}", e));
+          },
+          Some(grid_calc::Action::SelectTab(index)) => {
+            self.storage.switch_tab(index)
+              .unwrap_or_else(|e| error!("[BUG] Could not save storage: {}", e));
+            self.grid_calc_page.reload_input(&self.storage.calculator, &self.data);
+          },
+          Some(grid_calc::Action::NewTab) => {
+            self.storage.new_tab(self.config.default_calculator())
+              .unwrap_or_else(|e| error!("[BUG] Could not save storage: {}", e));
+            self.histories.push(CommandHistory::default());
+            self.grid_calc_page.reload_input(&self.storage.calculator, &self.data);
+          },
+          Some(grid_calc::Action::CloseTab) => if self.storage.calculator_modified {
+            self.current_page = Page::CloseTabConfirmDiscard(load_confirm_discard::Page::new());
+          } else {
+            self.close_tab();
+          },
+          Some(grid_calc::Action::Start) => self.current_page = Page::start(&self.storage),
+          Some(grid_calc::Action::SaveAs) => self.current_page = Page::save_as(self.storage.calculator_name.clone()),
+          Some(grid_calc::Action::Load) => if self.storage.calculator_modified {
+            self.current_page = Page::load_confirm_discard();
+          } else {
+            self.current_page = Page::load(&self.storage);
+          },
+          Some(grid_calc::Action::Presets) => self.current_page = Page::Presets(presets::Page::new()),
+          Some(grid_calc::Action::Templates) => self.current_page = Page::Templates(templates::Page::new(&self.config.templates)),
+          #[cfg(not(target_arch = "wasm32"))]
+          Some(grid_calc::Action::Charts) => self.current_page = Page::Charts(charts::Page::new(&self.storage.calculator, &self.data)),
+          #[cfg(target_arch = "wasm32")]
+          Some(grid_calc::Action::Charts) => {},
+          None => {},
+        }
       },
       (Page::SaveAs(page), Message::SaveAsPage(ref m)) => match page.update(m.clone()) {
         Some(save_as::Action::Save(name)) => if self.storage.contains_saved_calculator(&name) {
@@ -127,28 +294,180 @@ impl Application for App {
         load_confirm_discard::Action::Cancel => self.current_page = Page::GridCalc,
         load_confirm_discard::Action::Discard => self.current_page = Page::load(&self.storage),
       },
+      (Page::CloseTabConfirmDiscard(page), Message::CloseTabConfirmDiscardPage(ref m)) => match page.update(m.clone()) {
+        load_confirm_discard::Action::Cancel => self.current_page = Page::GridCalc,
+        load_confirm_discard::Action::Discard => {
+          self.close_tab();
+          self.current_page = Page::GridCalc;
+        },
+      },
       (Page::Load(page), Message::LoadPage(ref m)) => match page.update(m.clone()) {
         Some(load::Action::Load(name)) => {
           self.storage.load_calculator(name)
             .unwrap_or_else(|e| error!("[BUG] Could not load calculator: {}", e));
+          self.histories[self.storage.current_tab()].clear();
           self.grid_calc_page.reload_input(&self.storage.calculator, &self.data);
           self.current_page = Page::GridCalc;
         },
         Some(load::Action::Cancel) => self.current_page = Page::GridCalc,
         None => {},
       },
+      (Page::Presets(page), Message::PresetsPage(m)) => match page.update(m) {
+        Some(presets::Action::Open(preset)) => {
+          match preset.load() {
+            Ok(calculator) => self.open_preset(tr(preset.name), calculator),
+            Err(e) => error!("[BUG] Could not load preset: {}", e),
+          }
+          self.current_page = Page::GridCalc;
+        },
+        Some(presets::Action::Cancel) => self.current_page = Page::GridCalc,
+        None => {},
+      },
+      (Page::Templates(page), Message::TemplatesPage(m)) => match page.update(m) {
+        Some(templates::Action::Apply(name)) => {
+          if let Some(template) = self.config.templates.get(&name) {
+            let previous = self.storage.calculator.clone();
+            match template.apply(&mut self.storage.calculator) {
+              Ok(()) => {
+                self.histories[self.storage.current_tab()].record(&previous, &self.storage.calculator);
+                self.storage.calculator_modified = true;
+                self.storage.save()
+                  .unwrap_or_else(|e| error!("[BUG] Could not save storage: {}", e));
+                self.grid_calc_page.reload_input(&self.storage.calculator, &self.data);
+              },
+              Err(e) => {
+                // Stay on the templates page, so that the user sees why the template was not applied.
+                page.set_error(tr_format("Could not apply template '{}': {}", &[&name, &e]));
+                return Command::none();
+              }
+            }
+          }
+          self.current_page = Page::GridCalc;
+        },
+        Some(templates::Action::Delete(name)) => {
+          self.config.templates.remove(&name);
+          config::save(&self.config)
+            .unwrap_or_else(|e| error!("[BUG] Could not save config: {}", e));
+          self.current_page = Page::Templates(templates::Page::new(&self.config.templates));
+        },
+        Some(templates::Action::Save(name, description)) => {
+          self.config.templates.insert(GridTemplate::from_calculator(name, description, &self.storage.calculator));
+          config::save(&self.config)
+            .unwrap_or_else(|e| error!("[BUG] Could not save config: {}", e));
+          self.current_page = Page::GridCalc;
+        },
+        Some(templates::Action::Cancel) => self.current_page = Page::GridCalc,
+        None => {},
+      },
+      (Page::Settings(page), Message::SettingsPage(m)) => match page.update(m) {
+        Some(settings::Action::Save(config)) => {
+          if config.data_path != self.config.data_path || config.custom_blocks_path != self.config.custom_blocks_path {
+            match config::load_data(&config) {
+              Ok(data) => self.data = data,
+              Err(e) => error!("Could not load data file: {}", e),
+            }
+          }
+          if config.translations_path != self.config.translations_path {
+            match config::load_translations(&config) {
+              Ok(translations) => i18n::set_current(translations),
+              Err(e) => error!("Could not load translations file: {}", e),
+            }
+          }
+          self.config = config;
+          Theme::from(self.config.theme).set_current();
+          config::save(&self.config)
+            .unwrap_or_else(|e| error!("[BUG] Could not save config: {}", e));
+          self.grid_calc_page = grid_calc::Page::new(&self.data, &self.config.default_calculator(), &self.storage.calculator, &self.storage.collapsed_panels, self.config.units);
+          self.current_page = Page::GridCalc;
+        },
+        Some(settings::Action::Cancel) => self.current_page = Page::GridCalc,
+        None => {},
+      },
+      (Page::Compare(page), Message::ComparePage(m)) => match page.update(m, &self.data) {
+        Some(compare::Action::Back) => self.current_page = Page::GridCalc,
+        None => {},
+      },
+      (Page::Breakdown(page), Message::BreakdownPage(m)) => match page.update(m) {
+        Some(breakdown::Action::Back) => self.current_page = Page::GridCalc,
+        None => {},
+      },
+      (Page::Environments(page), Message::EnvironmentsPage(m)) => match page.update(m) {
+        Some(environments::Action::Back) => self.current_page = Page::GridCalc,
+        None => {},
+      },
+      #[cfg(not(target_arch = "wasm32"))]
+      (Page::Charts(page), Message::ChartsPage(m)) => match page.update(m) {
+        charts::Action::Back => self.current_page = Page::GridCalc,
+      },
+      #[cfg(not(target_arch = "wasm32"))]
+      (Page::GridCalc, Message::Event(Event::Mouse(mouse::Event::WheelScrolled { delta }))) => {
+        let lines = match delta {
+          mouse::ScrollDelta::Lines { y, .. } => y,
+          mouse::ScrollDelta::Pixels { y, .. } => y / 20.0,
+        };
+        let steps = if lines.abs() < 1.0 { lines.signum() } else { lines.round() } as i64;
+        if steps != 0 {
+          return self.update(Message::GridCalcPage(grid_calc::Message::WheelScrolled(steps)));
+        }
+      }
+      #[cfg(not(target_arch = "wasm32"))]
+      (Page::GridCalc, Message::Event(Event::Keyboard(keyboard::Event::KeyPressed { key_code, modifiers }))) if modifiers.control => {
+        let message = match key_code {
+          keyboard::KeyCode::Z if modifiers.shift => Some(grid_calc::Message::RedoPressed),
+          keyboard::KeyCode::Z => Some(grid_calc::Message::UndoPressed),
+          keyboard::KeyCode::Y => Some(grid_calc::Message::RedoPressed),
+          _ => None,
+        };
+        if let Some(message) = message {
+          return self.update(Message::GridCalcPage(message));
+        }
+      }
+      #[cfg(not(target_arch = "wasm32"))]
+      (_, Message::Event(Event::Window(window::Event::FileDropped(path)))) => {
+        self.open_file(&path)
+          .unwrap_or_else(|e| error!("Could not open dropped file '{}': {}", path.display(), e));
+      }
+      #[cfg(not(target_arch = "wasm32"))]
+      (_, Message::Event(_)) => {}
       (page, m) => error!("[BUG] Requested update with message '{:?}', but that message cannot be handled by the current page '{:?}' or the application itself", m, page),
     }
     Command::none()
   }
 
+  #[cfg(not(target_arch = "wasm32"))]
+  fn subscription(&self) -> Subscription<Message> {
+    iced_native::subscription::events().map(Message::Event)
+  }
+
+  // Native events are not available on the web, so mouse wheel stepping, keyboard shortcuts, and opening dropped files
+  // are not supported there.
+  #[cfg(target_arch = "wasm32")]
+  fn subscription(&self) -> Subscription<Message> {
+    Subscription::none()
+  }
+
   fn view(&mut self) -> Element<Message> {
-    match &mut self.current_page {
-      Page::GridCalc => self.grid_calc_page.view().map(Message::GridCalcPage),
+    let page = match &mut self.current_page {
+      Page::Start(page) => page.view().map(Message::StartPage),
+      Page::GridCalc => {
+        let tabs: Vec<_> = self.storage.iter_tabs().collect();
+        let history = &self.histories[self.storage.current_tab()];
+        self.grid_calc_page.view(&tabs, self.storage.current_tab(), history.can_undo(), history.can_redo()).map(Message::GridCalcPage)
+      },
       Page::SaveAs(page) => page.view().map(Message::SaveAsPage),
       Page::SaveAsOverwriteConfirm(page) => page.view().map(Message::SaveAsOverwriteConfirmPage),
       Page::LoadConfirmDiscard(page) => page.view().map(Message::LoadConfirmDiscardPage),
+      Page::CloseTabConfirmDiscard(page) => page.view().map(Message::CloseTabConfirmDiscardPage),
       Page::Load(page) => page.view().map(Message::LoadPage),
-    }
+      Page::Presets(page) => page.view().map(Message::PresetsPage),
+      Page::Templates(page) => page.view(&self.config.templates).map(Message::TemplatesPage),
+      Page::Settings(page) => page.view().map(Message::SettingsPage),
+      Page::Compare(page) => page.view().map(Message::ComparePage),
+      Page::Breakdown(page) => page.view().map(Message::BreakdownPage),
+      Page::Environments(page) => page.view().map(Message::EnvironmentsPage),
+      #[cfg(not(target_arch = "wasm32"))]
+      Page::Charts(page) => page.view().map(Message::ChartsPage),
+    };
+    root(page).into()
   }
 }
@@ -0,0 +1,86 @@
+//! Loading and saving the configuration shared with the other frontends, and applying it on startup.
+
+use anyhow::Result;
+use log::error;
+
+use secalc_core::data::Data;
+use secalc_core::i18n::Translations;
+
+pub use secalc_core::config::Config;
+
+#[cfg(target_arch = "wasm32")]
+use crate::persist;
+
+/// Loads the configuration file shared with the other frontends, or the default configuration if it was never saved.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load() -> Result<Config> {
+  Ok(Config::load()?)
+}
+
+/// Loads the configuration from local storage, or the default configuration if it was never saved.
+#[cfg(target_arch = "wasm32")]
+pub fn load() -> Result<Config> {
+  Ok(persist::load_string(KEY)?.map(|string| Config::from_json_string(&string)).transpose()?.unwrap_or_default())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save(config: &Config) -> Result<()> {
+  Ok(config.save()?)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save(config: &Config) -> Result<()> {
+  persist::save_string(KEY, &config.to_json_string()?)
+}
+
+#[cfg(target_arch = "wasm32")]
+const KEY: &'static str = "config";
+
+
+/// Loads the data file of `config`, or the built-in data if there is none, with the custom blocks of `config` layered
+/// over it. In debug builds, logs problems with the loaded data. Data and custom blocks files are ignored on the web.
+pub fn load_data(config: &Config) -> Result<Data> {
+  let mut data = load_base_data(config)?;
+  add_custom_blocks(config, &mut data)?;
+  if cfg!(debug_assertions) {
+    for diagnostic in data.validate() {
+      error!("Game data problem: {}", diagnostic);
+    }
+  }
+  Ok(data)
+}
+
+#[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+fn load_base_data(config: &Config) -> Result<Data> {
+  #[cfg(not(target_arch = "wasm32"))] {
+    if let Some(data_path) = &config.data_path {
+      return Ok(Data::from_path(data_path)?);
+    }
+  }
+  let bytes: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/data.bin"));
+  Ok(Data::from_binary(bytes)?)
+}
+
+/// Layers the custom blocks file of `config`, if any, over `data`.
+#[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+fn add_custom_blocks(config: &Config, data: &mut Data) -> Result<()> {
+  #[cfg(not(target_arch = "wasm32"))] {
+    if let Some(custom_blocks_path) = &config.custom_blocks_path {
+      let reader = std::fs::File::open(custom_blocks_path)?;
+      data.add_custom_blocks(&secalc_core::data::custom::CustomBlocks::from_json(reader)?);
+    }
+  }
+  Ok(())
+}
+
+/// Loads the translations file of `config`, or no translations if there is none. Ignored on the web.
+#[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+pub fn load_translations(config: &Config) -> Result<Translations> {
+  #[cfg(not(target_arch = "wasm32"))] {
+    if let Some(translations_path) = &config.translations_path {
+      let reader = std::fs::File::open(translations_path)?;
+      return Ok(Translations::from_json(reader)?);
+    }
+  }
+  Ok(Translations::default())
+}
@@ -5,6 +5,7 @@ use iced::{Align, Element, Length, text_input};
 
 use crate::view::{danger_color, foreground_color, lbl, row, text_input};
 
+#[derive(Debug)]
 pub struct DataBind<T> {
   input_default: T,
   input_placeholder: String,
@@ -51,6 +52,11 @@ impl<T: Copy + FromStr + PartialEq> DataBind<T> {
     self.error = error;
   }
 
+  /// Marks the current input as erroneous, for inputs that parse but are invalid in context.
+  pub fn set_error(&mut self) {
+    self.error = true;
+  }
+
   pub fn reload(&mut self, val: String) {
     let (value, error) = Self::value_and_error(val, self.input_default);
     self.value = value;
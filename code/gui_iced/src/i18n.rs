@@ -0,0 +1,33 @@
+use std::cell::RefCell;
+
+use secalc_core::i18n::Translations;
+
+thread_local! {
+  static CURRENT: RefCell<Translations> = RefCell::new(Translations::default());
+}
+
+/// Sets the translations that user interface texts are translated with from now on.
+pub fn set_current(translations: Translations) {
+  CURRENT.with(|current| *current.borrow_mut() = translations);
+}
+
+/// Translates English `text` with the current translations.
+pub fn tr(text: &str) -> String {
+  CURRENT.with(|current| current.borrow().translate(text).to_owned())
+}
+
+/// Translates English `template` with the current translations, and then replaces each `{}` in the translated template
+/// with the next argument from `args`.
+pub fn tr_format(template: &str, args: &[&dyn std::fmt::Display]) -> String {
+  let translated = tr(template);
+  let mut args = args.iter();
+  let mut parts = translated.split("{}");
+  let mut result = parts.next().unwrap_or_default().to_owned();
+  for part in parts {
+    if let Some(arg) = args.next() {
+      result.push_str(&arg.to_string());
+    }
+    result.push_str(part);
+  }
+  result
+}
@@ -5,17 +5,28 @@ pub mod view;
 pub mod storage;
 pub mod page;
 pub mod data_bind;
+pub mod stepper;
+pub mod config;
+pub mod i18n;
+pub mod persist;
+pub mod theme;
 
 fn main() {
-  let log_level = log::Level::Error;
-
+  // Natively, log records and tracing spans are printed to stderr, filtered by the SECALC_LOG environment variable
+  // (errors only by default). Closed spans are printed with their duration.
   #[cfg(not(target_arch = "wasm32"))] {
-    simple_logger::init_with_level(log_level)
+    use tracing_subscriber::EnvFilter;
+    use tracing_subscriber::fmt::format::FmtSpan;
+    tracing_subscriber::fmt()
+      .with_env_filter(EnvFilter::from_env("SECALC_LOG"))
+      .with_span_events(FmtSpan::CLOSE)
+      .with_writer(std::io::stderr)
+      .try_init()
       .unwrap_or_else(|e| eprintln!("Could not initialize logger: {:?}", e));
   }
 
   #[cfg(target_arch = "wasm32")] {
-    console_log::init_with_level(log_level)
+    console_log::init_with_level(log::Level::Error)
       .unwrap_or_else(|e| eprintln!("Could not initialize logger: {:?}", e));
   }
 
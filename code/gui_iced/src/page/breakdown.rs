@@ -0,0 +1,169 @@
+use std::cmp::Ordering;
+
+use iced::{Align, button, Element, scrollable};
+
+use secalc_core::data::Data;
+use secalc_core::format::UnitPreferences;
+use secalc_core::grid::breakdown::BlockContribution;
+use secalc_core::grid::GridCalculator;
+
+use crate::i18n::tr;
+use crate::view::{button, col, h1, lbl, row, scl, val};
+
+#[derive(Debug)]
+pub struct Page {
+  /// Localized block name and contribution of each entered block.
+  contributions: Vec<(String, BlockContribution)>,
+  units: UnitPreferences,
+  sort_column: Column,
+  descending: bool,
+  column_button_states: [button::State; 9],
+  back_button_state: button::State,
+  scrollable_state: scrollable::State,
+}
+
+/// Column of the contribution table.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Column {
+  Name,
+  Count,
+  Mass,
+  Volume,
+  PowerGeneration,
+  PowerConsumption,
+  HydrogenGeneration,
+  HydrogenConsumption,
+  Force,
+}
+
+impl Column {
+  const ALL: [Column; 9] = [Column::Name, Column::Count, Column::Mass, Column::Volume, Column::PowerGeneration, Column::PowerConsumption, Column::HydrogenGeneration, Column::HydrogenConsumption, Column::Force];
+
+  fn label(self) -> &'static str {
+    match self {
+      Column::Name => "Block",
+      Column::Count => "Count",
+      Column::Mass => "Mass",
+      Column::Volume => "Volume",
+      Column::PowerGeneration => "Power generation",
+      Column::PowerConsumption => "Power consumption",
+      Column::HydrogenGeneration => "Hydrogen generation",
+      Column::HydrogenConsumption => "Hydrogen consumption",
+      Column::Force => "Force",
+    }
+  }
+
+  fn value(self, contribution: &BlockContribution) -> f64 {
+    match self {
+      Column::Name => 0.0,
+      Column::Count => contribution.count as f64,
+      Column::Mass => contribution.mass.0,
+      Column::Volume => contribution.volume.0,
+      Column::PowerGeneration => contribution.power_generation.0,
+      Column::PowerConsumption => contribution.power_consumption.0,
+      Column::HydrogenGeneration => contribution.hydrogen_generation.0,
+      Column::HydrogenConsumption => contribution.hydrogen_consumption.0,
+      Column::Force => contribution.force.0,
+    }
+  }
+
+  fn format(self, contribution: &BlockContribution, units: UnitPreferences) -> String {
+    match self {
+      Column::Name => String::new(),
+      Column::Count => format!("{}", contribution.count),
+      Column::Mass => units.format_mass(contribution.mass),
+      Column::Volume => units.format_volume(contribution.volume),
+      Column::PowerGeneration => units.format_power(contribution.power_generation),
+      Column::PowerConsumption => units.format_power(contribution.power_consumption),
+      Column::HydrogenGeneration => units.format_flow(contribution.hydrogen_generation),
+      Column::HydrogenConsumption => units.format_flow(contribution.hydrogen_consumption),
+      Column::Force => units.format_force(contribution.force),
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+  SortBy(Column),
+  Back,
+}
+
+#[derive(Debug)]
+pub enum Action {
+  Back,
+}
+
+impl Page {
+  pub fn new(calculator: &GridCalculator, data: &Data, units: UnitPreferences) -> Self {
+    let contributions = calculator.breakdown(data).into_iter()
+      .map(|c| (data.blocks.localized_name(&c.id, &data.localization).unwrap_or(c.id.as_str()).to_owned(), c))
+      .collect();
+    let mut page = Self {
+      contributions,
+      units,
+      sort_column: Column::Name,
+      descending: false,
+      column_button_states: Default::default(),
+      back_button_state: Default::default(),
+      scrollable_state: Default::default(),
+    };
+    page.sort();
+    page
+  }
+
+  pub fn update(&mut self, message: Message) -> Option<Action> {
+    match message {
+      Message::SortBy(column) => {
+        // Sorting by the same column again reverses the order. Numbers are sorted largest first by default.
+        self.descending = if column == self.sort_column { !self.descending } else { column != Column::Name };
+        self.sort_column = column;
+        self.sort();
+        None
+      }
+      Message::Back => Some(Action::Back),
+    }
+  }
+
+  fn sort(&mut self) {
+    let column = self.sort_column;
+    self.contributions.sort_by(|(name_a, a), (name_b, b)| {
+      let ordering = match column {
+        Column::Name => name_a.cmp(name_b),
+        _ => column.value(a).partial_cmp(&column.value(b)).unwrap_or(Ordering::Equal),
+      };
+      if self.descending { ordering.reverse() } else { ordering }
+    });
+  }
+
+  pub fn view(&mut self) -> Element<Message> {
+    let mut table = row().spacing(10);
+    for (column, button_state) in Column::ALL.iter().zip(self.column_button_states.iter_mut()) {
+      let label = if *column == self.sort_column {
+        format!("{} {}", tr(column.label()), if self.descending { "v" } else { "^" })
+      } else {
+        tr(column.label())
+      };
+      let mut cells = col().spacing(1).align_items(if *column == Column::Name { Align::Start } else { Align::End })
+        .push(button(button_state, label).on_press(Message::SortBy(*column)));
+      for (name, contribution) in &self.contributions {
+        cells = cells.push(match column {
+          Column::Name => lbl(name.as_str()),
+          _ => val(column.format(contribution, self.units)),
+        });
+      }
+      table = table.push(cells);
+    }
+
+    col()
+      .padding(10)
+      .spacing(10)
+      .push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(h1(tr("Breakdown")))
+        .push(button(&mut self.back_button_state, tr("Back")).on_press(Message::Back))
+      )
+      .push(scl(&mut self.scrollable_state).push(table))
+      .into()
+  }
+}
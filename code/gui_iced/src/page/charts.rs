@@ -0,0 +1,192 @@
+use iced::{Align, button, Color, Element, Length, Point, Rectangle, Size};
+use iced::canvas::{self, Cache, Canvas, Cursor, Frame, Geometry, Path, Stroke, Text};
+
+use secalc_core::data::Data;
+use secalc_core::grid::{Direction, GridCalculator};
+use secalc_core::grid::sweep;
+
+use crate::i18n::tr;
+use crate::view::{button, col, danger_color, foreground_color, h1, h2, row, TXT_SIZE};
+
+/// Number of steps in each sweep; the charts have one more point than this.
+const STEPS: usize = 20;
+
+#[derive(Debug)]
+pub struct Page {
+  acceleration: Chart,
+  power: Chart,
+  back_button_state: button::State,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+  Back,
+}
+
+#[derive(Debug)]
+pub enum Action {
+  Back,
+}
+
+impl Page {
+  pub fn new(calculator: &GridCalculator, data: &Data) -> Self {
+    let acceleration = {
+      let series = Direction::iter().zip(SERIES_COLORS.iter())
+        .map(|(direction, color)| Series {
+          label: tr(&format!("{:?}", direction)),
+          color: *color,
+          points: sweep::acceleration_by_cargo_fill(calculator, data, *direction, STEPS),
+        })
+        .collect();
+      Chart::new(tr("Cargo fill (%)"), tr("Acceleration, filled, gravity (m/s^2)"), series)
+    };
+    let power = {
+      let points = sweep::power_balance_by_thruster_utilization(&calculator.calculate(data), STEPS);
+      let series = vec![Series { label: tr("Power balance"), color: SERIES_COLORS[0], points }];
+      Chart::new(tr("Thruster utilization (%)"), tr("Power balance (MW)"), series)
+    };
+    Self { acceleration, power, back_button_state: Default::default() }
+  }
+
+  pub fn update(&mut self, message: Message) -> Action {
+    match message {
+      Message::Back => Action::Back,
+    }
+  }
+
+  pub fn view(&mut self) -> Element<Message> {
+    col()
+      .padding(10)
+      .spacing(10)
+      .push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(h1(tr("Charts")))
+        .push(button(&mut self.back_button_state, tr("Back")).on_press(Message::Back))
+      )
+      .push(row()
+        .spacing(10)
+        .push(col()
+          .width(Length::FillPortion(1))
+          .push(h2(tr("Acceleration vs cargo fill")))
+          .push(Canvas::new(&mut self.acceleration).width(Length::Fill).height(Length::Units(500)))
+        )
+        .push(col()
+          .width(Length::FillPortion(1))
+          .push(h2(tr("Power balance vs thruster utilization")))
+          .push(Canvas::new(&mut self.power).width(Length::Fill).height(Length::Units(500)))
+        )
+      )
+      .into()
+  }
+}
+
+
+const SERIES_COLORS: [Color; 6] = [
+  Color { r: 0.2, g: 0.4, b: 0.8, a: 1.0 },
+  Color { r: 0.8, g: 0.4, b: 0.2, a: 1.0 },
+  Color { r: 0.2, g: 0.6, b: 0.3, a: 1.0 },
+  Color { r: 0.6, g: 0.3, b: 0.7, a: 1.0 },
+  Color { r: 0.5, g: 0.5, b: 0.5, a: 1.0 },
+  Color { r: 0.7, g: 0.6, b: 0.1, a: 1.0 },
+];
+
+/// Margin around the plot area, leaving room for the axis labels.
+const MARGIN: f32 = 60.0;
+
+#[derive(Debug)]
+struct Series {
+  label: String,
+  color: Color,
+  points: Vec<(f64, f64)>,
+}
+
+/// Line chart of one or more series, drawn on a canvas.
+#[derive(Debug)]
+struct Chart {
+  x_label: String,
+  y_label: String,
+  series: Vec<Series>,
+  x_range: (f64, f64),
+  y_range: (f64, f64),
+  cache: Cache,
+}
+
+impl Chart {
+  fn new(x_label: String, y_label: String, series: Vec<Series>) -> Self {
+    let points = || series.iter().flat_map(|s| s.points.iter());
+    let x_range = Self::range(points().map(|(x, _)| *x));
+    // Always include zero on the Y axis, so that the sign of values is visible.
+    let y_range = Self::range(points().map(|(_, y)| *y).chain(std::iter::once(0.0)));
+    Self { x_label, y_label, series, x_range, y_range, cache: Cache::new() }
+  }
+
+  fn range<I: Iterator<Item=f64>>(values: I) -> (f64, f64) {
+    let (min, max) = values.filter(|v| v.is_finite()).fold((std::f64::MAX, std::f64::MIN), |(min, max), v| (min.min(v), max.max(v)));
+    if min > max {
+      (0.0, 1.0)
+    } else if min == max {
+      (min - 1.0, max + 1.0)
+    } else {
+      (min, max)
+    }
+  }
+
+  fn draw_chart(&self, frame: &mut Frame) {
+    let plot = Rectangle { x: MARGIN, y: MARGIN / 2.0, width: frame.width() - MARGIN * 1.5, height: frame.height() - MARGIN * 1.5 };
+    if plot.width <= 0.0 || plot.height <= 0.0 { return; }
+    let to_point = |(x, y): (f64, f64)| {
+      let x = (x - self.x_range.0) / (self.x_range.1 - self.x_range.0);
+      let y = (y - self.y_range.0) / (self.y_range.1 - self.y_range.0);
+      Point::new(plot.x + x as f32 * plot.width, plot.y + (1.0 - y as f32) * plot.height)
+    };
+
+    // Axes and zero line.
+    let axis_stroke = Stroke { width: 1.0, color: foreground_color(), ..Stroke::default() };
+    frame.stroke(&line(Point::new(plot.x, plot.y), Point::new(plot.x, plot.y + plot.height)), axis_stroke);
+    frame.stroke(&line(Point::new(plot.x, plot.y + plot.height), Point::new(plot.x + plot.width, plot.y + plot.height)), axis_stroke);
+    if self.y_range.0 < 0.0 {
+      let zero = to_point((self.x_range.0, 0.0));
+      frame.stroke(&line(zero, Point::new(plot.x + plot.width, zero.y)), Stroke { width: 1.0, color: danger_color(), ..Stroke::default() });
+    }
+
+    // Axis labels and range values.
+    let text = |content: String, position: Point| Text { content, position, color: foreground_color(), size: TXT_SIZE as f32, ..Text::default() };
+    frame.fill_text(text(format!("{:.1}", self.y_range.1), Point::new(4.0, plot.y)));
+    frame.fill_text(text(format!("{:.1}", self.y_range.0), Point::new(4.0, plot.y + plot.height - TXT_SIZE as f32)));
+    frame.fill_text(text(format!("{:.0}", self.x_range.0), Point::new(plot.x, plot.y + plot.height + 4.0)));
+    frame.fill_text(text(format!("{:.0}", self.x_range.1), Point::new(plot.x + plot.width - TXT_SIZE as f32 * 2.0, plot.y + plot.height + 4.0)));
+    frame.fill_text(text(self.x_label.clone(), Point::new(plot.x + plot.width / 3.0, plot.y + plot.height + 4.0)));
+    frame.fill_text(text(self.y_label.clone(), Point::new(plot.x + 4.0, 4.0)));
+
+    // Series lines and legend.
+    for (index, series) in self.series.iter().enumerate() {
+      let path = Path::new(|builder| {
+        let mut points = series.points.iter().filter(|(_, y)| y.is_finite()).map(|p| to_point(*p));
+        if let Some(first) = points.next() {
+          builder.move_to(first);
+          for point in points {
+            builder.line_to(point);
+          }
+        }
+      });
+      frame.stroke(&path, Stroke { width: 2.0, color: series.color, ..Stroke::default() });
+      let legend_position = Point::new(plot.x + plot.width - 120.0, plot.y + index as f32 * (TXT_SIZE as f32 + 2.0));
+      frame.fill_text(Text { color: series.color, ..text(series.label.clone(), legend_position) });
+    }
+  }
+}
+
+fn line(from: Point, to: Point) -> Path {
+  Path::new(|builder| {
+    builder.move_to(from);
+    builder.line_to(to);
+  })
+}
+
+impl canvas::Program<Message> for Chart {
+  fn draw(&self, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+    let geometry = self.cache.draw(Size::new(bounds.width, bounds.height), |frame| self.draw_chart(frame));
+    vec![geometry]
+  }
+}
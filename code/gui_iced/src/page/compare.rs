@@ -0,0 +1,140 @@
+use std::ops::Deref;
+
+use iced::{Align, button, Element, Length, scrollable};
+
+use secalc_core::data::Data;
+use secalc_core::grid::compare::{compare, Comparison, fleet_metrics};
+use secalc_core::grid::{GridCalculator, ResultValue};
+
+use crate::storage::Storage;
+use crate::i18n::tr;
+use crate::view::{button, col, danger_color, h1, h2, h3, lbl, row, scl, val};
+
+#[derive(Debug)]
+pub struct Page {
+  calculators: Vec<(String, GridCalculator, button::State, button::State)>,
+  left: Option<String>,
+  right: Option<String>,
+  comparisons: Vec<Comparison>,
+  fleet: Vec<(String, Vec<ResultValue>)>,
+  back_button_state: button::State,
+  scrollable_state: scrollable::State,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+  SetLeft(String),
+  SetRight(String),
+  Back,
+}
+
+#[derive(Debug)]
+pub enum Action {
+  Back,
+}
+
+impl Page {
+  pub fn new(storage: &Storage, data: &Data) -> Self {
+    let calculators: Vec<_> = storage.iter_saved_calculators()
+      .map(|(name, calculator)| (name.clone(), calculator.clone(), Default::default(), Default::default()))
+      .collect();
+    let fleet = calculators.iter()
+      .map(|(name, calculator, ..)| (name.clone(), fleet_metrics(calculator, data)))
+      .collect();
+    Self {
+      calculators,
+      left: None,
+      right: None,
+      comparisons: Vec::new(),
+      fleet,
+      back_button_state: Default::default(),
+      scrollable_state: Default::default(),
+    }
+  }
+
+  pub fn update(&mut self, message: Message, data: &Data) -> Option<Action> {
+    match message {
+      Message::SetLeft(name) => self.left = Some(name),
+      Message::SetRight(name) => self.right = Some(name),
+      Message::Back => return Some(Action::Back),
+    }
+    let calculator = |name: &Option<String>| name.as_ref()
+      .and_then(|name| self.calculators.iter().find(|(n, ..)| n == name))
+      .map(|(_, calculator, ..)| calculator.calculate(data));
+    self.comparisons = match (calculator(&self.left), calculator(&self.right)) {
+      (Some(left), Some(right)) => compare(&left, &right),
+      _ => Vec::new(),
+    };
+    None
+  }
+
+  pub fn view(&mut self) -> Element<Message> {
+    let mut calculators = col().spacing(2);
+    for (name, _, left_state, right_state) in &mut self.calculators {
+      let mut left = button(left_state, tr("Left"));
+      if self.left.as_ref() != Some(name) { left = left.on_press(Message::SetLeft(name.clone())); }
+      let mut right = button(right_state, tr("Right"));
+      if self.right.as_ref() != Some(name) { right = right.on_press(Message::SetRight(name.clone())); }
+      calculators = calculators.push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(h3(name.deref()).width(Length::Units(300)))
+        .push(left)
+        .push(right)
+      );
+    }
+
+    let mut labels = col().spacing(1).push(lbl(tr("Result")));
+    let mut lefts = col().spacing(1).push(lbl(self.left.as_deref().unwrap_or("-")));
+    let mut rights = col().spacing(1).push(lbl(self.right.as_deref().unwrap_or("-")));
+    let mut deltas = col().spacing(1).push(lbl(tr("Difference")));
+    for comparison in &self.comparisons {
+      labels = labels.push(lbl(format!("{} ({})", tr(&comparison.label), comparison.unit)));
+      lefts = lefts.push(val(comparison.quantity.format(comparison.left)));
+      rights = rights.push(val(comparison.quantity.format(comparison.right)));
+      let delta = val(comparison.quantity.format_signed(comparison.delta()));
+      deltas = deltas.push(if comparison.is_changed() { delta.color(danger_color()) } else { delta });
+    }
+
+    let mut fleet = row().spacing(10);
+    if let Some((_, first)) = self.fleet.first() {
+      let mut labels = col().spacing(1).push(lbl(tr("Metric")));
+      for value in first {
+        labels = labels.push(lbl(format!("{} ({})", tr(&value.label), value.unit)));
+      }
+      fleet = fleet.push(labels);
+    }
+    for (name, metrics) in &self.fleet {
+      let mut values = col().spacing(1).push(lbl(name.deref()));
+      for value in metrics {
+        values = values.push(val(if value.value.is_finite() { value.format() } else { "-".to_owned() }));
+      }
+      fleet = fleet.push(values);
+    }
+
+    col()
+      .padding(10)
+      .spacing(10)
+      .push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(h1(tr("Compare")))
+        .push(button(&mut self.back_button_state, tr("Back")).on_press(Message::Back))
+      )
+      .push(h2(tr("Saved calculations")))
+      .push(calculators)
+      .push(h2(tr("Results")))
+      .push(scl(&mut self.scrollable_state)
+        .push(row()
+          .spacing(10)
+          .push(labels)
+          .push(lefts)
+          .push(rights)
+          .push(deltas)
+        )
+        .push(h2(tr("Fleet")))
+        .push(fleet)
+      )
+      .into()
+  }
+}
@@ -0,0 +1,72 @@
+use iced::{Align, button, Element, scrollable};
+
+use secalc_core::data::Data;
+use secalc_core::grid::GridCalculator;
+use secalc_core::grid::planet::{EnvironmentValues, PLANET_PRESETS, values_per_environment};
+
+use crate::i18n::tr;
+use crate::view::{button, col, h1, lbl, row, scl, val};
+
+#[derive(Debug)]
+pub struct Page {
+  environment_names: Vec<&'static str>,
+  values: Vec<EnvironmentValues>,
+  back_button_state: button::State,
+  scrollable_state: scrollable::State,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+  Back,
+}
+
+#[derive(Debug)]
+pub enum Action {
+  Back,
+}
+
+impl Page {
+  /// Creates a page showing the results of `calculator` with a column per planet preset.
+  pub fn new(calculator: &GridCalculator, data: &Data) -> Self {
+    let results = calculator.calculate_in_environments(data, &PLANET_PRESETS);
+    Self {
+      environment_names: results.iter().map(|r| r.environment.name).collect(),
+      values: values_per_environment(&results),
+      back_button_state: Default::default(),
+      scrollable_state: Default::default(),
+    }
+  }
+
+  pub fn update(&mut self, message: Message) -> Option<Action> {
+    match message {
+      Message::Back => Some(Action::Back),
+    }
+  }
+
+  pub fn view(&mut self) -> Element<Message> {
+    let mut labels = col().spacing(1).push(lbl(tr("Result")));
+    for values in &self.values {
+      labels = labels.push(lbl(format!("{} ({})", tr(&values.label), values.unit)));
+    }
+    let mut table = row().spacing(10).push(labels);
+    for (index, name) in self.environment_names.iter().enumerate() {
+      let mut column = col().spacing(1).push(lbl(tr(name)));
+      for values in &self.values {
+        column = column.push(val(values.quantity.format(values.values[index])));
+      }
+      table = table.push(column);
+    }
+
+    col()
+      .padding(10)
+      .spacing(10)
+      .push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(h1(tr("Environments")))
+        .push(button(&mut self.back_button_state, tr("Back")).on_press(Message::Back))
+      )
+      .push(scl(&mut self.scrollable_state).push(table))
+      .into()
+  }
+}
@@ -0,0 +1,66 @@
+use iced::{button, Element, Length};
+
+use secalc_core::data::blocks::{BlockId, BlockStat};
+use secalc_core::data::Data;
+
+use crate::i18n::{tr, tr_format};
+use crate::view::{button, col, h2, h3, lbl, row, val};
+
+/// Details of a single block, shown in a side pane so that the calculator doubles as a block reference.
+pub struct BlockDetails {
+  name: String,
+  stats: Vec<BlockStat>,
+  /// Thruster effectiveness (%) at planetary influences (%), or empty if the block is not a thruster.
+  effectiveness: Vec<(f64, f64)>,
+  close_button_state: button::State,
+}
+
+#[derive(Clone, Debug)]
+pub enum BlockDetailsMessage {
+  Close,
+}
+
+impl BlockDetails {
+  /// Creates the details of the block with `id`, or returns `None` if there is no such block.
+  pub fn new(id: &BlockId, data: &Data) -> Option<Self> {
+    let name = data.blocks.localized_name(id, &data.localization)?.to_owned();
+    let stats = data.blocks.stats(id, &data.components, &data.gas_properties)?;
+    let effectiveness = data.blocks.thrusters.get(id)
+      .map(|thruster| (0..=4).map(|i| {
+        let planetary_influence = i as f64 / 4.0;
+        (planetary_influence * 100.0, thruster.details.effectiveness(planetary_influence) * 100.0)
+      }).collect())
+      .unwrap_or_default();
+    Some(Self { name, stats, effectiveness, close_button_state: Default::default() })
+  }
+
+  pub fn view(&mut self) -> Element<BlockDetailsMessage> {
+    let mut labels = col().spacing(1);
+    let mut values = col().spacing(1);
+    for stat in &self.stats {
+      labels = labels.push(lbl(tr(stat.label)));
+      values = values.push(val(stat.format()));
+    }
+    let mut column = col()
+      .spacing(10)
+      .width(Length::Units(350))
+      .push(row()
+        .spacing(10)
+        .push(h2(&self.name).width(Length::Fill))
+        .push(button(&mut self.close_button_state, tr("Close")).on_press(BlockDetailsMessage::Close))
+      )
+      .push(row().spacing(10).push(labels).push(values));
+    if !self.effectiveness.is_empty() {
+      let mut labels = col().spacing(1);
+      let mut values = col().spacing(1);
+      for (planetary_influence, effectiveness) in &self.effectiveness {
+        labels = labels.push(lbl(tr_format("{}% planetary influence", &[&format!("{:.0}", planetary_influence)])));
+        values = values.push(val(format!("{:.0}%", effectiveness)));
+      }
+      column = column
+        .push(h3(tr("Thrust effectiveness")))
+        .push(row().spacing(10).push(labels).push(values));
+    }
+    column.into()
+  }
+}
@@ -1,26 +1,42 @@
-use std::ops::Deref;
+use std::collections::HashSet;
+use std::ops::{Deref, Range};
 
-use iced::{Align, Element, Length};
+use iced::{Align, button, Checkbox, Element, Length};
 use linked_hash_map::LinkedHashMap;
 
 use secalc_core::data::blocks::{Block, BlockId, Blocks, GridSize};
 use secalc_core::data::Data;
 use secalc_core::grid::GridCalculator;
 
-use crate::data_bind::{DataBind, DataBindMessage};
-use crate::view::{col, h3, lbl, row};
+use crate::stepper::{Stepper, StepperMessage};
+use crate::i18n::tr;
+use crate::view::{col, h3, label_button, row};
 
-type Map = LinkedHashMap<BlockId, (String, DataBind<u64>)>;
+use super::{BlockFilter, matches_filter};
+use super::pager::{Pager, PagerMessage};
+
+type Map = LinkedHashMap<BlockId, (String, button::State, Stepper)>;
 
 pub struct BlockInput {
   small: Map,
   large: Map,
   label_width: Length,
   input_width: Length,
+  pager: Pager,
+  filter: BlockFilter,
+  /// Blocks with a toggle for stockpile mode.
+  stockpile_ids: HashSet<BlockId>,
+  /// Blocks currently in stockpile mode.
+  stockpiled: HashSet<BlockId>,
 }
 
 #[derive(Clone, Debug)]
-pub struct BlockInputMessage(BlockId, GridSize, DataBindMessage);
+pub enum BlockInputMessage {
+  Count(BlockId, GridSize, StepperMessage),
+  Stockpile(BlockId, bool),
+  Details(BlockId),
+  Pager(PagerMessage),
+}
 
 impl BlockInput {
   pub fn new(label_width: Length, input_width: Length) -> Self {
@@ -29,6 +45,10 @@ impl BlockInput {
       large: Map::default(),
       label_width,
       input_width,
+      pager: Pager::default(),
+      filter: None,
+      stockpile_ids: HashSet::default(),
+      stockpiled: HashSet::default(),
     }
   }
 
@@ -40,8 +60,8 @@ impl BlockInput {
           let default_count = default_calculator.blocks.get(&block.id).map_or(0, |c| *c);
           let loaded_count = loaded_calculator.blocks.get(&block.id).map_or(0, |c| *c);
           let label = block.name(&data.localization).to_owned();
-          let data_bind = DataBind::new(default_count, format!("{}", default_count), input_width, "#", format!("{}", loaded_count));
-          (id, (label, data_bind))
+          let stepper = Stepper::new(default_count, input_width, format!("{}", loaded_count));
+          (id, (label, button::State::default(), stepper))
         })
       );
     }
@@ -50,48 +70,104 @@ impl BlockInput {
     add_to_map(data, default_calculator, loaded_calculator, self.input_width, large, &mut self.large);
   }
 
+  /// Adds a toggle for stockpile mode to already added hydrogen tanks in `blocks_iter`.
+  pub fn add_stockpile_toggles<'a, T: 'a, I: Iterator<Item=&'a Block<T>>>(&mut self, loaded_calculator: &GridCalculator, blocks_iter: I) {
+    self.stockpile_ids.extend(blocks_iter.map(|block| block.id.clone()));
+    self.stockpiled = loaded_calculator.stockpile_hydrogen_tanks.clone();
+  }
+
   pub fn update(&mut self, message: BlockInputMessage, calc: &mut GridCalculator) {
-    let BlockInputMessage(id, size, m) = message;
-    if let Some((_, data_bind)) = self.map_for_size(size).get_mut(&id) {
-      data_bind.update(m, calc.blocks.entry(id.clone()).or_default())
+    match message {
+      BlockInputMessage::Count(id, size, m) => if let Some((_, _, stepper)) = self.map_for_size(size).get_mut(&id) {
+        stepper.update(m, calc.blocks.entry(id.clone()).or_default())
+      },
+      BlockInputMessage::Stockpile(id, stockpile) => {
+        if stockpile {
+          calc.stockpile_hydrogen_tanks.insert(id);
+        } else {
+          calc.stockpile_hydrogen_tanks.remove(&id);
+        }
+        self.stockpiled = calc.stockpile_hydrogen_tanks.clone();
+      }
+      BlockInputMessage::Details(_) => {}
+      BlockInputMessage::Pager(m) => {
+        let row_count = self.row_count();
+        self.pager.update(m, row_count);
+      }
+    }
+  }
+
+  /// Steps the count of the focused block by `steps`, returning true if a block was focused.
+  pub fn step_focused(&mut self, steps: i64, calc: &mut GridCalculator) -> bool {
+    let focused = self.small.iter_mut().chain(self.large.iter_mut()).find(|(_, (_, _, stepper))| stepper.is_focused());
+    if let Some((id, (_, _, stepper))) = focused {
+      stepper.update(StepperMessage::Step(steps), calc.blocks.entry(id.clone()).or_default());
+      true
+    } else {
+      false
     }
   }
 
   pub fn reload(&mut self, calc: &GridCalculator) {
-    for (id, (_, data_bind)) in self.small.iter_mut().chain(self.large.iter_mut()) {
+    for (id, (_, _, stepper)) in self.small.iter_mut().chain(self.large.iter_mut()) {
       let count = calc.blocks.get(id).map_or(0, |c| *c);
-      data_bind.reload(format!("{}", count));
+      stepper.reload(format!("{}", count));
     }
+    self.stockpiled = calc.stockpile_hydrogen_tanks.clone();
+  }
+
+  /// Only shows blocks in `filter`, starting at the first page.
+  pub fn set_filter(&mut self, filter: &BlockFilter) {
+    self.filter = filter.clone();
+    self.pager = Pager::default();
   }
 
   pub fn view(&mut self) -> Element<BlockInputMessage> {
-    fn create_column(map: &mut Map, label_width: Length, grid_size: GridSize) -> Element<BlockInputMessage> {
+    fn create_column<'a>(map: &'a mut Map, filter: &BlockFilter, stockpile_ids: &HashSet<BlockId>, stockpiled: &HashSet<BlockId>, rows: Range<usize>, label_width: Length, grid_size: GridSize) -> Element<'a, BlockInputMessage> {
       let mut column = col();
-      for (id, (label, data_bind)) in map {
+      let filtered = map.iter_mut().filter(|(id, _)| matches_filter(id, filter));
+      for (id, (label, label_state, stepper)) in filtered.skip(rows.start).take(rows.len()) {
         let id = id.clone(); // Clone before closure so that we are not passing references into 'static closure.
-        column = column.push(row().align_items(Align::Center)
-          .push(lbl(label.deref()).width(label_width))
-          .push(data_bind.view().map(move |m| BlockInputMessage(
+        let mut block_row = row().align_items(Align::Center)
+          .push(label_button(label_state, label.deref()).width(label_width).on_press(BlockInputMessage::Details(id.clone())));
+        if stockpile_ids.contains(&id) {
+          let stockpile_id = id.clone();
+          block_row = block_row.push(stepper.view().map(move |m| BlockInputMessage::Count(id.clone(), grid_size, m)))
+            .push(Checkbox::new(stockpiled.contains(&stockpile_id), &tr("Stockpile"), move |v| BlockInputMessage::Stockpile(stockpile_id.clone(), v)));
+        } else {
+          block_row = block_row.push(stepper.view().map(move |m| BlockInputMessage::Count(
             // Clone again because this is a Fn closure that is callable multiple times: each call needs a separate clone and String does not implement Copy.
             id.clone(),
             grid_size,
             m
-          )))
-        )
+          )));
+        }
+        column = column.push(block_row)
       }
       col()
-        .push(h3(match grid_size { GridSize::Small => "Small grid", GridSize::Large => "Large grid" }))
+        .push(h3(tr(match grid_size { GridSize::Small => "Small grid", GridSize::Large => "Large grid" })))
         .push(column)
         .into()
     }
-    let input_small = create_column(&mut self.small, self.label_width, GridSize::Small);
-    let input_large = create_column(&mut self.large, self.label_width, GridSize::Large);
-    row()
+    let row_count = self.row_count();
+    let rows = self.pager.range(row_count);
+    let input_small = create_column(&mut self.small, &self.filter, &self.stockpile_ids, &self.stockpiled, rows.clone(), self.label_width, GridSize::Small);
+    let input_large = create_column(&mut self.large, &self.filter, &self.stockpile_ids, &self.stockpiled, rows, self.label_width, GridSize::Large);
+    let inputs = row()
       .spacing(10)
       .padding(0)
       .push(input_small)
-      .push(input_large)
-      .into()
+      .push(input_large);
+    let mut column = col().push(inputs);
+    if let Some(pager) = self.pager.view(row_count) {
+      column = column.push(pager.map(BlockInputMessage::Pager));
+    }
+    column.into()
+  }
+
+  fn row_count(&self) -> usize {
+    let count = |map: &Map| map.keys().filter(|id| matches_filter(id, &self.filter)).count();
+    count(&self.small).max(count(&self.large))
   }
 
   fn map_for_size(&mut self, size: GridSize) -> &mut Map {
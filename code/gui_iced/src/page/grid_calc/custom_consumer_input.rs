@@ -0,0 +1,136 @@
+use iced::{Align, button, Element, Length, text_input};
+
+use secalc_core::grid::{CustomConsumer, GridCalculator};
+use secalc_core::units::{LitresPerSecond, MegaWatts, Unit};
+
+use crate::i18n::tr;
+use crate::view::{button, col, danger_color, foreground_color, lbl, row, small_button, text_input};
+
+/// List of named power and hydrogen consumers that are not blocks of the game data, with inputs for adding a consumer.
+#[derive(Default)]
+pub struct CustomConsumerInput {
+  power_consumers: Vec<CustomConsumer<MegaWatts>>,
+  hydrogen_consumers: Vec<CustomConsumer<LitresPerSecond>>,
+  remove_power_button_states: Vec<button::State>,
+  remove_hydrogen_button_states: Vec<button::State>,
+  name: String,
+  name_state: text_input::State,
+  consumption: String,
+  consumption_error: bool,
+  consumption_state: text_input::State,
+  hydrogen: bool,
+  unit_button_state: button::State,
+  add_button_state: button::State,
+}
+
+#[derive(Clone, Debug)]
+pub enum CustomConsumerInputMessage {
+  SetName(String),
+  SetConsumption(String),
+  ToggleUnit,
+  Add,
+  RemovePower(usize),
+  RemoveHydrogen(usize),
+}
+
+impl CustomConsumerInput {
+  pub fn new(calc: &GridCalculator) -> Self {
+    let mut input = Self::default();
+    input.reload(calc);
+    input
+  }
+
+  /// Updates the input, returning true if a consumer was added to or removed from `calc`.
+  pub fn update(&mut self, message: CustomConsumerInputMessage, calc: &mut GridCalculator) -> bool {
+    match message {
+      CustomConsumerInputMessage::SetName(name) => {
+        self.name = name;
+        false
+      }
+      CustomConsumerInputMessage::SetConsumption(consumption) => {
+        self.consumption = consumption;
+        self.consumption_error = false;
+        false
+      }
+      CustomConsumerInputMessage::ToggleUnit => {
+        self.hydrogen = !self.hydrogen;
+        false
+      }
+      CustomConsumerInputMessage::Add => {
+        let name = self.name.trim();
+        if name.is_empty() { return false; }
+        let mut updated = calc.clone();
+        let hydrogen = self.hydrogen;
+        let added = self.consumption.trim().parse().ok().and_then(|consumption| {
+          let name = name.to_owned();
+          if hydrogen {
+            updated.custom_hydrogen_consumers.push(CustomConsumer { name, consumption: LitresPerSecond(consumption) });
+          } else {
+            updated.custom_power_consumers.push(CustomConsumer { name, consumption: MegaWatts(consumption) });
+          }
+          updated.validate().ok()
+        });
+        if added.is_none() {
+          self.consumption_error = true;
+          return false;
+        }
+        *calc = updated;
+        self.name.clear();
+        self.consumption.clear();
+        self.reload(calc);
+        true
+      }
+      CustomConsumerInputMessage::RemovePower(index) => {
+        if index >= calc.custom_power_consumers.len() { return false; }
+        calc.custom_power_consumers.remove(index);
+        self.reload(calc);
+        true
+      }
+      CustomConsumerInputMessage::RemoveHydrogen(index) => {
+        if index >= calc.custom_hydrogen_consumers.len() { return false; }
+        calc.custom_hydrogen_consumers.remove(index);
+        self.reload(calc);
+        true
+      }
+    }
+  }
+
+  pub fn reload(&mut self, calc: &GridCalculator) {
+    self.power_consumers = calc.custom_power_consumers.clone();
+    self.hydrogen_consumers = calc.custom_hydrogen_consumers.clone();
+    self.remove_power_button_states.resize_with(self.power_consumers.len(), Default::default);
+    self.remove_hydrogen_button_states.resize_with(self.hydrogen_consumers.len(), Default::default);
+  }
+
+  pub fn view(&mut self, label_width: Length) -> Element<CustomConsumerInputMessage> {
+    fn consumer_row<'a>(label_width: Length, name: &str, consumption: String, remove_button_state: &'a mut button::State, message: CustomConsumerInputMessage) -> Element<'a, CustomConsumerInputMessage> {
+      row()
+        .spacing(2)
+        .align_items(Align::Center)
+        .push(lbl(name).width(label_width))
+        .push(lbl(consumption))
+        .push(small_button(remove_button_state, "x").on_press(message))
+        .into()
+    }
+
+    let mut column = col()
+      .push(lbl(tr("Custom Consumers")).color(if self.consumption_error { danger_color() } else { foreground_color() }));
+    for (index, (consumer, remove_button_state)) in self.power_consumers.iter().zip(self.remove_power_button_states.iter_mut()).enumerate() {
+      column = column.push(consumer_row(label_width, &consumer.name, format!("{} MW", consumer.consumption.format()), remove_button_state, CustomConsumerInputMessage::RemovePower(index)));
+    }
+    for (index, (consumer, remove_button_state)) in self.hydrogen_consumers.iter().zip(self.remove_hydrogen_button_states.iter_mut()).enumerate() {
+      column = column.push(consumer_row(label_width, &consumer.name, format!("{} L/s", consumer.consumption.format()), remove_button_state, CustomConsumerInputMessage::RemoveHydrogen(index)));
+    }
+    let unit = if self.hydrogen { "L/s" } else { "MW" };
+    column
+      .push(row()
+        .spacing(2)
+        .align_items(Align::Center)
+        .push(text_input(label_width, &mut self.name_state, &tr("Name"), &self.name, CustomConsumerInputMessage::SetName))
+        .push(text_input(Length::Units(95), &mut self.consumption_state, "0", &self.consumption, CustomConsumerInputMessage::SetConsumption))
+        .push(small_button(&mut self.unit_button_state, unit).on_press(CustomConsumerInputMessage::ToggleUnit))
+        .push(button(&mut self.add_button_state, tr("Add")).on_press(CustomConsumerInputMessage::Add))
+      )
+      .into()
+  }
+}
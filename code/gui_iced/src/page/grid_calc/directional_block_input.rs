@@ -1,17 +1,21 @@
-use std::ops::Deref;
+use std::ops::{Deref, Range};
 
-use iced::{Align, Element, Length};
+use iced::{Align, button, Element, Length};
 use linked_hash_map::LinkedHashMap;
 
 use secalc_core::data::blocks::{Block, BlockId, Blocks, GridSize};
 use secalc_core::data::Data;
 use secalc_core::grid::{Direction, GridCalculator};
 
-use crate::data_bind::{DataBind, DataBindMessage};
-use crate::view::{col, empty, h3, lbl, row};
+use crate::stepper::{Stepper, StepperMessage};
+use crate::i18n::tr;
+use crate::view::{col, empty, h3, label_button, lbl, row};
 
-type InnerMap = LinkedHashMap<Direction, DataBind<u64>>;
-type Map = LinkedHashMap<BlockId, (String, InnerMap)>;
+use super::{BlockFilter, matches_filter};
+use super::pager::{Pager, PagerMessage};
+
+type InnerMap = LinkedHashMap<Direction, Stepper>;
+type Map = LinkedHashMap<BlockId, (String, button::State, InnerMap)>;
 
 pub struct DirectionalBlockInput {
   small: Map,
@@ -19,10 +23,16 @@ pub struct DirectionalBlockInput {
   label_width: Length,
   input_width: Length,
   direction_label_width: Length,
+  pager: Pager,
+  filter: BlockFilter,
 }
 
 #[derive(Clone, Debug)]
-pub struct DirectionalBlockInputMessage(GridSize, BlockId, Direction, DataBindMessage);
+pub enum DirectionalBlockInputMessage {
+  Count(GridSize, BlockId, Direction, StepperMessage),
+  Details(BlockId),
+  Pager(PagerMessage),
+}
 
 impl DirectionalBlockInput {
   pub fn new(label_width: Length, input_width: Length, direction_label_width: Length) -> Self {
@@ -32,6 +42,8 @@ impl DirectionalBlockInput {
       label_width,
       input_width,
       direction_label_width,
+      pager: Pager::default(),
+      filter: None,
     }
   }
 
@@ -40,12 +52,12 @@ impl DirectionalBlockInput {
       for block in vec {
         let id = block.id.clone();
         let label = block.name(&data.localization).to_owned();
-        let (_, inner_map) = map.entry(id).or_insert((label, InnerMap::default()));
+        let (_, _, inner_map) = map.entry(id).or_insert((label, button::State::default(), InnerMap::default()));
         for direction in Direction::iter() {
-          let default_count = default_calculator.directional_blocks.get(direction).map_or(0, |map| map.get(&block.id).map_or(0, |c| *c));
-          let loaded_count = loaded_calculator.directional_blocks.get(direction).map_or(0, |map| map.get(&block.id).map_or(0, |c| *c));
-          let data_bind = DataBind::new(default_count, format!("{}", default_count), input_width, "#", format!("{}", loaded_count));
-          inner_map.insert(*direction, data_bind);
+          let default_count = default_calculator.direction_count(*direction, &block.id);
+          let loaded_count = loaded_calculator.direction_count(*direction, &block.id);
+          let stepper = Stepper::new(default_count, input_width, format!("{}", loaded_count));
+          inner_map.insert(*direction, stepper);
         }
       }
     }
@@ -55,37 +67,68 @@ impl DirectionalBlockInput {
   }
 
   pub fn update(&mut self, message: DirectionalBlockInputMessage, calc: &mut GridCalculator) {
-    let DirectionalBlockInputMessage(size, id, direction, m) = message;
-    if let Some((_, inner_map)) = self.map_for_size(size).get_mut(&id) {
-      if let Some(data_bind) = inner_map.get_mut(&direction) {
-        data_bind.update(m, calc.directional_blocks.entry(direction).or_default().entry(id.clone()).or_default())
+    match message {
+      DirectionalBlockInputMessage::Count(size, id, direction, m) => if let Some((_, _, inner_map)) = self.map_for_size(size).get_mut(&id) {
+        if let Some(stepper) = inner_map.get_mut(&direction) {
+          stepper.update(m, calc.direction_mut(direction).entry(id.clone()).or_default())
+        }
+      },
+      DirectionalBlockInputMessage::Details(_) => {}
+      DirectionalBlockInputMessage::Pager(m) => {
+        let row_count = self.row_count();
+        self.pager.update(m, row_count);
+      }
+    }
+  }
+
+  /// Steps the count of the focused block and direction by `steps`, returning true if one was focused.
+  pub fn step_focused(&mut self, steps: i64, calc: &mut GridCalculator) -> bool {
+    for (id, (_, _, inner_map)) in self.small.iter_mut().chain(self.large.iter_mut()) {
+      for (direction, stepper) in inner_map.iter_mut().filter(|(_, stepper)| stepper.is_focused()) {
+        stepper.update(StepperMessage::Step(steps), calc.direction_mut(*direction).entry(id.clone()).or_default());
+        return true;
       }
     }
+    false
   }
 
   pub fn reload(&mut self, calc: &GridCalculator) {
-    for (id, (_, inner_map)) in self.small.iter_mut().chain(self.large.iter_mut()) {
-      for (direction, data_bind) in inner_map {
-        if let Some(inner_calc_map) = calc.directional_blocks.get(direction) {
-          let count = inner_calc_map.get(id).map_or(0, |c| *c);
-          data_bind.reload(format!("{}", count));
-        }
+    for (id, (_, _, inner_map)) in self.small.iter_mut().chain(self.large.iter_mut()) {
+      for (direction, stepper) in inner_map {
+        stepper.reload(format!("{}", calc.direction_count(*direction, id)));
       }
     }
   }
 
+  /// Only shows blocks in `filter`, starting at the first page.
+  pub fn set_filter(&mut self, filter: &BlockFilter) {
+    self.filter = filter.clone();
+    self.pager = Pager::default();
+  }
+
   pub fn view(&mut self) -> Element<DirectionalBlockInputMessage> {
-    let input_small = Self::create_column(&mut self.small, self.label_width, self.direction_label_width, GridSize::Small);
-    let input_large = Self::create_column(&mut self.large, self.label_width, self.direction_label_width, GridSize::Large);
-    row()
+    let row_count = self.row_count();
+    let rows = self.pager.range(row_count);
+    let input_small = Self::create_column(&mut self.small, &self.filter, rows.clone(), self.label_width, self.direction_label_width, GridSize::Small);
+    let input_large = Self::create_column(&mut self.large, &self.filter, rows, self.label_width, self.direction_label_width, GridSize::Large);
+    let inputs = row()
       .spacing(10)
       .padding(0)
       .push(input_small)
-      .push(input_large)
-      .into()
+      .push(input_large);
+    let mut column = col().push(inputs);
+    if let Some(pager) = self.pager.view(row_count) {
+      column = column.push(pager.map(DirectionalBlockInputMessage::Pager));
+    }
+    column.into()
+  }
+
+  fn row_count(&self) -> usize {
+    let count = |map: &Map| map.keys().filter(|id| matches_filter(id, &self.filter)).count();
+    count(&self.small).max(count(&self.large))
   }
 
-  fn create_column(map: &mut Map, label_width: Length, direction_label_width: Length, grid_size: GridSize) -> Element<DirectionalBlockInputMessage> {
+  fn create_column<'a>(map: &'a mut Map, filter: &BlockFilter, rows: Range<usize>, label_width: Length, direction_label_width: Length, grid_size: GridSize) -> Element<'a, DirectionalBlockInputMessage> {
     let mut column = {
       let mut first_row = row()
         .spacing(2)
@@ -94,19 +137,20 @@ impl DirectionalBlockInput {
         .push(empty().width(label_width))
         ;
       for direction in Direction::iter() {
-        first_row = first_row.push(lbl(format!("{:?}", direction)).width(direction_label_width))
+        first_row = first_row.push(lbl(tr(&format!("{:?}", direction))).width(direction_label_width))
       }
       col().push(first_row)
     };
 
-    for (id, (label, inner_map)) in map.iter_mut() {
+    let filtered = map.iter_mut().filter(|(id, _)| matches_filter(id, filter));
+    for (id, (label, label_state, inner_map)) in filtered.skip(rows.start).take(rows.len()) {
       let mut row = row().spacing(2).align_items(Align::Center);
-      row = row.push(lbl(label.deref()).width(label_width));
-      for (direction, data_bind) in inner_map {
+      row = row.push(label_button(label_state, label.deref()).width(label_width).on_press(DirectionalBlockInputMessage::Details(id.clone())));
+      for (direction, stepper) in inner_map {
         // Clone and copy before closure so that we are not passing references into 'static closure.
         let id = id.clone();
         let direction = *direction;
-        row = row.push(data_bind.view().map(move |m| DirectionalBlockInputMessage(
+        row = row.push(stepper.view().map(move |m| DirectionalBlockInputMessage::Count(
           grid_size,
           // Clone again because this is a Fn closure that is callable multiple times: each call needs a separate clone and String does not implement Copy.
           id.clone(),
@@ -117,7 +161,7 @@ impl DirectionalBlockInput {
       column = column.push(row)
     }
     col()
-      .push(h3(match grid_size { GridSize::Small => "Small grid", GridSize::Large => "Large grid" }))
+      .push(h3(tr(match grid_size { GridSize::Small => "Small grid", GridSize::Large => "Large grid" })))
       .push(column)
       .into()
   }
@@ -1,67 +1,185 @@
-use iced::{Align, button, Element, HorizontalAlignment, Length, scrollable};
+use std::collections::HashSet;
 
+use iced::{Align, button, Checkbox, Element, HorizontalAlignment, Length, scrollable, text_input};
+
+use secalc_core::data::blocks::BlockId;
 use secalc_core::data::Data;
-use secalc_core::grid::{Direction, GridCalculated, GridCalculator};
+use secalc_core::format::UnitPreferences;
+use secalc_core::grid::{Direction, GridCalculated, GridCalculator, PowerConsumer};
+use secalc_core::grid::calculator::IncrementalCalculator;
+use secalc_core::grid::recommendation::{self, Recommendation};
+use secalc_core::grid::warning::{self, Warning};
+use secalc_core::precision::Quantity;
+use secalc_core::units::Unit;
 
-use crate::view::{button, col, empty, h1, h2, h3, lbl, row, scl, val};
+use crate::theme::Theme;
+use crate::i18n::{tr, tr_format};
+use crate::view::{button, col, danger_color, empty, foreground_color, h1, h2, h3, lbl, row, scl, text_input, val};
 
+use self::block_details::{BlockDetails, BlockDetailsMessage};
 use self::block_input::{BlockInput, BlockInputMessage};
+use self::custom_consumer_input::{CustomConsumerInput, CustomConsumerInputMessage};
 use self::directional_block_input::{DirectionalBlockInput, DirectionalBlockInputMessage};
 use self::option_input::{OptionInput, OptionInputMessage};
+use self::planet_picker::{PlanetPicker, PlanetPickerMessage};
+use self::trip_planner::{TripPlanner, TripPlannerMessage};
 
+pub mod block_details;
+pub mod custom_consumer_input;
 pub mod option_input;
 pub mod block_input;
 pub mod directional_block_input;
+pub mod pager;
+pub mod planet_picker;
+pub mod trip_planner;
+
+/// Blocks matching the search, or `None` when not searching.
+type BlockFilter = Option<HashSet<BlockId>>;
+
+/// Returns true if there is no `filter` or block `id` is in it.
+fn matches_filter(id: &BlockId, filter: &BlockFilter) -> bool {
+  filter.as_ref().map_or(true, |ids| ids.contains(id))
+}
 
 pub struct Page {
   input: Input,
   result: Result,
+  /// Caches intermediate results, as the calculator is recalculated on every input change.
+  incremental_calculator: IncrementalCalculator,
   result_mut: ResultMut,
+  details: Option<BlockDetails>,
+  start_button_state: button::State,
   save_button_state: button::State,
   save_as_button_state: button::State,
   load_button_state: button::State,
+  presets_button_state: button::State,
+  templates_button_state: button::State,
+  undo_button_state: button::State,
+  redo_button_state: button::State,
+  charts_button_state: button::State,
+  theme_button_state: button::State,
+  compare_button_state: button::State,
+  breakdown_button_state: button::State,
+  environments_button_state: button::State,
+  settings_button_state: button::State,
+  tab_button_states: Vec<button::State>,
+  new_tab_button_state: button::State,
+  close_tab_button_state: button::State,
 }
 
 pub struct Input {
+  planet: PlanetPicker,
   options: OptionInput,
+  idle_hydrogen_thrusters: bool,
+  power_priority: String,
+  power_priority_valid: bool,
+  power_priority_state: text_input::State,
+  custom_consumers: CustomConsumerInput,
   storage: BlockInput,
   thrust: DirectionalBlockInput,
   power: BlockInput,
   hydrogen: BlockInput,
+  search: String,
+  search_state: text_input::State,
+  collapsed_panels: HashSet<String>,
+  panel_button_states: [button::State; 4],
   scrollable_state: scrollable::State,
 }
 
 pub struct Result {
   calculated: GridCalculated,
+  warnings: Vec<Warning>,
+  recommendations: Vec<Recommendation>,
+  units: UnitPreferences,
+  show_ship_tools: bool,
+}
+
+impl Result {
+  fn new(calculated: GridCalculated, calculator: &GridCalculator, data: &Data, units: UnitPreferences) -> Self {
+    let warnings = warning::check(&calculated, calculator, data);
+    let recommendations = recommendation::recommend(&calculated, calculator, data);
+    let show_ship_tools = !data.blocks.ship_tools.is_empty();
+    Self { calculated, warnings, recommendations, units, show_ship_tools }
+  }
+
+  fn set_calculated(&mut self, calculated: GridCalculated, calculator: &GridCalculator, data: &Data) {
+    self.warnings = warning::check(&calculated, calculator, data);
+    self.recommendations = recommendation::recommend(&calculated, calculator, data);
+    self.calculated = calculated;
+  }
 }
 
 pub struct ResultMut {
+  trip: TripPlanner,
   scrollable_state: scrollable::State,
 }
 
 #[derive(Clone, Debug)]
 pub enum Message {
+  InputPlanetChange(PlanetPickerMessage),
   InputOptionChange(OptionInputMessage),
+  IdleHydrogenThrustersToggled(bool),
+  PowerPriorityChange(String),
+  InputCustomConsumerChange(CustomConsumerInputMessage),
   InputStorageChange(BlockInputMessage),
   InputThrustChange(DirectionalBlockInputMessage),
   InputPowerChange(BlockInputMessage),
   InputHydrogenChange(BlockInputMessage),
+  DetailsChange(BlockDetailsMessage),
+  TripChange(TripPlannerMessage),
+  SearchChange(String),
+  /// Mouse wheel scrolled by a number of lines, positive when scrolling up.
+  WheelScrolled(i64),
+  PanelToggled(&'static str),
+  StartPressed,
   SavePressed,
   SaveAsPressed,
   LoadPressed,
+  PresetsPressed,
+  TemplatesPressed,
+  UndoPressed,
+  RedoPressed,
+  ChartsPressed,
+  ThemeToggled,
+  ComparePressed,
+  BreakdownPressed,
+  EnvironmentsPressed,
+  SettingsPressed,
+  TabSelected(usize),
+  NewTabPressed,
+  CloseTabPressed,
 }
 
 pub enum Action {
   CalculatorModified,
+  Start,
   Save,
   SaveAs,
   Load,
+  Presets,
+  Templates,
+  Undo,
+  Redo,
+  Charts,
+  ToggleTheme,
+  Compare,
+  Breakdown,
+  Environments,
+  Settings,
+  SelectTab(usize),
+  NewTab,
+  CloseTab,
+  CollapsedPanelsChanged(HashSet<String>),
 }
 
 impl Page {
-  pub fn new(data: &Data, default_calculator: &GridCalculator, loaded_calculator: &GridCalculator) -> Self {
+  pub fn new(data: &Data, default_calculator: &GridCalculator, loaded_calculator: &GridCalculator, collapsed_panels: &HashSet<String>, units: UnitPreferences) -> Self {
     let input = {
-      let options = OptionInput::new(default_calculator, loaded_calculator);
+      let mut options = OptionInput::new(default_calculator, loaded_calculator);
+      // Data extracted before these block kinds were supported has none of them, so hide their options.
+      if data.blocks.renewables.is_empty() { options.hide("renewable_output"); }
+      if data.blocks.utilities.is_empty() { options.hide("utility_range"); }
+      if data.blocks.weapons.is_empty() { options.hide("ammo_fill"); }
       #[cfg(not(target_arch = "wasm32"))] let label_width = Length::Units(230);
       #[cfg(target_arch = "wasm32")] let label_width = Length::Units(180);
       #[cfg(not(target_arch = "wasm32"))] let input_width = Length::Units(35);
@@ -70,6 +188,7 @@ impl Page {
         let mut blocks = BlockInput::new(label_width, input_width);
         blocks.add_blocks(&data, default_calculator, loaded_calculator, data.blocks.containers.values().filter(|c| c.details.store_any));
         blocks.add_blocks(&data, default_calculator, loaded_calculator, data.blocks.cockpits.values().filter(|c| c.details.has_inventory));
+        blocks.add_blocks(&data, default_calculator, loaded_calculator, data.blocks.weapons.values());
         blocks
       };
       let thrust = {
@@ -83,42 +202,123 @@ impl Page {
         let mut blocks = BlockInput::new(label_width, input_width);
         blocks.add_blocks(&data, default_calculator, loaded_calculator, data.blocks.hydrogen_engines.values());
         blocks.add_blocks(&data, default_calculator, loaded_calculator, data.blocks.reactors.values());
+        blocks.add_blocks(&data, default_calculator, loaded_calculator, data.blocks.renewables.values());
         blocks.add_blocks(&data, default_calculator, loaded_calculator, data.blocks.batteries.values());
+        blocks.add_blocks(&data, default_calculator, loaded_calculator, data.blocks.ship_tools.values());
+        blocks.add_blocks(&data, default_calculator, loaded_calculator, data.blocks.utilities.values());
+        blocks.add_blocks(&data, default_calculator, loaded_calculator, data.blocks.accessories.values());
+        blocks.add_blocks(&data, default_calculator, loaded_calculator, data.blocks.automations.values());
+        blocks.add_blocks(&data, default_calculator, loaded_calculator, data.blocks.life_supports.values());
+        blocks.add_blocks(&data, default_calculator, loaded_calculator, data.blocks.gyroscopes.values());
         blocks
       };
       let hydrogen = {
         let mut blocks = BlockInput::new(label_width, input_width);
         blocks.add_blocks(&data, default_calculator, loaded_calculator, data.blocks.generators.values());
         blocks.add_blocks(&data, default_calculator, loaded_calculator, data.blocks.hydrogen_tanks.values());
+        blocks.add_stockpile_toggles(loaded_calculator, data.blocks.hydrogen_tanks.values());
         blocks
       };
       Input {
+        planet: PlanetPicker::new(loaded_calculator),
         options,
+        idle_hydrogen_thrusters: loaded_calculator.idle_hydrogen_thrusters,
+        power_priority: PowerConsumer::format_priority(&loaded_calculator.power_priority),
+        power_priority_valid: true,
+        power_priority_state: Default::default(),
+        custom_consumers: CustomConsumerInput::new(loaded_calculator),
         storage,
         thrust,
         power,
         hydrogen,
+        search: String::new(),
+        search_state: Default::default(),
+        collapsed_panels: collapsed_panels.clone(),
+        panel_button_states: Default::default(),
         scrollable_state: Default::default(),
       }
     };
-    let result = Result { calculated: loaded_calculator.calculate(&data) };
-    let result_mut = ResultMut { scrollable_state: Default::default() };
+    let incremental_calculator = IncrementalCalculator::new();
+    let result = Result::new(loaded_calculator.calculate_with(&incremental_calculator, &data), loaded_calculator, &data, units);
+    let result_mut = ResultMut { trip: TripPlanner::new(loaded_calculator), scrollable_state: Default::default() };
     Self {
       input,
       result,
+      incremental_calculator,
       result_mut,
+      details: None,
+      start_button_state: Default::default(),
       save_button_state: Default::default(),
       save_as_button_state: Default::default(),
       load_button_state: Default::default(),
+      presets_button_state: Default::default(),
+      templates_button_state: Default::default(),
+      undo_button_state: Default::default(),
+      redo_button_state: Default::default(),
+      charts_button_state: Default::default(),
+      theme_button_state: Default::default(),
+      compare_button_state: Default::default(),
+      breakdown_button_state: Default::default(),
+      environments_button_state: Default::default(),
+      settings_button_state: Default::default(),
+      tab_button_states: Vec::new(),
+      new_tab_button_state: Default::default(),
+      close_tab_button_state: Default::default(),
     }
   }
 
   pub fn update(&mut self, message: Message, calculator: &mut GridCalculator, data: &Data) -> Option<Action> {
     let action = match message {
+      Message::InputStorageChange(BlockInputMessage::Details(id)) |
+      Message::InputThrustChange(DirectionalBlockInputMessage::Details(id)) |
+      Message::InputPowerChange(BlockInputMessage::Details(id)) |
+      Message::InputHydrogenChange(BlockInputMessage::Details(id)) => {
+        self.details = BlockDetails::new(&id, data);
+        None
+      }
+      Message::DetailsChange(BlockDetailsMessage::Close) => {
+        self.details = None;
+        None
+      }
+      Message::TripChange(m) => {
+        self.result_mut.trip.update(m);
+        None
+      }
+      Message::InputPlanetChange(m) => if self.input.planet.update(m, calculator) {
+        self.input.options.reload(calculator);
+        Some(Action::CalculatorModified)
+      } else {
+        None
+      },
       Message::InputOptionChange(m) => {
         self.input.options.update(m, calculator);
+        self.input.planet.reload(calculator);
+        Some(Action::CalculatorModified)
+      }
+      Message::IdleHydrogenThrustersToggled(idle_hydrogen_thrusters) => {
+        self.input.idle_hydrogen_thrusters = idle_hydrogen_thrusters;
+        calculator.idle_hydrogen_thrusters = idle_hydrogen_thrusters;
         Some(Action::CalculatorModified)
       }
+      Message::PowerPriorityChange(power_priority) => {
+        // Only update the calculator with a valid priority, keeping the text so that users can finish typing.
+        let updated = PowerConsumer::parse_priority(&power_priority).ok()
+          .map(|priority| GridCalculator { power_priority: priority, ..calculator.clone() })
+          .filter(|updated| updated.validate().is_ok());
+        self.input.power_priority = power_priority;
+        self.input.power_priority_valid = updated.is_some();
+        if let Some(updated) = updated {
+          *calculator = updated;
+          Some(Action::CalculatorModified)
+        } else {
+          None
+        }
+      }
+      Message::InputCustomConsumerChange(m) => if self.input.custom_consumers.update(m, calculator) {
+        Some(Action::CalculatorModified)
+      } else {
+        None
+      },
       Message::InputStorageChange(m) => {
         self.input.storage.update(m, calculator);
         Some(Action::CalculatorModified)
@@ -135,29 +335,90 @@ impl Page {
         self.input.hydrogen.update(m, calculator);
         Some(Action::CalculatorModified)
       }
+      Message::SearchChange(search) => {
+        let filter = if search.trim().is_empty() {
+          None
+        } else {
+          Some(data.blocks.query(&data.localization, &data.components).search(&search).run().into_iter().map(|m| m.id.clone()).collect())
+        };
+        self.input.storage.set_filter(&filter);
+        self.input.thrust.set_filter(&filter);
+        self.input.power.set_filter(&filter);
+        self.input.hydrogen.set_filter(&filter);
+        self.input.search = search;
+        None
+      }
+      Message::WheelScrolled(steps) => {
+        let Input { storage, thrust, power, hydrogen, .. } = &mut self.input;
+        let stepped = storage.step_focused(steps, calculator)
+          || thrust.step_focused(steps, calculator)
+          || power.step_focused(steps, calculator)
+          || hydrogen.step_focused(steps, calculator);
+        if stepped { Some(Action::CalculatorModified) } else { None }
+      }
+      Message::PanelToggled(id) => {
+        let collapsed_panels = &mut self.input.collapsed_panels;
+        if !collapsed_panels.remove(id) {
+          collapsed_panels.insert(id.to_owned());
+        }
+        Some(Action::CollapsedPanelsChanged(collapsed_panels.clone()))
+      }
+      Message::StartPressed => Some(Action::Start),
       Message::SavePressed => Some(Action::Save),
       Message::SaveAsPressed => Some(Action::SaveAs),
       Message::LoadPressed => Some(Action::Load),
+      Message::PresetsPressed => Some(Action::Presets),
+      Message::TemplatesPressed => Some(Action::Templates),
+      Message::UndoPressed => Some(Action::Undo),
+      Message::RedoPressed => Some(Action::Redo),
+      Message::ChartsPressed => Some(Action::Charts),
+      Message::ThemeToggled => Some(Action::ToggleTheme),
+      Message::ComparePressed => Some(Action::Compare),
+      Message::BreakdownPressed => Some(Action::Breakdown),
+      Message::EnvironmentsPressed => Some(Action::Environments),
+      Message::SettingsPressed => Some(Action::Settings),
+      Message::TabSelected(index) => Some(Action::SelectTab(index)),
+      Message::NewTabPressed => Some(Action::NewTab),
+      Message::CloseTabPressed => Some(Action::CloseTab),
     };
 
     if let Some(Action::CalculatorModified) = &action {
-      self.result.calculated = calculator.calculate(data);
+      self.result.set_calculated(calculator.calculate_with(&self.incremental_calculator, data), calculator, data);
+      self.result_mut.trip.reload(calculator);
     }
 
     action
   }
 
   pub fn reload_input(&mut self, calculator: &GridCalculator, data: &Data) {
+    self.input.planet.reload(calculator);
     self.input.options.reload(calculator);
+    self.input.idle_hydrogen_thrusters = calculator.idle_hydrogen_thrusters;
+    self.input.power_priority = PowerConsumer::format_priority(&calculator.power_priority);
+    self.input.power_priority_valid = true;
+    self.input.custom_consumers.reload(calculator);
     self.input.storage.reload(calculator);
     self.input.thrust.reload(calculator);
     self.input.power.reload(calculator);
     self.input.hydrogen.reload(calculator);
-    self.result.calculated = calculator.calculate(data);
+    self.result.set_calculated(calculator.calculate_with(&self.incremental_calculator, data), calculator, data);
+    self.result_mut.trip.reload(calculator);
   }
 
-  pub fn view(&mut self) -> Element<Message> {
+  /// Creates the view, with the name of the calculator of each of the `tabs` and whether it was modified since it was
+  /// last saved, the index of the `current_tab`, and whether changes can be undone (`can_undo`) or redone (`can_redo`).
+  pub fn view(&mut self, tabs: &[(Option<&str>, bool)], current_tab: usize, can_undo: bool, can_redo: bool) -> Element<Message> {
+    let (name, modified) = tabs[current_tab];
+    let tab_bar = Self::view_tab_bar(tabs, current_tab, &mut self.tab_button_states, &mut self.new_tab_button_state, &mut self.close_tab_button_state);
+    let mut undo = button(&mut self.undo_button_state, tr("Undo"));
+    if can_undo { undo = undo.on_press(Message::UndoPressed); }
+    let mut redo = button(&mut self.redo_button_state, tr("Redo"));
+    if can_redo { redo = redo.on_press(Message::RedoPressed); }
     let input = Self::view_input(&mut self.input);
+    let name = name.map_or_else(|| tr("Untitled"), |name| name.to_owned());
+    let name = lbl(if modified { tr_format("{} (unsaved changes)", &[&name]) } else { name });
+    let name = if modified { name.color(danger_color()) } else { name };
+    let warnings = Self::view_warnings(&self.result.warnings, &self.result.recommendations);
     let result = Self::view_result(&self.result, &mut self.result_mut);
     let root: Element<_> = col()
       .spacing(10)
@@ -168,142 +429,230 @@ impl Page {
           .spacing(10)
           .width(Length::Fill)
           .align_items(Align::Center)
-          .push(h1("Space Engineers Calculator"))
-          .push(button(&mut self.save_button_state, "Save").on_press(Message::SavePressed))
-          .push(button(&mut self.save_as_button_state, "Save as").on_press(Message::SaveAsPressed))
-          .push(button(&mut self.load_button_state, "Load").on_press(Message::LoadPressed))
+          .push(h1(tr("Space Engineers Calculator")))
+          .push(name)
+          .push(button(&mut self.start_button_state, tr("Start")).on_press(Message::StartPressed))
+          .push(button(&mut self.save_button_state, tr("Save")).on_press(Message::SavePressed))
+          .push(button(&mut self.save_as_button_state, tr("Save as")).on_press(Message::SaveAsPressed))
+          .push(button(&mut self.load_button_state, tr("Load")).on_press(Message::LoadPressed))
+          .push(button(&mut self.presets_button_state, tr("New from preset")).on_press(Message::PresetsPressed))
+          .push(button(&mut self.templates_button_state, tr("Templates")).on_press(Message::TemplatesPressed))
+          .push(undo)
+          .push(redo)
+          .push(Self::view_charts_button(&mut self.charts_button_state))
+          .push(button(&mut self.compare_button_state, tr("Compare")).on_press(Message::ComparePressed))
+          .push(button(&mut self.breakdown_button_state, tr("Breakdown")).on_press(Message::BreakdownPressed))
+          .push(button(&mut self.environments_button_state, tr("Environments")).on_press(Message::EnvironmentsPressed))
+          .push(button(&mut self.theme_button_state, tr(match Theme::current() { Theme::Light => "Dark theme", Theme::Dark => "Light theme" })).on_press(Message::ThemeToggled))
+          .push(button(&mut self.settings_button_state, tr("Settings")).on_press(Message::SettingsPressed))
         )
         .push(row()
           .width(Length::Fill)
           .push(h3("https://github.com/Gohla/space_engineers_calc").width(Length::Fill).horizontal_alignment(HorizontalAlignment::Right))
         )
       )
+      .push(tab_bar)
+      .push(warnings)
       .push(row()
         .spacing(10)
         .push(input)
         .push(result)
+        .push(Self::view_details(&mut self.details))
       ).into();
     root
     //.explain(iced::Color::BLACK)
   }
 
 
+  fn view_details(details: &mut Option<BlockDetails>) -> Element<Message> {
+    match details {
+      Some(details) => details.view().map(Message::DetailsChange),
+      None => empty().into(),
+    }
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  fn view_charts_button(state: &mut button::State) -> Element<Message> {
+    button(state, tr("Charts")).on_press(Message::ChartsPressed).into()
+  }
+
+  // Charts are drawn on a canvas, which is not supported on the web.
+  #[cfg(target_arch = "wasm32")]
+  fn view_charts_button(_state: &mut button::State) -> Element<Message> {
+    empty().into()
+  }
+
+
+  /// Creates a row with a button for each of the `tabs`, and buttons to open a new tab and close the current one.
+  fn view_tab_bar<'a>(tabs: &[(Option<&str>, bool)], current_tab: usize, tab_button_states: &'a mut Vec<button::State>, new_tab_button_state: &'a mut button::State, close_tab_button_state: &'a mut button::State) -> Element<'a, Message> {
+    tab_button_states.resize_with(tabs.len(), Default::default);
+    let mut row = row().spacing(2).align_items(Align::Center);
+    for (index, ((name, modified), button_state)) in tabs.iter().zip(tab_button_states.iter_mut()).enumerate() {
+      let label = format!("{}{}", name.map_or_else(|| tr("Untitled"), |name| name.to_owned()), if *modified { "*" } else { "" });
+      row = row.push(if index == current_tab {
+        button(button_state, format!("[{}]", label))
+      } else {
+        button(button_state, label).on_press(Message::TabSelected(index))
+      });
+    }
+    let mut close_tab = button(close_tab_button_state, tr("Close tab"));
+    if tabs.len() > 1 { close_tab = close_tab.on_press(Message::CloseTabPressed); }
+    row
+      .push(button(new_tab_button_state, "+").on_press(Message::NewTabPressed))
+      .push(close_tab)
+      .into()
+  }
+
+  /// Creates a banner listing `warnings` followed by `recommendations`, or an empty element if there are neither.
+  fn view_warnings<'a>(warnings: &[Warning], recommendations: &[Recommendation]) -> Element<'a, Message> {
+    if warnings.is_empty() && recommendations.is_empty() { return empty().into(); }
+    let mut column = col()
+      .spacing(1)
+      .push(h2(tr("Warnings")).color(danger_color()));
+    for warning in warnings {
+      column = column.push(lbl(format!("{}", warning)).color(danger_color()));
+    }
+    for recommendation in recommendations {
+      column = column.push(lbl(format!("{}", recommendation)));
+    }
+    column.into()
+  }
+
   fn view_input(input: &mut Input) -> Element<Message> {
-    scl(&mut input.scrollable_state)
+    let Input { planet, options, idle_hydrogen_thrusters, power_priority, power_priority_valid, power_priority_state, custom_consumers, storage, thrust, power, hydrogen, search, search_state, collapsed_panels, panel_button_states, scrollable_state } = input;
+    let [storage_button_state, thrust_button_state, power_button_state, hydrogen_button_state] = panel_button_states;
+    scl(scrollable_state)
       .spacing(10)
       .padding(1)
       .push(col()
-        .push(h2("Options"))
-        .push(input.options.view().map(Message::InputOptionChange))
-      )
-      .push(col()
-        .push(h2("Storage"))
-        .push(input.storage.view().map(Message::InputStorageChange))
-      )
-      .push(col()
-        .push(h2("Thrusters"))
-        .push(input.thrust.view().map(Message::InputThrustChange))
-      )
-      .push(col()
-        .push(h2("Power"))
-        .push(input.power.view().map(Message::InputPowerChange))
-      )
-      .push(col()
-        .push(h2("Hydrogen"))
-        .push(input.hydrogen.view().map(Message::InputHydrogenChange))
+        .push(h2(tr("Options")))
+        .push(planet.view(Length::Units(200)).map(Message::InputPlanetChange))
+        .push(options.view().map(Message::InputOptionChange))
+        .push(Checkbox::new(*idle_hydrogen_thrusters, &tr("Idle hydrogen thrusters consume hydrogen"), Message::IdleHydrogenThrustersToggled))
+        .push(row()
+          .align_items(Align::Center)
+          .push(lbl(tr("Power Priority")).width(Length::Units(200)).color(if *power_priority_valid { foreground_color() } else { danger_color() }))
+          .push(text_input(Length::Units(300), power_priority_state, "generator, battery", power_priority, Message::PowerPriorityChange))
+        )
+        .push(custom_consumers.view(Length::Units(200)).map(Message::InputCustomConsumerChange))
       )
+      .push(text_input(Length::Units(300), search_state, &tr("Search blocks"), search, Message::SearchChange))
+      .push(Self::view_panel("storage", "Storage", collapsed_panels, storage_button_state, || storage.view().map(Message::InputStorageChange)))
+      .push(Self::view_panel("thrust", "Thrusters", collapsed_panels, thrust_button_state, || thrust.view().map(Message::InputThrustChange)))
+      .push(Self::view_panel("power", "Power", collapsed_panels, power_button_state, || power.view().map(Message::InputPowerChange)))
+      .push(Self::view_panel("hydrogen", "Hydrogen", collapsed_panels, hydrogen_button_state, || hydrogen.view().map(Message::InputHydrogenChange)))
       .into()
   }
 
+  /// Creates a panel with a header button that collapses or expands it, only creating its content when expanded.
+  fn view_panel<'a, F: FnOnce() -> Element<'a, Message>>(id: &'static str, title: &str, collapsed_panels: &HashSet<String>, button_state: &'a mut button::State, content: F) -> Element<'a, Message> {
+    let collapsed = collapsed_panels.contains(id);
+    let header = button(button_state, format!("{} {}", if collapsed { "+" } else { "-" }, tr(title)))
+      .on_press(Message::PanelToggled(id));
+    let mut column = col().push(header);
+    if !collapsed {
+      column = column.push(content());
+    }
+    column.into()
+  }
+
 
   fn view_result<'a>(result: &'a Result, result_mut: &'a mut ResultMut) -> Element<'a, Message> {
     scl(&mut result_mut.scrollable_state)
       .spacing(10)
       .padding(1)
       .push(col()
-        .push(h2("Mass"))
-        .push(Self::view_result_mass(&result.calculated))
+        .push(h2(tr("Mass")))
+        .push(Self::view_result_mass(&result.calculated, result.units))
+      )
+      .push(col()
+        .push(h2(tr("Volume")))
+        .push(Self::view_result_volume(&result.calculated, result.units))
+      )
+      .push(col()
+        .push(h2(tr("Items")))
+        .push(Self::view_result_items(&result.calculated, result.units))
       )
       .push(col()
-        .push(h2("Volume"))
-        .push(Self::view_result_volume(&result.calculated))
+        .push(h2(tr("Acceleration")))
+        .push(Self::view_result_acceleration(&result.calculated, result.units))
       )
       .push(col()
-        .push(h2("Items"))
-        .push(Self::view_result_items(&result.calculated))
+        .push(h2(tr("Climb & Descent")))
+        .push(Self::view_result_climb(&result.calculated, result.units))
       )
       .push(col()
-        .push(h2("Acceleration (m/s^2)"))
-        .push(Self::view_result_acceleration(&result.calculated))
+        .push(h2(tr("Trip")))
+        .push(result_mut.trip.view(&result.calculated).map(Message::TripChange))
       )
       .push(col()
-        .push(h2("Power"))
-        .push(Self::view_result_power(&result.calculated))
+        .push(h2(tr("Power")))
+        .push(Self::view_result_power(&result.calculated, result.units, result.show_ship_tools))
       )
       .push(col()
-        .push(h2("Hydrogen"))
-        .push(Self::view_result_hydrogen(&result.calculated))
+        .push(h2(tr("Hydrogen")))
+        .push(Self::view_result_hydrogen(&result.calculated, result.units))
       )
       .into()
   }
 
-  fn view_result_mass(result: &GridCalculated) -> Element<Message> {
+  fn view_result_mass(result: &GridCalculated, units: UnitPreferences) -> Element<Message> {
     row()
       .spacing(10)
       .push(col()
         .spacing(1)
-        .push(lbl("Empty"))
-        .push(lbl("Filled"))
+        .push(lbl(tr("Empty")))
+        .push(lbl(tr("Filled")))
       )
       .push(col()
         .spacing(1)
-        .push(val(format!("{:.0} kg", result.total_mass_empty)))
-        .push(val(format!("{:.0} kg", result.total_mass_filled)))
+        .push(val(units.format_mass(result.volume_mass.total_mass_empty)))
+        .push(val(units.format_mass(result.volume_mass.total_mass_filled)))
       )
       .into()
   }
 
-  fn view_result_volume(result: &GridCalculated) -> Element<Message> {
+  fn view_result_volume(result: &GridCalculated, units: UnitPreferences) -> Element<Message> {
     row()
       .spacing(10)
       .push(col()
         .spacing(1)
-        .push(lbl("Any"))
-        .push(lbl("Ore"))
-        .push(lbl("Ice"))
-        .push(lbl("Ore-only"))
-        .push(lbl("Ice-only"))
+        .push(lbl(tr("Any")))
+        .push(lbl(tr("Ore")))
+        .push(lbl(tr("Ice")))
+        .push(lbl(tr("Ore-only")))
+        .push(lbl(tr("Ice-only")))
       )
       .push(col()
         .spacing(1)
-        .push(val(format!("{:.0} L", result.total_volume_any)))
-        .push(val(format!("{:.0} L", result.total_volume_ore)))
-        .push(val(format!("{:.0} L", result.total_volume_ice)))
-        .push(val(format!("{:.0} L", result.total_volume_ore_only)))
-        .push(val(format!("{:.0} L", result.total_volume_ice_only)))
+        .push(val(units.format_volume(result.volume_mass.total_volume_any)))
+        .push(val(units.format_volume(result.volume_mass.total_volume_ore)))
+        .push(val(units.format_volume(result.volume_mass.total_volume_ice)))
+        .push(val(units.format_volume(result.volume_mass.total_volume_ore_only)))
+        .push(val(units.format_volume(result.volume_mass.total_volume_ice_only)))
       )
       .into()
   }
 
-  fn view_result_items(result: &GridCalculated) -> Element<Message> {
+  fn view_result_items(result: &GridCalculated, units: UnitPreferences) -> Element<Message> {
     row()
       .spacing(10)
       .push(col()
         .spacing(1)
-        .push(lbl("Any"))
-        .push(lbl("Ice"))
-        .push(lbl("Steel Plates"))
+        .push(lbl(tr("Any")))
+        .push(lbl(tr("Ice")))
+        .push(lbl(tr("Steel Plates")))
       )
       .push(col()
         .spacing(1)
-        .push(val(format!("{:.0} #", result.total_items_ore)))
-        .push(val(format!("{:.0} #", result.total_items_ice)))
-        .push(val(format!("{:.0} #", result.total_items_steel_plate)))
+        .push(val(units.format_items(result.volume_mass.total_items_ore)))
+        .push(val(units.format_items(result.volume_mass.total_items_ice)))
+        .push(val(units.format_items(result.volume_mass.total_items_steel_plate)))
       )
       .into()
   }
 
-  fn view_result_acceleration(result: &GridCalculated) -> Element<Message> {
+  fn view_result_acceleration(result: &GridCalculated, units: UnitPreferences) -> Element<Message> {
     row()
       .spacing(10)
       .push({
@@ -318,12 +667,12 @@ impl Page {
       })
       .push({
         let mut column = col()
-          .push(lbl("Filled").horizontal_alignment(HorizontalAlignment::Center))
-          .push(lbl("Gravity").horizontal_alignment(HorizontalAlignment::Center))
+          .push(lbl(tr("Filled")).horizontal_alignment(HorizontalAlignment::Center))
+          .push(lbl(tr("Gravity")).horizontal_alignment(HorizontalAlignment::Center))
           ;
         for direction in Direction::iter() {
           if let Some(acceleration) = result.acceleration.get(direction) {
-            column = column.push(val(format!("{:.2}", acceleration.acceleration_filled_gravity)))
+            column = column.push(val(units.format_acceleration(acceleration.acceleration_filled_gravity)))
           }
         }
         column
@@ -331,23 +680,23 @@ impl Page {
       .push({
         let mut column = col()
           .push(empty())
-          .push(lbl("No grav.").horizontal_alignment(HorizontalAlignment::Center))
+          .push(lbl(tr("No grav.")).horizontal_alignment(HorizontalAlignment::Center))
           ;
         for direction in Direction::iter() {
           if let Some(acceleration) = result.acceleration.get(direction) {
-            column = column.push(val(format!("{:.2}", acceleration.acceleration_filled_no_gravity)))
+            column = column.push(val(units.format_acceleration(acceleration.acceleration_filled_no_gravity)))
           }
         }
         column
       })
       .push({
         let mut column = col()
-          .push(lbl("Empty").horizontal_alignment(HorizontalAlignment::Center))
-          .push(lbl("Gravity").horizontal_alignment(HorizontalAlignment::Center))
+          .push(lbl(tr("Empty")).horizontal_alignment(HorizontalAlignment::Center))
+          .push(lbl(tr("Gravity")).horizontal_alignment(HorizontalAlignment::Center))
           ;
         for direction in Direction::iter() {
           if let Some(acceleration) = result.acceleration.get(direction) {
-            column = column.push(val(format!("{:.2}", acceleration.acceleration_empty_gravity)))
+            column = column.push(val(units.format_acceleration(acceleration.acceleration_empty_gravity)))
           }
         }
         column
@@ -355,11 +704,47 @@ impl Page {
       .push({
         let mut column = col()
           .push(empty())
-          .push(lbl("No grav.").horizontal_alignment(HorizontalAlignment::Center))
+          .push(lbl(tr("No grav.")).horizontal_alignment(HorizontalAlignment::Center))
           ;
         for direction in Direction::iter() {
           if let Some(acceleration) = result.acceleration.get(direction) {
-            column = column.push(val(format!("{:.2}", acceleration.acceleration_empty_no_gravity)))
+            column = column.push(val(units.format_acceleration(acceleration.acceleration_empty_no_gravity)))
+          }
+        }
+        column
+      })
+      .push({
+        let mut column = col()
+          .push(lbl(tr("To Max Speed")).horizontal_alignment(HorizontalAlignment::Center))
+          .push(lbl(tr("Filled")).horizontal_alignment(HorizontalAlignment::Center))
+          ;
+        for direction in Direction::iter() {
+          if let Some(acceleration) = result.acceleration.get(direction) {
+            column = column.push(val(format!("{} s, {} m", acceleration.time_to_max_speed_filled.format(), acceleration.distance_to_max_speed_filled.format())))
+          }
+        }
+        column
+      })
+      .push({
+        let mut column = col()
+          .push(empty())
+          .push(lbl(tr("Empty")).horizontal_alignment(HorizontalAlignment::Center))
+          ;
+        for direction in Direction::iter() {
+          if let Some(acceleration) = result.acceleration.get(direction) {
+            column = column.push(val(format!("{} s, {} m", acceleration.time_to_max_speed_empty.format(), acceleration.distance_to_max_speed_empty.format())))
+          }
+        }
+        column
+      })
+      .push({
+        let mut column = col()
+          .push(lbl(tr("Hydrogen")).horizontal_alignment(HorizontalAlignment::Center))
+          .push(lbl(tr("Full Burn")).horizontal_alignment(HorizontalAlignment::Center))
+          ;
+        for direction in Direction::iter() {
+          if result.acceleration.contains_key(direction) {
+            column = column.push(val(result.hydrogen.burn_duration.get(direction).map_or_else(|| "-".to_owned(), |d| format!("{} s", d.format()))))
           }
         }
         column
@@ -367,119 +752,199 @@ impl Page {
       .into()
   }
 
-  fn view_result_power(result: &GridCalculated) -> Element<Message> {
+  fn view_result_climb(result: &GridCalculated, units: UnitPreferences) -> Element<Message> {
+    let climb = &result.climb;
     row()
       .spacing(10)
       .push(col()
         .spacing(1)
-        .push(lbl("Generation"))
-        .push(lbl("Capacity: Batteries"))
+        .push(lbl(tr("Speed")))
+        .push(lbl(tr("Thrust")))
+        .push(empty())
+        .push(lbl(tr("Power")))
+        .push(lbl(tr("Hydrogen")))
         .push(empty())
-        .push(lbl("Ide"))
-        .push(lbl("Misc"))
-        .push(lbl("+ Charge Jump Drives"))
-        .push(lbl("+ Generators"))
-        .push(lbl("+ Up/Down Thrusters"))
-        .push(lbl("+ Front/Back Thrusters"))
-        .push(lbl("+ Left/Right Thrusters"))
-        .push(lbl("+ Charge Batteries"))
+        .push(lbl(tr("Safe Descent Speed")))
+        .push(lbl(tr("Climb Acceleration")))
+        .push(lbl(tr("Descent Braking Acceleration")))
       )
       .push(col()
         .spacing(1)
-        .push(val(format!("{:.2} MW", result.power_generation)))
-        .push(val(format!("{:.2} MWh", result.power_capacity_battery)))
-        .push(lbl("Consumption"))
-        .push(val(format!("{:.2} MW", result.power_idle.consumption)))
-        .push(val(format!("{:.2} MW", result.power_misc.consumption)))
-        .push(val(format!("{:.2} MW", result.power_upto_jump_drive.consumption)))
-        .push(val(format!("{:.2} MW", result.power_upto_generator.consumption)))
-        .push(val(format!("{:.2} MW", result.power_upto_up_down_thruster.consumption)))
-        .push(val(format!("{:.2} MW", result.power_upto_front_back_thruster.consumption)))
-        .push(val(format!("{:.2} MW", result.power_upto_left_right_thruster.consumption)))
-        .push(val(format!("{:.2} MW", result.power_upto_battery.consumption)))
+        .push(val(format!("{} m/s", climb.speed.format())))
+        .push(val(format!("{} %", Quantity::Ratio.format(climb.thrust))))
+        .push(lbl(tr("Consumption")))
+        .push(val(units.format_power(climb.power.consumption)))
+        .push(val(units.format_flow(climb.hydrogen.consumption)))
+        .push(lbl(tr("Empty")))
+        .push(val(format!("{} m/s", climb.safe_descent_speed_empty.format())))
+        .push(val(units.format_acceleration(climb.climb_acceleration_empty)))
+        .push(val(units.format_acceleration(climb.descent_braking_acceleration_empty)))
       )
       .push(col()
         .spacing(1)
         .push(empty())
         .push(empty())
-        .push(lbl("Balance"))
-        .push(val(format!("{:.2} MW", result.power_idle.balance)))
-        .push(val(format!("{:.2} MW", result.power_misc.balance)))
-        .push(val(format!("{:.2} MW", result.power_upto_jump_drive.balance)))
-        .push(val(format!("{:.2} MW", result.power_upto_generator.balance)))
-        .push(val(format!("{:.2} MW", result.power_upto_up_down_thruster.balance)))
-        .push(val(format!("{:.2} MW", result.power_upto_front_back_thruster.balance)))
-        .push(val(format!("{:.2} MW", result.power_upto_left_right_thruster.balance)))
-        .push(val(format!("{:.2} MW", result.power_upto_battery.balance)))
+        .push(lbl(tr("Balance")))
+        .push(val(units.format_power(climb.power.balance)))
+        .push(val(units.format_flow(climb.hydrogen.balance)))
+        .push(lbl(tr("Filled")))
+        .push(val(format!("{} m/s", climb.safe_descent_speed_filled.format())))
+        .push(val(units.format_acceleration(climb.climb_acceleration_filled)))
+        .push(val(units.format_acceleration(climb.descent_braking_acceleration_filled)))
       )
       .push(col()
         .spacing(1)
         .push(empty())
         .push(empty())
-        .push(lbl("Duration: Batteries"))
-        .push(val(format!("{:.2} min", result.power_idle.duration)))
-        .push(val(format!("{:.2} min", result.power_misc.duration)))
-        .push(val(format!("{:.2} min", result.power_upto_jump_drive.duration)))
-        .push(val(format!("{:.2} min", result.power_upto_generator.duration)))
-        .push(val(format!("{:.2} min", result.power_upto_up_down_thruster.duration)))
-        .push(val(format!("{:.2} min", result.power_upto_front_back_thruster.duration)))
-        .push(val(format!("{:.2} min", result.power_upto_left_right_thruster.duration)))
-        .push(val(format!("{:.2} min", result.power_upto_battery.duration)))
+        .push(lbl(tr("Duration")))
+        .push(val(units.format_duration(climb.power.duration)))
+        .push(val(units.format_duration(climb.hydrogen.duration)))
       )
       .into()
   }
 
-  fn view_result_hydrogen(result: &GridCalculated) -> Element<Message> {
+  fn view_result_power(result: &GridCalculated, units: UnitPreferences, show_ship_tools: bool) -> Element<Message> {
+    let power = &result.power;
+    let mut labels = col()
+      .spacing(1)
+      .push(lbl(tr("Generation")))
+      .push(lbl(tr("Capacity: Batteries")))
+      .push(lbl(tr("Stored: Batteries")))
+      .push(lbl(tr("Recharge: Batteries")))
+      .push(empty())
+      .push(lbl(tr("Idle")))
+      .push(lbl(tr("Misc")))
+      ;
+    let mut consumption = col()
+      .spacing(1)
+      .push(val(units.format_power(power.generation)))
+      .push(val(units.format_energy(power.capacity_battery)))
+      .push(val(units.format_energy(power.stored_battery)))
+      .push(val(units.format_duration(power.recharge_duration)))
+      .push(lbl(tr("Consumption")))
+      .push(val(units.format_power(power.idle.consumption)))
+      .push(val(units.format_power(power.misc.consumption)))
+      ;
+    let mut balance = col()
+      .spacing(1)
+      .push(empty())
+      .push(empty())
+      .push(empty())
+      .push(empty())
+      .push(lbl(tr("Balance")))
+      .push(val(units.format_power(power.idle.balance)))
+      .push(val(units.format_power(power.misc.balance)))
+      ;
+    let mut duration = col()
+      .spacing(1)
+      .push(empty())
+      .push(empty())
+      .push(empty())
+      .push(empty())
+      .push(lbl(tr("Duration: Batteries")))
+      .push(val(units.format_duration(power.idle.duration)))
+      .push(val(units.format_duration(power.misc.duration)))
+      ;
+    // Cumulative rows follow the power priority of the calculator, which omits disabled consumers.
+    for (consumer, resource) in &power.upto {
+      // Data extracted before ship tools were supported has none of them.
+      if *consumer == PowerConsumer::ShipTool && !show_ship_tools { continue; }
+      labels = labels.push(lbl(tr(&format!("+ {}", consumer.label()))));
+      consumption = consumption.push(val(units.format_power(resource.consumption)));
+      balance = balance.push(val(units.format_power(resource.balance)));
+      duration = duration.push(val(units.format_duration(resource.duration)));
+    }
+    labels = labels.push(empty());
+    consumption = consumption.push(lbl(tr("Budget")));
+    balance = balance.push(lbl(tr("Share")));
+    duration = duration.push(empty());
+    for share in &power.budget {
+      labels = labels.push(lbl(tr(share.category.label())));
+      consumption = consumption.push(val(units.format_power(share.consumption)));
+      balance = balance.push(val(format!("{} %", Quantity::Ratio.format(share.share))));
+      duration = duration.push(empty());
+    }
     row()
       .spacing(10)
-      .push(col()
-        .spacing(1)
-        .push(lbl("Generation"))
-        .push(lbl("Capacity: Engines"))
-        .push(lbl("Capacity: Tanks"))
-        .push(empty())
-        .push(lbl("Idle"))
-        .push(lbl("Engines"))
-        .push(lbl("+ Up/Down Thrusters"))
-        .push(lbl("+ Front/Back Thrusters"))
-        .push(lbl("+ Left/Right Thrusters"))
-      )
-      .push(col()
-        .spacing(1)
-        .push(val(format!("{:.0} L/s", result.hydrogen_generation)))
-        .push(val(format!("{:.0} L", result.hydrogen_capacity_engine)))
-        .push(val(format!("{:.0} L", result.hydrogen_capacity_tank)))
-        .push(lbl("Consumption"))
-        .push(val(format!("{:.1} L/s", result.hydrogen_idle.consumption)))
-        .push(val(format!("{:.1} L/s", result.hydrogen_engine.consumption)))
-        .push(val(format!("{:.1} L/s", result.hydrogen_upto_up_down_thruster.consumption)))
-        .push(val(format!("{:.1} L/s", result.hydrogen_upto_front_back_thruster.consumption)))
-        .push(val(format!("{:.1} L/s", result.hydrogen_upto_left_right_thruster.consumption)))
-      )
-      .push(col()
-        .spacing(1)
-        .push(empty())
-        .push(empty())
-        .push(empty())
-        .push(lbl("Balance"))
-        .push(val(format!("{:.1} L/s", result.hydrogen_idle.balance)))
-        .push(val(format!("{:.1} L/s", result.hydrogen_engine.balance)))
-        .push(val(format!("{:.1} L/s", result.hydrogen_upto_up_down_thruster.balance)))
-        .push(val(format!("{:.1} L/s", result.hydrogen_upto_front_back_thruster.balance)))
-        .push(val(format!("{:.1} L/s", result.hydrogen_upto_left_right_thruster.balance)))
-      )
-      .push(col()
-        .spacing(1)
-        .push(empty())
-        .push(empty())
-        .push(empty())
-        .push(lbl("Duration: Tanks"))
-        .push(val(format!("{:.2} min", result.hydrogen_idle.duration)))
-        .push(val(format!("{:.2} min", result.hydrogen_engine.duration)))
-        .push(val(format!("{:.2} min", result.hydrogen_upto_up_down_thruster.duration)))
-        .push(val(format!("{:.2} min", result.hydrogen_upto_front_back_thruster.duration)))
-        .push(val(format!("{:.2} min", result.hydrogen_upto_left_right_thruster.duration)))
-      )
+      .push(labels)
+      .push(consumption)
+      .push(balance)
+      .push(duration)
+      .into()
+  }
+
+  fn view_result_hydrogen(result: &GridCalculated, units: UnitPreferences) -> Element<Message> {
+    let hydrogen = &result.hydrogen;
+    let mut labels = col()
+      .spacing(1)
+      .push(lbl(tr("Generation")))
+      .push(lbl(tr("Capacity: Engines")))
+      .push(lbl(tr("Capacity: Tanks")))
+      .push(lbl(tr("Capacity: Stockpiled Tanks")))
+      .push(empty())
+      .push(lbl(tr("Idle")))
+      .push(lbl(tr("Engines")))
+      .push(lbl(tr("+ Up/Down Thrusters")))
+      .push(lbl(tr("+ Front/Back Thrusters")))
+      .push(lbl(tr("+ Left/Right Thrusters")))
+      .push(empty())
+      ;
+    let mut consumption = col()
+      .spacing(1)
+      .push(val(units.format_flow(hydrogen.generation)))
+      .push(val(units.format_volume(hydrogen.capacity_engine)))
+      .push(val(units.format_volume(hydrogen.capacity_tank)))
+      .push(val(units.format_volume(hydrogen.capacity_tank_stockpile)))
+      .push(lbl(tr("Consumption")))
+      .push(val(units.format_flow(hydrogen.idle.consumption)))
+      .push(val(units.format_flow(hydrogen.engine.consumption)))
+      .push(val(units.format_flow(hydrogen.upto_up_down_thruster.consumption)))
+      .push(val(units.format_flow(hydrogen.upto_front_back_thruster.consumption)))
+      .push(val(units.format_flow(hydrogen.upto_left_right_thruster.consumption)))
+      .push(lbl(tr("Budget")))
+      ;
+    let mut balance = col()
+      .spacing(1)
+      .push(empty())
+      .push(empty())
+      .push(empty())
+      .push(empty())
+      .push(lbl(tr("Balance")))
+      .push(val(units.format_flow(hydrogen.idle.balance)))
+      .push(val(units.format_flow(hydrogen.engine.balance)))
+      .push(val(units.format_flow(hydrogen.upto_up_down_thruster.balance)))
+      .push(val(units.format_flow(hydrogen.upto_front_back_thruster.balance)))
+      .push(val(units.format_flow(hydrogen.upto_left_right_thruster.balance)))
+      .push(lbl(tr("Share")))
+      ;
+    let duration = col()
+      .spacing(1)
+      .push(empty())
+      .push(empty())
+      .push(empty())
+      .push(empty())
+      .push(lbl(tr("Duration: Tanks")))
+      .push(val(units.format_duration(hydrogen.idle.duration)))
+      .push(val(units.format_duration(hydrogen.engine.duration)))
+      .push(val(units.format_duration(hydrogen.upto_up_down_thruster.duration)))
+      .push(val(units.format_duration(hydrogen.upto_front_back_thruster.duration)))
+      .push(val(units.format_duration(hydrogen.upto_left_right_thruster.duration)))
+      ;
+    let budget = &hydrogen.budget;
+    for share in &budget.consumption {
+      labels = labels.push(lbl(tr(share.category.label())));
+      consumption = consumption.push(val(units.format_flow(share.consumption)));
+      balance = balance.push(val(format!("{} %", Quantity::Ratio.format(share.share))));
+    }
+    labels = labels.push(lbl(tr("Generators"))).push(lbl(tr("Net")));
+    consumption = consumption.push(val(units.format_flow(budget.generation))).push(val(units.format_flow(budget.net)));
+    balance = balance.push(val(format!("{} %", Quantity::Ratio.format(budget.generation_share))));
+    row()
+      .spacing(10)
+      .push(labels)
+      .push(consumption)
+      .push(balance)
+      .push(duration)
       .into()
   }
 }
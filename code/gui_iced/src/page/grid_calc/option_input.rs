@@ -3,22 +3,31 @@ use std::fmt::Debug;
 use iced::{Align, Element, Length};
 
 use secalc_core::grid::GridCalculator;
+use secalc_core::units::{Kilograms, Litres, Meters, MetersPerSecond};
 
 use crate::data_bind::{DataBind, DataBindMessage};
+use crate::i18n::tr;
 use crate::view::{col, lbl, row};
 
 macro_rules! create_option_input {
   ($label_width:expr; $input_width:expr; $($field:ident, $type:ty, $message:ident, $label:expr, $format:expr, $unit:expr);*) => {
     pub struct OptionInput {
       $($field: DataBind<$type>,)*
+      hidden: Vec<&'static str>,
     }
 
     impl OptionInput {
       pub fn new(default_calculator: &GridCalculator, loaded_calculator: &GridCalculator) -> Self {
         Self {
           $($field: DataBind::new(default_calculator.$field, format!($format, default_calculator.$field), $input_width, $unit, format!("{}", loaded_calculator.$field)),)*
+          hidden: Vec::new(),
         }
       }
+
+      /// Hides the input of `field` from the view.
+      pub fn hide(&mut self, field: &'static str) {
+        self.hidden.push(field);
+      }
     }
 
     #[derive(Clone, Debug)]
@@ -27,9 +36,15 @@ macro_rules! create_option_input {
     }
 
     impl OptionInput {
+      /// Updates `calc` with the input of `message`, unless that makes `calc` invalid, in which case the input is
+      /// marked as erroneous and `calc` is kept as is.
       pub fn update(&mut self, message: OptionInputMessage, calc: &mut GridCalculator) {
+        let mut updated = calc.clone();
         match message {
-          $(OptionInputMessage::$message(m) => self.$field.update(m, &mut calc.$field),)*
+          $(OptionInputMessage::$message(m) => {
+            self.$field.update(m, &mut updated.$field);
+            if updated.validate().is_ok() { *calc = updated; } else { self.$field.set_error(); }
+          })*
         }
       }
 
@@ -38,9 +53,11 @@ macro_rules! create_option_input {
       }
 
       pub fn view(&mut self) -> Element<OptionInputMessage> {
-        col()
-          $(.push(row().push(lbl($label).width($label_width)).align_items(Align::Center).push(self.$field.view().map(move |s| OptionInputMessage::$message(s)))))*
-          .into()
+        let mut column = col();
+        $(if !self.hidden.contains(&stringify!($field)) {
+          column = column.push(row().push(lbl(tr($label)).width($label_width)).align_items(Align::Center).push(self.$field.view().map(move |s| OptionInputMessage::$message(s))));
+        })*
+        column.into()
       }
     }
   }
@@ -50,10 +67,19 @@ create_option_input!(Length::Units(200); Length::Units(95);
   gravity_multiplier, f64, GravityMultiplier, "Gravity Multiplier", "{:.1}", "*";
   container_multiplier, f64, ContainerMultiplier, "Container Multiplier", "{:.1}", "*";
   planetary_influence, f64, PlanetaryInfluence, "Planetary Influence", "{:.1}", "*";
-  additional_mass, f64, AdditionalMass, "Additional Mass", "{}", "kg";
+  additional_mass, Kilograms, AdditionalMass, "Additional Mass", "{}", "kg";
+  additional_volume, Litres, AdditionalVolume, "Additional Volume", "{}", "L";
+  max_speed, MetersPerSecond, MaxSpeed, "Max Speed", "{}", "m/s";
+  descent_altitude, Meters, DescentAltitude, "Descent Altitude", "{}", "m";
   ice_only_fill, f64, IceOnlyFill, "Ice-only-fill", "{:.1}", "%";
   ore_only_fill, f64, OreOnlyFill, "Ore-only-fill", "{:.1}", "%";
   any_fill_with_ice, f64, AnyFillWithIce, "Any-fill with Ice", "{:.1}", "%";
   any_fill_with_ore, f64, AnyFillWithOre, "Any-fill with Ore", "{:.1}", "%";
-  any_fill_with_steel_plates, f64, AnyFillWithSteelPlates, "Any-fill with Steel Plates", "{:.1}", "%"
+  any_fill_with_steel_plates, f64, AnyFillWithSteelPlates, "Any-fill with Steel Plates", "{:.1}", "%";
+  ammo_fill, f64, AmmoFill, "Ammo Fill", "{:.1}", "%";
+  generator_hydrogen_share, f64, GeneratorHydrogenShare, "Generator Hydrogen Share", "{:.1}", "%";
+  battery_charge, f64, BatteryCharge, "Battery Charge", "{:.1}", "%";
+  reactor_output_multiplier, f64, ReactorOutputMultiplier, "Reactor Output Multiplier", "{:.1}", "*";
+  renewable_output, f64, RenewableOutput, "Renewable Output", "{:.1}", "%";
+  utility_range, f64, UtilityRange, "Antenna/Beacon/Ore Detector Range", "{:.1}", "%"
 );
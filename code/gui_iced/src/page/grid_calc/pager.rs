@@ -0,0 +1,62 @@
+use std::ops::Range;
+
+use iced::{Align, button, Element};
+
+use crate::i18n::tr_format;
+use crate::view::{button, lbl, row};
+
+/// Pages through block input rows, so that only the rows of the current page are built and laid out. With mods, block
+/// lists can have thousands of rows, which makes building all of them on every view too slow.
+#[derive(Default)]
+pub struct Pager {
+  page: usize,
+  previous_button_state: button::State,
+  next_button_state: button::State,
+}
+
+#[derive(Clone, Debug)]
+pub enum PagerMessage {
+  Previous,
+  Next,
+}
+
+impl Pager {
+  pub const ROWS_PER_PAGE: usize = 50;
+
+  pub fn update(&mut self, message: PagerMessage, row_count: usize) {
+    match message {
+      PagerMessage::Previous => self.page = self.page.saturating_sub(1),
+      PagerMessage::Next => self.page = (self.page + 1).min(Self::page_count(row_count) - 1),
+    }
+  }
+
+  /// Gets the range of rows on the current page.
+  pub fn range(&self, row_count: usize) -> Range<usize> {
+    let page = self.page.min(Self::page_count(row_count) - 1);
+    let start = page * Self::ROWS_PER_PAGE;
+    start..(start + Self::ROWS_PER_PAGE).min(row_count)
+  }
+
+  /// Creates the paging controls, or returns `None` if all rows fit on a single page.
+  pub fn view(&mut self, row_count: usize) -> Option<Element<PagerMessage>> {
+    let page_count = Self::page_count(row_count);
+    if page_count <= 1 { return None; }
+    let page = self.page.min(page_count - 1);
+    let range = self.range(row_count);
+    let mut previous = button(&mut self.previous_button_state, tr("<"));
+    if page > 0 { previous = previous.on_press(PagerMessage::Previous); }
+    let mut next = button(&mut self.next_button_state, tr(">"));
+    if page + 1 < page_count { next = next.on_press(PagerMessage::Next); }
+    Some(row()
+      .spacing(10)
+      .align_items(Align::Center)
+      .push(previous)
+      .push(lbl(tr_format("Blocks {}-{} of {}", &[&(range.start + 1), &range.end, &row_count])))
+      .push(next)
+      .into())
+  }
+
+  fn page_count(row_count: usize) -> usize {
+    ((row_count + Self::ROWS_PER_PAGE - 1) / Self::ROWS_PER_PAGE).max(1)
+  }
+}
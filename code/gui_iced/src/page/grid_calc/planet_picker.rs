@@ -0,0 +1,67 @@
+use iced::{Align, button, Element, Length};
+
+use secalc_core::grid::GridCalculator;
+use secalc_core::grid::planet::{PLANET_PRESETS, PlanetPreset};
+
+use crate::i18n::tr;
+use crate::view::{button, col, lbl, row};
+
+/// Drop-down for applying a planet preset to the gravity multiplier and planetary influence options.
+#[derive(Default)]
+pub struct PlanetPicker {
+  /// Index of the preset matching the current options, or `None` if the options are custom.
+  selected: Option<usize>,
+  expanded: bool,
+  toggle_button_state: button::State,
+  preset_button_states: [button::State; PLANET_PRESETS.len()],
+}
+
+#[derive(Clone, Debug)]
+pub enum PlanetPickerMessage {
+  Toggle,
+  Select(usize),
+}
+
+impl PlanetPicker {
+  pub fn new(calc: &GridCalculator) -> Self {
+    let mut picker = Self::default();
+    picker.reload(calc);
+    picker
+  }
+
+  /// Updates the picker, returning true if a preset was applied to `calc`.
+  pub fn update(&mut self, message: PlanetPickerMessage, calc: &mut GridCalculator) -> bool {
+    match message {
+      PlanetPickerMessage::Toggle => {
+        self.expanded = !self.expanded;
+        false
+      }
+      PlanetPickerMessage::Select(index) => {
+        PLANET_PRESETS[index].apply(calc);
+        self.selected = Some(index);
+        self.expanded = false;
+        true
+      }
+    }
+  }
+
+  pub fn reload(&mut self, calc: &GridCalculator) {
+    self.selected = PlanetPreset::find(calc).and_then(|preset| PLANET_PRESETS.iter().position(|p| p == preset));
+  }
+
+  pub fn view(&mut self, label_width: Length) -> Element<PlanetPickerMessage> {
+    let selected = self.selected.map_or_else(|| tr("Custom"), |index| tr(PLANET_PRESETS[index].name));
+    let toggle = button(&mut self.toggle_button_state, format!("{} {}", selected, if self.expanded { "^" } else { "v" }))
+      .on_press(PlanetPickerMessage::Toggle);
+    let mut column = col()
+      .push(row().align_items(Align::Center).push(lbl(tr("Planet")).width(label_width)).push(toggle));
+    if self.expanded {
+      let mut presets = row().spacing(2);
+      for (index, (preset, button_state)) in PLANET_PRESETS.iter().zip(self.preset_button_states.iter_mut()).enumerate() {
+        presets = presets.push(button(button_state, tr(preset.name)).on_press(PlanetPickerMessage::Select(index)));
+      }
+      column = column.push(presets);
+    }
+    column.into()
+  }
+}
@@ -0,0 +1,101 @@
+use iced::{Align, button, Element, HorizontalAlignment, Length, text_input};
+
+use secalc_core::grid::{Direction, GridCalculated, GridCalculator};
+use secalc_core::grid::trip::{trip, Trip};
+use secalc_core::units::{Meters, MetersPerSecond, Unit};
+
+use crate::i18n::tr;
+use crate::view::{col, danger_color, empty, foreground_color, lbl, row, small_button, text_input, val};
+
+/// Travel distance and direction inputs, with the time and thruster fuel of a burn-coast-brake trip over that distance.
+pub struct TripPlanner {
+  distance: String,
+  distance_error: bool,
+  distance_state: text_input::State,
+  direction: Direction,
+  direction_button_state: button::State,
+  max_speed: MetersPerSecond,
+}
+
+#[derive(Clone, Debug)]
+pub enum TripPlannerMessage {
+  SetDistance(String),
+  NextDirection,
+}
+
+impl TripPlanner {
+  pub fn new(calc: &GridCalculator) -> Self {
+    Self {
+      distance: "10000".to_owned(),
+      distance_error: false,
+      distance_state: Default::default(),
+      direction: Direction::Front,
+      direction_button_state: Default::default(),
+      max_speed: calc.max_speed,
+    }
+  }
+
+  pub fn update(&mut self, message: TripPlannerMessage) {
+    match message {
+      TripPlannerMessage::SetDistance(distance) => {
+        self.distance_error = self.parse_distance(&distance).is_none();
+        self.distance = distance;
+      }
+      TripPlannerMessage::NextDirection => {
+        let directions: Vec<_> = Direction::iter().copied().collect();
+        let index = directions.iter().position(|d| *d == self.direction).unwrap_or_default();
+        self.direction = directions[(index + 1) % directions.len()];
+      }
+    }
+  }
+
+  pub fn reload(&mut self, calc: &GridCalculator) {
+    self.max_speed = calc.max_speed;
+  }
+
+  fn parse_distance(&self, distance: &str) -> Option<Meters> {
+    distance.trim().parse().ok().filter(|d: &f64| d.is_finite() && *d >= 0.0).map(Meters)
+  }
+
+  pub fn view(&mut self, calculated: &GridCalculated) -> Element<TripPlannerMessage> {
+    let (empty_trip, filled_trip) = match self.parse_distance(&self.distance) {
+      Some(distance) => (
+        trip(calculated, self.max_speed, self.direction, distance, false),
+        trip(calculated, self.max_speed, self.direction, distance, true),
+      ),
+      None => (None, None),
+    };
+    let rows: [(&str, fn(&Trip) -> String); 7] = [
+      ("Top Speed", |t| format!("{} m/s", t.top_speed.format())),
+      ("Accelerate", |t| format!("{} s", t.accelerate_duration.format())),
+      ("Coast", |t| format!("{} s", t.coast_duration.format())),
+      ("Brake", |t| format!("{} s", t.brake_duration.format())),
+      ("Duration", |t| format!("{} s", t.duration.format())),
+      ("Hydrogen", |t| format!("{} L", t.hydrogen.format())),
+      ("Power", |t| format!("{} MWh", t.power.format())),
+    ];
+    let mut labels = col().spacing(1).push(empty());
+    let mut empty_values = col().spacing(1).push(lbl(tr("Empty")).horizontal_alignment(HorizontalAlignment::Center));
+    let mut filled_values = col().spacing(1).push(lbl(tr("Filled")).horizontal_alignment(HorizontalAlignment::Center));
+    for (label, format) in rows.iter() {
+      labels = labels.push(lbl(tr(label)));
+      empty_values = empty_values.push(val(empty_trip.as_ref().map_or_else(|| "-".to_owned(), format)));
+      filled_values = filled_values.push(val(filled_trip.as_ref().map_or_else(|| "-".to_owned(), format)));
+    }
+    col()
+      .push(row()
+        .spacing(2)
+        .align_items(Align::Center)
+        .push(lbl(tr("Distance (m)")).color(if self.distance_error { danger_color() } else { foreground_color() }))
+        .push(text_input(Length::Units(95), &mut self.distance_state, "0", &self.distance, TripPlannerMessage::SetDistance))
+        .push(small_button(&mut self.direction_button_state, format!("{:?}", self.direction)).on_press(TripPlannerMessage::NextDirection))
+      )
+      .push(row()
+        .spacing(10)
+        .push(labels)
+        .push(empty_values)
+        .push(filled_values)
+      )
+      .into()
+  }
+}
@@ -1,6 +1,7 @@
 use iced::{Align, button, Element};
 
 use crate::storage::Storage;
+use crate::i18n::tr;
 use crate::view::{button, col, h1, h3, row};
 use std::ops::Deref;
 
@@ -42,15 +43,15 @@ impl Page {
       .push(row()
         .spacing(10)
         .align_items(Align::Center)
-        .push(h1("Load"))
-        .push(button(&mut self.cancel_button_state, "Cancel").on_press(Message::Cancel))
+        .push(h1(tr("Load")))
+        .push(button(&mut self.cancel_button_state, tr("Cancel")).on_press(Message::Cancel))
       )
       ;
     for (name, button_state) in &mut self.load_states {
       column = column.push(row()
         .spacing(10)
         .push(h3(name.deref()))
-        .push(button(button_state, "Load").on_press(Message::Load(name.clone())))
+        .push(button(button_state, tr("Load")).on_press(Message::Load(name.clone())))
       )
     }
     column.into()
@@ -1,5 +1,6 @@
 use iced::{Align, button, Element};
 
+use crate::i18n::tr;
 use crate::view::{button, col, h1, row};
 
 #[derive(Default, Debug)]
@@ -37,12 +38,12 @@ impl Page {
       .push(row()
         .spacing(10)
         .align_items(Align::End)
-        .push(h1("Unsaved changes - discard?"))
+        .push(h1(tr("Unsaved changes - discard?")))
       )
       .push(row()
         .spacing(10)
-        .push(button(&mut self.cancel_button_state, "Cancel").on_press(Message::Cancel))
-        .push(button(&mut self.discard_button_state, "Discard unsaved changes")/*.background(danger_color())*/.on_press(Message::Discard))
+        .push(button(&mut self.cancel_button_state, tr("Cancel")).on_press(Message::Cancel))
+        .push(button(&mut self.discard_button_state, tr("Discard unsaved changes"))/*.background(danger_color())*/.on_press(Message::Discard))
       )
       .into()
   }
@@ -3,3 +3,12 @@ pub mod save_as;
 pub mod save_overwrite_confirm;
 pub mod load_confirm_discard;
 pub mod load;
+pub mod presets;
+pub mod templates;
+pub mod start;
+pub mod settings;
+pub mod compare;
+pub mod environments;
+pub mod breakdown;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod charts;
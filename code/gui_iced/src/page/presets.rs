@@ -0,0 +1,61 @@
+use iced::{Align, button, Element};
+
+use secalc_core::grid::preset::{GRID_PRESETS, GridPreset};
+
+use crate::i18n::tr;
+use crate::view::{button, col, h1, h3, lbl, row};
+
+#[derive(Debug)]
+pub struct Page {
+  open_button_states: Vec<button::State>,
+  cancel_button_state: button::State,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+  Open(usize),
+  Cancel,
+}
+
+#[derive(Debug)]
+pub enum Action {
+  Open(&'static GridPreset),
+  Cancel,
+}
+
+impl Page {
+  pub fn new() -> Self {
+    let open_button_states = GRID_PRESETS.iter().map(|_| button::State::default()).collect();
+    Self { open_button_states, cancel_button_state: button::State::default() }
+  }
+
+  pub fn update(&mut self, message: Message) -> Option<Action> {
+    match message {
+      Message::Open(index) => GRID_PRESETS.get(index).map(Action::Open),
+      Message::Cancel => Some(Action::Cancel),
+    }
+  }
+
+  pub fn view(&mut self) -> Element<Message> {
+    let mut column = col()
+      .spacing(10)
+      .padding(10)
+      .push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(h1(tr("New from preset")))
+        .push(button(&mut self.cancel_button_state, tr("Cancel")).on_press(Message::Cancel))
+      )
+      ;
+    for (index, (preset, button_state)) in GRID_PRESETS.iter().zip(&mut self.open_button_states).enumerate() {
+      column = column.push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(button(button_state, tr("Open")).on_press(Message::Open(index)))
+        .push(h3(tr(preset.name)))
+        .push(lbl(tr(preset.description)))
+      )
+    }
+    column.into()
+  }
+}
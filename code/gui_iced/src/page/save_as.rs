@@ -1,5 +1,6 @@
 use iced::{Align, button, Element, Length, text_input};
 
+use crate::i18n::tr;
 use crate::view::{button, col, danger_color, foreground_color, h1, lbl, row, text_input};
 
 #[derive(Debug)]
@@ -52,17 +53,17 @@ impl Page {
       .push(row()
         .spacing(10)
         .align_items(Align::Center)
-        .push(h1("Save as"))
-        .push(button(&mut self.cancel_button_state, "Cancel").on_press(Message::Cancel))
+        .push(h1(tr("Save as")))
+        .push(button(&mut self.cancel_button_state, tr("Cancel")).on_press(Message::Cancel))
       )
       .push(row()
         .spacing(10)
-        .push(lbl("Name: ").color(if self.name.is_empty() { danger_color() } else { foreground_color() }))
+        .push(lbl(tr("Name: ")).color(if self.name.is_empty() { danger_color() } else { foreground_color() }))
         .push(text_input(Length::Units(250), &mut self.name_input_state, "", &self.name, Message::SetName))
       )
       .push(row()
         .spacing(10)
-        .push(button(&mut self.save_button_state, "Save").on_press(Message::Save))
+        .push(button(&mut self.save_button_state, tr("Save")).on_press(Message::Save))
       )
       .into()
   }
@@ -1,5 +1,6 @@
 use iced::{Align, button, Element};
 
+use crate::i18n::tr;
 use crate::view::{button, col, h1, row};
 
 #[derive(Debug)]
@@ -44,12 +45,12 @@ impl Page {
       .push(row()
         .spacing(10)
         .align_items(Align::End)
-        .push(h1("Overwrite?"))
+        .push(h1(tr("Overwrite?")))
       )
       .push(row()
         .spacing(10)
-        .push(button(&mut self.cancel_button_state, "Cancel").on_press(Message::Cancel))
-        .push(button(&mut self.overwrite_button_state, "Overwrite")/*.background(danger_color())*/.on_press(Message::Overwrite))
+        .push(button(&mut self.cancel_button_state, tr("Cancel")).on_press(Message::Cancel))
+        .push(button(&mut self.overwrite_button_state, tr("Overwrite"))/*.background(danger_color())*/.on_press(Message::Overwrite))
       )
       .into()
   }
@@ -0,0 +1,191 @@
+use std::path::PathBuf;
+
+use iced::{Align, button, Element, Length, text_input};
+
+use secalc_core::config::{Config, Theme};
+use secalc_core::format::{AccelerationUnit, MassUnit, PowerUnit, VolumeUnit};
+
+use crate::data_bind::{DataBind, DataBindMessage};
+use crate::i18n::tr;
+use crate::view::{button, col, h1, h2, lbl, row, text_input};
+
+#[derive(Debug)]
+pub struct Page {
+  config: Config,
+  gravity_multiplier: DataBind<f64>,
+  container_multiplier: DataBind<f64>,
+  planetary_influence: DataBind<f64>,
+  data_path_input_state: text_input::State,
+  custom_blocks_path_input_state: text_input::State,
+  translations_path_input_state: text_input::State,
+  system_button_state: button::State,
+  light_button_state: button::State,
+  dark_button_state: button::State,
+  kilogram_button_state: button::State,
+  tonne_button_state: button::State,
+  liter_button_state: button::State,
+  cubic_meter_button_state: button::State,
+  kilowatt_button_state: button::State,
+  megawatt_button_state: button::State,
+  meters_per_second_squared_button_state: button::State,
+  gravity_button_state: button::State,
+  save_button_state: button::State,
+  cancel_button_state: button::State,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+  SetTheme(Theme),
+  SetMassUnit(MassUnit),
+  SetVolumeUnit(VolumeUnit),
+  SetPowerUnit(PowerUnit),
+  SetAccelerationUnit(AccelerationUnit),
+  GravityMultiplier(DataBindMessage),
+  ContainerMultiplier(DataBindMessage),
+  PlanetaryInfluence(DataBindMessage),
+  SetDataPath(String),
+  SetCustomBlocksPath(String),
+  SetTranslationsPath(String),
+  Save,
+  Cancel,
+}
+
+#[derive(Debug)]
+pub enum Action {
+  Save(Config),
+  Cancel,
+}
+
+impl Page {
+  pub fn new(config: &Config) -> Self {
+    let defaults = Config::default();
+    let input_width = Length::Units(95);
+    Self {
+      config: config.clone(),
+      gravity_multiplier: DataBind::new(defaults.gravity_multiplier, format!("{:.1}", defaults.gravity_multiplier), input_width, "*", format!("{}", config.gravity_multiplier)),
+      container_multiplier: DataBind::new(defaults.container_multiplier, format!("{:.1}", defaults.container_multiplier), input_width, "*", format!("{}", config.container_multiplier)),
+      planetary_influence: DataBind::new(defaults.planetary_influence, format!("{:.1}", defaults.planetary_influence), input_width, "*", format!("{}", config.planetary_influence)),
+      data_path_input_state: Default::default(),
+      custom_blocks_path_input_state: Default::default(),
+      translations_path_input_state: Default::default(),
+      system_button_state: Default::default(),
+      light_button_state: Default::default(),
+      dark_button_state: Default::default(),
+      kilogram_button_state: Default::default(),
+      tonne_button_state: Default::default(),
+      liter_button_state: Default::default(),
+      cubic_meter_button_state: Default::default(),
+      kilowatt_button_state: Default::default(),
+      megawatt_button_state: Default::default(),
+      meters_per_second_squared_button_state: Default::default(),
+      gravity_button_state: Default::default(),
+      save_button_state: Default::default(),
+      cancel_button_state: Default::default(),
+    }
+  }
+
+  pub fn update(&mut self, message: Message) -> Option<Action> {
+    match message {
+      Message::SetTheme(theme) => self.config.theme = theme,
+      Message::SetMassUnit(unit) => self.config.units.mass = unit,
+      Message::SetVolumeUnit(unit) => self.config.units.volume = unit,
+      Message::SetPowerUnit(unit) => self.config.units.power = unit,
+      Message::SetAccelerationUnit(unit) => self.config.units.acceleration = unit,
+      Message::GravityMultiplier(m) => self.gravity_multiplier.update(m, &mut self.config.gravity_multiplier),
+      Message::ContainerMultiplier(m) => self.container_multiplier.update(m, &mut self.config.container_multiplier),
+      Message::PlanetaryInfluence(m) => self.planetary_influence.update(m, &mut self.config.planetary_influence),
+      Message::SetDataPath(data_path) => self.config.data_path = if data_path.is_empty() { None } else { Some(PathBuf::from(data_path)) },
+      Message::SetCustomBlocksPath(custom_blocks_path) => self.config.custom_blocks_path = if custom_blocks_path.is_empty() { None } else { Some(PathBuf::from(custom_blocks_path)) },
+      Message::SetTranslationsPath(translations_path) => self.config.translations_path = if translations_path.is_empty() { None } else { Some(PathBuf::from(translations_path)) },
+      Message::Save => return Some(Action::Save(self.config.clone())),
+      Message::Cancel => return Some(Action::Cancel),
+    }
+    None
+  }
+
+  pub fn view(&mut self) -> Element<Message> {
+    let label_width = Length::Units(200);
+    let choice = |state, label, selected: bool, message| {
+      let button = button(state, if selected { format!("[{}]", label) } else { label.to_owned() });
+      if selected { button } else { button.on_press(message) }
+    };
+    let theme = self.config.theme;
+    let units = self.config.units;
+    let data_path = self.config.data_path.as_ref().map_or_else(String::new, |p| p.display().to_string());
+    let custom_blocks_path = self.config.custom_blocks_path.as_ref().map_or_else(String::new, |p| p.display().to_string());
+    let translations_path = self.config.translations_path.as_ref().map_or_else(String::new, |p| p.display().to_string());
+    col()
+      .padding(10)
+      .spacing(10)
+      .push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(h1(tr("Settings")))
+        .push(button(&mut self.save_button_state, tr("Save")).on_press(Message::Save))
+        .push(button(&mut self.cancel_button_state, tr("Cancel")).on_press(Message::Cancel))
+      )
+      .push(h2(tr("Appearance")))
+      .push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(lbl(tr("Theme")).width(label_width))
+        .push(choice(&mut self.system_button_state, tr("System default"), theme == Theme::System, Message::SetTheme(Theme::System)))
+        .push(choice(&mut self.light_button_state, tr("Light"), theme == Theme::Light, Message::SetTheme(Theme::Light)))
+        .push(choice(&mut self.dark_button_state, tr("Dark"), theme == Theme::Dark, Message::SetTheme(Theme::Dark)))
+      )
+      .push(h2(tr("Units")))
+      .push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(lbl(tr("Mass")).width(label_width))
+        .push(choice(&mut self.kilogram_button_state, "kg".to_owned(), units.mass == MassUnit::Kilogram, Message::SetMassUnit(MassUnit::Kilogram)))
+        .push(choice(&mut self.tonne_button_state, "t".to_owned(), units.mass == MassUnit::Tonne, Message::SetMassUnit(MassUnit::Tonne)))
+      )
+      .push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(lbl(tr("Volume")).width(label_width))
+        .push(choice(&mut self.liter_button_state, "L".to_owned(), units.volume == VolumeUnit::Liter, Message::SetVolumeUnit(VolumeUnit::Liter)))
+        .push(choice(&mut self.cubic_meter_button_state, "m³".to_owned(), units.volume == VolumeUnit::CubicMeter, Message::SetVolumeUnit(VolumeUnit::CubicMeter)))
+      )
+      .push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(lbl(tr("Power")).width(label_width))
+        .push(choice(&mut self.kilowatt_button_state, "kW".to_owned(), units.power == PowerUnit::Kilowatt, Message::SetPowerUnit(PowerUnit::Kilowatt)))
+        .push(choice(&mut self.megawatt_button_state, "MW".to_owned(), units.power == PowerUnit::Megawatt, Message::SetPowerUnit(PowerUnit::Megawatt)))
+      )
+      .push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(lbl(tr("Acceleration")).width(label_width))
+        .push(choice(&mut self.meters_per_second_squared_button_state, "m/s²".to_owned(), units.acceleration == AccelerationUnit::MetersPerSecondSquared, Message::SetAccelerationUnit(AccelerationUnit::MetersPerSecondSquared)))
+        .push(choice(&mut self.gravity_button_state, "g".to_owned(), units.acceleration == AccelerationUnit::Gravity, Message::SetAccelerationUnit(AccelerationUnit::Gravity)))
+      )
+      .push(h2(tr("Defaults for new calculations")))
+      .push(row().align_items(Align::Center).push(lbl(tr("Gravity Multiplier")).width(label_width)).push(self.gravity_multiplier.view().map(Message::GravityMultiplier)))
+      .push(row().align_items(Align::Center).push(lbl(tr("Container Multiplier")).width(label_width)).push(self.container_multiplier.view().map(Message::ContainerMultiplier)))
+      .push(row().align_items(Align::Center).push(lbl(tr("Planetary Influence")).width(label_width)).push(self.planetary_influence.view().map(Message::PlanetaryInfluence)))
+      .push(h2(tr("Data")))
+      .push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(lbl(tr("Data file or directory (empty for built-in)")).width(label_width))
+        .push(text_input(Length::Units(500), &mut self.data_path_input_state, "", &data_path, Message::SetDataPath))
+      )
+      .push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(lbl(tr("Custom blocks file (empty for none)")).width(label_width))
+        .push(text_input(Length::Units(500), &mut self.custom_blocks_path_input_state, "", &custom_blocks_path, Message::SetCustomBlocksPath))
+      )
+      .push(h2(tr("Language")))
+      .push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(lbl(tr("Translations file (empty for English)")).width(label_width))
+        .push(text_input(Length::Units(500), &mut self.translations_path_input_state, "", &translations_path, Message::SetTranslationsPath))
+      )
+      .into()
+  }
+}
@@ -0,0 +1,80 @@
+use std::ops::Deref;
+
+use iced::{Align, button, Element};
+
+use crate::storage::Storage;
+use crate::i18n::{tr, tr_format};
+use crate::view::{button, col, h1, h2, h3, lbl, row};
+
+#[derive(Debug)]
+pub struct Page {
+  current: Option<String>,
+  current_modified: bool,
+  recent_states: Vec<(String, button::State)>,
+  continue_button_state: button::State,
+  new_button_state: button::State,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+  Continue,
+  New,
+  Open(String),
+}
+
+#[derive(Debug)]
+pub enum Action {
+  Continue,
+  New,
+  Open(String),
+}
+
+impl Page {
+  pub fn new(storage: &Storage) -> Self {
+    let recent_states = storage.iter_recent_calculators().map(|name| (name.clone(), button::State::default())).collect();
+    Self {
+      current: storage.calculator_name.clone(),
+      current_modified: storage.calculator_modified,
+      recent_states,
+      continue_button_state: Default::default(),
+      new_button_state: Default::default(),
+    }
+  }
+
+  pub fn update(&mut self, message: Message) -> Action {
+    match message {
+      Message::Continue => Action::Continue,
+      Message::New => Action::New,
+      Message::Open(name) => Action::Open(name),
+    }
+  }
+
+  pub fn view(&mut self) -> Element<Message> {
+    let name = self.current.clone().unwrap_or_else(|| tr("untitled calculation"));
+    let current = if self.current_modified { tr_format("Continue with {} (unsaved changes)", &[&name]) } else { tr_format("Continue with {}", &[&name]) };
+    let mut column = col()
+      .spacing(10)
+      .padding(10)
+      .push(h1(tr("Space Engineers Calculator")))
+      .push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(button(&mut self.continue_button_state, current).on_press(Message::Continue))
+        .push(button(&mut self.new_button_state, tr("New calculation")).on_press(Message::New))
+      )
+      .push(h2(tr("Recent calculations")))
+      ;
+    if self.recent_states.is_empty() {
+      column = column.push(lbl(tr("No saved calculations yet")));
+    }
+    for (name, button_state) in &mut self.recent_states {
+      column = column.push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(h3(name.deref()))
+        .push(button(button_state, tr("Open")).on_press(Message::Open(name.clone())))
+      )
+    }
+    column.into()
+  }
+}
@@ -0,0 +1,126 @@
+use iced::{Align, button, Element, Length, text_input};
+
+use secalc_core::grid::template::GridTemplates;
+
+use crate::i18n::tr;
+use crate::view::{button, col, danger_color, foreground_color, h1, h2, h3, lbl, row, text_input};
+
+#[derive(Debug)]
+pub struct Page {
+  template_states: Vec<(String, button::State, button::State)>,
+  name: String,
+  name_input_state: text_input::State,
+  description: String,
+  description_input_state: text_input::State,
+  save_button_state: button::State,
+  cancel_button_state: button::State,
+  /// Error of the last template that could not be applied.
+  error: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+  Apply(String),
+  Delete(String),
+  SetName(String),
+  SetDescription(String),
+  Save,
+  Cancel,
+}
+
+#[derive(Debug)]
+pub enum Action {
+  /// Apply the template with given name onto the current calculator.
+  Apply(String),
+  /// Delete the template with given name.
+  Delete(String),
+  /// Save the current calculator as a template with given name and description.
+  Save(String, String),
+  Cancel,
+}
+
+impl Page {
+  pub fn new(templates: &GridTemplates) -> Self {
+    let template_states = templates.iter().map(|t| (t.name.clone(), button::State::default(), button::State::default())).collect();
+    Self {
+      template_states,
+      name: String::new(),
+      name_input_state: Default::default(),
+      description: String::new(),
+      description_input_state: Default::default(),
+      save_button_state: Default::default(),
+      cancel_button_state: Default::default(),
+      error: None,
+    }
+  }
+
+  /// Shows `error` to explain why a template could not be applied.
+  pub fn set_error(&mut self, error: String) {
+    self.error = Some(error);
+  }
+
+  pub fn update(&mut self, message: Message) -> Option<Action> {
+    match message {
+      Message::Apply(name) => Some(Action::Apply(name)),
+      Message::Delete(name) => Some(Action::Delete(name)),
+      Message::SetName(name) => {
+        self.name = name;
+        None
+      },
+      Message::SetDescription(description) => {
+        self.description = description;
+        None
+      },
+      Message::Save if !self.name.trim().is_empty() => Some(Action::Save(self.name.trim().to_owned(), self.description.trim().to_owned())),
+      Message::Cancel => Some(Action::Cancel),
+      _ => None,
+    }
+  }
+
+  pub fn view(&mut self, templates: &GridTemplates) -> Element<Message> {
+    let mut column = col()
+      .spacing(10)
+      .padding(10)
+      .push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(h1(tr("Templates")))
+        .push(button(&mut self.cancel_button_state, tr("Cancel")).on_press(Message::Cancel))
+      )
+      ;
+    if let Some(error) = &self.error {
+      column = column.push(lbl(error.as_str()).color(danger_color()));
+    }
+    if self.template_states.is_empty() {
+      column = column.push(lbl(tr("There are no templates yet.")));
+    }
+    for (name, apply_button_state, delete_button_state) in &mut self.template_states {
+      let description = templates.get(name).map_or("", |t| t.description.as_str());
+      column = column.push(row()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(button(apply_button_state, tr("Apply")).on_press(Message::Apply(name.clone())))
+        .push(button(delete_button_state, tr("Delete")).on_press(Message::Delete(name.clone())))
+        .push(h3(name.as_str()))
+        .push(lbl(description))
+      )
+    }
+    column
+      .push(h2(tr("Save current calculator as template")))
+      .push(row()
+        .spacing(10)
+        .push(lbl(tr("Name: ")).color(if self.name.trim().is_empty() { danger_color() } else { foreground_color() }))
+        .push(text_input(Length::Units(250), &mut self.name_input_state, "", &self.name, Message::SetName))
+      )
+      .push(row()
+        .spacing(10)
+        .push(lbl(tr("Description: ")))
+        .push(text_input(Length::Units(400), &mut self.description_input_state, "", &self.description, Message::SetDescription))
+      )
+      .push(row()
+        .spacing(10)
+        .push(button(&mut self.save_button_state, tr("Save")).on_press(Message::Save))
+      )
+      .into()
+  }
+}
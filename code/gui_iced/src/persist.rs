@@ -0,0 +1,94 @@
+//! Persisting strings under a key: to a file in the configuration directory natively, or to local storage on the web.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use self::native::{load_string, save_string};
+#[cfg(target_arch = "wasm32")]
+pub use self::web::{load_string, save_string};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+  use std::path::PathBuf;
+
+  use anyhow::{Context, format_err, Result};
+
+  pub fn save_string(key: &str, string: &str) -> Result<()> {
+    let file = get_file(key)?;
+    std::fs::write(&file, string)
+      .with_context(|| format!("Failed to write file '{:?}'", file))
+  }
+
+  pub fn load_string(key: &str) -> Result<Option<String>> {
+    let file = get_file(key)?;
+    if !file.exists() {
+      Ok(None)
+    } else {
+      let string = std::fs::read_to_string(&file)
+        .with_context(|| format!("Failed to read file '{:?}'", file))?;
+      Ok(Some(string))
+    }
+  }
+
+  fn get_file(key: &str) -> Result<PathBuf> {
+    let dir = get_dir()?;
+    std::fs::create_dir_all(dir.clone())
+      .with_context(|| format!("Failed to create directory '{:?}'", dir))?;
+    Ok(dir.join(format!("{}.json", key)))
+  }
+
+  const SUBDIR: &'static str = "SECalc";
+
+  fn get_dir() -> Result<PathBuf> {
+    if let Some(dir) = dirs::config_dir() {
+      return Ok(dir.join(SUBDIR));
+    }
+    if let Ok(dir) = std::env::current_dir() {
+      return Ok(dir);
+    }
+    if let Ok(exe) = std::env::current_exe() {
+      if let Some(dir) = exe.parent() {
+        return Ok(dir.into());
+      }
+    }
+    if let Some(dir) = dirs::home_dir() {
+      return Ok(dir.join(SUBDIR));
+    }
+    return Err(format_err!("Failed to get storage directory"))
+  }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod web {
+  use anyhow::{format_err, Result};
+  use thiserror::Error;
+
+  #[derive(Error, Debug)]
+  pub enum GetLocalStorageError {
+    #[error("Could not get local storage; cannot get window")]
+    NoWindow,
+    #[error("Could not get local storage; window returned no storage")]
+    NoLocalStorage,
+    #[error("Cannot get local storage; an error occurred")]
+    LocalStorageError(String)
+  }
+
+  const KEY_PREFIX: &'static str = "secalc_";
+
+  pub fn save_string(key: &str, string: &str) -> Result<()> {
+    let storage = get_local_storage()?;
+    Ok(storage.set(&format!("{}{}", KEY_PREFIX, key), string).map_err(|e| format_err!("{:?}", e))?)
+  }
+
+  pub fn load_string(key: &str) -> Result<Option<String>> {
+    let storage = get_local_storage()?;
+    Ok(storage.get(&format!("{}{}", KEY_PREFIX, key)).map_err(|e| format_err!("{:?}", e))?)
+  }
+
+  fn get_local_storage() -> Result<web_sys::Storage, GetLocalStorageError> {
+    web_sys::window()
+      .ok_or(GetLocalStorageError::NoWindow)?
+      .local_storage()
+      // Format exception into string, as JsValue is not `Send` and thus cannot be converted into `dyn Error` by `anyhow` later.
+      .map_err(|e| GetLocalStorageError::LocalStorageError(format!("{:?}", e)))?
+      .ok_or(GetLocalStorageError::NoLocalStorage)
+  }
+}
@@ -0,0 +1,95 @@
+use iced::{Align, button, Element, Length, text_input};
+
+use crate::view::{danger_color, foreground_color, lbl, row, small_button, text_input};
+
+/// Input for a non-negative integer count: a text input with buttons to decrement and increment the count. Like
+/// `DataBind`, an empty input stands for the default count, and invalid input is highlighted and treated as the default.
+#[derive(Debug)]
+pub struct Stepper {
+  input_default: u64,
+  input_width: Length,
+  value: String,
+  error: bool,
+  state: text_input::State,
+  decrement_state: button::State,
+  increment_state: button::State,
+}
+
+#[derive(Clone, Debug)]
+pub enum StepperMessage {
+  Text(String),
+  Step(i64),
+}
+
+impl Stepper {
+  pub fn new<V: Into<String>>(input_default: u64, input_width: Length, value: V) -> Self {
+    let (value, error) = Self::value_and_error(value.into(), input_default);
+    Self {
+      input_default,
+      input_width,
+      value,
+      error,
+      state: Default::default(),
+      decrement_state: Default::default(),
+      increment_state: Default::default(),
+    }
+  }
+
+  fn value_and_error(val: String, default: u64) -> (String, bool) {
+    match val.parse::<u64>() {
+      Ok(parsed) if parsed == default => ("".to_owned(), false),
+      Ok(_) => (val, false),
+      Err(_) => (val, true),
+    }
+  }
+
+  pub fn update(&mut self, message: StepperMessage, val: &mut u64) {
+    match message {
+      StepperMessage::Text(text) => {
+        let (v, error) = match (text.trim().is_empty(), text.trim().parse()) {
+          (true, _) => (self.input_default, false),
+          (_, Err(_)) => (self.input_default, true),
+          (false, Ok(v)) => (v, false)
+        };
+        *val = v;
+        self.value = text;
+        self.error = error;
+      }
+      StepperMessage::Step(steps) => {
+        let v = if steps < 0 { val.saturating_sub((-steps) as u64) } else { val.saturating_add(steps as u64) };
+        *val = v;
+        self.reload(format!("{}", v));
+      }
+    }
+  }
+
+  pub fn reload(&mut self, val: String) {
+    let (value, error) = Self::value_and_error(val, self.input_default);
+    self.value = value;
+    self.error = error;
+  }
+
+  /// Returns true if the text input of this stepper has keyboard focus, in which case mouse wheel steps apply to it.
+  pub fn is_focused(&self) -> bool {
+    self.state.is_focused()
+  }
+
+  pub fn view(&mut self) -> Element<StepperMessage> {
+    let placeholder = format!("{}", self.input_default);
+    let input = text_input(self.input_width, &mut self.state, &placeholder, &self.value, StepperMessage::Text)
+      .padding(1)
+      ;
+    let unit = lbl("#")
+      .color(if self.error { danger_color() } else { foreground_color() })
+      ;
+    row()
+      .spacing(1)
+      .padding(1)
+      .align_items(Align::Center)
+      .push(small_button(&mut self.decrement_state, "-").on_press(StepperMessage::Step(-1)))
+      .push(input)
+      .push(small_button(&mut self.increment_state, "+").on_press(StepperMessage::Step(1)))
+      .push(unit)
+      .into()
+  }
+}
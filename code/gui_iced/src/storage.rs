@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::io;
 
 use anyhow::{format_err, Result};
@@ -7,11 +8,33 @@ use serde::{Deserialize, Serialize};
 
 use secalc_core::grid::GridCalculator;
 
+use crate::persist;
+
+/// Calculator that is open in a tab other than the current one.
+#[derive(Default, Serialize, Deserialize)]
+struct BackgroundTab {
+  calculator: GridCalculator,
+  calculator_name: Option<String>,
+  calculator_modified: bool,
+}
+
 #[derive(Default, Serialize, Deserialize)]
 pub struct Storage {
   pub calculator: GridCalculator,
   pub calculator_name: Option<String>,
   pub calculator_modified: bool,
+  #[serde(default)]
+  pub collapsed_panels: HashSet<String>,
+
+  /// Calculators open in other tabs, in tab order. The current calculator is at index `current_tab` between them.
+  #[serde(default)]
+  background_tabs: Vec<BackgroundTab>,
+  #[serde(default)]
+  current_tab: usize,
+
+  /// Names of recently saved or loaded calculators, most recent first.
+  #[serde(default)]
+  recent_calculators: Vec<String>,
 
   saved_calculators: LinkedHashMap<String, GridCalculator>
 }
@@ -25,19 +48,43 @@ impl Storage {
     self.saved_calculators.iter()
   }
 
+  pub fn iter_recent_calculators(&self) -> impl Iterator<Item=&String> {
+    self.recent_calculators.iter().filter(move |name| self.saved_calculators.contains_key(*name))
+  }
+
+
+  pub fn new_calculator(&mut self, calculator: GridCalculator) -> Result<()> {
+    self.calculator = calculator;
+    self.calculator_name = None;
+    self.calculator_modified = false;
+    self.save()
+  }
+
+
+  /// Replaces the current calculator with `calculator` opened from outside of storage, under `name`. The calculator is
+  /// marked as modified because it is not saved in storage yet.
+  pub fn open_calculator(&mut self, name: String, calculator: GridCalculator) -> Result<()> {
+    self.calculator = calculator;
+    self.calculator_name = Some(name);
+    self.calculator_modified = true;
+    self.save()
+  }
+
 
   pub fn save_calculator(&mut self, name: String) -> Result<()> {
     self.calculator_name = Some(name.clone());
     self.calculator_modified = false;
-    self.saved_calculators.insert(name, self.calculator.clone());
+    self.saved_calculators.insert(name.clone(), self.calculator.clone());
+    self.push_recent_calculator(name);
     self.save()
   }
 
   pub fn load_calculator(&mut self, name: String) -> Result<()> {
     if let Some(calculator) = self.saved_calculators.get(&name) {
       self.calculator = calculator.clone();
-      self.calculator_name = Some(name);
+      self.calculator_name = Some(name.clone());
       self.calculator_modified = false;
+      self.push_recent_calculator(name);
       self.save()
     } else {
       Err(format_err!("No saved calculator with name '{}' was found", name))
@@ -45,14 +92,80 @@ impl Storage {
   }
 
 
+  pub fn tab_count(&self) -> usize { self.background_tabs.len() + 1 }
+
+  pub fn current_tab(&self) -> usize { self.current_tab }
+
+  /// Iterates over the name and modified flag of the calculators in all tabs, in tab order.
+  pub fn iter_tabs(&self) -> impl Iterator<Item=(Option<&str>, bool)> {
+    let background = self.background_tabs.iter().map(|tab| (tab.calculator_name.as_deref(), tab.calculator_modified));
+    background.clone().take(self.current_tab)
+      .chain(std::iter::once((self.calculator_name.as_deref(), self.calculator_modified)))
+      .chain(background.skip(self.current_tab))
+  }
+
+  /// Opens `calculator` in a new tab after all other tabs, and makes it the current tab.
+  pub fn new_tab(&mut self, calculator: GridCalculator) -> Result<()> {
+    let previous = self.replace_current(BackgroundTab { calculator, calculator_name: None, calculator_modified: false });
+    self.background_tabs.insert(self.current_tab, previous);
+    self.current_tab = self.background_tabs.len();
+    self.save()
+  }
+
+  /// Makes the tab at `index` the current tab.
+  pub fn switch_tab(&mut self, index: usize) -> Result<()> {
+    if index == self.current_tab || index >= self.tab_count() { return Ok(()); }
+    let (background_index, previous_index) = if index > self.current_tab {
+      (index - 1, self.current_tab)
+    } else {
+      (index, self.current_tab - 1)
+    };
+    let tab = self.background_tabs.remove(background_index);
+    let previous = self.replace_current(tab);
+    self.background_tabs.insert(previous_index, previous);
+    self.current_tab = index;
+    self.save()
+  }
+
+  /// Closes the current tab, discarding its calculator, and makes the next (or otherwise previous) tab current. Does
+  /// nothing if this is the only tab.
+  pub fn close_tab(&mut self) -> Result<()> {
+    if self.background_tabs.is_empty() { return Ok(()); }
+    if self.current_tab == self.background_tabs.len() {
+      self.current_tab -= 1;
+    }
+    let tab = self.background_tabs.remove(self.current_tab);
+    self.replace_current(tab);
+    self.save()
+  }
+
+  fn replace_current(&mut self, tab: BackgroundTab) -> BackgroundTab {
+    BackgroundTab {
+      calculator: std::mem::replace(&mut self.calculator, tab.calculator),
+      calculator_name: std::mem::replace(&mut self.calculator_name, tab.calculator_name),
+      calculator_modified: std::mem::replace(&mut self.calculator_modified, tab.calculator_modified),
+    }
+  }
+
+
+  fn push_recent_calculator(&mut self, name: String) {
+    const MAX_RECENT_CALCULATORS: usize = 10;
+    self.recent_calculators.retain(|n| n != &name);
+    self.recent_calculators.insert(0, name);
+    self.recent_calculators.truncate(MAX_RECENT_CALCULATORS);
+  }
+
+
   pub fn save(&self) -> Result<()> {
-    self.save_internal()
+    persist::save_string(Self::KEY, &self.to_json_string()?)
   }
 
   pub fn load() -> Result<Option<Storage>> {
-    Self::load_internal()
+    persist::load_string(Self::KEY)?.map(|string| Self::from_json_string(&string)).transpose()
   }
 
+  const KEY: &'static str = "storage";
+
 
   pub fn from_json<R: io::Read>(reader: R) -> Result<Self> {
     Ok(serde_json::from_reader::<_, Self>(reader)?)
@@ -71,114 +184,12 @@ impl Storage {
   }
 }
 
+// Closing the window cannot be intercepted with the current version of iced, so there is no exit confirmation. Unsaved
+// changes are not lost however: the calculators of all tabs and their modified flags are saved here, and restored on
+// startup.
 impl Drop for Storage {
   fn drop(&mut self) {
     self.save()
       .unwrap_or_else(|e| error!("[BUG] Could not save storage: {}", e));
   }
 }
-
-#[cfg(not(target_arch = "wasm32"))]
-mod native {
-  use std::fs::OpenOptions;
-  use std::path::PathBuf;
-
-  use anyhow::{Context, format_err, Result};
-
-  use super::Storage;
-
-  impl Storage {
-    pub(crate) fn save_internal(&self) -> Result<()> {
-      let storage_file = Self::get_storage_file()?;
-      let writer = OpenOptions::new().write(true).create(true).open(storage_file.clone())
-        .with_context(|| format!("Failed to open file '{:?}' for writing", storage_file))?;
-      Ok(self.to_json(writer)?)
-    }
-
-    pub(crate) fn load_internal() -> Result<Option<Storage>> {
-      let storage_file = Self::get_storage_file()?;
-      if !storage_file.exists() {
-        Ok(None)
-      } else {
-        let reader = OpenOptions::new().read(true).open(storage_file.clone())
-          .with_context(|| format!("Failed to open file '{:?}' for reading", storage_file))?;
-        Ok(Some(Self::from_json(reader)?))
-      }
-    }
-
-    const STORAGE_FILE: &'static str = "storage.json";
-
-    fn get_storage_file() -> Result<PathBuf> {
-      let storage_dir = Self::get_storage_dir()?;
-      std::fs::create_dir_all(storage_dir.clone())
-        .with_context(|| format!("Failed to create directory '{:?}'", storage_dir))?;
-      Ok(storage_dir.join(Self::STORAGE_FILE))
-    }
-
-    const STORAGE_SUBDIR: &'static str = "SECalc";
-
-    fn get_storage_dir() -> Result<PathBuf> {
-      if let Some(dir) = dirs::config_dir() {
-        return Ok(dir.join(Self::STORAGE_SUBDIR));
-      }
-      if let Ok(dir) = std::env::current_dir() {
-        return Ok(dir);
-      }
-      if let Ok(exe) = std::env::current_exe() {
-        if let Some(dir) = exe.parent() {
-          return Ok(dir.into());
-        }
-      }
-      if let Some(dir) = dirs::home_dir() {
-        return Ok(dir.join(Self::STORAGE_SUBDIR));
-      }
-      return Err(format_err!("Failed to get storage directory"))
-    }
-  }
-}
-
-#[cfg(target_arch = "wasm32")]
-mod web {
-  use anyhow::{format_err, Result};
-  use thiserror::Error;
-
-  use super::Storage;
-
-  #[derive(Error, Debug)]
-  pub enum GetLocalStorageError {
-    #[error("Could not get local storage; cannot get window")]
-    NoWindow,
-    #[error("Could not get local storage; window returned no storage")]
-    NoLocalStorage,
-    #[error("Cannot get local storage; an error occurred")]
-    LocalStorageError(String)
-  }
-
-  const STORAGE_KEY: &'static str = "secalc_storage";
-
-  impl Storage {
-    pub(crate) fn save_internal(&self) -> Result<()> {
-      let storage = Self::get_local_storage()?;
-      let string = self.to_json_string()?;
-      Ok(storage.set(STORAGE_KEY, &string).map_err(|e| format_err!("{:?}", e))?)
-    }
-
-    pub(crate) fn load_internal() -> Result<Option<Storage>> {
-      let storage = Self::get_local_storage()?;
-      if let Some(string) = storage.get(STORAGE_KEY).map_err(|e| format_err!("{:?}", e))? {
-        Ok(Some(Self::from_json_string(&string)?))
-      } else {
-        Ok(None)
-      }
-    }
-
-    fn get_local_storage() -> Result<web_sys::Storage, GetLocalStorageError> {
-      web_sys::window()
-        .ok_or(GetLocalStorageError::NoWindow)?
-        .local_storage()
-        // Format exception into string, as JsValue is not `Send` and thus cannot be converted into `dyn Error` by `anyhow` later.
-        .map_err(|e| GetLocalStorageError::LocalStorageError(format!("{:?}", e)))?
-        .ok_or(GetLocalStorageError::NoLocalStorage)
-    }
-  }
-}
\ No newline at end of file
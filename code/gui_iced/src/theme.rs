@@ -0,0 +1,185 @@
+use std::cell::Cell;
+
+use iced::{Background, button, Color, container, text_input, Vector};
+use serde::{Deserialize, Serialize};
+
+use secalc_core::config;
+
+/// Color theme of the application.
+#[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub enum Theme {
+  Light,
+  Dark,
+}
+
+impl Default for Theme {
+  fn default() -> Self { Theme::Light }
+}
+
+/// Styles with light colors for `System`, as the system preference cannot be detected.
+impl From<config::Theme> for Theme {
+  fn from(theme: config::Theme) -> Self {
+    match theme {
+      config::Theme::System | config::Theme::Light => Theme::Light,
+      config::Theme::Dark => Theme::Dark,
+    }
+  }
+}
+
+impl From<Theme> for config::Theme {
+  fn from(theme: Theme) -> Self {
+    match theme {
+      Theme::Light => config::Theme::Light,
+      Theme::Dark => config::Theme::Dark,
+    }
+  }
+}
+
+thread_local! {
+  static CURRENT: Cell<Theme> = Cell::new(Theme::default());
+}
+
+impl Theme {
+  /// Gets the theme that views are currently styled with.
+  pub fn current() -> Theme {
+    CURRENT.with(|current| current.get())
+  }
+
+  /// Sets the theme that views are styled with from now on.
+  pub fn set_current(self) {
+    CURRENT.with(|current| current.set(self));
+  }
+
+  pub fn toggled(self) -> Theme {
+    match self {
+      Theme::Light => Theme::Dark,
+      Theme::Dark => Theme::Light,
+    }
+  }
+
+  pub fn palette(self) -> Palette {
+    match self {
+      Theme::Light => Palette {
+        background: Color::WHITE,
+        foreground: Color::BLACK,
+        danger: Color::from_rgb(0.8, 0.2, 0.2),
+        button: Color::from_rgb(0.85, 0.85, 0.85),
+        button_hovered: Color::from_rgb(0.75, 0.75, 0.75),
+        input: Color::WHITE,
+        border: Color::from_rgb(0.7, 0.7, 0.7),
+        border_focused: Color::from_rgb(0.4, 0.5, 0.8),
+        placeholder: Color::from_rgb(0.6, 0.6, 0.6),
+        selection: Color::from_rgb(0.8, 0.85, 1.0),
+      },
+      Theme::Dark => Palette {
+        background: Color::from_rgb(0.15, 0.15, 0.17),
+        foreground: Color::from_rgb(0.9, 0.9, 0.9),
+        danger: Color::from_rgb(1.0, 0.4, 0.4),
+        button: Color::from_rgb(0.3, 0.3, 0.33),
+        button_hovered: Color::from_rgb(0.4, 0.4, 0.43),
+        input: Color::from_rgb(0.2, 0.2, 0.22),
+        border: Color::from_rgb(0.4, 0.4, 0.42),
+        border_focused: Color::from_rgb(0.5, 0.6, 0.9),
+        placeholder: Color::from_rgb(0.55, 0.55, 0.55),
+        selection: Color::from_rgb(0.3, 0.35, 0.5),
+      },
+    }
+  }
+}
+
+/// Colors of a theme.
+#[derive(Copy, Clone, Debug)]
+pub struct Palette {
+  pub background: Color,
+  pub foreground: Color,
+  pub danger: Color,
+  pub button: Color,
+  pub button_hovered: Color,
+  pub input: Color,
+  pub border: Color,
+  pub border_focused: Color,
+  pub placeholder: Color,
+  pub selection: Color,
+}
+
+
+/// Style sheet of the root container, setting the background and default text color.
+pub struct RootStyle(pub Palette);
+
+impl container::StyleSheet for RootStyle {
+  fn style(&self) -> container::Style {
+    container::Style {
+      text_color: Some(self.0.foreground),
+      background: Some(Background::Color(self.0.background)),
+      ..container::Style::default()
+    }
+  }
+}
+
+pub struct ButtonStyle(pub Palette);
+
+impl button::StyleSheet for ButtonStyle {
+  fn active(&self) -> button::Style {
+    button::Style {
+      shadow_offset: Vector::new(0.0, 0.0),
+      background: Some(Background::Color(self.0.button)),
+      border_radius: 2,
+      text_color: self.0.foreground,
+      ..button::Style::default()
+    }
+  }
+
+  fn hovered(&self) -> button::Style {
+    button::Style {
+      background: Some(Background::Color(self.0.button_hovered)),
+      ..self.active()
+    }
+  }
+}
+
+/// Button that looks like a label until hovered.
+pub struct LabelButtonStyle(pub Palette);
+
+impl button::StyleSheet for LabelButtonStyle {
+  fn active(&self) -> button::Style {
+    button::Style {
+      shadow_offset: Vector::new(0.0, 0.0),
+      background: None,
+      text_color: self.0.foreground,
+      ..button::Style::default()
+    }
+  }
+
+  fn hovered(&self) -> button::Style {
+    button::Style {
+      background: Some(Background::Color(self.0.button_hovered)),
+      ..self.active()
+    }
+  }
+}
+
+pub struct TextInputStyle(pub Palette);
+
+impl text_input::StyleSheet for TextInputStyle {
+  fn active(&self) -> text_input::Style {
+    text_input::Style {
+      background: Background::Color(self.0.input),
+      border_radius: 2,
+      border_width: 1,
+      border_color: self.0.border,
+    }
+  }
+
+  fn focused(&self) -> text_input::Style {
+    text_input::Style {
+      border_color: self.0.border_focused,
+      ..self.active()
+    }
+  }
+
+  fn placeholder_color(&self) -> Color { self.0.placeholder }
+
+  fn value_color(&self) -> Color { self.0.foreground }
+
+  fn selection_color(&self) -> Color { self.0.selection }
+}
@@ -1,4 +1,6 @@
-use iced::{Button, button, Color, Column, HorizontalAlignment, Length, Row, Scrollable, scrollable, Text, text_input, TextInput, VerticalAlignment};
+use iced::{Button, button, Color, Column, Container, Element, HorizontalAlignment, Length, Row, Scrollable, scrollable, Text, text_input, TextInput, VerticalAlignment};
+
+use crate::theme::{ButtonStyle, LabelButtonStyle, RootStyle, TextInputStyle, Theme};
 
 #[inline]
 pub fn row<'a, M>() -> Row<'a, M> { Row::new() }
@@ -38,6 +40,7 @@ pub fn text_input<'a, M, F: 'static + Fn(String) -> M>(width: Length, state: &'a
     .width(width)
     .padding(2)
     .size(TXT_SIZE)
+    .style(TextInputStyle(Theme::current().palette()))
 }
 
 #[inline]
@@ -45,19 +48,43 @@ pub fn button<M, L: Into<String>>(state: &mut button::State, label: L) -> Button
   Button::new(state, h2(label).vertical_alignment(VerticalAlignment::Center))
     .padding(2)
     .min_width(50)
-    //.background(Color::from_rgb(0.75, 0.75, 0.75))
-    //.border_radius(2)
+    .style(ButtonStyle(Theme::current().palette()))
+}
+
+#[inline]
+pub fn small_button<M, L: Into<String>>(state: &mut button::State, label: L) -> Button<M> {
+  Button::new(state, lbl(label).horizontal_alignment(HorizontalAlignment::Center).vertical_alignment(VerticalAlignment::Center))
+    .padding(1)
+    .min_width(TXT_SIZE as u32)
+    .style(ButtonStyle(Theme::current().palette()))
+}
+
+#[inline]
+pub fn label_button<M, L: Into<String>>(state: &mut button::State, label: L) -> Button<M> {
+  Button::new(state, lbl(label))
+    .padding(0)
+    .style(LabelButtonStyle(Theme::current().palette()))
 }
 
 #[inline]
 pub fn empty() -> Text { Text::new(" ").size(TXT_SIZE).width(Length::Shrink) }
 
 
+/// Wraps `content` in a container filling the window with the background and text color of the current theme.
+#[inline]
+pub fn root<'a, M: 'a, E: Into<Element<'a, M>>>(content: E) -> Container<'a, M> {
+  Container::new(content)
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .style(RootStyle(Theme::current().palette()))
+}
+
+
 #[inline]
-pub fn background_color() -> Color { Color::WHITE }
+pub fn background_color() -> Color { Theme::current().palette().background }
 
 #[inline]
-pub fn foreground_color() -> Color { Color::BLACK }
+pub fn foreground_color() -> Color { Theme::current().palette().foreground }
 
 #[inline]
-pub fn danger_color() -> Color { Color::from_rgb(0.8, 0.2, 0.2) }
+pub fn danger_color() -> Color { Theme::current().palette().danger }